@@ -1,6 +1,14 @@
+use crate::protocol::DEFAULT_MAX_FRAME_BYTES;
+use buffer::eviction::EvictionPolicyKind;
+use page::insertion_plan::DEFAULT_COMPACTION_THRESHOLD;
 use serde::Deserialize;
+use std::net::SocketAddr;
 use std::path::PathBuf;
-use std::{num::NonZeroUsize, path::Path};
+use std::time::Duration;
+use std::{
+    num::{NonZeroU32, NonZeroU64, NonZeroUsize},
+    path::Path,
+};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -22,6 +30,47 @@ pub enum ConfigError {
 #[derive(Debug, Clone, Deserialize)]
 pub struct EngineConfig {
     pub storage: StorageConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// Address the TCP listener binds to, e.g. `127.0.0.1:8080`
+    pub listen_addr: String,
+    /// Maximum number of queries executed concurrently across all connections
+    pub max_connections: NonZeroUsize,
+    /// Maximum time a single request may run before the client receives a timeout error
+    pub request_timeout_ms: NonZeroU64,
+    /// Largest request frame accepted from a client, in bytes. Connections announcing a larger frame
+    /// receive an error frame and are closed.
+    pub max_frame_bytes: NonZeroU32,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            listen_addr: "127.0.0.1:8080".to_string(),
+            max_connections: NonZeroUsize::new(8).unwrap(),
+            request_timeout_ms: NonZeroU64::new(30_000).unwrap(),
+            max_frame_bytes: NonZeroU32::new(DEFAULT_MAX_FRAME_BYTES).unwrap(),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Returns the parsed listen address. Only call on a validated config.
+    pub fn socket_addr(&self) -> SocketAddr {
+        self.listen_addr
+            .parse()
+            .expect("server.listen_addr is validated when the config is loaded")
+    }
+
+    /// Returns the per-request timeout as a `Duration`
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout_ms.get())
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -29,7 +78,44 @@ pub struct StorageConfig {
     pub data_dir: PathBuf,
     pub logs_dir: PathBuf,
     pub buffer_pages: NonZeroUsize,
+    /// When `true`, every page write is followed by an fsync. Otherwise, data is synced only on flush.
+    #[serde(default)]
+    pub sync_on_write: bool,
+    /// Fraction of a page's free space that may be fragmented before an insert compacts the page
+    /// instead of filling a gap. `1.0` only compacts when no gap can fit the row.
+    #[serde(default = "default_compaction_threshold")]
+    pub compaction_threshold: f64,
+    /// Policy picking the page evicted when the buffer is full
+    #[serde(default)]
+    pub eviction: EvictionConfig,
+    /// Optional file listing pages (`file_id:page_number`, one per line) loaded into the buffer on
+    /// startup, before connections are accepted
+    #[serde(default)]
+    pub warm_up_manifest: Option<PathBuf>,
+}
+
+/// Eviction policies selectable with `storage.eviction`, see `EvictionPolicyKind`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EvictionConfig {
+    #[default]
+    Clock,
+    Lru,
+}
+
+impl From<EvictionConfig> for EvictionPolicyKind {
+    fn from(config: EvictionConfig) -> Self {
+        match config {
+            EvictionConfig::Clock => EvictionPolicyKind::Clock,
+            EvictionConfig::Lru => EvictionPolicyKind::Lru,
+        }
+    }
+}
+
+fn default_compaction_threshold() -> f64 {
+    DEFAULT_COMPACTION_THRESHOLD
 }
+
 impl EngineConfig {
     pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
         let path = path.as_ref().to_path_buf();
@@ -38,7 +124,13 @@ impl EngineConfig {
             source: e,
         })?;
 
-        let cfg: EngineConfig = toml::from_str(&text).map_err(|e| ConfigError::ParseToml {
+        Self::parse(&text, path)
+    }
+
+    /// Parses and validates a config from its TOML representation.
+    /// `path` is only used for error reporting.
+    pub fn parse(text: &str, path: PathBuf) -> Result<Self, ConfigError> {
+        let cfg: EngineConfig = toml::from_str(text).map_err(|e| ConfigError::ParseToml {
             path: path.clone(),
             source: e,
         })?;
@@ -55,6 +147,200 @@ impl EngineConfig {
                 message: "storage.data_dir must not be empty".to_string(),
             });
         }
+        if !(0.0..=1.0).contains(&self.storage.compaction_threshold) {
+            return Err(ConfigError::Invalid {
+                message: format!(
+                    "storage.compaction_threshold must be between 0.0 and 1.0, got {}",
+                    self.storage.compaction_threshold
+                ),
+            });
+        }
+        if let Err(e) = self.server.listen_addr.parse::<SocketAddr>() {
+            return Err(ConfigError::Invalid {
+                message: format!(
+                    "server.listen_addr `{}` is not a valid socket address: {e}",
+                    self.server.listen_addr
+                ),
+            });
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STORAGE: &str = r#"
+        [storage]
+        data_dir = "./DATA"
+        logs_dir = "./LOGS"
+        buffer_pages = 100
+    "#;
+
+    fn parse(text: &str) -> Result<EngineConfig, ConfigError> {
+        EngineConfig::parse(text, PathBuf::from("trdb.toml"))
+    }
+
+    #[test]
+    fn parse_without_server_section_uses_defaults() {
+        let cfg = parse(STORAGE).unwrap();
+
+        assert_eq!(cfg.server.socket_addr(), "127.0.0.1:8080".parse().unwrap());
+        assert_eq!(cfg.server.max_connections.get(), 8);
+        assert_eq!(cfg.server.request_timeout(), Duration::from_secs(30));
+        assert_eq!(cfg.server.max_frame_bytes.get(), DEFAULT_MAX_FRAME_BYTES);
+    }
+
+    #[test]
+    fn parse_valid_server_section_is_surfaced() {
+        let text = format!(
+            "{STORAGE}
+            [server]
+            listen_addr = \"0.0.0.0:9000\"
+            max_connections = 32
+            request_timeout_ms = 1500
+            max_frame_bytes = 1048576
+            "
+        );
+
+        let cfg = parse(&text).unwrap();
+
+        assert_eq!(cfg.server.listen_addr, "0.0.0.0:9000");
+        assert_eq!(cfg.server.socket_addr(), "0.0.0.0:9000".parse().unwrap());
+        assert_eq!(cfg.server.max_connections.get(), 32);
+        assert_eq!(cfg.server.request_timeout(), Duration::from_millis(1500));
+        assert_eq!(cfg.server.max_frame_bytes.get(), 1 << 20);
+    }
+
+    #[test]
+    fn parse_malformed_listen_addr_is_rejected() {
+        let text = format!(
+            "{STORAGE}
+            [server]
+            listen_addr = \"localhost:not-a-port\"
+            "
+        );
+
+        let err = parse(&text).unwrap_err();
+
+        assert!(
+            matches!(err, ConfigError::Invalid { message } if message.contains("server.listen_addr"))
+        );
+    }
+
+    #[test]
+    fn parse_zero_max_connections_is_rejected() {
+        let text = format!(
+            "{STORAGE}
+            [server]
+            max_connections = 0
+            "
+        );
+
+        assert!(matches!(parse(&text), Err(ConfigError::ParseToml { .. })));
+    }
+
+    #[test]
+    fn parse_zero_max_frame_bytes_is_rejected() {
+        let text = format!(
+            "{STORAGE}
+            [server]
+            max_frame_bytes = 0
+            "
+        );
+
+        assert!(matches!(parse(&text), Err(ConfigError::ParseToml { .. })));
+    }
+
+    #[test]
+    fn parse_without_compaction_threshold_uses_default() {
+        let cfg = parse(STORAGE).unwrap();
+
+        assert_eq!(
+            cfg.storage.compaction_threshold,
+            DEFAULT_COMPACTION_THRESHOLD
+        );
+    }
+
+    #[test]
+    fn parse_compaction_threshold_out_of_range_is_rejected() {
+        let text = STORAGE.replace(
+            "buffer_pages = 100",
+            "buffer_pages = 100\n        compaction_threshold = 1.5",
+        );
+
+        let err = parse(&text).unwrap_err();
+
+        assert!(
+            matches!(err, ConfigError::Invalid { message } if message.contains("storage.compaction_threshold"))
+        );
+    }
+
+    #[test]
+    fn parse_compaction_threshold_is_surfaced() {
+        let text = STORAGE.replace(
+            "buffer_pages = 100",
+            "buffer_pages = 100\n        compaction_threshold = 0.25",
+        );
+
+        let cfg = parse(&text).unwrap();
+
+        assert_eq!(cfg.storage.compaction_threshold, 0.25);
+    }
+
+    #[test]
+    fn parse_without_eviction_uses_clock() {
+        let cfg = parse(STORAGE).unwrap();
+
+        assert_eq!(cfg.storage.eviction, EvictionConfig::Clock);
+    }
+
+    #[test]
+    fn parse_eviction_is_surfaced() {
+        let text = STORAGE.replace(
+            "buffer_pages = 100",
+            "buffer_pages = 100\n        eviction = \"lru\"",
+        );
+
+        let cfg = parse(&text).unwrap();
+
+        assert_eq!(cfg.storage.eviction, EvictionConfig::Lru);
+        assert_eq!(
+            EvictionPolicyKind::from(cfg.storage.eviction),
+            EvictionPolicyKind::Lru
+        );
+    }
+
+    #[test]
+    fn parse_without_warm_up_manifest_is_none() {
+        let cfg = parse(STORAGE).unwrap();
+
+        assert_eq!(cfg.storage.warm_up_manifest, None);
+    }
+
+    #[test]
+    fn parse_warm_up_manifest_is_surfaced() {
+        let text = STORAGE.replace(
+            "buffer_pages = 100",
+            "buffer_pages = 100\n        warm_up_manifest = \"./hot_pages.txt\"",
+        );
+
+        let cfg = parse(&text).unwrap();
+
+        assert_eq!(
+            cfg.storage.warm_up_manifest,
+            Some(PathBuf::from("./hot_pages.txt"))
+        );
+    }
+
+    #[test]
+    fn parse_unknown_eviction_is_rejected() {
+        let text = STORAGE.replace(
+            "buffer_pages = 100",
+            "buffer_pages = 100\n        eviction = \"fifo\"",
+        );
+
+        assert!(matches!(parse(&text), Err(ConfigError::ParseToml { .. })));
+    }
+}
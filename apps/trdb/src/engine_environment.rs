@@ -1,11 +1,14 @@
 use crate::config::EngineConfig;
 use buffer::buffer::BufferManager;
+use buffer::eviction::EvictionPolicyKind;
 use file::api::FileManager;
-use file::disk_file_manager::DiskFileManager;
+use file::disk_file_manager::{DiskFileManager, SyncMode};
 use file::file_catalog::FileCatalog;
+use file::storage_layout::StorageLayout;
 use std::env;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use storage_api::schema_catalog::SchemaCatalog;
 use storage_api::storage_manager::StorageManager;
 
 /// Owner of the singleton-like instances that are needed for the entire lifetime of the server
@@ -15,31 +18,70 @@ pub struct EngineEnvironment {
     pub buffer: Arc<BufferManager<DiskFileManager>>,
     pub storage: Arc<StorageManager<DiskFileManager>>,
     pub file_catalog: Arc<FileCatalog>,
+    pub layout: StorageLayout,
+    pub schema_catalog: Arc<SchemaCatalog>,
     pub engine_config: EngineConfig,
 }
 
 impl EngineEnvironment {
     pub fn new(config: EngineConfig) -> Self {
         let file_catalog = Arc::new(FileCatalog::new());
-        let file_manager = Arc::new(DiskFileManager::new(file_catalog.clone()));
-        let buffer = Arc::new(BufferManager::new(
+        let sync_mode = if config.storage.sync_on_write {
+            SyncMode::EveryWrite
+        } else {
+            SyncMode::Explicit
+        };
+        let file_manager = Arc::new(DiskFileManager::with_sync_mode(
+            file_catalog.clone(),
+            sync_mode,
+        ));
+        let buffer_pages = config.storage.buffer_pages.get();
+        let eviction = EvictionPolicyKind::from(config.storage.eviction).build(buffer_pages);
+        let buffer = Arc::new(BufferManager::with_eviction_policy(
             file_manager.clone(),
-            config.storage.buffer_pages.get(),
+            buffer_pages,
+            false,
+            eviction,
         ));
-        let storage = Arc::new(StorageManager::new(file_manager.clone(), buffer.clone()));
+        let layout = StorageLayout::new(config.storage.data_dir.clone());
+        let schema_catalog = Arc::new(Self::load_schema_catalog(&layout));
+        let storage = Arc::new(
+            StorageManager::with_compaction_threshold(
+                file_manager.clone(),
+                buffer.clone(),
+                config.storage.compaction_threshold,
+            )
+            .with_schema_catalog(schema_catalog.clone()),
+        );
         Self {
             file_manager,
             buffer,
             storage,
             file_catalog,
+            layout,
+            schema_catalog,
             engine_config: config,
         }
     }
 
+    /// Loads the table schemas saved in the data directory.
+    /// A missing catalog file means no table was created yet, so an empty catalog is returned.
+    fn load_schema_catalog(layout: &StorageLayout) -> SchemaCatalog {
+        let path = layout.schema_catalog_path();
+        if !path.exists() {
+            return SchemaCatalog::new();
+        }
+        let catalog = SchemaCatalog::load(&path).expect("schema catalog is readable");
+        tracing::info!("Loaded schema catalog from {}", path.display());
+        catalog
+    }
+
     pub fn setup_test_data(&self) {
-        let path = self.engine_config.storage.data_dir.join("test.tbl");
+        let path = self
+            .layout
+            .register(&self.file_catalog, 1)
+            .expect("test data file is registered only once");
         let full_path = std::path::absolute(&path).unwrap();
         tracing::info!("Full path: {}", full_path.display());
-        self.file_catalog.add_file(1, path)
     }
 }
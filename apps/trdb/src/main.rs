@@ -13,17 +13,24 @@
 
 use crate::config::EngineConfig;
 use crate::engine_environment::EngineEnvironment;
+use crate::protocol::{Request, Response};
+use crate::server_error::ServerError;
+use buffer::buffer::BufferManager;
+use file::api::FileManager;
 use page::page_id::PageId;
 use page::page_type::PageType;
 use std::error::Error;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::Semaphore;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio::task;
 use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
+use tracing::{Dispatch, Instrument};
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::{
     EnvFilter, filter::LevelFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt,
@@ -31,6 +38,8 @@ use tracing_subscriber::{
 
 mod config;
 mod engine_environment;
+mod protocol;
+mod server_error;
 
 // Temporarily placed a lot of logic in here for creating the TCP server, handling client requests, delegating them to the engine for processing, etc
 // All of this will be stripped into separate crates/modules, but for now it will do.
@@ -58,19 +67,29 @@ async fn main() {
             let slot_count = existing_page.slot_count().unwrap();
             tracing::info!("Found {slot_count} rows");
             for i in 0..slot_count {
-                let r = existing_page.row(i as u32).unwrap();
-                tracing::info!("Row from slot {i}: {:?}", r)
+                match existing_page.row(i as u32) {
+                    Ok(r) => tracing::info!("Row from slot {i}: {:?}", r),
+                    // Slots of deleted rows cannot be read
+                    Err(err) => tracing::info!("No row in slot {i}: {err}"),
+                }
             }
         }
         Err(err) => {
             tracing::info!("Did not find page with ID {:?}, creating it...", page_id);
-            let mut new_page = e.storage.new_page(page_id).unwrap();
+            let mut new_page = e.storage.new_page(page_id.file_id).unwrap();
+            let page_id = new_page.page_id();
             new_page.initialize(page_id, PageType::Unsorted).unwrap();
             e.storage.write_page(page_id, new_page);
         }
     };
 
-    let semaphore = Arc::new(Semaphore::new(8));
+    if let Some(manifest) = &e.engine_config.storage.warm_up_manifest
+        && let Err(err) = e.buffer.warm_up_from_manifest(manifest)
+    {
+        tracing::warn!("buffer warm-up from {} failed: {err}", manifest.display());
+    }
+
+    let semaphore = Arc::new(Semaphore::new(e.engine_config.server.max_connections.get()));
     let shutdown = CancellationToken::new();
 
     // Spawn a task that waits for OS shutdown signals and triggers cancellation.
@@ -83,7 +102,9 @@ async fn main() {
         });
     }
 
-    let listener = TcpListener::bind("127.0.0.1:8080").await.unwrap();
+    let listener = TcpListener::bind(e.engine_config.server.socket_addr())
+        .await
+        .unwrap();
     tracing::info!("listening on {:?}", listener.local_addr());
 
     let mut connections = JoinSet::new();
@@ -119,6 +140,15 @@ async fn main() {
         }
     }
 
+    drain_and_flush(connections, &e.buffer).await;
+
+    drop(logging_guard);
+    tracing::info!("shutdown complete");
+}
+
+/// Final steps of a graceful shutdown: waits for the in-flight connections to finish, then persists
+/// every dirty page from the buffer, so no acknowledged write is lost.
+async fn drain_and_flush<F: FileManager>(mut connections: JoinSet<()>, buffer: &BufferManager<F>) {
     tracing::info!("waiting for existing connections to finish");
     while let Some(res) = connections.join_next().await {
         if let Err(join_err) = res {
@@ -126,8 +156,10 @@ async fn main() {
         }
     }
 
-    drop(logging_guard);
-    tracing::info!("shutdown complete");
+    tracing::info!("flushing buffered pages to disk");
+    if let Err(e) = buffer.flush_all() {
+        tracing::error!("failed to flush buffered pages on shutdown: {e}");
+    }
 }
 
 async fn wait_for_shutdown_signal() {
@@ -161,7 +193,10 @@ async fn handle_client(
     tracing::info!("client connected on {:?}", peer);
 
     // Split the socket so we can read and write concurrently from different tasks.
-    let (mut reader, writer) = socket.into_split();
+    let (reader, writer) = socket.into_split();
+    // Buffer reads so the length prefix and body of a frame don't each cost a syscall
+    let mut reader = BufReader::new(reader);
+    let max_frame_bytes = env.engine_config.server.max_frame_bytes.get();
 
     // mpsc channel for workers to send completed rows to the writer task
     let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Vec<u8>>();
@@ -185,48 +220,55 @@ async fn handle_client(
 
     // Serve multiple requests over the same connection until the client disconnects or shutdown is triggered
     loop {
-        // for now the client only sends a u32.
-        let mut buf = [0u8; 4];
-
         let read_res = tokio::select! {
             _ = shutdown.cancelled() => {
                 tracing::info!("shutdown: stop reading new requests");
                 return;
             }
-            r = reader.read_exact(&mut buf) => r,
+            r = Request::read_from(&mut reader, max_frame_bytes) => r,
         };
 
-        if read_res.is_err() {
-            tracing::error!("error while reading data from socket for client {:?}", peer);
-            break;
-        }
-
-        let value = u32::from_le_bytes(buf);
-        tracing::info!("Received {} from {:?}", value, peer);
-
-        // Acquire an owned permit so it can be moved into the background worker
-        let permit = tokio::select! {
-            _ = shutdown.cancelled() => {
-                tracing::info!("shutdown: refuse starting new query");
-                return;
+        let request = match read_res {
+            Ok(Ok(request)) => request,
+            Ok(Err(error @ ServerError::FrameTooLarge { .. })) => {
+                // The body of an oversized frame is never read, so the stream cannot be resynced
+                tracing::warn!("oversized frame from {:?}: {error}", peer);
+                let _ = tx.send(Response::Error(error).encode());
+                break;
+            }
+            Ok(Err(error)) => {
+                // The malformed frame was read in full, so the next request can still be served
+                tracing::warn!("malformed request from {:?}: {error}", peer);
+                let _ = tx.send(Response::Error(error).encode());
+                continue;
+            }
+            Err(_) => {
+                tracing::error!("error while reading data from socket for client {:?}", peer);
+                break;
             }
-            p = semaphore.clone().acquire_owned() => p.unwrap(),
         };
-
-        // Clone what the worker needs
-        let tx_clone = tx.clone();
         let env_clone = env.clone();
+        let shutdown_clone = shutdown.clone();
+        let request_timeout = env.engine_config.server.request_timeout();
 
         // proposed (reader waits for query to finish before continuing)
-        let row = task::spawn_blocking(move || process_query(env_clone, value))
-            .await
-            .unwrap();
+        let Some(response) = serve_request(
+            request,
+            peer,
+            &semaphore,
+            &shutdown,
+            request_timeout,
+            move |value| process_query(env_clone, value, &shutdown_clone),
+        )
+        .await
+        else {
+            tracing::info!("shutdown: refuse starting new query");
+            return;
+        };
 
-        if tx.send(row).is_err() {
+        if tx.send(response.encode()).is_err() {
             tracing::warn!("failed to send row to writer: receiver closed for client");
         }
-
-        drop(permit);
     }
 
     // Reader is done (client disconnected or error); drop tx to signal writer to finish
@@ -240,33 +282,149 @@ async fn handle_client(
     tracing::info!("client handler exiting for {:?}", peer);
 }
 
-fn process_query(e: Arc<EngineEnvironment>, number: u32) -> Vec<u8> {
+/// Serves a single request through `dispatch`, inside a `request` span carrying the `peer` address.
+/// The `page_id` field of the span is left empty until the query records the page it targets (see
+/// `record_page_id`), so every log line of the request can be correlated in the JSON logs.
+async fn serve_request<Q>(
+    request: Request,
+    peer: Option<SocketAddr>,
+    semaphore: &Arc<Semaphore>,
+    shutdown: &CancellationToken,
+    timeout: Duration,
+    query: Q,
+) -> Option<Response>
+where
+    Q: FnOnce(u32) -> Result<Vec<u8>, ServerError> + Send + 'static,
+{
+    let span = tracing::info_span!("request", peer = ?peer, page_id = tracing::field::Empty);
+    async {
+        tracing::info!("Received {:?}", request);
+        dispatch(request, semaphore, shutdown, timeout, query).await
+    }
+    .instrument(span)
+    .await
+}
+
+/// Records the page targeted by the request being served on its `request` span
+fn record_page_id(page_id: PageId) {
+    tracing::Span::current().record("page_id", tracing::field::debug(page_id));
+}
+
+/// Serves a single request.
+/// Pings are answered right away, without waiting for a permit or touching storage. Queries wait
+/// for a permit, then run through `query`. Returns `None` if shutdown is triggered while waiting.
+async fn dispatch<Q>(
+    request: Request,
+    semaphore: &Arc<Semaphore>,
+    shutdown: &CancellationToken,
+    timeout: Duration,
+    query: Q,
+) -> Option<Response>
+where
+    Q: FnOnce(u32) -> Result<Vec<u8>, ServerError> + Send + 'static,
+{
+    let value = match request {
+        Request::Ping => return Some(Response::pong()),
+        Request::Query(value) => value,
+    };
+
+    // Acquire an owned permit so it can be moved into the background worker.
+    // Biased, so that no new query starts once shutdown is triggered, even if a permit is free.
+    let permit = tokio::select! {
+        biased;
+        _ = shutdown.cancelled() => return None,
+        p = semaphore.clone().acquire_owned() => p.unwrap(),
+    };
+
+    Some(execute_with_timeout(permit, timeout, shutdown, move || query(value)).await)
+}
+
+/// Runs `query` on the blocking pool, bounded by `timeout` and by `shutdown`.
+/// The `permit` is released as soon as the query finishes, the deadline passes or shutdown is
+/// triggered, whichever comes first.
+///
+/// A blocking task cannot be aborted mid-flight, so on timeout or shutdown the query is left to
+/// finish in the background and its result is discarded. Queries are expected to check `shutdown`
+/// themselves between page operations (see `process_query`), so they stop at the next boundary.
+/// Failures are turned into an error frame, so the client always receives a response.
+async fn execute_with_timeout<Q>(
+    permit: OwnedSemaphorePermit,
+    timeout: Duration,
+    shutdown: &CancellationToken,
+    query: Q,
+) -> Response
+where
+    Q: FnOnce() -> Result<Vec<u8>, ServerError> + Send + 'static,
+{
+    // The blocking pool does not inherit the span nor the subscriber of the calling task, so both
+    // are carried over for the logs of the query to stay attached to its request
+    let span = tracing::Span::current();
+    let dispatcher = tracing::dispatcher::get_default(Dispatch::clone);
+    let query = move || tracing::dispatcher::with_default(&dispatcher, || span.in_scope(query));
+
+    let result = tokio::select! {
+        r = tokio::time::timeout(timeout, task::spawn_blocking(query)) => r,
+        _ = shutdown.cancelled() => Ok(Ok(Err(ServerError::Cancelled))),
+    };
+    drop(permit);
+
+    let error = match result {
+        Ok(Ok(Ok(row))) => return Response::Row(row),
+        Ok(Ok(Err(error))) => {
+            tracing::warn!("query failed: {error}");
+            error
+        }
+        Ok(Err(join_err)) => {
+            tracing::error!("query task failed: {join_err}");
+            ServerError::QueryFailed
+        }
+        Err(_) => {
+            tracing::warn!("query exceeded the request timeout of {timeout:?}");
+            ServerError::Timeout
+        }
+    };
+    Response::Error(error)
+}
+
+/// Fails with `ServerError::Cancelled` once shutdown is triggered.
+/// Called by queries between page operations, the only points where they can stop cleanly.
+fn check_cancelled(shutdown: &CancellationToken) -> Result<(), ServerError> {
+    if shutdown.is_cancelled() {
+        return Err(ServerError::Cancelled);
+    }
+    Ok(())
+}
+
+fn process_query(
+    e: Arc<EngineEnvironment>,
+    number: u32,
+    shutdown: &CancellationToken,
+) -> Result<Vec<u8>, ServerError> {
+    // Rows are addressed starting from 1
+    let Some(slot_index) = number.checked_sub(1) else {
+        return Err(ServerError::Protocol("row numbers start at 1".to_string()));
+    };
+
     // Read the page with the hardcoded ID
+    check_cancelled(shutdown)?;
     let page_id = PageId::new(1, 0);
-    let mut page = e.storage.read_page_mut(page_id).unwrap();
+    record_page_id(page_id);
+    let mut page = e.storage.read_page_mut(page_id)?;
 
     // Attempt an insert of a 100bytes row
-    let Ok(insert_plan) = page.plan_insert(100) else {
-        panic!("insert plan failed");
-    };
+    let insert_plan = page.plan_insert(100)?;
 
     // Create a row composed of 100 bytes with the provided number and insert it
     let byte_value = number as u8;
-    match page.insert_heap(insert_plan, vec![byte_value; 100]) {
-        Ok(_) => {}
-        Err(e) => panic!("insert failed: {}", e),
-    }
+    page.insert_heap(insert_plan, vec![byte_value; 100])?;
     e.storage.write_page(page_id, page);
 
-    let page = e.storage.read_page(page_id).unwrap();
+    // The insert is done, only stop between page operations so it is never left half applied
+    check_cancelled(shutdown)?;
+    let page = e.storage.read_page(page_id)?;
 
-    // Re-read the row to ensure it was inserted
-    let Ok(row) = page.row(number - 1) else {
-        panic!("row 0 failed");
-    };
-
-    // Return the raw row binary data
-    Vec::from(row)
+    // Re-read the row to ensure it was inserted, and return its raw binary data
+    Ok(Vec::from(page.row(slot_index)?))
 }
 
 /// Sets up the logging for the server
@@ -298,3 +456,397 @@ pub fn init_logging(log_dir: &PathBuf) -> Result<WorkerGuard, Box<dyn Error + Se
 
     Ok(guard)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ERROR_RESPONSE_TAG;
+    use file::errors::FileError;
+    use file::file_catalog::FileCatalog;
+    use page::page::api::Page;
+    use page::page_id::FileId;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use storage_api::storage_manager::StorageErrors;
+
+    #[derive(Default)]
+    struct RecordingFileManager {
+        written_pages: Mutex<Vec<PageId>>,
+        synced_files: Mutex<Vec<FileId>>,
+    }
+
+    impl FileManager for RecordingFileManager {
+        fn new(_: Arc<FileCatalog>) -> Self {
+            Self::default()
+        }
+
+        /// Serves an empty heap page for every page ID
+        fn read_page(&self, page_id: PageId, destination: &mut [u8]) -> bool {
+            let mut page = Page::new_zeroed(page_id);
+            page.initialize(page_id, PageType::Unsorted).unwrap();
+            destination.copy_from_slice(page.data());
+            true
+        }
+
+        fn page_exists(&self, _: PageId) -> Result<bool, FileError> {
+            Ok(true)
+        }
+
+        fn write_page(&self, page_id: PageId, _: &[u8]) {
+            self.written_pages.lock().unwrap().push(page_id);
+        }
+
+        fn allocate_page(&self, _: FileId) -> Result<PageId, FileError> {
+            unimplemented!("shutdown never allocates pages")
+        }
+
+        fn sync(&self, file_id: FileId) -> std::io::Result<()> {
+            self.synced_files.lock().unwrap().push(file_id);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn shutdown_persists_dirty_pages_after_connections_drain() {
+        let file_manager = Arc::new(RecordingFileManager::default());
+        let buffer = Arc::new(BufferManager::new(file_manager.clone(), 4));
+        let shutdown = CancellationToken::new();
+        let page_id = PageId::new(1, 0);
+
+        // A connection that modifies a page and finishes only once shutdown is triggered
+        let mut connections = JoinSet::new();
+        {
+            let buffer = buffer.clone();
+            let shutdown = shutdown.clone();
+            connections.spawn(async move {
+                buffer.read_page_mut(page_id).unwrap().data_mut()[200] = 0xAB;
+                shutdown.cancelled().await;
+            });
+        }
+        tokio::task::yield_now().await;
+        assert!(file_manager.written_pages.lock().unwrap().is_empty());
+
+        shutdown.cancel();
+        drain_and_flush(connections, &buffer).await;
+
+        assert_eq!(*file_manager.written_pages.lock().unwrap(), vec![page_id]);
+        assert_eq!(*file_manager.synced_files.lock().unwrap(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn execute_with_timeout_fast_query_returns_result() {
+        let semaphore = Arc::new(Semaphore::new(1));
+        let permit = semaphore.clone().acquire_owned().await.unwrap();
+
+        let row = execute_with_timeout(
+            permit,
+            Duration::from_secs(5),
+            &CancellationToken::new(),
+            || Ok(vec![7u8; 4]),
+        )
+        .await
+        .encode();
+
+        assert_eq!(row, Response::Row(vec![7u8; 4]).encode());
+        assert_eq!(semaphore.available_permits(), 1);
+    }
+
+    #[tokio::test]
+    async fn execute_with_timeout_slow_query_sends_error_frame_and_releases_permit() {
+        let semaphore = Arc::new(Semaphore::new(1));
+        let permit = semaphore.clone().acquire_owned().await.unwrap();
+        let slow_query = || {
+            std::thread::sleep(Duration::from_millis(500));
+            Ok(vec![1u8; 100])
+        };
+
+        let started = std::time::Instant::now();
+        let row = execute_with_timeout(
+            permit,
+            Duration::from_millis(50),
+            &CancellationToken::new(),
+            slow_query,
+        )
+        .await
+        .encode();
+
+        assert_eq!(row, Response::Error(ServerError::Timeout).encode());
+        assert_eq!(row[4], ERROR_RESPONSE_TAG);
+        assert!(started.elapsed() < Duration::from_millis(500));
+        // The permit is available again while the slow query is still running
+        assert_eq!(semaphore.available_permits(), 1);
+    }
+
+    #[tokio::test]
+    async fn execute_with_timeout_panicking_query_sends_error_frame() {
+        let semaphore = Arc::new(Semaphore::new(1));
+        let permit = semaphore.clone().acquire_owned().await.unwrap();
+
+        let row = execute_with_timeout(
+            permit,
+            Duration::from_secs(5),
+            &CancellationToken::new(),
+            || panic!("boom"),
+        )
+        .await
+        .encode();
+
+        assert_eq!(row, Response::Error(ServerError::QueryFailed).encode());
+        assert_eq!(semaphore.available_permits(), 1);
+    }
+
+    #[tokio::test]
+    async fn execute_with_timeout_failing_query_sends_its_error_frame() {
+        let semaphore = Arc::new(Semaphore::new(1));
+        let permit = semaphore.clone().acquire_owned().await.unwrap();
+
+        let row = execute_with_timeout(
+            permit,
+            Duration::from_secs(5),
+            &CancellationToken::new(),
+            || Err(ServerError::Storage(StorageErrors::ReadPage)),
+        )
+        .await
+        .encode();
+
+        assert_eq!(
+            row,
+            Response::Error(ServerError::Storage(StorageErrors::ReadPage)).encode()
+        );
+        assert_eq!(semaphore.available_permits(), 1);
+    }
+
+    #[tokio::test]
+    async fn dispatch_ping_answers_pong_without_permit_or_query() {
+        // No permit is available, so a request waiting for one would never complete
+        let semaphore = Arc::new(Semaphore::new(0));
+        let shutdown = CancellationToken::new();
+
+        let response = tokio::time::timeout(
+            Duration::from_secs(5),
+            dispatch(
+                Request::Ping,
+                &semaphore,
+                &shutdown,
+                Duration::from_secs(5),
+                |_| -> Result<Vec<u8>, ServerError> { panic!("ping must not reach storage") },
+            ),
+        )
+        .await
+        .expect("ping waited for a permit");
+
+        assert!(matches!(
+            response,
+            Some(Response::Pong { server_time_micros }) if server_time_micros > 0
+        ));
+        assert_eq!(semaphore.available_permits(), 0);
+    }
+
+    #[tokio::test]
+    async fn dispatch_query_runs_with_permit() {
+        let semaphore = Arc::new(Semaphore::new(1));
+        let shutdown = CancellationToken::new();
+
+        let response = dispatch(
+            Request::Query(3),
+            &semaphore,
+            &shutdown,
+            Duration::from_secs(5),
+            |value| Ok(vec![value as u8; 2]),
+        )
+        .await;
+
+        assert_eq!(
+            response.unwrap().encode(),
+            Response::Row(vec![3u8; 2]).encode()
+        );
+        assert_eq!(semaphore.available_permits(), 1);
+    }
+
+    /// Fields and number of entries of a span captured by `SpanRecorder`
+    #[derive(Debug)]
+    struct RecordedSpan {
+        id: tracing::span::Id,
+        name: &'static str,
+        fields: HashMap<&'static str, String>,
+        entered: usize,
+    }
+
+    /// Layer capturing every span created while it is the default subscriber
+    #[derive(Clone, Default)]
+    struct SpanRecorder {
+        spans: Arc<Mutex<Vec<RecordedSpan>>>,
+    }
+
+    impl tracing::field::Visit for RecordedSpan {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.fields.insert(field.name(), format!("{value:?}"));
+        }
+    }
+
+    impl SpanRecorder {
+        /// Applies `f` to the live span with the given id. Ids of closed spans may be reused, so
+        /// the most recent span with that id is the live one.
+        fn with_span(&self, id: &tracing::span::Id, f: impl FnOnce(&mut RecordedSpan)) {
+            let mut spans = self.spans.lock().unwrap();
+            if let Some(span) = spans.iter_mut().rev().find(|span| &span.id == id) {
+                f(span);
+            }
+        }
+    }
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SpanRecorder {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            id: &tracing::span::Id,
+            _: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut span = RecordedSpan {
+                id: id.clone(),
+                name: attrs.metadata().name(),
+                fields: HashMap::new(),
+                entered: 0,
+            };
+            attrs.record(&mut span);
+            self.spans.lock().unwrap().push(span);
+        }
+
+        fn on_record(
+            &self,
+            id: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            _: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            self.with_span(id, |span| values.record(span));
+        }
+
+        fn on_enter(&self, id: &tracing::span::Id, _: tracing_subscriber::layer::Context<'_, S>) {
+            self.with_span(id, |span| span.entered += 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn serve_request_enters_a_span_per_request_with_peer_and_page_id() {
+        let recorder = SpanRecorder::default();
+        let _subscriber = tracing_subscriber::registry()
+            .with(recorder.clone())
+            .set_default();
+        let semaphore = Arc::new(Semaphore::new(1));
+        let shutdown = CancellationToken::new();
+        let peer: SocketAddr = "127.0.0.1:5000".parse().unwrap();
+        let page_id = PageId::new(1, 0);
+
+        for request in [Request::Ping, Request::Query(3)] {
+            serve_request(
+                request,
+                Some(peer),
+                &semaphore,
+                &shutdown,
+                Duration::from_secs(5),
+                move |value| {
+                    // Runs on the blocking pool, like `process_query`
+                    record_page_id(page_id);
+                    Ok(vec![value as u8; 2])
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let spans = recorder.spans.lock().unwrap();
+        let requests: Vec<&RecordedSpan> =
+            spans.iter().filter(|span| span.name == "request").collect();
+        assert_eq!(requests.len(), 2);
+        for span in &requests {
+            assert!(span.entered > 0);
+            assert_eq!(span.fields["peer"], format!("{:?}", Some(peer)));
+        }
+        // A ping never reaches storage, so it targets no page
+        assert!(!requests[0].fields.contains_key("page_id"));
+        assert_eq!(requests[1].fields["page_id"], format!("{page_id:?}"));
+    }
+
+    #[tokio::test]
+    async fn dispatch_query_during_shutdown_returns_none() {
+        let semaphore = Arc::new(Semaphore::new(0));
+        let shutdown = CancellationToken::new();
+        shutdown.cancel();
+
+        let response = dispatch(
+            Request::Query(3),
+            &semaphore,
+            &shutdown,
+            Duration::from_secs(5),
+            |value| Ok(vec![value as u8; 2]),
+        )
+        .await;
+
+        assert!(response.is_none());
+    }
+
+    #[tokio::test]
+    async fn dispatch_query_during_shutdown_does_not_start_query_even_with_free_permit() {
+        let semaphore = Arc::new(Semaphore::new(1));
+        let shutdown = CancellationToken::new();
+        shutdown.cancel();
+        let started = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let started_clone = started.clone();
+        let response = dispatch(
+            Request::Query(3),
+            &semaphore,
+            &shutdown,
+            Duration::from_secs(5),
+            move |value| {
+                started_clone.store(true, std::sync::atomic::Ordering::SeqCst);
+                Ok(vec![value as u8; 2])
+            },
+        )
+        .await;
+
+        assert!(response.is_none());
+        assert!(!started.load(std::sync::atomic::Ordering::SeqCst));
+        assert_eq!(semaphore.available_permits(), 1);
+    }
+
+    #[tokio::test]
+    async fn execute_with_timeout_shutdown_during_query_sends_cancelled_frame() {
+        let semaphore = Arc::new(Semaphore::new(1));
+        let permit = semaphore.clone().acquire_owned().await.unwrap();
+        let shutdown = CancellationToken::new();
+        let slow_query = || {
+            std::thread::sleep(Duration::from_millis(500));
+            Ok(vec![1u8; 100])
+        };
+
+        let started = std::time::Instant::now();
+        let (response, _) = tokio::join!(
+            execute_with_timeout(permit, Duration::from_secs(5), &shutdown, slow_query),
+            async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                shutdown.cancel();
+            }
+        );
+
+        assert_eq!(
+            response.encode(),
+            Response::Error(ServerError::Cancelled).encode()
+        );
+        assert!(started.elapsed() < Duration::from_millis(500));
+        assert_eq!(semaphore.available_permits(), 1);
+    }
+
+    #[test]
+    fn check_cancelled_fails_once_shutdown_is_triggered() {
+        let shutdown = CancellationToken::new();
+        assert!(check_cancelled(&shutdown).is_ok());
+
+        shutdown.cancel();
+
+        assert!(matches!(
+            check_cancelled(&shutdown),
+            Err(ServerError::Cancelled)
+        ));
+    }
+}
@@ -0,0 +1,257 @@
+use crate::server_error::ServerError;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Largest request frame accepted by default, in bytes, excluding the length prefix
+pub const DEFAULT_MAX_FRAME_BYTES: u32 = 64 * 1024;
+/// Tag of a [`Request::Query`], followed by the queried value as a little-endian `u32`
+pub const QUERY_TAG: u8 = 1;
+/// Tag of a [`Request::Ping`], which carries no payload
+pub const PING_TAG: u8 = 2;
+/// Tag of a [`Response::Row`], followed by the raw bytes of the row
+pub const ROW_RESPONSE_TAG: u8 = 1;
+/// Tag of a [`Response::Pong`], followed by the server time, in microseconds since the Unix epoch,
+/// as a little-endian `u64`
+pub const PONG_RESPONSE_TAG: u8 = 2;
+/// Tag of a [`Response::Error`], followed by the [`ServerError::code`] byte, then by the error
+/// message as UTF-8
+pub const ERROR_RESPONSE_TAG: u8 = 3;
+
+/// Prefixes `body` with its length as a little-endian `u32`, which is how both requests and
+/// responses are framed on the wire
+pub fn frame(body: &[u8]) -> Vec<u8> {
+    [&(body.len() as u32).to_le_bytes()[..], body].concat()
+}
+
+/// Requests a client can send.
+///
+/// Every request is sent as a frame: a little-endian `u32` length, followed by that many bytes. The
+/// body starts with a one byte tag identifying the kind of request, followed by its payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Request {
+    /// Inserts a row built from the value, then returns it
+    Query(u32),
+    /// No-op round trip, used by clients to check the connection and measure latency
+    Ping,
+}
+
+impl Request {
+    /// Reads the next request frame from `reader`.
+    /// The outer error means the connection failed, the inner one that the request is malformed.
+    ///
+    /// A length prefix above `max_frame_bytes` is rejected with `ServerError::FrameTooLarge` before
+    /// anything is allocated for the body, which is left unread. Any other malformed request is read
+    /// in full, so the next frame can still be read.
+    pub async fn read_from<R: AsyncRead + Unpin>(
+        reader: &mut R,
+        max_frame_bytes: u32,
+    ) -> std::io::Result<Result<Request, ServerError>> {
+        let len = reader.read_u32_le().await?;
+        if len > max_frame_bytes {
+            return Ok(Err(ServerError::FrameTooLarge {
+                len,
+                max: max_frame_bytes,
+            }));
+        }
+
+        let mut body = vec![0u8; len as usize];
+        reader.read_exact(&mut body).await?;
+        Ok(Self::decode(&body))
+    }
+
+    /// Parses the body of a frame
+    fn decode(body: &[u8]) -> Result<Request, ServerError> {
+        let Some((&tag, payload)) = body.split_first() else {
+            return Err(ServerError::Protocol("empty frame".to_string()));
+        };
+
+        match tag {
+            QUERY_TAG => payload
+                .try_into()
+                .map(|value| Request::Query(u32::from_le_bytes(value)))
+                .map_err(|_| {
+                    ServerError::Protocol(format!(
+                        "query payload must be 4 bytes long, got {}",
+                        payload.len()
+                    ))
+                }),
+            PING_TAG if payload.is_empty() => Ok(Request::Ping),
+            PING_TAG => Err(ServerError::Protocol("ping carries no payload".to_string())),
+            tag => Err(ServerError::Protocol(format!("unknown request tag {tag}"))),
+        }
+    }
+
+    /// Serializes the request into a frame, as sent by clients
+    pub fn encode(&self) -> Vec<u8> {
+        let body = match self {
+            Request::Query(value) => [&[QUERY_TAG][..], &value.to_le_bytes()].concat(),
+            Request::Ping => vec![PING_TAG],
+        };
+        frame(&body)
+    }
+}
+
+/// Responses sent back to the client.
+///
+/// Every response is sent as a frame, like requests: a little-endian `u32` length, followed by that
+/// many bytes. The body starts with a one byte tag identifying the kind of response, so a row is
+/// never mistaken for an error or a pong, whatever its bytes.
+#[derive(Debug)]
+pub enum Response {
+    /// Raw bytes of the row produced by a query
+    Row(Vec<u8>),
+    /// Answer to a ping, carrying the server time in microseconds since the Unix epoch
+    Pong {
+        /// Server time when the ping was answered
+        server_time_micros: u64,
+    },
+    /// The request failed
+    Error(ServerError),
+}
+
+impl Response {
+    /// Answers a ping with the current server time
+    pub fn pong() -> Self {
+        let server_time_micros = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_micros() as u64);
+        Response::Pong { server_time_micros }
+    }
+
+    /// Serializes the response into the frame written to the client
+    pub fn encode(&self) -> Vec<u8> {
+        let body = match self {
+            Response::Row(row) => [&[ROW_RESPONSE_TAG][..], row].concat(),
+            Response::Pong { server_time_micros } => {
+                [&[PONG_RESPONSE_TAG][..], &server_time_micros.to_le_bytes()].concat()
+            }
+            Response::Error(error) => [&[ERROR_RESPONSE_TAG][..], &error.to_payload()].concat(),
+        };
+        frame(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read(bytes: &mut &[u8]) -> std::io::Result<Result<Request, ServerError>> {
+        Request::read_from(bytes, DEFAULT_MAX_FRAME_BYTES).await
+    }
+
+    #[tokio::test]
+    async fn read_from_parses_query_and_ping() {
+        let frames = [Request::Query(7).encode(), Request::Ping.encode()].concat();
+        let mut bytes: &[u8] = &frames;
+
+        assert_eq!(read(&mut bytes).await.unwrap().unwrap(), Request::Query(7));
+        assert_eq!(read(&mut bytes).await.unwrap().unwrap(), Request::Ping);
+        assert!(read(&mut bytes).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn read_from_unknown_tag_is_protocol_error() {
+        let mut bytes: &[u8] = &[1, 0, 0, 0, 42];
+
+        let request = read(&mut bytes).await.unwrap();
+
+        assert!(matches!(request, Err(ServerError::Protocol(_))));
+    }
+
+    #[tokio::test]
+    async fn read_from_length_above_max_is_rejected_without_reading_body() {
+        // A length prefix of u32::MAX, followed by the start of what would be its body
+        let mut bytes: &[u8] = &[0xFF, 0xFF, 0xFF, 0xFF, QUERY_TAG, 7, 0, 0, 0];
+
+        let request = read(&mut bytes).await.unwrap();
+
+        assert!(matches!(
+            request,
+            Err(ServerError::FrameTooLarge {
+                len: u32::MAX,
+                max: DEFAULT_MAX_FRAME_BYTES
+            })
+        ));
+        // Nothing past the length prefix was consumed
+        assert_eq!(bytes.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn read_from_length_equal_to_max_is_accepted() {
+        let frame = Request::Query(7).encode();
+        let mut bytes: &[u8] = &frame;
+
+        let request = Request::read_from(&mut bytes, 5).await.unwrap();
+
+        assert_eq!(request.unwrap(), Request::Query(7));
+    }
+
+    #[tokio::test]
+    async fn read_from_large_allowed_frame_is_consumed_and_stream_stays_in_sync() {
+        let len = DEFAULT_MAX_FRAME_BYTES - 1;
+        let mut frames = len.to_le_bytes().to_vec();
+        frames.push(QUERY_TAG);
+        frames.resize(4 + len as usize, 0xAB);
+        frames.extend(Request::Ping.encode());
+        let mut bytes: &[u8] = &frames;
+
+        // The oversized query payload is malformed, but the frame itself is within bounds
+        assert!(matches!(
+            read(&mut bytes).await.unwrap(),
+            Err(ServerError::Protocol(_))
+        ));
+        assert_eq!(read(&mut bytes).await.unwrap().unwrap(), Request::Ping);
+    }
+
+    #[tokio::test]
+    async fn read_from_malformed_bodies_are_protocol_errors() {
+        for body in [&[][..], &[QUERY_TAG, 1, 2][..], &[PING_TAG, 0][..]] {
+            let frame = frame(body);
+            let mut bytes: &[u8] = &frame;
+
+            assert!(matches!(
+                read(&mut bytes).await.unwrap(),
+                Err(ServerError::Protocol(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn pong_encodes_length_prefix_then_tag_then_timestamp() {
+        let frame = Response::Pong {
+            server_time_micros: 0x0102,
+        }
+        .encode();
+
+        assert_eq!(
+            frame,
+            [9, 0, 0, 0, PONG_RESPONSE_TAG, 2, 1, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn row_and_error_responses_are_length_prefixed_and_tagged() {
+        assert_eq!(
+            Response::Row(vec![]).encode(),
+            [1, 0, 0, 0, ROW_RESPONSE_TAG]
+        );
+        assert_eq!(
+            Response::Row(vec![9; 3]).encode(),
+            [4, 0, 0, 0, ROW_RESPONSE_TAG, 9, 9, 9]
+        );
+
+        let payload = ServerError::Timeout.to_payload();
+        let encoded = Response::Error(ServerError::Timeout).encode();
+        assert_eq!(encoded[..4], (payload.len() as u32 + 1).to_le_bytes());
+        assert_eq!(encoded[4], ERROR_RESPONSE_TAG);
+        assert_eq!(encoded[5..], payload);
+    }
+
+    #[test]
+    fn row_shaped_like_an_error_is_still_tagged_as_a_row() {
+        let encoded = Response::Row(b"ERR \x04timeout".to_vec()).encode();
+
+        assert_eq!(encoded[4], ROW_RESPONSE_TAG);
+        assert_eq!(&encoded[5..], b"ERR \x04timeout");
+    }
+}
@@ -0,0 +1,113 @@
+use page::errors::page_error::PageError;
+use storage_api::storage_manager::StorageErrors;
+use thiserror::Error;
+
+/// Errors reported back to the client when a request cannot be served.
+///
+/// Every variant has a stable numeric code, which clients use to tell failures apart without
+/// parsing the message. Codes must never be reused or renumbered once released.
+#[derive(Debug, Error)]
+pub enum ServerError {
+    /// The request could not be parsed. Code `1`.
+    #[error("malformed request: {0}")]
+    Protocol(String),
+    /// The storage layer failed to serve a page. Code `2`.
+    #[error("storage error: {0}")]
+    Storage(#[from] StorageErrors),
+    /// An operation on a page failed. Code `3`.
+    #[error("page error: {0}")]
+    Page(#[from] PageError),
+    /// The request did not complete within the request timeout. Code `4`.
+    #[error("timeout")]
+    Timeout,
+    /// The query aborted unexpectedly. Code `5`.
+    #[error("query failed")]
+    QueryFailed,
+    /// The server started shutting down before the request completed. Code `6`.
+    #[error("server is shutting down")]
+    Cancelled,
+    /// The length prefix of a request frame exceeds the configured maximum. Code `7`.
+    #[error("frame of {len} bytes exceeds the maximum of {max} bytes")]
+    FrameTooLarge {
+        /// Length announced by the frame
+        len: u32,
+        /// Largest accepted frame length
+        max: u32,
+    },
+}
+
+impl ServerError {
+    /// Stable numeric code identifying the variant on the wire
+    pub fn code(&self) -> u8 {
+        match self {
+            ServerError::Protocol(_) => 1,
+            ServerError::Storage(_) => 2,
+            ServerError::Page(_) => 3,
+            ServerError::Timeout => 4,
+            ServerError::QueryFailed => 5,
+            ServerError::Cancelled => 6,
+            ServerError::FrameTooLarge { .. } => 7,
+        }
+    }
+
+    /// Builds the payload of the error response sent to the client for this error: the code byte,
+    /// then the message. See [`crate::protocol::ERROR_RESPONSE_TAG`].
+    pub fn to_payload(&self) -> Vec<u8> {
+        let message = self.to_string();
+        let mut payload = Vec::with_capacity(1 + message.len());
+        payload.push(self.code());
+        payload.extend_from_slice(message.as_bytes());
+        payload
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use page::page::api::Page;
+    use page::page_id::PageId;
+    use page::page_type::PageType;
+
+    fn page_error() -> PageError {
+        let page_id = PageId::new(1, 1);
+        let mut page = Page::new_zeroed(page_id);
+        page.initialize(page_id, PageType::Unsorted).unwrap();
+        page.row(0).unwrap_err()
+    }
+
+    #[test]
+    fn codes_match_documented_values() {
+        assert_eq!(ServerError::Protocol("bad".into()).code(), 1);
+        assert_eq!(ServerError::Storage(StorageErrors::ReadPage).code(), 2);
+        assert_eq!(ServerError::Page(page_error()).code(), 3);
+        assert_eq!(ServerError::Timeout.code(), 4);
+        assert_eq!(ServerError::QueryFailed.code(), 5);
+        assert_eq!(ServerError::Cancelled.code(), 6);
+        assert_eq!(ServerError::FrameTooLarge { len: 2, max: 1 }.code(), 7);
+    }
+
+    #[test]
+    fn to_payload_writes_code_then_message() {
+        let error = ServerError::Storage(StorageErrors::ReadPage);
+
+        let payload = error.to_payload();
+
+        assert_eq!(payload[0], 2);
+        assert_eq!(&payload[1..], b"storage error: Error while reading page");
+    }
+
+    #[test]
+    fn to_payload_every_variant_starts_with_its_code() {
+        for error in [
+            ServerError::Protocol("bad".into()),
+            ServerError::Storage(StorageErrors::NewPage),
+            ServerError::Page(page_error()),
+            ServerError::Timeout,
+            ServerError::QueryFailed,
+            ServerError::Cancelled,
+            ServerError::FrameTooLarge { len: 2, max: 1 },
+        ] {
+            assert_eq!(error.to_payload()[0], error.code());
+        }
+    }
+}
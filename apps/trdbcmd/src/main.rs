@@ -1,37 +1,161 @@
 //! Client for the TRDB server
 
 use std::env;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::io;
-use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 
 // This is just a hardcoded client used for testing the communication with the server
 // but this crate will basically contain the CLI client that will read queries from the user,
 // send them to the server and print back the result.
 
+/// Server address used when `--addr` is not provided
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+/// Largest response frame accepted, in bytes, excluding the length prefix
+const MAX_RESPONSE_FRAME_BYTES: u32 = 16 * 1024 * 1024;
+
+/// Tag of a query request, followed by the value as a little-endian `u32`
+const QUERY_TAG: u8 = 1;
+/// Tag of a ping request, which carries no payload
+const PING_TAG: u8 = 2;
+
+/// Tag of a response carrying a row, followed by the raw bytes of the row
+const ROW_RESPONSE_TAG: u8 = 1;
+/// Tag of the response answering a ping, followed by the server time in microseconds since the
+/// Unix epoch as a little-endian `u64`
+const PONG_RESPONSE_TAG: u8 = 2;
+/// Tag of the response the server sends instead of a result when a request fails. It is followed
+/// by a one byte error code, then by the server's error message.
+const ERROR_RESPONSE_TAG: u8 = 3;
+
+/// Maps the error code of an error frame to a description. Mirrors the codes of the server's
+/// `ServerError`.
+fn error_code_description(code: u8) -> &'static str {
+    match code {
+        1 => "malformed request",
+        2 => "storage error",
+        3 => "page error",
+        4 => "request timed out",
+        5 => "query failed",
+        6 => "server is shutting down",
+        7 => "request frame too large",
+        _ => "unknown error",
+    }
+}
+
+/// Decodes an error frame into a readable message, or returns `None` if `response` is not an
+/// error frame.
+fn decode_error_frame(response: &[u8]) -> Option<String> {
+    let rest = response.strip_prefix(&[ERROR_RESPONSE_TAG])?;
+    let (&code, details) = rest.split_first()?;
+    Some(format!(
+        "{} (code {code}): {}",
+        error_code_description(code),
+        String::from_utf8_lossy(details)
+    ))
+}
+
+/// Splits the command line arguments into the server address (`--addr <host:port>`) and the
+/// optional value to send in single-shot mode.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<(String, Option<String>), String> {
+    let mut addr = DEFAULT_ADDR.to_string();
+    let mut value = None;
+
+    while let Some(arg) = args.next() {
+        if arg == "--addr" {
+            addr = args.next().ok_or("--addr requires a value")?;
+        } else if let Some(a) = arg.strip_prefix("--addr=") {
+            addr = a.to_string();
+        } else if value.is_none() {
+            value = Some(arg);
+        } else {
+            return Err(format!("unexpected argument `{arg}`"));
+        }
+    }
+
+    Ok((addr, value))
+}
+
+/// Prefixes a request body (tag, then payload) with its length as a little-endian `u32`, as the
+/// server expects
+fn frame_request(body: &[u8]) -> Vec<u8> {
+    [&(body.len() as u32).to_le_bytes()[..], body].concat()
+}
+
+/// Reads the next response frame (a little-endian `u32` length, then that many bytes) and returns
+/// its body, or `None` if the server closed the connection before sending one.
+///
+/// A length prefix above `max_frame_bytes` is rejected with `ErrorKind::InvalidData` before anything
+/// is allocated for the body, which is left unread.
+async fn read_frame<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    max_frame_bytes: u32,
+) -> io::Result<Option<Vec<u8>>> {
+    let len = match reader.read_u32_le().await {
+        Ok(len) => len,
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    if len > max_frame_bytes {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("response frame of {len} bytes exceeds the maximum of {max_frame_bytes} bytes"),
+        ));
+    }
+
+    let mut body = vec![0u8; len as usize];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(body))
+}
+
+/// Sends a query and returns the body of the response, or `None` if the server closed the
+/// connection instead of answering.
 async fn send_and_receive(
     stream: &mut TcpStream,
     value: u32,
-) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
-    // Send the u32
-    let bytes = value.to_le_bytes();
+) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
+    // Send the tagged u32
+    let bytes = frame_request(&[&[QUERY_TAG][..], &value.to_le_bytes()].concat());
     stream.write_all(&bytes).await?;
     eprintln!("Sent {} (bytes: {:?})", value, bytes);
 
-    // Read response - up to 1024 bytes
-    let mut buf = vec![0u8; 1024];
-    let n = stream.read(&mut buf).await?;
-    eprintln!("read returned: {}", n);
-    if n == 0 {
-        // connection closed by server; return empty Vec to signal closed connection
-        return Ok(Vec::new());
-    }
-    buf.truncate(n);
-    Ok(buf)
+    Ok(read_frame(stream, MAX_RESPONSE_FRAME_BYTES).await?)
+}
+
+/// Extracts the server timestamp from a pong frame, or returns `None` if `response` is not one.
+fn decode_pong(response: &[u8]) -> Option<u64> {
+    let timestamp = response.strip_prefix(&[PONG_RESPONSE_TAG])?;
+    Some(u64::from_le_bytes(timestamp.try_into().ok()?))
+}
+
+/// Extracts the row bytes from a row frame, or returns `None` if `response` is not one.
+fn decode_row(response: &[u8]) -> Option<&[u8]> {
+    response.strip_prefix(&[ROW_RESPONSE_TAG])
+}
+
+/// Sends a ping and waits for the pong, returning the server timestamp and the round trip time.
+async fn ping(stream: &mut TcpStream) -> Result<(u64, Duration), Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    stream.write_all(&frame_request(&[PING_TAG])).await?;
+
+    let response = read_frame(stream, MAX_RESPONSE_FRAME_BYTES)
+        .await?
+        .ok_or("connection closed before the pong")?;
+    let server_time = decode_pong(&response).ok_or("unexpected response to ping")?;
+    Ok((server_time, started.elapsed()))
 }
 
 fn print_result(result: &[u8]) {
+    if let Some(message) = decode_error_frame(result) {
+        println!("Server error - {message}");
+        return;
+    }
+    let Some(result) = decode_row(result) else {
+        println!("Unexpected response from server: {result:?}");
+        return;
+    };
+
     // Show number of bytes and a hex preview
     let len = result.len();
     // hex preview for first up to 32 bytes
@@ -50,38 +174,34 @@ fn print_result(result: &[u8]) {
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (addr, value_arg) = parse_args(env::args().skip(1))?;
+
     // If a cmd line arg was provided, do a single send/receive and exit
-    if let Some(arg) = env::args().nth(1) {
+    if let Some(arg) = value_arg {
         let value: u32 = arg.trim().parse()?;
 
         // Connect to the server
-        let mut stream = TcpStream::connect("127.0.0.1:8080").await?;
+        let mut stream = TcpStream::connect(&addr).await?;
 
-        let result = send_and_receive(&mut stream, value).await?;
-        if result.is_empty() {
+        if let Some(result) = send_and_receive(&mut stream, value).await? {
+            print_result(&result);
+        } else {
             eprintln!(
                 "Server closed the connection before sending data. Attempting one reconnect/resend..."
             );
             // Try reconnect once
-            match TcpStream::connect("127.0.0.1:8080").await {
+            match TcpStream::connect(&addr).await {
                 Ok(mut new_stream) => {
                     // small backoff to give server time
                     tokio::time::sleep(Duration::from_millis(50)).await;
                     match send_and_receive(&mut new_stream, value).await {
-                        Ok(r2) => {
-                            if r2.is_empty() {
-                                eprintln!("Server sent nothing after reconnect either");
-                            } else {
-                                print_result(&r2);
-                            }
-                        }
+                        Ok(Some(r2)) => print_result(&r2),
+                        Ok(None) => eprintln!("Server sent nothing after reconnect either"),
                         Err(e) => eprintln!("Error on resend after reconnect: {}", e),
                     }
                 }
                 Err(e) => eprintln!("Reconnect failed: {}", e),
             }
-        } else {
-            print_result(&result);
         }
 
         return Ok(());
@@ -91,7 +211,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Note: the server currently handles one request per connection and then closes it. To
     // keep the interactive UX "connect once then loop", we will reconnect transparently if
     // the server closes the connection after serving a request, and retry the current value once.
-    let mut stream_opt = match TcpStream::connect("127.0.0.1:8080").await {
+    let mut stream_opt = match TcpStream::connect(&addr).await {
         Ok(s) => Some(s),
         Err(e) => {
             eprintln!(
@@ -105,10 +225,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let stdin = io::BufReader::new(io::stdin());
     let mut lines = stdin.lines();
 
-    println!("Interactive mode. Connected to server at 127.0.0.1:8080 (or will connect on demand)");
+    println!("Interactive mode. Connected to server at {addr} (or will connect on demand)");
 
     loop {
-        println!("Enter a number to send to the server (or 'quit' to exit):");
+        println!(
+            "Enter a number to send to the server ('ping' to check the connection, 'quit' to exit):"
+        );
 
         let line = match lines.next_line().await? {
             Some(l) => l,
@@ -123,6 +245,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             continue;
         }
 
+        if trimmed.eq_ignore_ascii_case("ping") {
+            let result = match stream_opt.as_mut() {
+                Some(stream) => ping(stream).await,
+                None => match TcpStream::connect(&addr).await {
+                    Ok(s) => ping(stream_opt.insert(s)).await,
+                    Err(e) => Err(e.into()),
+                },
+            };
+            match result {
+                Ok((server_time, latency)) => {
+                    println!("Pong from {addr}: server time {server_time}us, latency {latency:?}")
+                }
+                Err(e) => {
+                    eprintln!("Ping failed: {}", e);
+                    stream_opt = None;
+                }
+            }
+            continue;
+        }
+
         let value = match trimmed.parse::<u32>() {
             Ok(v) => v,
             Err(e) => {
@@ -133,7 +275,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         // Ensure we have a connection. If not, try to connect now.
         if stream_opt.is_none() {
-            match TcpStream::connect("127.0.0.1:8080").await {
+            match TcpStream::connect(&addr).await {
                 Ok(s) => {
                     stream_opt = Some(s);
                     println!("Reconnected to server");
@@ -150,48 +292,44 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         if let Some(mut stream) = stream_opt.take() {
             // Try sending once
             match send_and_receive(&mut stream, value).await {
-                Ok(result) => {
-                    if result.is_empty() {
-                        // Server closed connection after serving the request. Try to reconnect and resend once.
-                        eprintln!(
-                            "Server closed the connection; attempting reconnect and resend once"
-                        );
-                        match TcpStream::connect("127.0.0.1:8080").await {
-                            Ok(mut new_stream) => match send_and_receive(&mut new_stream, value)
-                                .await
-                            {
-                                Ok(r2) => {
-                                    if r2.is_empty() {
-                                        eprintln!(
-                                            "Server closed connection immediately after reconnect; giving up on this value"
-                                        );
-                                        // Keep stream_opt as None; continue to next input
-                                        stream_opt = None;
-                                        continue;
-                                    } else {
-                                        print_result(&r2);
-                                        // keep the new connection for subsequent requests
-                                        stream_opt = Some(new_stream);
-                                        continue;
-                                    }
+                Ok(Some(result)) => {
+                    print_result(&result);
+                    // keep the current connection for further requests
+                    stream_opt = Some(stream);
+                    continue;
+                }
+                Ok(None) => {
+                    // Server closed connection after serving the request. Try to reconnect and resend once.
+                    eprintln!("Server closed the connection; attempting reconnect and resend once");
+                    match TcpStream::connect(&addr).await {
+                        Ok(mut new_stream) => {
+                            match send_and_receive(&mut new_stream, value).await {
+                                Ok(Some(r2)) => {
+                                    print_result(&r2);
+                                    // keep the new connection for subsequent requests
+                                    stream_opt = Some(new_stream);
+                                    continue;
+                                }
+                                Ok(None) => {
+                                    eprintln!(
+                                        "Server closed connection immediately after reconnect; giving up on this value"
+                                    );
+                                    // Keep stream_opt as None; continue to next input
+                                    stream_opt = None;
+                                    continue;
                                 }
                                 Err(e) => {
                                     eprintln!("Error after reconnect when sending value: {}", e);
                                     stream_opt = None;
                                     continue;
                                 }
-                            },
-                            Err(e) => {
-                                eprintln!("Reconnect failed: {}", e);
-                                stream_opt = None;
-                                continue;
                             }
                         }
-                    } else {
-                        print_result(&result);
-                        // keep the current connection for further requests
-                        stream_opt = Some(stream);
-                        continue;
+                        Err(e) => {
+                            eprintln!("Reconnect failed: {}", e);
+                            stream_opt = None;
+                            continue;
+                        }
                     }
                 }
                 Err(e) => {
@@ -206,3 +344,111 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(code: u8, message: &str) -> Vec<u8> {
+        [&[ERROR_RESPONSE_TAG][..], &[code], message.as_bytes()].concat()
+    }
+
+    #[test]
+    fn decode_error_frame_maps_every_server_code() {
+        let expected = [
+            (1, "malformed request"),
+            (2, "storage error"),
+            (3, "page error"),
+            (4, "request timed out"),
+            (5, "query failed"),
+            (6, "server is shutting down"),
+            (7, "request frame too large"),
+        ];
+        for (code, description) in expected {
+            assert_eq!(
+                decode_error_frame(&frame(code, "details")),
+                Some(format!("{description} (code {code}): details"))
+            );
+        }
+    }
+
+    #[test]
+    fn decode_error_frame_unknown_code_is_still_readable() {
+        assert_eq!(
+            decode_error_frame(&frame(200, "boom")),
+            Some("unknown error (code 200): boom".to_string())
+        );
+    }
+
+    #[test]
+    fn decode_pong_reads_server_timestamp() {
+        let frame = [&[PONG_RESPONSE_TAG][..], &42u64.to_le_bytes()].concat();
+
+        assert_eq!(decode_pong(&frame), Some(42));
+        assert_eq!(decode_pong(&[ROW_RESPONSE_TAG; 9]), None);
+    }
+
+    async fn read(bytes: &mut &[u8]) -> io::Result<Option<Vec<u8>>> {
+        read_frame(bytes, MAX_RESPONSE_FRAME_BYTES).await
+    }
+
+    #[tokio::test]
+    async fn read_frame_reads_whole_frames_in_order() {
+        let large = vec![0xAB; 4096];
+        let frames = [
+            frame_request(&large),
+            frame_request(&[]),
+            frame_request(b"x"),
+        ]
+        .concat();
+        let mut bytes: &[u8] = &frames;
+
+        assert_eq!(read(&mut bytes).await.unwrap(), Some(large));
+        assert_eq!(read(&mut bytes).await.unwrap(), Some(vec![]));
+        assert_eq!(read(&mut bytes).await.unwrap(), Some(b"x".to_vec()));
+        assert_eq!(read(&mut bytes).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn read_frame_length_above_max_is_rejected_without_reading_body() {
+        let mut bytes: &[u8] = &[0xFF, 0xFF, 0xFF, 0xFF, ROW_RESPONSE_TAG, 1, 2];
+
+        let err = read(&mut bytes).await.unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        // Nothing past the length prefix was consumed
+        assert_eq!(bytes.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn read_frame_length_equal_to_max_is_accepted() {
+        let frame = frame_request(&[ROW_RESPONSE_TAG, 7]);
+        let mut bytes: &[u8] = &frame;
+
+        let body = read_frame(&mut bytes, 2).await.unwrap();
+
+        assert_eq!(body, Some(vec![ROW_RESPONSE_TAG, 7]));
+    }
+
+    #[tokio::test]
+    async fn read_frame_truncated_body_is_an_error() {
+        let mut bytes: &[u8] = &[4, 0, 0, 0, 1, 2];
+
+        assert!(read(&mut bytes).await.is_err());
+    }
+
+    #[test]
+    fn decode_error_frame_ignores_rows() {
+        assert_eq!(decode_error_frame(&[ROW_RESPONSE_TAG; 100]), None);
+        assert_eq!(decode_error_frame(&[ERROR_RESPONSE_TAG]), None);
+    }
+
+    #[test]
+    fn row_starting_like_an_error_is_decoded_as_a_row() {
+        let response = [&[ROW_RESPONSE_TAG][..], b"ERR \x04timeout"].concat();
+
+        assert_eq!(decode_error_frame(&response), None);
+        assert_eq!(decode_pong(&response), None);
+        assert_eq!(decode_row(&response), Some(&b"ERR \x04timeout"[..]));
+    }
+}
@@ -0,0 +1,94 @@
+/// Reflected polynomial of the IEEE 802.3 CRC-32 (the one used by zlib, PNG, etc.).
+const POLYNOMIAL: u32 = 0xEDB8_8320;
+
+/// Lookup table for the byte-at-a-time CRC-32 computation, generated at compile time.
+const TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Incremental CRC-32 (IEEE) hasher.
+/// Useful when the checksummed data is not contiguous in memory.
+#[derive(Debug, Clone, Copy)]
+pub struct Crc32 {
+    state: u32,
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Crc32 {
+    /// Creates a new hasher with an empty state.
+    pub fn new() -> Self {
+        Self { state: u32::MAX }
+    }
+
+    /// Feeds the provided bytes into the hasher.
+    pub fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            let index = ((self.state ^ *byte as u32) & 0xFF) as usize;
+            self.state = (self.state >> 8) ^ TABLE[index];
+        }
+    }
+
+    /// Consumes the hasher and returns the checksum of all the bytes fed so far.
+    pub fn finalize(self) -> u32 {
+        !self.state
+    }
+}
+
+/// Computes the CRC-32 (IEEE) checksum of the provided bytes.
+///
+/// # Parameters
+/// - `bytes`: The data to checksum.
+///
+/// # Returns
+/// - The 32-bit checksum.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut hasher = Crc32::new();
+    hasher.update(bytes);
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod crc32_tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_known_vector() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_crc32_empty_input() {
+        assert_eq!(crc32(&[]), 0);
+    }
+
+    #[test]
+    fn test_crc32_incremental_matches_one_shot() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        let mut hasher = Crc32::new();
+        hasher.update(&data[..10]);
+        hasher.update(&data[10..]);
+        assert_eq!(hasher.finalize(), crc32(data));
+        assert_eq!(crc32(data), 0x414F_A339);
+    }
+}
@@ -120,6 +120,51 @@ pub fn write_le<T: LittleEndianInteger>(
     value.to_le(slice)
 }
 
+/// Reads a fixed-length run of bytes from a byte slice, without copying.
+///
+/// # Parameters
+/// - `bytes`: The input byte slice containing the data to be read.
+/// - `start_offset`: The starting position in the slice from which to read.
+/// - `len`: The number of bytes to read.
+///
+/// # Returns
+/// - `Ok(&[u8])`: The `len` bytes starting at `start_offset`.
+/// - `Err(BinaryError)`: If the range does not fit within the slice.
+pub fn read_bytes(bytes: &[u8], start_offset: usize, len: usize) -> Result<&[u8], BinaryError> {
+    start_offset
+        .checked_add(len)
+        .and_then(|end| bytes.get(start_offset..end))
+        .ok_or(BinaryError::BytesSliceSizeMismatch {
+            expected: len,
+            from_offset: start_offset,
+        })
+}
+
+/// Copies a run of bytes into a byte slice.
+///
+/// # Parameters
+/// - `bytes`: The output byte slice where the data will be written.
+/// - `start_offset`: The starting position in the slice to write to.
+/// - `src`: The bytes to write. All of them are written, or none.
+///
+/// # Returns
+/// - `Ok(())`: If the bytes were written.
+/// - `Err(BinaryError)`: If the range does not fit within the slice, in which case `bytes` is left untouched.
+pub fn write_bytes(bytes: &mut [u8], start_offset: usize, src: &[u8]) -> Result<(), BinaryError> {
+    let Some(slice) = start_offset
+        .checked_add(src.len())
+        .and_then(|end| bytes.get_mut(start_offset..end))
+    else {
+        return Err(BinaryError::BytesSliceSizeMismatch {
+            expected: src.len(),
+            from_offset: start_offset,
+        });
+    };
+
+    slice.copy_from_slice(src);
+    Ok(())
+}
+
 #[cfg(test)]
 mod write_le_test {
     use crate::bin_error::BinaryError;
@@ -299,3 +344,74 @@ mod to_le_tests {
         ));
     }
 }
+
+#[cfg(test)]
+mod bytes_tests {
+    use super::*;
+    use crate::bin_error::BinaryError;
+
+    #[test]
+    fn test_read_bytes_in_bounds() {
+        let bytes = [1u8, 2, 3, 4, 5, 6];
+        assert_eq!(read_bytes(&bytes, 2, 3).unwrap(), &[3, 4, 5]);
+        assert_eq!(read_bytes(&bytes, 0, 6).unwrap(), &bytes);
+        assert!(read_bytes(&bytes, 6, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_bytes_out_of_bounds() {
+        let bytes = [0u8; 6];
+        let err = read_bytes(&bytes, 4, 3).unwrap_err();
+        assert!(matches!(
+            err,
+            BinaryError::BytesSliceSizeMismatch {
+                expected: 3,
+                from_offset: 4
+            }
+        ));
+    }
+
+    #[test]
+    fn test_read_bytes_offset_overflow() {
+        let bytes = [0u8; 6];
+        let err = read_bytes(&bytes, usize::MAX, 2).unwrap_err();
+        assert!(matches!(
+            err,
+            BinaryError::BytesSliceSizeMismatch {
+                expected: 2,
+                from_offset: usize::MAX
+            }
+        ));
+    }
+
+    #[test]
+    fn test_write_bytes_in_bounds() {
+        let mut buf = [0u8; 20];
+        let key = [0xABu8; 16];
+        write_bytes(&mut buf, 2, &key).unwrap();
+        assert_eq!(&buf[2..18], &key);
+        assert_eq!(&buf[..2], &[0, 0]);
+        assert_eq!(&buf[18..], &[0, 0]);
+    }
+
+    #[test]
+    fn test_write_bytes_out_of_bounds_leaves_buffer() {
+        let mut buf = [0u8; 10];
+        let err = write_bytes(&mut buf, 4, &[0xAB; 8]).unwrap_err();
+        assert!(matches!(
+            err,
+            BinaryError::BytesSliceSizeMismatch {
+                expected: 8,
+                from_offset: 4
+            }
+        ));
+        assert_eq!(buf, [0u8; 10]);
+    }
+
+    #[test]
+    fn test_write_then_read_bytes_round_trip() {
+        let mut buf = [0u8; 32];
+        write_bytes(&mut buf, 8, b"fixed-width key!").unwrap();
+        assert_eq!(read_bytes(&buf, 8, 16).unwrap(), b"fixed-width key!");
+    }
+}
@@ -1,5 +1,6 @@
 //! Helpers for reading and writing binary data.
 
 pub mod bin_error;
+pub mod checksum;
 pub mod conversions;
 pub mod le;
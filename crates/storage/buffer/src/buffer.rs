@@ -1,15 +1,24 @@
 //! Provides the implementation for the main buffer leveraged by the engine
 
-use crate::errors::BufferError;
+use crate::errors::{BufferError, BufferOpError, WithPageId};
+use crate::eviction::{EvictionPolicy, EvictionPolicyKind};
 use crate::frame::{BufferFrame, FrameId};
 use crate::guards::{PageReadGuard, PageWriteGuard};
 use file::api::FileManager;
+use page::PAGE_SIZE;
+use page::page::api::Page;
 use page::page_id::PageId;
+use page::page_type::PageType;
 use std::collections::HashMap;
+use std::fs;
+use std::ops::Deref;
+use std::path::Path;
 use std::sync::atomic::Ordering;
-use std::sync::{Arc, Condvar, Mutex, RwLock, TryLockError};
+use std::sync::{Arc, Condvar, Mutex, RwLock, RwLockWriteGuard, TryLockError};
 #[cfg(test)]
 use std::sync::{Barrier, OnceLock};
+use std::thread;
+use std::thread::JoinHandle;
 
 /// The state of the page in the buffer
 #[derive(Debug)]
@@ -18,6 +27,21 @@ enum PageState {
     Loading,
     /// Page has been loaded and available at the provided FrameId
     Ready(FrameId),
+    /// Loading the page failed. The entry is already out of the map, its waiters give up with the error.
+    Failed(BufferOpError),
+}
+
+/// How much a page read from disk is trusted before it is handed out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoadMode {
+    /// The page is validated with `Page::validate` (header, checksum and row placement). A page failing
+    /// validation is not cached, and the read fails with `BufferError::InvalidPage`.
+    #[default]
+    Strict,
+    /// The raw bytes are handed out even if the page fails validation, so that repair or forensic tools
+    /// can inspect a page strict mode refuses. Such a page stays cached, and strict reads keep refusing
+    /// it until it is fixed.
+    Lenient,
 }
 
 /// An entry in the `PageId`->`FrameId` map.
@@ -35,6 +59,10 @@ pub struct BufferManager<F: FileManager> {
     file_manager: Arc<F>,
     page_map: RwLock<HashMap<PageId, Arc<PageEntry>>>,
     frames: Vec<BufferFrame>,
+    /// Picks the frame to evict when no frame is free.
+    eviction: Box<dyn EvictionPolicy + Send + Sync>,
+    /// Whether pages loaded from disk get their header upgraded, see `Page::upgrade_header`.
+    upgrade_headers: bool,
     #[cfg(test)]
     hooks: OnceLock<Arc<Barrier>>,
 }
@@ -44,6 +72,38 @@ impl<F: FileManager> BufferManager<F> {
     /// Allocates a predefined number of buffer frames.
     /// Sets up internal structures required for managing the pool.
     pub fn new(file_manager: Arc<F>, pool_size: usize) -> Self {
+        Self::with_header_upgrade(file_manager, pool_size, false)
+    }
+
+    /// Creates a new empty buffer manager, choosing whether pages are upgraded as they are loaded.
+    ///
+    /// # Params
+    /// - `pool_size`: the number of buffer frames
+    /// - `upgrade_headers`: whether pages written before the `checksum` and `live_row_count` header fields
+    ///   existed get them filled in on load. Upgraded pages are marked dirty, so the upgrade is persisted
+    ///   by the next flush.
+    pub fn with_header_upgrade(
+        file_manager: Arc<F>,
+        pool_size: usize,
+        upgrade_headers: bool,
+    ) -> Self {
+        let eviction = EvictionPolicyKind::default().build(pool_size);
+        Self::with_eviction_policy(file_manager, pool_size, upgrade_headers, eviction)
+    }
+
+    /// Creates a new empty buffer manager, choosing how victims are picked when the buffer is full.
+    ///
+    /// # Params
+    /// - `pool_size`: the number of buffer frames
+    /// - `upgrade_headers`: see `with_header_upgrade`
+    /// - `eviction`: the policy picking the frame to evict, built for `pool_size` frames. See
+    ///   `EvictionPolicyKind::build`.
+    pub fn with_eviction_policy(
+        file_manager: Arc<F>,
+        pool_size: usize,
+        upgrade_headers: bool,
+        eviction: Box<dyn EvictionPolicy + Send + Sync>,
+    ) -> Self {
         tracing::info!("Starting up buffer manager with {} frames", pool_size);
         let mut frames = Vec::with_capacity(pool_size);
         for _ in 0..pool_size {
@@ -53,6 +113,8 @@ impl<F: FileManager> BufferManager<F> {
             file_manager,
             frames,
             page_map: RwLock::new(HashMap::new()),
+            eviction,
+            upgrade_headers,
             #[cfg(test)]
             hooks: OnceLock::new(),
         }
@@ -66,20 +128,196 @@ impl<F: FileManager> BufferManager<F> {
     ///
     /// # Returns
     /// A `Result` where the `Ok` contains a `PageReadGuard`. A `PageReadGuard` encapsulates the latch
-    /// needed to access the underlying `&Page`.
+    /// needed to access the underlying `&Page`. Pages are loaded with `LoadMode::Strict`.
     pub fn read_page(&self, page_id: PageId) -> Result<PageReadGuard<'_>, BufferError> {
-        self.get_or_load_buffered_page(page_id, |s, fid| s.read_guard_from_frame(fid))
+        self.read_page_with_mode(page_id, LoadMode::Strict)
+    }
+
+    /// Retrieves a page like `read_page`, choosing how much its contents are trusted.
+    ///
+    /// # Params
+    /// - `page_id`: The identifier of the page to be retrieved.
+    /// - `mode`: `LoadMode::Lenient` hands out pages that fail validation, for repair or inspection.
+    ///
+    /// # Returns
+    /// The `PageReadGuard` of the page, or `BufferError::InvalidPage` if the page fails validation in
+    /// `LoadMode::Strict`.
+    pub fn read_page_with_mode(
+        &self,
+        page_id: PageId,
+        mode: LoadMode,
+    ) -> Result<PageReadGuard<'_>, BufferError> {
+        self.get_or_load_buffered_page(page_id, mode, |s, fid| s.read_guard_from_frame(fid))
+    }
+
+    /// Retrieves a page like `read_page`, making sure it is of the type the caller expects, e.g. so that a
+    /// page id reused by another kind of page is caught before a type-specific operation fails on it.
+    ///
+    /// # Params
+    /// - `page_id`: The identifier of the page to be retrieved.
+    /// - `expected`: The type the page must have.
+    ///
+    /// # Returns
+    /// The `PageReadGuard` of the page, `BufferError::PageTypeMismatch` if the page is of another type,
+    /// or any error of `read_page`.
+    pub fn read_page_typed(
+        &self,
+        page_id: PageId,
+        expected: PageType,
+    ) -> Result<PageReadGuard<'_>, BufferError> {
+        let guard = self.read_page(page_id)?;
+        // Strictly loaded pages were validated, so their page type is known
+        let actual = guard
+            .page_type()
+            .map_err(|_| BufferError::InvalidPage(page_id))?;
+        if actual != expected {
+            return Err(BufferError::PageTypeMismatch {
+                page_id,
+                expected,
+                actual,
+            });
+        }
+        Ok(guard)
     }
 
-    /// Write guard
+    /// Write guard. Pages are loaded with `LoadMode::Strict`.
     pub fn read_page_mut(&self, page_id: PageId) -> Result<PageWriteGuard<'_>, BufferError> {
-        self.get_or_load_buffered_page(page_id, |s, fid| s.write_guard_from_frame(fid))
+        self.get_or_load_buffered_page(page_id, LoadMode::Strict, |s, fid| {
+            s.write_guard_from_frame(fid)
+        })
+    }
+
+    /// Returns a copy of the bytes of a page, loading it from disk first if needed.
+    /// The read latch is only held while the bytes are copied, so writers are not blocked by whoever
+    /// works on the copy afterward.
+    ///
+    /// # Params
+    /// - `page_id`: The identifier of the page to be copied.
+    ///
+    /// # Returns
+    /// A consistent snapshot of the page, as of the moment it was latched.
+    pub fn read_page_copy(&self, page_id: PageId) -> Result<Box<[u8; PAGE_SIZE]>, BufferError> {
+        let guard = self.read_page(page_id)?;
+        Ok(Box::new(*guard.data()))
+    }
+
+    /// Tells whether a page is loaded in the buffer, without latching it or waiting for it to load.
+    ///
+    /// Never blocks: if the page map or the state of the page is being updated at that very moment, the
+    /// page is reported as not resident. The answer can be outdated as soon as it is returned, e.g. if the
+    /// page gets evicted.
+    ///
+    /// # Returns
+    /// `true` if the page is `Ready` in a frame, `false` if it is unknown, still loading, or failed to load.
+    pub fn is_resident(&self, page_id: PageId) -> bool {
+        let Ok(map) = self.page_map.try_read() else {
+            return false;
+        };
+        map.get(&page_id).is_some_and(|entry| {
+            entry
+                .state
+                .try_lock()
+                .is_ok_and(|state| matches!(*state, PageState::Ready(_)))
+        })
+    }
+
+    /// Starts loading the provided pages into the buffer in the background.
+    ///
+    /// A `Loading` entry is registered synchronously for every page that is not yet resident (or
+    /// being loaded by someone else), so any `read_page`/`read_page_mut` issued after this call
+    /// returns will wait on the entry's condition variable instead of reading the page from disk
+    /// a second time. The actual disk reads are then performed on a background thread, in the
+    /// order provided.
+    ///
+    /// A page that fails to load (e.g. `BufferFull`) has its entry removed, so a later request
+    /// will simply attempt the load again. Requests already waiting on the load get the error.
+    ///
+    /// # Params
+    /// - `page_ids`: the pages to load.
+    ///
+    /// # Returns
+    /// The `JoinHandle` of the background loader thread. It can be dropped if the caller does not
+    /// care about waiting for the prefetch to complete.
+    pub fn prefetch(self: &Arc<Self>, page_ids: &[PageId]) -> JoinHandle<()>
+    where
+        F: Send + Sync + 'static,
+    {
+        let to_load: Vec<(PageId, Arc<PageEntry>)> = page_ids
+            .iter()
+            .filter_map(|&page_id| match self.register_loading_entry(page_id) {
+                (entry, true) => Some((page_id, entry)),
+                (_, false) => None,
+            })
+            .collect();
+
+        let buffer = Arc::clone(self);
+        thread::spawn(move || {
+            for (page_id, entry) in to_load {
+                match buffer.load_into_free_frame(page_id, LoadMode::Strict) {
+                    Ok(frame_id) => Self::mark_ready(&entry, frame_id),
+                    Err(err) => {
+                        tracing::warn!("Prefetch of page {} failed: {:?}", page_id, err);
+                        buffer.fail_loading(page_id, &entry, err);
+                    }
+                }
+            }
+        })
+    }
+
+    /// Loads the provided pages into the buffer, returning once every load has completed.
+    /// Meant to be called on startup, before serving requests, so that hot pages do not have to be
+    /// read from disk by the first requests touching them.
+    ///
+    /// Pages are loaded through `prefetch`, so they go through the regular frame claiming and
+    /// eviction: listing more pages than the buffer holds evicts the earlier ones. A page that fails
+    /// to load is skipped.
+    ///
+    /// # Params
+    /// - `page_ids`: the pages to load, hottest last.
+    ///
+    /// # Returns
+    /// The number of listed pages resident in the buffer once the warm-up is done.
+    pub fn warm_up(self: &Arc<Self>, page_ids: &[PageId]) -> usize
+    where
+        F: Send + Sync + 'static,
+    {
+        if self.prefetch(page_ids).join().is_err() {
+            tracing::warn!("Warm-up loader thread panicked");
+        }
+
+        let resident = page_ids
+            .iter()
+            .filter(|page_id| self.is_resident(**page_id))
+            .count();
+        tracing::info!(
+            "Warmed up buffer with {} of {} pages",
+            resident,
+            page_ids.len()
+        );
+        resident
+    }
+
+    /// Loads the pages listed in a warm-up manifest into the buffer, see `warm_up`.
+    ///
+    /// The manifest lists one page per line, as `file_id:page_number`. Empty lines and lines starting
+    /// with `#` are ignored.
+    ///
+    /// # Returns
+    /// The number of listed pages resident in the buffer once the warm-up is done, or an error if the
+    /// manifest cannot be read or holds a malformed line. Nothing is loaded from a malformed manifest.
+    pub fn warm_up_from_manifest(self: &Arc<Self>, path: &Path) -> std::io::Result<usize>
+    where
+        F: Send + Sync + 'static,
+    {
+        let page_ids = parse_warm_up_manifest(&fs::read_to_string(path)?)?;
+        Ok(self.warm_up(&page_ids))
     }
 
     /// Finds a free frame and claims it for a new page with the given page ID.
+    /// When no frame is free, the page of another frame is evicted to make room. See `evict_frame`.
     ///
-    /// Note that the frame might contain either a zeroed-page or a previous page that was flushed.
-    /// Regardless, the caller is responsible for initializing the page and zero-ing it out.
+    /// The frame might have previously held another page, so its bytes are zeroed out before the
+    /// guard is handed out. The caller is still responsible for initializing the page.
     ///
     /// # Params
     /// - `page_id`: the ID for the new page.
@@ -88,28 +326,154 @@ impl<F: FileManager> BufferManager<F> {
     /// `PageWriteGuard` instance with write-access to the underlying page.
     pub fn allocate_new_page(&self, page_id: PageId) -> Result<PageWriteGuard<'_>, BufferError> {
         tracing::info!("Allocating new page {} for buffer manager", page_id);
-        let frame_id = self
-            .claim_free_frame(page_id)
-            .ok_or(BufferError::BufferFull)?;
+        // The page is latched before it is published in the map, so no reader can observe it before
+        // it has been wiped.
+        let (frame_id, page) = self.claim_frame(page_id, true).with_page_id(page_id)?;
+        let mut guard = self.write_guard_for_wipe(frame_id, page);
+        // Wiping goes through the inner guard, so an allocated but untouched page is not dirty.
+        Self::wipe_page(&mut guard.guard, page_id);
 
-        {
-            let mut map_guard = self.page_map.write().unwrap();
-            map_guard.insert(
-                page_id,
-                Arc::new(PageEntry {
-                    state: Mutex::new(PageState::Ready(frame_id)),
-                    cond_var: Condvar::new(),
-                }),
-            );
+        Ok(guard)
+    }
+
+    /// Replaces the contents of a page with bytes received from elsewhere, e.g. a page shipped by
+    /// `PageReadGuard::to_bytes` for replication or backup.
+    ///
+    /// The bytes are validated with `Page::try_from_bytes` before anything is touched. A resident page
+    /// is overwritten in its frame, otherwise a frame is claimed for it, without reading the page from
+    /// disk. Either way, the page is marked dirty so that the next flush persists it.
+    /// A resident page that failed validation, see `LoadMode::Lenient`, is repaired this way.
+    ///
+    /// # Params
+    /// - `page_id`: the page the bytes belong to.
+    /// - `bytes`: the contents of the page.
+    ///
+    /// # Returns
+    /// `BufferError::InvalidPage` if the bytes do not form a valid page for `page_id`, or any error of
+    /// `read_page_mut`, or `BufferError::BufferFull` if no frame can be claimed for the page.
+    pub fn install_page(
+        &self,
+        page_id: PageId,
+        bytes: Box<[u8; PAGE_SIZE]>,
+    ) -> Result<(), BufferError> {
+        let received =
+            Page::try_from_bytes(bytes, page_id).map_err(|_| BufferError::InvalidPage(page_id))?;
+
+        // Lets tests line up concurrent installs of the same page right before they race for it.
+        #[cfg(test)]
+        self.test_pause();
+
+        // Checking for residency and publishing the page happen under the same map lock, so two
+        // concurrent installs of a page can never both claim a frame for it.
+        let (entry, is_installer) = self.register_loading_entry(page_id);
+        let mut guard = if is_installer {
+            let (frame_id, page) = match self.claim_frame(page_id, false) {
+                Ok(claimed) => claimed,
+                Err(err) => {
+                    self.fail_loading(page_id, &entry, err);
+                    return Err(err).with_page_id(page_id);
+                }
+            };
+            let mut guard = self.write_guard_for_wipe(frame_id, page);
+            Self::wipe_page(&mut guard.guard, page_id);
+            // Waiters are woken up, but the page stays latched until the new contents are in.
+            Self::mark_ready(&entry, frame_id);
+            guard
+        } else {
+            // The current contents are replaced wholesale, so they do not need to be valid
+            self.get_or_load_buffered_page(page_id, LoadMode::Lenient, |s, fid| {
+                s.write_guard_from_frame(fid)
+            })?
+        };
+        // Goes through `DerefMut`, so the frame is marked dirty.
+        guard.data_mut().copy_from_slice(received.data());
+        guard.frame.unvalidated.store(false, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Writes the page to disk.
+    /// The page checksum is refreshed right before the write, so that the bytes on disk can be verified later on.
+    pub fn write_page(&self, page_id: PageId, mut page_guard: PageWriteGuard<'_>) {
+        self.write_latched_page(page_id, &mut page_guard.guard);
+        page_guard.frame.dirty.store(false, Ordering::Release);
+    }
+
+    /// Writes every dirty page in the buffer to disk, then syncs the files that were written to.
+    /// Meant to be called on shutdown, after all the users of the buffer are done.
+    ///
+    /// Each page is latched exclusively while being written, so this waits for any guard still held, and
+    /// for any write guard in the middle of a downgrade.
+    ///
+    /// # Returns
+    /// The number of pages written, or the first error returned by `FileManager::sync`.
+    pub fn flush_all(&self) -> std::io::Result<usize> {
+        let resident: Vec<(PageId, FrameId)> = {
+            let map = self.page_map.read().unwrap();
+            map.iter()
+                .filter_map(|(page_id, entry)| match *entry.state.lock().unwrap() {
+                    PageState::Ready(frame_id) => Some((*page_id, frame_id)),
+                    PageState::Loading | PageState::Failed(_) => None,
+                })
+                .collect()
+        };
+
+        let mut written_files = Vec::new();
+        let mut flushed = 0;
+        for (page_id, frame_id) in resident {
+            let frame = &self.frames[frame_id];
+            let Some(mut page) = Self::latch_for_flush(frame) else {
+                // Whoever poisoned the latch might have left the page half-modified, better not persist it
+                tracing::warn!(
+                    "Skipping flush of page {}: frame latch is poisoned",
+                    page_id
+                );
+                continue;
+            };
+            // The frame could have been handed over to another page since the map snapshot was taken
+            if page.page_id() != page_id || !frame.dirty.load(Ordering::Acquire) {
+                continue;
+            }
+
+            self.write_latched_page(page_id, &mut page);
+            frame.dirty.store(false, Ordering::Release);
+            flushed += 1;
+
+            if !written_files.contains(&page_id.file_id) {
+                written_files.push(page_id.file_id);
+            }
+        }
+
+        for file_id in written_files {
+            self.file_manager.sync(file_id)?;
         }
 
-        Ok(self.write_guard_from_frame(frame_id))
+        tracing::info!("Flushed {} dirty pages", flushed);
+        Ok(flushed)
     }
 
-    /// Writes the page to disk
-    pub fn write_page(&self, page_id: PageId, page_guard: PageWriteGuard<'_>) {
-        self.file_manager
-            .write_page(page_id, page_guard.guard.data())
+    /// Latches the page of a frame exclusively for `flush_all`.
+    /// A frame being downgraded is waited for, the same way `write_guard_from_frame` does: the latch is
+    /// handed over to the downgrading writer, so the page is never written while it is still changing.
+    ///
+    /// # Returns
+    /// The latch, or `None` if a thread panicked while holding it exclusively.
+    fn latch_for_flush(frame: &BufferFrame) -> Option<RwLockWriteGuard<'_, Page>> {
+        loop {
+            let page = frame.page.write().ok()?;
+            if !frame.downgrading.load(Ordering::Acquire) {
+                return Some(page);
+            }
+            drop(page);
+            thread::yield_now();
+        }
+    }
+
+    /// Refreshes the checksum of an already latched page and hands it over to the file manager.
+    fn write_latched_page(&self, page_id: PageId, page: &mut Page) {
+        page.update_checksum()
+            .expect("page header is always within bounds");
+        self.file_manager.write_page(page_id, page.data())
     }
 
     /// Shared helper that contains the common logic for loading or returning a page from the buffer.
@@ -118,96 +482,332 @@ impl<F: FileManager> BufferManager<F> {
     fn get_or_load_buffered_page<'a, Guard, MakeGuard>(
         &'a self,
         page_id: PageId,
+        mode: LoadMode,
         make_guard: MakeGuard,
     ) -> Result<Guard, BufferError>
     where
-        MakeGuard: Fn(&'a Self, FrameId) -> Guard,
-        Guard: 'a,
+        MakeGuard: Fn(&'a Self, FrameId) -> Result<Guard, BufferOpError>,
+        Guard: Deref<Target = Page> + 'a,
     {
-        // Check if there is a frame that holds this page
-        let possible_page_entry = {
-            let map_guard = self.page_map.read().unwrap();
-            map_guard.get(&page_id).cloned()
-        };
+        loop {
+            // Check if there is a frame that holds this page
+            let possible_page_entry = {
+                let map_guard = self.page_map.read().unwrap();
+                map_guard.get(&page_id).cloned()
+            };
+
+            // Happiest of flows - the page is already cached.
+            // Note that if the page is either being loaded right now by another thread, or if there is
+            // a write latch on the page, this will block.
+            if let Some(page_entry) = possible_page_entry {
+                let fid = Self::wait_until_ready(&page_entry).with_page_id(page_id)?;
+                if let Some(guard) = Self::guard_if_holds(make_guard(self, fid), page_id)? {
+                    return self.check_mode(fid, guard, mode).with_page_id(page_id);
+                }
+                continue;
+            }
+
+            // From this point, we only have logic for cache miss.
+
+            // A place to inject a barrier hook in testing. Not included in release builds.
+            // Pauses the execution of all threads on a `Barrier`, until all of them reach this place
+            // Used to test some race conditions.
+            #[cfg(test)]
+            self.test_pause();
+
+            let (entry, is_loader_thread) = self.register_loading_entry(page_id);
+
+            // Someone else is doing the work, just wait here until they are done
+            if !is_loader_thread {
+                let frame_id = Self::wait_until_ready(&entry).with_page_id(page_id)?;
+                if let Some(guard) = Self::guard_if_holds(make_guard(self, frame_id), page_id)? {
+                    return self.check_mode(frame_id, guard, mode).with_page_id(page_id);
+                }
+                continue;
+            }
+
+            // We gotta do the load from disk work ourselves.
+            let frame_id = match self.load_into_free_frame(page_id, mode) {
+                Ok(frame_id) => frame_id,
+                Err(err) => {
+                    // Waiters would otherwise block forever on an entry that never becomes `Ready`
+                    self.fail_loading(page_id, &entry, err);
+                    return Err(err).with_page_id(page_id);
+                }
+            };
 
-        // Happiest of flows - the page is already cached.
-        // Note that if the page is either being loaded right now by another thread, or if there is
-        // a write latch on the page, this will block.
-        if let Some(page_entry) = possible_page_entry {
-            let fid = Self::wait_until_ready(&page_entry);
-            return Ok(make_guard(self, fid));
+            // Frame is loaded with page contents, and the latch used to load it is released.
+            // First get a latch on the page to be able to return it. The entry is still `Loading`, so
+            // the frame cannot be evicted in the meantime, and waiters only latch the page once it is
+            // `Ready`, so the loader is the first to get it - even exclusively.
+            let guard = make_guard(self, frame_id);
+
+            // Set the entry to Ready (no need to add it in the map, already there) and wake up waiters.
+            Self::mark_ready(&entry, frame_id);
+
+            return guard.with_page_id(page_id);
         }
+    }
 
-        // From this point, we only have logic for cache miss.
+    /// Checks that a guard obtained from a map entry still latches the requested page.
+    /// The entry is looked up before the page is latched, so the frame might have been evicted and
+    /// handed over to another page in between.
+    ///
+    /// # Returns
+    /// The guard if the frame still holds `page_id`, or `None` if the lookup has to be retried.
+    fn guard_if_holds<Guard>(
+        guard: Result<Guard, BufferOpError>,
+        page_id: PageId,
+    ) -> Result<Option<Guard>, BufferError>
+    where
+        Guard: Deref<Target = Page>,
+    {
+        let guard = guard.with_page_id(page_id)?;
+        Ok((guard.page_id() == page_id).then_some(guard))
+    }
 
-        // A place to inject a barrier hook in testing. Not included in release builds.
-        // Pauses the execution of all threads on a `Barrier`, until all of them reach this place
-        // Used to test some race conditions.
-        #[cfg(test)]
-        self.test_pause();
+    /// Refuses to hand out a page loaded leniently to a strict caller, unless the page passes validation by now.
+    /// Lenient callers get the page as is.
+    fn check_mode<Guard>(
+        &self,
+        frame_id: FrameId,
+        guard: Guard,
+        mode: LoadMode,
+    ) -> Result<Guard, BufferOpError>
+    where
+        Guard: Deref<Target = Page>,
+    {
+        let frame = &self.frames[frame_id];
+        if mode == LoadMode::Lenient || !frame.unvalidated.load(Ordering::Acquire) {
+            return Ok(guard);
+        }
+        guard.validate().map_err(|_| BufferOpError::InvalidPage)?;
+        frame.unvalidated.store(false, Ordering::Release);
+        Ok(guard)
+    }
 
-        // First we have to lock the map again, this time for write, and check if no one added the entry
-        // in the meantime. This will only temporarily lock the entire map.
-        let (entry, is_loader_thread) = {
-            let mut map = self.page_map.write().unwrap();
+    /// Registers a `Loading` entry in the page map for the provided `PageId`, unless an entry
+    /// already exists.
+    ///
+    /// The map is locked for write, so only one thread can ever become the loader of a page.
+    /// The state mutex of the new entry is not locked - others will know to wait while the state
+    /// is `Loading`.
+    ///
+    /// # Returns
+    /// The entry for the page, along with a flag that is `true` if the caller is the loader (the
+    /// entry was inserted by this call), or `false` if someone else already registered it.
+    fn register_loading_entry(&self, page_id: PageId) -> (Arc<PageEntry>, bool) {
+        let mut map = self.page_map.write().unwrap();
+
+        if let Some(existing) = map.get(&page_id).cloned() {
+            // We did find it - means someone else is about to load it into memory right now,
+            // or it is already loaded.
+            return (existing, false);
+        }
 
-            if let Some(existing) = map.get(&page_id).cloned() {
-                // We did find it this time - means someone else is about to load it into memory right now
-                // We will declare ourselves as not_loaders, and only wait for the other thread to finish
-                (existing, false)
-            } else {
-                // No one else inserted it. We will insert it ourselves, and mark it as Loading,
-                // so others will know to wait in case they want this page.
-                // We will not lock this mutex though, as others will know to wait while state is Loading
-                let new_entry = Arc::new(PageEntry {
-                    state: Mutex::new(PageState::Loading),
-                    cond_var: Condvar::new(),
-                });
-                map.insert(page_id, new_entry.clone());
-                // Mark ourselves as loaders
-                (new_entry, true)
-            }
-        };
+        let new_entry = Arc::new(PageEntry {
+            state: Mutex::new(PageState::Loading),
+            cond_var: Condvar::new(),
+        });
+        map.insert(page_id, new_entry.clone());
+        (new_entry, true)
+    }
 
-        // Someone else is doing the work, just wait here until they are done
-        if !is_loader_thread {
-            let frame_id = Self::wait_until_ready(&entry);
-            return Ok(make_guard(self, frame_id));
+    /// Claims a frame and asks the file manager to read the page from disk into it.
+    /// On a failed read, the frame claim is rolled back. Either way, the `Loading` entry of the page is
+    /// left for the caller to resolve, see `mark_ready` and `fail_loading`.
+    ///
+    /// The frame's page latch is released before returning, so that the caller can latch the page again
+    /// in whichever mode it needs.
+    ///
+    /// The page is validated once loaded. In `LoadMode::Strict`, an invalid page is discarded like a failed
+    /// read, while in `LoadMode::Lenient` it is kept and its frame flagged, see `check_mode`.
+    ///
+    /// # Returns
+    /// The `FrameId` holding the freshly loaded page.
+    fn load_into_free_frame(
+        &self,
+        page_id: PageId,
+        mode: LoadMode,
+    ) -> Result<FrameId, BufferOpError> {
+        let (frame_id, mut page) = self.claim_frame(page_id, false)?;
+
+        // The frame might still hold the bytes of a page it served before. Wipe them, so a partial
+        // read cannot expose data belonging to another page.
+        Self::wipe_page(&mut page, page_id);
+
+        // Ask the file manager to load data from disk directly into the byte array of the page
+        // instance from the buffer frame
+        if !self.file_manager.read_page(page_id, page.data_mut()) {
+            // rollback claim. Whatever the failed read managed to copy into the frame is discarded as well.
+            Self::wipe_page(&mut page, PageId::new(0, 0));
+            *self.frames[frame_id].page_id.write().unwrap() = None;
+            return Err(BufferOpError::IoReadFailed);
         }
 
-        // We gotta do the load from disk work ourselves.
-        let frame_id = self
-            .claim_free_frame(page_id)
-            .ok_or(BufferError::BufferFull)?;
-        {
-            let mut page = self.frames[frame_id].page.write().unwrap();
+        // Also update the page's internal `page_id` field.
+        page.set_page_id(page_id);
+
+        if self.upgrade_headers {
+            match page.upgrade_header() {
+                Ok(true) => self.frames[frame_id].dirty.store(true, Ordering::Release),
+                Ok(false) => {}
+                Err(err) => tracing::warn!("Header upgrade of page {} failed: {}", page_id, err),
+            }
+        }
 
-            // Ask the file manager to load data from disk directly into the byte array of the page
-            // instance from the buffer frame
-            if !self.file_manager.read_page(page_id, page.data_mut()) {
-                // rollback claim and remove entry from map.
+        if let Err(err) = page.validate() {
+            tracing::warn!("Page {} failed validation: {}", page_id, err);
+            if mode == LoadMode::Strict {
+                Self::wipe_page(&mut page, PageId::new(0, 0));
+                self.frames[frame_id].dirty.store(false, Ordering::Release);
                 *self.frames[frame_id].page_id.write().unwrap() = None;
-                self.page_map.write().unwrap().remove(&page_id);
-                return Err(BufferError::IoReadFailed(page_id));
+                return Err(BufferOpError::InvalidPage);
+            }
+            self.frames[frame_id]
+                .unvalidated
+                .store(true, Ordering::Release);
+        }
+
+        // Still latched exclusively: building a write guard on the page while holding this would deadlock
+        drop(page);
+        Ok(frame_id)
+    }
+
+    /// Claims a frame for `for_page_id`, preferring a free one and evicting the page of another frame
+    /// otherwise. The page of the frame is returned latched, with whatever bytes it held before.
+    ///
+    /// # Params
+    /// - `for_page_id`: the ID of the page the frame is claimed for
+    /// - `publish`: whether to insert a `Ready` entry for `for_page_id` in the page map. Callers that
+    ///   already registered a `Loading` entry pass `false`.
+    ///
+    /// # Returns
+    /// The claimed `FrameId` and the latch on its page, or `BufferOpError::BufferFull` if every frame is
+    /// in use.
+    fn claim_frame(
+        &self,
+        for_page_id: PageId,
+        publish: bool,
+    ) -> Result<(FrameId, RwLockWriteGuard<'_, Page>), BufferOpError> {
+        if let Some(frame_id) = self.claim_free_frame(for_page_id) {
+            let page = Self::latch_for_wipe(&self.frames[frame_id]);
+            if publish {
+                self.page_map
+                    .write()
+                    .unwrap()
+                    .insert(for_page_id, Self::ready_entry(frame_id));
+            }
+            return Ok((frame_id, page));
+        }
+
+        self.evict_frame(for_page_id, publish)
+            .ok_or(BufferOpError::BufferFull)
+    }
+
+    /// Evicts the page held by one of the frames, and claims the frame for `for_page_id` instead.
+    ///
+    /// Victims are picked by the eviction policy, then probed without waiting on any latch. A victim is
+    /// only evicted if nobody holds a latch on its page and the page finished loading, otherwise the
+    /// policy is asked for another one.
+    /// A dirty victim is written to disk before the frame is reused.
+    /// The map entry of the victim is removed and, if `publish` is set, the entry of `for_page_id` is
+    /// inserted under the same map lock, so the frame is never seen as belonging to both pages.
+    ///
+    /// # Returns
+    /// The claimed `FrameId` and the latch on its page, or `None` if no frame could be evicted.
+    fn evict_frame(
+        &self,
+        for_page_id: PageId,
+        publish: bool,
+    ) -> Option<(FrameId, RwLockWriteGuard<'_, Page>)> {
+        let mut candidates: Vec<FrameId> = (0..self.frames.len()).collect();
+        while let Some(frame_id) = self.eviction.pick_victim(&candidates) {
+            candidates.retain(|&candidate| candidate != frame_id);
+            let frame = &self.frames[frame_id];
+
+            // Holding the frame's page ID latch keeps other evictions and claims away from the frame.
+            let Ok(mut claimed_by) = frame.page_id.try_write() else {
+                continue;
+            };
+            let Some(victim_id) = *claimed_by else {
+                continue;
+            };
+            // Pages latched by someone else are in use, poisoned ones are left for `flush_all` to skip.
+            let Ok(mut page) = frame.page.try_write() else {
+                continue;
+            };
+            // A page still being loaded is about to be handed out to its loader.
+            let is_ready = self.page_map.read().unwrap().get(&victim_id).is_some_and(
+                |entry| matches!(*entry.state.lock().unwrap(), PageState::Ready(fid) if fid == frame_id),
+            );
+            if !is_ready {
+                continue;
+            }
+
+            if frame.dirty.load(Ordering::Acquire) {
+                self.write_latched_page(victim_id, &mut page);
+            }
+
+            {
+                let mut map = self.page_map.write().unwrap();
+                map.remove(&victim_id);
+                if publish {
+                    map.insert(for_page_id, Self::ready_entry(frame_id));
+                }
             }
 
-            // Also update the page's internal `page_id` field.
-            page.set_page_id(page_id);
+            tracing::debug!(
+                "Evicted page {} to make room for {}",
+                victim_id,
+                for_page_id
+            );
+            *claimed_by = Some(for_page_id);
+            frame.pin_count.store(1, Ordering::Relaxed);
+            frame.dirty.store(false, Ordering::Relaxed);
+            frame.unvalidated.store(false, Ordering::Relaxed);
+            frame.write_epoch.fetch_add(1, Ordering::AcqRel);
+            frame.bump_version();
+            return Some((frame_id, page));
         }
+        None
+    }
+
+    /// Creates a page map entry for a page already loaded into `frame_id`.
+    fn ready_entry(frame_id: FrameId) -> Arc<PageEntry> {
+        Arc::new(PageEntry {
+            state: Mutex::new(PageState::Ready(frame_id)),
+            cond_var: Condvar::new(),
+        })
+    }
+
+    /// Zeroes out all the bytes of a frame's page and assigns it the provided `PageId`.
+    fn wipe_page(page: &mut Page, page_id: PageId) {
+        page.data_mut().fill(0);
+        page.set_page_id(page_id);
+    }
 
-        // Frame is loaded with page contents.
-        // First get a latch on the page to be able to return it.
-        let guard = make_guard(self, frame_id);
+    /// Locks the state mutex of the entry to set it to `Ready`, then notifies all waiters.
+    fn mark_ready(entry: &PageEntry, frame_id: FrameId) {
+        let mut st = entry.state.lock().unwrap();
+        *st = PageState::Ready(frame_id);
+        entry.cond_var.notify_all();
+    }
 
-        // Lock the state mutex to set it to Ready (no need to add it in the map, already there).
-        // Also notify all waiters that the condition has changed.
+    /// Gives up on loading a page: removes its `Loading` entry from the map, so that a later request
+    /// attempts the load again, then wakes up the waiters of the entry with `error`.
+    fn fail_loading(&self, page_id: PageId, entry: &Arc<PageEntry>, error: BufferOpError) {
         {
-            let mut st = entry.state.lock().unwrap();
-            *st = PageState::Ready(frame_id);
-            entry.cond_var.notify_all();
+            // Only drop our own entry, someone else might have registered a new one since.
+            let mut map = self.page_map.write().unwrap();
+            if map.get(&page_id).is_some_and(|e| Arc::ptr_eq(e, entry)) {
+                map.remove(&page_id);
+            }
         }
 
-        Ok(guard)
+        let mut st = entry.state.lock().unwrap();
+        *st = PageState::Failed(error);
+        entry.cond_var.notify_all();
     }
 
     /// Goes through the `frames` to find an empty one that can be used
@@ -232,6 +832,9 @@ impl<F: FileManager> BufferManager<F> {
                         *page_id = Some(for_page_id);
                         frame.pin_count.store(1, Ordering::Relaxed);
                         frame.dirty.store(false, Ordering::Relaxed);
+                        frame.unvalidated.store(false, Ordering::Relaxed);
+                        frame.write_epoch.fetch_add(1, Ordering::AcqRel);
+                        frame.bump_version();
                         return Some(frame_id);
                     }
                 }
@@ -249,24 +852,72 @@ impl<F: FileManager> BufferManager<F> {
     }
 
     /// Computes a `PageReadGuard` for a frame.
-    fn read_guard_from_frame(&self, frame_id: FrameId) -> PageReadGuard<'_> {
-        let guard = self.frames[frame_id].page.read().unwrap();
-        PageReadGuard { guard }
+    /// Fails if a thread panicked while holding the page latch exclusively.
+    fn read_guard_from_frame(&self, frame_id: FrameId) -> Result<PageReadGuard<'_>, BufferOpError> {
+        let frame = &self.frames[frame_id];
+        let guard = frame
+            .page
+            .read()
+            .map_err(|_| BufferOpError::FramePoisoned)?;
+        self.eviction.record_access(frame_id);
+        Ok(PageReadGuard { guard, frame })
     }
 
     /// Computes a `PageWriteGuard` for a frame.
-    fn write_guard_from_frame(&self, frame_id: FrameId) -> PageWriteGuard<'_> {
-        let guard = self.frames[frame_id].page.write().unwrap();
-        PageWriteGuard { guard }
+    /// Fails if a thread panicked while holding the page latch exclusively.
+    fn write_guard_from_frame(
+        &self,
+        frame_id: FrameId,
+    ) -> Result<PageWriteGuard<'_>, BufferOpError> {
+        let frame = &self.frames[frame_id];
+        let guard = loop {
+            let guard = frame
+                .page
+                .write()
+                .map_err(|_| BufferOpError::FramePoisoned)?;
+            // The latch is being handed over to a downgrading writer, let it take the read latch first.
+            if !frame.downgrading.load(Ordering::Acquire) {
+                break guard;
+            }
+            drop(guard);
+            thread::yield_now();
+        };
+        frame.write_epoch.fetch_add(1, Ordering::AcqRel);
+        self.eviction.record_access(frame_id);
+        Ok(PageWriteGuard { guard, frame })
+    }
+
+    /// Computes a `PageWriteGuard` for a frame whose contents are about to be wiped, from the latch
+    /// obtained when the frame was claimed. See `claim_frame`.
+    fn write_guard_for_wipe<'a>(
+        &'a self,
+        frame_id: FrameId,
+        guard: RwLockWriteGuard<'a, Page>,
+    ) -> PageWriteGuard<'a> {
+        let frame = &self.frames[frame_id];
+        frame.write_epoch.fetch_add(1, Ordering::AcqRel);
+        self.eviction.record_access(frame_id);
+        PageWriteGuard { guard, frame }
+    }
+
+    /// Latches the page of a frame exclusively, recovering the latch if it was poisoned.
+    /// Only meant for frames that were just claimed: their previous contents are discarded anyway,
+    /// so whatever state a panicking thread left them in does not matter.
+    fn latch_for_wipe(frame: &BufferFrame) -> RwLockWriteGuard<'_, Page> {
+        frame.page.write().unwrap_or_else(|poisoned| {
+            frame.page.clear_poison();
+            poisoned.into_inner()
+        })
     }
 
     /// Waits for the `Mutex` on a `PageEntry` to be free to access and the page is loaded into memory
-    /// (`PageState = Ready(FrameId)`)
-    fn wait_until_ready(entry: &Arc<PageEntry>) -> FrameId {
+    /// (`PageState = Ready(FrameId)`). Fails with the loader's error if the load failed.
+    fn wait_until_ready(entry: &Arc<PageEntry>) -> Result<FrameId, BufferOpError> {
         let mut state = entry.state.lock().unwrap();
         loop {
             match *state {
-                PageState::Ready(fid) => return fid,
+                PageState::Ready(fid) => return Ok(fid),
+                PageState::Failed(error) => return Err(error),
                 PageState::Loading => {
                     state = entry.cond_var.wait(state).unwrap();
                 }
@@ -292,21 +943,92 @@ impl<F: FileManager> BufferManager<F> {
     pub fn set_test_gate(&self, gate: Arc<Barrier>) {
         let _ = self.hooks.set(gate);
     }
+
+    /// Checks that `page_map` and `frames` agree with each other. Only meaningful while no other
+    /// thread is using the buffer.
+    /// - every entry is `Ready` and points to a frame claimed by the same page, whose page is
+    ///   tagged with that page ID
+    /// - no two entries point to the same frame
+    /// - every claimed frame is referenced by an entry
+    pub(crate) fn assert_consistent(&self) {
+        let map = self.page_map.read().unwrap();
+        let mut referenced = vec![false; self.frames.len()];
+
+        for (page_id, entry) in map.iter() {
+            let frame_id = match *entry.state.lock().unwrap() {
+                PageState::Ready(frame_id) => frame_id,
+                PageState::Loading => panic!("page {page_id} is still loading"),
+                PageState::Failed(_) => panic!("page {page_id} failed to load but is still mapped"),
+            };
+            assert!(
+                !referenced[frame_id],
+                "frame {frame_id} is referenced by more than one page"
+            );
+            referenced[frame_id] = true;
+
+            let frame = &self.frames[frame_id];
+            assert_eq!(*frame.page_id.read().unwrap(), Some(*page_id));
+            assert_eq!(frame.page.read().unwrap().page_id(), *page_id);
+        }
+
+        for (frame_id, frame) in self.frames.iter().enumerate() {
+            if let Some(page_id) = *frame.page_id.read().unwrap() {
+                assert!(
+                    referenced[frame_id],
+                    "frame {frame_id} is claimed by page {page_id}, which is missing from the page map"
+                );
+            }
+        }
+    }
 }
 
 // ------------------------------------------------------------------------------------------------
 
+/// Parses the contents of a warm-up manifest, see `BufferManager::warm_up_from_manifest`.
+fn parse_warm_up_manifest(text: &str) -> std::io::Result<Vec<PageId>> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| {
+            line.split_once(':')
+                .and_then(|(file_id, page_number)| {
+                    Some(PageId::new(
+                        file_id.trim().parse().ok()?,
+                        page_number.trim().parse().ok()?,
+                    ))
+                })
+                .ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!(
+                            "warm-up manifest line {}: expected `file_id:page_number`, got `{}`",
+                            index + 1,
+                            line
+                        ),
+                    )
+                })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::buffer::{BufferManager, PageEntry, PageState};
+    use crate::buffer::{BufferManager, LoadMode, PageEntry, PageState};
     use crate::errors::BufferError;
+    use crate::eviction::EvictionPolicyKind;
     use crate::frame::FrameId;
+    use crate::guards::set_upgrade_hook;
     use file::api::FileManager;
+    use file::errors::FileError;
     use file::file_catalog::FileCatalog;
-    use page::page_id::PageId;
+    use file::in_memory_file_manager::InMemoryFileManager;
+    use page::PAGE_SIZE;
+    use page::page::api::Page;
+    use page::page_id::{FileId, PageId};
     use page::page_type::PageType;
-    use std::sync::atomic::Ordering;
-    use std::sync::{Arc, Barrier, Condvar, Mutex, RwLock};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Barrier, Condvar, Mutex, RwLock, mpsc};
     use std::{
         thread,
         time::{Duration, Instant},
@@ -314,6 +1036,8 @@ mod tests {
 
     struct MockFileManager {
         requested_pages: RwLock<Vec<PageId>>,
+        written_pages: RwLock<Vec<PageId>>,
+        synced_files: RwLock<Vec<FileId>>,
         sleep_duration: RwLock<Duration>,
     }
 
@@ -321,18 +1045,39 @@ mod tests {
         fn new(_: Arc<FileCatalog>) -> Self {
             Self {
                 requested_pages: RwLock::new(Vec::new()),
+                written_pages: RwLock::new(Vec::new()),
+                synced_files: RwLock::new(Vec::new()),
                 sleep_duration: RwLock::new(Duration::from_millis(0)),
             }
         }
 
-        fn read_page(&self, page_id: PageId, _: &mut [u8]) -> bool {
+        /// Serves an empty heap page for every page ID
+        fn read_page(&self, page_id: PageId, destination: &mut [u8]) -> bool {
             let duration = self.sleep_duration.read().unwrap();
             thread::sleep(*duration);
             self.requested_pages.write().unwrap().push(page_id);
+            let mut page = Page::new_zeroed(page_id);
+            page.initialize(page_id, PageType::Unsorted).unwrap();
+            destination.copy_from_slice(page.data());
             true
         }
 
-        fn write_page(&self, _: PageId, _: &[u8]) {}
+        fn page_exists(&self, _: PageId) -> Result<bool, FileError> {
+            Ok(true)
+        }
+
+        fn write_page(&self, page_id: PageId, _: &[u8]) {
+            self.written_pages.write().unwrap().push(page_id);
+        }
+
+        fn allocate_page(&self, _: FileId) -> Result<PageId, FileError> {
+            unimplemented!("the buffer manager never allocates pages on disk")
+        }
+
+        fn sync(&self, file_id: FileId) -> std::io::Result<()> {
+            self.synced_files.write().unwrap().push(file_id);
+            Ok(())
+        }
     }
 
     impl MockFileManager {
@@ -605,31 +1350,254 @@ mod tests {
     }
 
     #[test]
-    fn read_page_file_manager_returns_error_frame_released() {
-        struct FailingFileManager;
-        impl FileManager for FailingFileManager {
-            fn new(_: Arc<FileCatalog>) -> Self {
-                Self
-            }
-
-            fn read_page(&self, _: PageId, _: &mut [u8]) -> bool {
-                false
-            }
-
-            fn write_page(&self, _: PageId, _: &[u8]) {}
-        }
+    fn read_page_mut_cold_page_does_not_deadlock() {
+        let buffer = Arc::new(create_buffer_manager(2));
         let page_id = PageId::new(1, 1);
+        let (tx, rx) = mpsc::channel();
 
-        let buffer = BufferManager::new(
-            Arc::new(FailingFileManager::new(Arc::new(FileCatalog::new()))),
-            10,
-        );
+        let thread_buffer = buffer.clone();
+        thread::spawn(move || {
+            let mut guard = thread_buffer.read_page_mut(page_id).unwrap();
+            guard.data_mut()[200] = 7;
+            tx.send(guard.page_id()).unwrap();
+        });
 
-        let result = buffer.read_page(page_id).unwrap_err();
+        let loaded = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("cold read_page_mut deadlocked");
+        assert_eq!(loaded, page_id);
+        assert_eq!(buffer.read_page(page_id).unwrap().data()[200], 7);
+        assert!(buffer.frames[0].dirty.load(Ordering::Relaxed));
+    }
 
-        assert!(matches!(result, BufferError::IoReadFailed(pageid) if pageid == page_id));
-        assert!(
-            buffer
+    #[test]
+    fn read_page_mut_cold_page_racing_loader_and_waiter_both_get_latch() {
+        let buffer = Arc::new(create_buffer_manager(2));
+        buffer
+            .file_manager
+            .set_sleep_duration(Duration::from_millis(100));
+        buffer.set_test_gate(Arc::new(Barrier::new(2)));
+        let page_id = PageId::new(1, 1);
+        let (tx, rx) = mpsc::channel();
+
+        // Both threads miss at once: one loads the page, the other waits for it to be `Ready`
+        for _ in 0..2 {
+            let tx = tx.clone();
+            let thread_buffer = buffer.clone();
+            thread::spawn(move || {
+                let mut guard = thread_buffer.read_page_mut(page_id).unwrap();
+                guard.data_mut()[200] += 1;
+                tx.send(()).unwrap();
+            });
+        }
+
+        for _ in 0..2 {
+            rx.recv_timeout(Duration::from_secs(5))
+                .expect("cold read_page_mut deadlocked");
+        }
+        assert_eq!(buffer.read_page(page_id).unwrap().data()[200], 2);
+        assert_eq!(buffer.file_manager.requested_pages.read().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn prefetch_racing_read_page_reads_from_disk_only_once() {
+        let buffer = Arc::new(create_buffer_manager(4));
+        buffer
+            .file_manager
+            .set_sleep_duration(Duration::from_millis(100));
+        let first_page = PageId::new(1, 1);
+        let second_page = PageId::new(1, 2);
+
+        let prefetch_handle = buffer.prefetch(&[first_page, second_page]);
+
+        let racing_buffer = buffer.clone();
+        let racing_handle =
+            thread::spawn(move || racing_buffer.read_page(second_page).unwrap().page_id());
+
+        assert_eq!(buffer.read_page(first_page).unwrap().page_id(), first_page);
+        assert_eq!(racing_handle.join().unwrap(), second_page);
+        prefetch_handle.join().unwrap();
+
+        let requested = buffer.file_manager.requested_pages.read().unwrap();
+        assert_eq!(requested.iter().filter(|p| **p == first_page).count(), 1);
+        assert_eq!(requested.iter().filter(|p| **p == second_page).count(), 1);
+        assert_eq!(buffer.page_map.read().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn prefetch_already_resident_page_is_not_read_again() {
+        let buffer = Arc::new(create_buffer_manager(4));
+        let page_id = PageId::new(1, 1);
+        drop(buffer.read_page(page_id).unwrap());
+
+        buffer.prefetch(&[page_id]).join().unwrap();
+
+        assert_eq!(buffer.file_manager.requested_pages.read().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn is_resident_loaded_page_returns_true() {
+        let buffer = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+        drop(buffer.read_page(page_id).unwrap());
+
+        assert!(buffer.is_resident(page_id));
+    }
+
+    #[test]
+    fn is_resident_unknown_page_returns_false() {
+        let buffer = create_buffer_manager(2);
+        drop(buffer.read_page(PageId::new(1, 1)).unwrap());
+
+        assert!(!buffer.is_resident(PageId::new(1, 2)));
+    }
+
+    #[test]
+    fn is_resident_loading_page_returns_false() {
+        let buffer = Arc::new(create_buffer_manager(2));
+        buffer
+            .file_manager
+            .set_sleep_duration(Duration::from_millis(100));
+        let page_id = PageId::new(1, 1);
+
+        let handle = buffer.prefetch(&[page_id]);
+
+        assert!(!buffer.is_resident(page_id));
+        handle.join().unwrap();
+        assert!(buffer.is_resident(page_id));
+    }
+
+    #[test]
+    fn is_resident_does_not_wait_for_latches() {
+        let buffer = Arc::new(create_buffer_manager(2));
+        let page_id = PageId::new(1, 1);
+        let write_guard = buffer.read_page_mut(page_id).unwrap();
+        let entry = buffer
+            .page_map
+            .read()
+            .unwrap()
+            .get(&page_id)
+            .cloned()
+            .unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let checker = buffer.clone();
+        thread::spawn(move || {
+            let latched = checker.is_resident(page_id);
+            let state_locked = {
+                let _state = entry.state.lock().unwrap();
+                checker.is_resident(page_id)
+            };
+            tx.send((latched, state_locked)).unwrap();
+        });
+
+        let (latched, state_locked) = rx
+            .recv_timeout(Duration::from_secs(1))
+            .expect("is_resident blocked");
+        assert!(latched);
+        assert!(!state_locked);
+        drop(write_guard);
+    }
+
+    #[test]
+    fn warm_up_listed_pages_are_served_without_disk_reads() {
+        let buffer = Arc::new(create_buffer_manager(4));
+        let page_ids = [PageId::new(1, 1), PageId::new(1, 2), PageId::new(2, 7)];
+
+        assert_eq!(buffer.warm_up(&page_ids), 3);
+        assert_eq!(buffer.file_manager.requested_pages.read().unwrap().len(), 3);
+
+        for page_id in page_ids {
+            assert_eq!(buffer.read_page(page_id).unwrap().page_id(), page_id);
+        }
+        assert_eq!(buffer.file_manager.requested_pages.read().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn warm_up_more_pages_than_frames_keeps_buffer_within_capacity() {
+        let buffer = Arc::new(create_buffer_manager(2));
+        let page_ids = [PageId::new(1, 1), PageId::new(1, 2), PageId::new(1, 3)];
+
+        assert_eq!(buffer.warm_up(&page_ids), 2);
+
+        assert_eq!(buffer.page_map.read().unwrap().len(), 2);
+        drop(buffer.read_page(PageId::new(1, 3)).unwrap());
+        assert_eq!(buffer.file_manager.requested_pages.read().unwrap().len(), 3);
+        buffer.assert_consistent();
+    }
+
+    #[test]
+    fn warm_up_from_manifest_loads_listed_pages() {
+        let buffer = Arc::new(create_buffer_manager(4));
+        let path = std::env::temp_dir().join(format!("trdb-warm-up-{}.txt", std::process::id()));
+        std::fs::write(&path, "# hot pages\n1:1\n\n 2:7 \n").unwrap();
+
+        let resident = buffer.warm_up_from_manifest(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(resident.unwrap(), 2);
+        assert_eq!(
+            *buffer.file_manager.requested_pages.read().unwrap(),
+            vec![PageId::new(1, 1), PageId::new(2, 7)]
+        );
+    }
+
+    #[test]
+    fn warm_up_from_manifest_malformed_line_loads_nothing() {
+        let buffer = Arc::new(create_buffer_manager(4));
+        let path =
+            std::env::temp_dir().join(format!("trdb-warm-up-bad-{}.txt", std::process::id()));
+        std::fs::write(&path, "1:1\n1-2\n").unwrap();
+
+        let err = buffer.warm_up_from_manifest(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("line 2"));
+        assert!(
+            buffer
+                .file_manager
+                .requested_pages
+                .read()
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn read_page_file_manager_returns_error_frame_released() {
+        struct FailingFileManager;
+        impl FileManager for FailingFileManager {
+            fn new(_: Arc<FileCatalog>) -> Self {
+                Self
+            }
+
+            fn read_page(&self, _: PageId, _: &mut [u8]) -> bool {
+                false
+            }
+
+            fn page_exists(&self, _: PageId) -> Result<bool, FileError> {
+                unimplemented!()
+            }
+
+            fn write_page(&self, _: PageId, _: &[u8]) {}
+
+            fn allocate_page(&self, _: FileId) -> Result<PageId, FileError> {
+                unimplemented!()
+            }
+        }
+        let page_id = PageId::new(1, 1);
+
+        let buffer = BufferManager::new(
+            Arc::new(FailingFileManager::new(Arc::new(FileCatalog::new()))),
+            10,
+        );
+
+        let result = buffer.read_page(page_id).unwrap_err();
+
+        assert!(matches!(result, BufferError::IoReadFailed(pageid) if pageid == page_id));
+        assert!(
+            buffer
                 .frames
                 .iter()
                 .all(|f| f.page_id.read().unwrap().is_none())
@@ -637,6 +1605,509 @@ mod tests {
         assert_eq!(buffer.page_map.read().unwrap().len(), 0)
     }
 
+    /// Stores a page with two rows, as written before the `checksum` and `live_row_count` header fields
+    /// existed.
+    fn in_memory_file_with_old_format_page(page_id: PageId) -> Arc<InMemoryFileManager> {
+        let mut page = Page::new_zeroed(page_id);
+        page.initialize(page_id, PageType::Unsorted).unwrap();
+        for row in [vec![1; 10], vec![2; 20]] {
+            let plan = page.plan_insert(row.len()).unwrap();
+            page.insert_heap(plan, row).unwrap();
+        }
+        // live_row_count lives at offset 38
+        page.data_mut()[38..40].fill(0);
+
+        let fm = Arc::new(InMemoryFileManager::new(Arc::new(FileCatalog::new())));
+        fm.write_page(page_id, page.data());
+        fm
+    }
+
+    #[test]
+    fn read_page_header_upgrade_enabled_upgrades_old_format_page() {
+        let page_id = PageId::new(1, 0);
+        let fm = in_memory_file_with_old_format_page(page_id);
+        let buffer = BufferManager::with_header_upgrade(fm, 2, true);
+
+        let guard = buffer.read_page(page_id).unwrap();
+
+        assert_eq!(guard.live_row_count().unwrap(), 2);
+        assert_ne!(guard.compute_checksum(), 0);
+        guard.verify_checksum().unwrap();
+        assert!(buffer.frames[0].dirty.load(Ordering::Acquire));
+    }
+
+    /// A page whose checksum no longer matches its rows, as left by a torn write
+    fn in_memory_file_with_corrupt_page(page_id: PageId) -> (Arc<InMemoryFileManager>, Page) {
+        let mut page = Page::new_zeroed(page_id);
+        page.initialize(page_id, PageType::Unsorted).unwrap();
+        let plan = page.plan_insert(10).unwrap();
+        page.insert_heap(plan, vec![1; 10]).unwrap();
+        page.update_checksum().unwrap();
+        let valid = Page::try_from_bytes(Box::new(*page.data()), page_id).unwrap();
+        page.row_mut(0).unwrap()[0] = 0xFF;
+
+        let fm = Arc::new(InMemoryFileManager::new(Arc::new(FileCatalog::new())));
+        fm.write_page(page_id, page.data());
+        (fm, valid)
+    }
+
+    fn in_memory_file_with_page(page_id: PageId, page_type: PageType) -> Arc<InMemoryFileManager> {
+        let mut page = Page::new_zeroed(page_id);
+        page.initialize(page_id, page_type).unwrap();
+
+        let fm = Arc::new(InMemoryFileManager::new(Arc::new(FileCatalog::new())));
+        fm.write_page(page_id, page.data());
+        fm
+    }
+
+    #[test]
+    fn read_page_typed_matching_type_returns_page() {
+        let page_id = PageId::new(1, 1);
+        let buffer = BufferManager::new(in_memory_file_with_page(page_id, PageType::IndexLeaf), 2);
+
+        let guard = buffer
+            .read_page_typed(page_id, PageType::IndexLeaf)
+            .unwrap();
+
+        assert_eq!(guard.page_id(), page_id);
+    }
+
+    #[test]
+    fn read_page_typed_index_page_requested_as_heap_returns_mismatch() {
+        let page_id = PageId::new(1, 1);
+        let buffer = BufferManager::new(in_memory_file_with_page(page_id, PageType::IndexLeaf), 2);
+
+        let result = buffer.read_page_typed(page_id, PageType::Unsorted);
+
+        assert!(matches!(
+            result,
+            Err(BufferError::PageTypeMismatch {
+                page_id: pid,
+                expected: PageType::Unsorted,
+                actual: PageType::IndexLeaf,
+            }) if pid == page_id
+        ));
+        // The page stays cached and unpinned for callers expecting the right type
+        assert!(buffer.is_resident(page_id));
+        assert!(buffer.read_page_mut(page_id).is_ok());
+    }
+
+    #[test]
+    fn read_page_with_mode_strict_corrupt_page_returns_error_and_releases_frame() {
+        let page_id = PageId::new(1, 0);
+        let (fm, _) = in_memory_file_with_corrupt_page(page_id);
+        let buffer = BufferManager::new(fm, 2);
+
+        let result = buffer.read_page_with_mode(page_id, LoadMode::Strict);
+
+        assert!(matches!(result, Err(BufferError::InvalidPage(pid)) if pid == page_id));
+        assert!(!buffer.is_resident(page_id));
+        assert!(
+            buffer
+                .frames
+                .iter()
+                .all(|f| f.page_id.read().unwrap().is_none())
+        );
+        buffer.assert_consistent();
+    }
+
+    #[test]
+    fn read_page_with_mode_lenient_corrupt_page_returns_raw_bytes() {
+        let page_id = PageId::new(1, 0);
+        let (fm, _) = in_memory_file_with_corrupt_page(page_id);
+        let buffer = BufferManager::new(fm.clone(), 2);
+
+        let guard = buffer
+            .read_page_with_mode(page_id, LoadMode::Lenient)
+            .unwrap();
+
+        let mut on_disk = [0u8; PAGE_SIZE];
+        assert!(fm.read_page(page_id, &mut on_disk));
+        assert_eq!(guard.data(), &on_disk);
+        assert!(guard.validate().is_err());
+    }
+
+    #[test]
+    fn read_page_corrupt_page_loaded_leniently_is_refused_until_fixed() {
+        let page_id = PageId::new(1, 0);
+        let (fm, valid) = in_memory_file_with_corrupt_page(page_id);
+        let buffer = BufferManager::new(fm, 2);
+        drop(
+            buffer
+                .read_page_with_mode(page_id, LoadMode::Lenient)
+                .unwrap(),
+        );
+
+        assert!(matches!(
+            buffer.read_page(page_id),
+            Err(BufferError::InvalidPage(pid)) if pid == page_id
+        ));
+        assert!(matches!(
+            buffer.read_page_mut(page_id),
+            Err(BufferError::InvalidPage(pid)) if pid == page_id
+        ));
+
+        buffer
+            .install_page(page_id, Box::new(*valid.data()))
+            .unwrap();
+
+        assert_eq!(buffer.read_page(page_id).unwrap().row(0).unwrap(), &[1; 10]);
+    }
+
+    #[test]
+    fn read_page_with_mode_valid_page_loads_in_both_modes() {
+        for mode in [LoadMode::Strict, LoadMode::Lenient] {
+            let buffer = create_buffer_manager(2);
+            let page_id = PageId::new(1, 1);
+
+            let guard = buffer.read_page_with_mode(page_id, mode).unwrap();
+
+            assert_eq!(guard.page_id(), page_id);
+            assert_eq!(guard.slot_count().unwrap(), 0);
+        }
+    }
+
+    #[test]
+    fn read_page_header_upgrade_disabled_leaves_page_untouched() {
+        let page_id = PageId::new(1, 0);
+        let fm = in_memory_file_with_old_format_page(page_id);
+        let buffer = BufferManager::new(fm, 2);
+
+        let guard = buffer.read_page(page_id).unwrap();
+
+        assert_eq!(guard.live_row_count().unwrap(), 0);
+        assert!(!buffer.frames[0].dirty.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn read_page_buffer_full_error_contains_requested_page_id() {
+        let buffer = create_buffer_manager(1);
+        let _first = buffer.read_page(PageId::new(1, 1)).unwrap();
+
+        let requested = PageId::new(1, 2);
+        let result = buffer.read_page(requested).unwrap_err();
+
+        assert!(matches!(result, BufferError::BufferFull(pid) if pid == requested));
+    }
+
+    #[test]
+    fn allocate_new_page_buffer_full_error_contains_requested_page_id() {
+        let buffer = create_buffer_manager(1);
+        let _first = buffer.allocate_new_page(PageId::new(1, 1)).unwrap();
+
+        let requested = PageId::new(1, 2);
+        let result = buffer.allocate_new_page(requested).unwrap_err();
+
+        assert!(matches!(result, BufferError::BufferFull(pid) if pid == requested));
+    }
+
+    #[test]
+    fn allocate_new_page_full_pool_evicts_and_succeeds() {
+        let buffer = create_buffer_manager(2);
+        let (first, second, allocated) = (PageId::new(1, 1), PageId::new(1, 2), PageId::new(2, 1));
+        drop(buffer.read_page(first).unwrap());
+        drop(buffer.read_page(second).unwrap());
+
+        let guard = buffer.allocate_new_page(allocated).unwrap();
+
+        assert_eq!(guard.page_id(), allocated);
+        drop(guard);
+        let map = buffer.page_map.read().unwrap();
+        assert_eq!(map.len(), 2);
+        assert!(map.contains_key(&allocated));
+        assert!(map.contains_key(&first) != map.contains_key(&second));
+        drop(map);
+        buffer.assert_consistent();
+    }
+
+    #[test]
+    fn allocate_new_page_dirty_victim_written_before_reuse() {
+        let buffer = create_buffer_manager(1);
+        let (victim, allocated) = (PageId::new(1, 1), PageId::new(2, 1));
+        buffer.read_page_mut(victim).unwrap().data_mut()[200] = 0xAB;
+
+        let guard = buffer.allocate_new_page(allocated).unwrap();
+
+        assert_eq!(
+            *buffer.file_manager.written_pages.read().unwrap(),
+            vec![victim]
+        );
+        assert!(guard.data().iter().all(|b| *b == 0));
+        assert!(!buffer.frames[0].dirty.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn allocate_new_page_clean_victim_not_written() {
+        let buffer = create_buffer_manager(1);
+        drop(buffer.read_page(PageId::new(1, 1)).unwrap());
+
+        drop(buffer.allocate_new_page(PageId::new(2, 1)).unwrap());
+
+        assert!(buffer.file_manager.written_pages.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn read_page_full_pool_evicted_page_reloaded_from_disk() {
+        let buffer = create_buffer_manager(1);
+        let (first, second) = (PageId::new(1, 1), PageId::new(1, 2));
+
+        drop(buffer.read_page(first).unwrap());
+        drop(buffer.read_page(second).unwrap());
+        let guard = buffer.read_page(first).unwrap();
+
+        assert_eq!(guard.page_id(), first);
+        assert_eq!(
+            *buffer.file_manager.requested_pages.read().unwrap(),
+            vec![first, second, first]
+        );
+    }
+
+    /// Loads pages 1 and 2 into a two-frame buffer, touches page 1 again, then loads page 3.
+    /// Returns the page evicted to make room for page 3.
+    fn evicted_for_third_page(kind: EvictionPolicyKind) -> PageId {
+        let fm = Arc::new(MockFileManager::new(Arc::new(FileCatalog::new())));
+        let buffer = BufferManager::with_eviction_policy(fm, 2, false, kind.build(2));
+        let pages = [1, 2, 3].map(|n| PageId::new(1, n));
+
+        for page_id in [pages[0], pages[1], pages[0], pages[2]] {
+            drop(buffer.read_page(page_id).unwrap());
+        }
+
+        let map = buffer.page_map.read().unwrap();
+        assert_eq!(map.len(), 2);
+        assert!(map.contains_key(&pages[2]));
+        *pages[..2].iter().find(|p| !map.contains_key(p)).unwrap()
+    }
+
+    #[test]
+    fn read_page_full_pool_lru_evicts_least_recently_used_page() {
+        assert_eq!(
+            evicted_for_third_page(EvictionPolicyKind::Lru),
+            PageId::new(1, 2)
+        );
+    }
+
+    #[test]
+    fn read_page_full_pool_clock_evicts_page_under_hand() {
+        // Both frames are referenced, so the hand clears them and comes back to the first one
+        assert_eq!(
+            evicted_for_third_page(EvictionPolicyKind::Clock),
+            PageId::new(1, 1)
+        );
+    }
+
+    #[test]
+    fn allocate_new_page_loading_page_not_evicted() {
+        let buffer = create_buffer_manager(1);
+        let loading = PageId::new(1, 1);
+        // Simulate a load in progress: the frame is claimed, but the entry is not ready yet.
+        *buffer.frames[0].page_id.write().unwrap() = Some(loading);
+        buffer.register_loading_entry(loading);
+
+        let requested = PageId::new(2, 1);
+        let result = buffer.allocate_new_page(requested).unwrap_err();
+
+        assert!(matches!(result, BufferError::BufferFull(pid) if pid == requested));
+    }
+
+    /// Fails the first read, after a delay leaving time for other requests to wait on the load.
+    /// Every subsequent read succeeds.
+    #[derive(Default)]
+    struct FailFirstReadFileManager {
+        reads: AtomicUsize,
+    }
+
+    impl FileManager for FailFirstReadFileManager {
+        fn new(_: Arc<FileCatalog>) -> Self {
+            Self::default()
+        }
+
+        fn read_page(&self, page_id: PageId, destination: &mut [u8]) -> bool {
+            if self.reads.fetch_add(1, Ordering::Relaxed) == 0 {
+                thread::sleep(Duration::from_millis(100));
+                return false;
+            }
+            let mut page = Page::new_zeroed(page_id);
+            page.initialize(page_id, PageType::Unsorted).unwrap();
+            destination.copy_from_slice(page.data());
+            true
+        }
+
+        fn page_exists(&self, _: PageId) -> Result<bool, FileError> {
+            unimplemented!()
+        }
+
+        fn write_page(&self, _: PageId, _: &[u8]) {}
+
+        fn allocate_page(&self, _: FileId) -> Result<PageId, FileError> {
+            unimplemented!()
+        }
+    }
+
+    /// Runs `read_page` on another thread, failing the test if it does not return within a few seconds
+    fn read_page_in_thread<F: FileManager + Send + Sync + 'static>(
+        buffer: &Arc<BufferManager<F>>,
+        page_id: PageId,
+    ) -> mpsc::Receiver<Result<PageId, BufferError>> {
+        let (tx, rx) = mpsc::channel();
+        let buffer = buffer.clone();
+        thread::spawn(move || {
+            let _ = tx.send(buffer.read_page(page_id).map(|guard| guard.page_id()));
+        });
+        rx
+    }
+
+    #[test]
+    fn read_page_failed_load_wakes_waiter_with_error() {
+        let buffer = Arc::new(BufferManager::new(
+            Arc::new(FailFirstReadFileManager::default()),
+            2,
+        ));
+        let page_id = PageId::new(1, 1);
+
+        let loader = read_page_in_thread(&buffer, page_id);
+        // Only start waiting once the loader registered the entry, so the second request waits on it
+        while !buffer.page_map.read().unwrap().contains_key(&page_id) {
+            thread::yield_now();
+        }
+        let waiter = read_page_in_thread(&buffer, page_id);
+
+        let timeout = Duration::from_secs(5);
+        for rx in [loader, waiter] {
+            let result = rx.recv_timeout(timeout).expect("read_page hung");
+            assert!(matches!(result, Err(BufferError::IoReadFailed(pid)) if pid == page_id));
+        }
+        assert_eq!(buffer.file_manager.reads.load(Ordering::Relaxed), 1);
+        assert!(buffer.page_map.read().unwrap().is_empty());
+
+        // The failed entry is gone, so the next request loads the page again
+        assert_eq!(buffer.read_page(page_id).unwrap().page_id(), page_id);
+        buffer.assert_consistent();
+    }
+
+    #[test]
+    fn read_page_buffer_full_wakes_waiter_with_error() {
+        let buffer = Arc::new(create_buffer_manager(1));
+        // Keeps the only frame latched, so no frame can be claimed for another page
+        let resident = PageId::new(1, 1);
+        let _guard = buffer.read_page(resident).unwrap();
+        buffer.set_test_gate(Arc::new(Barrier::new(2)));
+        let page_id = PageId::new(1, 2);
+
+        // Both requests miss at the same time: one becomes the loader, the other either waits on it
+        // or retries the load once it failed
+        let first = read_page_in_thread(&buffer, page_id);
+        let second = read_page_in_thread(&buffer, page_id);
+
+        let timeout = Duration::from_secs(5);
+        for rx in [first, second] {
+            let result = rx.recv_timeout(timeout).expect("read_page hung");
+            assert!(matches!(result, Err(BufferError::BufferFull(pid)) if pid == page_id));
+        }
+        assert!(!buffer.page_map.read().unwrap().contains_key(&page_id));
+    }
+
+    #[test]
+    fn prefetch_failed_load_wakes_waiter_with_error() {
+        let buffer = Arc::new(BufferManager::new(
+            Arc::new(FailFirstReadFileManager::default()),
+            2,
+        ));
+        let page_id = PageId::new(1, 1);
+
+        // The entry is registered before `prefetch` returns, so the request waits on the prefetch
+        let prefetch = buffer.prefetch(&[page_id]);
+        let waiter = read_page_in_thread(&buffer, page_id);
+
+        let result = waiter
+            .recv_timeout(Duration::from_secs(5))
+            .expect("read_page hung");
+        assert!(matches!(result, Err(BufferError::IoReadFailed(pid)) if pid == page_id));
+        prefetch.join().unwrap();
+        assert!(buffer.page_map.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn read_page_failed_read_into_reused_frame_leaves_no_stale_bytes() {
+        /// Fills the destination with 7s on the first read. Every subsequent read only partially
+        /// fills it with 9s and reports a failure.
+        struct PartialReadFileManager {
+            reads: AtomicUsize,
+        }
+        impl FileManager for PartialReadFileManager {
+            fn new(_: Arc<FileCatalog>) -> Self {
+                Self {
+                    reads: AtomicUsize::new(0),
+                }
+            }
+
+            fn read_page(&self, _: PageId, destination: &mut [u8]) -> bool {
+                if self.reads.fetch_add(1, Ordering::Relaxed) == 0 {
+                    destination.fill(7);
+                    true
+                } else {
+                    destination[..100].fill(9);
+                    false
+                }
+            }
+
+            fn page_exists(&self, _: PageId) -> Result<bool, FileError> {
+                unimplemented!()
+            }
+
+            fn write_page(&self, _: PageId, _: &[u8]) {}
+
+            fn allocate_page(&self, _: FileId) -> Result<PageId, FileError> {
+                unimplemented!()
+            }
+        }
+
+        let buffer = BufferManager::new(
+            Arc::new(PartialReadFileManager::new(Arc::new(FileCatalog::new()))),
+            1,
+        );
+        let first_page = PageId::new(1, 1);
+        // A page of 7s is no valid page, so it is only handed out leniently
+        drop(
+            buffer
+                .read_page_with_mode(first_page, LoadMode::Lenient)
+                .unwrap(),
+        );
+        assert!(
+            buffer.frames[0]
+                .page
+                .read()
+                .unwrap()
+                .data()
+                .iter()
+                .all(|b| *b == 7)
+        );
+
+        // Simulate the frame being released after the first page was flushed.
+        *buffer.frames[0].page_id.write().unwrap() = None;
+        buffer.page_map.write().unwrap().remove(&first_page);
+
+        let second_page = PageId::new(2, 1);
+        let result = buffer.read_page(second_page).unwrap_err();
+
+        assert!(matches!(result, BufferError::IoReadFailed(pid) if pid == second_page));
+        let page = buffer.frames[0].page.read().unwrap();
+        assert!(page.data().iter().all(|b| *b == 0));
+        assert_eq!(page.page_id(), PageId::new(0, 0));
+    }
+
+    #[test]
+    fn allocate_new_page_into_reused_frame_is_zeroed() {
+        let buffer = create_buffer_manager(1);
+        buffer.frames[0].page.write().unwrap().data_mut().fill(5);
+
+        let page_id = PageId::new(1, 1);
+        let guard = buffer.allocate_new_page(page_id).unwrap();
+
+        assert!(guard.data().iter().all(|b| *b == 0));
+        assert_eq!(guard.page_id(), page_id);
+    }
+
     #[test]
     fn allocate_new_page_correct_metadata_and_page_allocation() {
         let buffer = create_buffer_manager(100);
@@ -649,4 +2120,451 @@ mod tests {
         assert!(!buffer.frames[0].dirty.load(Ordering::Relaxed));
         assert_eq!(buffer.frames[0].pin_count.load(Ordering::Relaxed), 1);
     }
+
+    #[test]
+    fn flush_all_writes_only_dirty_pages_and_syncs_their_files() {
+        let buffer = create_buffer_manager(4);
+        let (modified, clean, allocated) =
+            (PageId::new(1, 1), PageId::new(1, 2), PageId::new(2, 1));
+
+        buffer.read_page_mut(modified).unwrap().data_mut()[200] = 0xAB;
+        drop(buffer.read_page(clean).unwrap());
+        drop(buffer.allocate_new_page(allocated).unwrap());
+
+        let flushed = buffer.flush_all().unwrap();
+
+        assert_eq!(flushed, 1);
+        assert_eq!(
+            *buffer.file_manager.written_pages.read().unwrap(),
+            vec![modified]
+        );
+        assert_eq!(*buffer.file_manager.synced_files.read().unwrap(), vec![1]);
+        assert!(!buffer.frames[0].dirty.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn write_page_clears_dirty_flag() {
+        let buffer = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+
+        let mut guard = buffer.read_page_mut(page_id).unwrap();
+        guard.data_mut()[200] = 0xAB;
+        buffer.write_page(page_id, guard);
+
+        assert_eq!(buffer.flush_all().unwrap(), 0);
+        assert_eq!(buffer.file_manager.written_pages.read().unwrap().len(), 1);
+        assert!(buffer.file_manager.synced_files.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn read_page_copy_reflects_state_before_later_write() {
+        let buffer = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+        buffer.read_page_mut(page_id).unwrap().data_mut()[200] = 0xAB;
+
+        let copy = buffer.read_page_copy(page_id).unwrap();
+        buffer.read_page_mut(page_id).unwrap().data_mut()[200] = 0xCD;
+
+        assert_eq!(copy[200], 0xAB);
+        assert_eq!(buffer.read_page(page_id).unwrap().data()[200], 0xCD);
+    }
+
+    #[test]
+    fn read_page_copy_releases_latch() {
+        let buffer = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+
+        let _copy = buffer.read_page_copy(page_id).unwrap();
+
+        let frame_id = match *buffer.page_map.read().unwrap()[&page_id]
+            .state
+            .lock()
+            .unwrap()
+        {
+            PageState::Ready(frame_id) => frame_id,
+            PageState::Loading | PageState::Failed(_) => panic!("page should be loaded"),
+        };
+        assert!(buffer.frames[frame_id].page.try_write().is_ok());
+    }
+
+    #[test]
+    fn version_unchanged_without_mutation() {
+        let buffer = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+
+        let first = buffer.read_page(page_id).unwrap().version();
+        // A write latch alone does not change the page.
+        drop(buffer.read_page_mut(page_id).unwrap());
+        let second = buffer.read_page(page_id).unwrap().version();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn version_increases_after_mutation() {
+        let buffer = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+
+        let before = buffer.read_page(page_id).unwrap().version();
+        let mut guard = buffer.read_page_mut(page_id).unwrap();
+        guard.data_mut()[200] = 0xAB;
+        drop(guard);
+        let after = buffer.read_page(page_id).unwrap().version();
+
+        assert!(after > before);
+    }
+
+    #[test]
+    fn write_guard_insert_heap_marks_frame_dirty_and_is_visible_to_readers() {
+        let buffer = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+        let mut guard = buffer.allocate_new_page(page_id).unwrap();
+        guard.guard.initialize(page_id, PageType::Unsorted).unwrap();
+        assert!(!buffer.frames[0].dirty.load(Ordering::Relaxed));
+
+        let plan = guard.plan_insert(3).unwrap();
+        guard.insert_heap(plan, vec![1, 2, 3]).unwrap();
+
+        assert!(buffer.frames[0].dirty.load(Ordering::Relaxed));
+        assert_eq!(guard.slot_count().unwrap(), 1);
+        assert_eq!(guard.row(0).unwrap(), &[1, 2, 3]);
+        drop(guard);
+
+        let guard = buffer.read_page(page_id).unwrap();
+        assert_eq!(guard.slot_count().unwrap(), 1);
+        assert_eq!(guard.row(0).unwrap(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn read_guard_row_invalid_slot_returns_error() {
+        let buffer = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+        let mut guard = buffer.allocate_new_page(page_id).unwrap();
+        guard.initialize(page_id, PageType::Unsorted).unwrap();
+        drop(guard);
+
+        let guard = buffer.read_page(page_id).unwrap();
+
+        assert_eq!(guard.slot_count().unwrap(), 0);
+        assert!(guard.row(0).is_err());
+    }
+
+    /// Allocates a heap page in the buffer holding a single row, then releases it.
+    fn allocate_page_with_row(
+        buffer: &BufferManager<MockFileManager>,
+        page_id: PageId,
+        row: &[u8],
+    ) {
+        let mut guard = buffer.allocate_new_page(page_id).unwrap();
+        guard.initialize(page_id, PageType::Unsorted).unwrap();
+        let plan = guard.plan_insert(row.len()).unwrap();
+        guard.insert_heap(plan, row.to_vec()).unwrap();
+    }
+
+    #[test]
+    fn read_guard_to_bytes_round_trips_into_equal_page() {
+        let buffer = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+        allocate_page_with_row(&buffer, page_id, &[1, 2, 3]);
+        let guard = buffer.read_page(page_id).unwrap();
+
+        let bytes = guard.to_bytes();
+        let copy = Page::try_from_bytes(bytes.clone(), page_id).unwrap();
+
+        assert_eq!(copy.data(), &*bytes);
+        assert_eq!(copy.compute_checksum(), guard.compute_checksum());
+        assert_eq!(copy.slot_count().unwrap(), 1);
+        assert_eq!(copy.row(0).unwrap(), guard.row(0).unwrap());
+    }
+
+    #[test]
+    fn install_page_not_resident_is_served_without_disk_read_and_flushed() {
+        let source = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+        allocate_page_with_row(&source, page_id, &[1, 2, 3]);
+        let bytes = source.read_page(page_id).unwrap().to_bytes();
+        let buffer = create_buffer_manager(2);
+
+        buffer.install_page(page_id, bytes.clone()).unwrap();
+
+        let guard = buffer.read_page(page_id).unwrap();
+        assert_eq!(guard.data(), &*bytes);
+        assert_eq!(guard.row(0).unwrap(), &[1, 2, 3]);
+        drop(guard);
+        assert!(
+            buffer
+                .file_manager
+                .requested_pages
+                .read()
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(buffer.flush_all().unwrap(), 1);
+        assert_eq!(
+            *buffer.file_manager.written_pages.read().unwrap(),
+            vec![page_id]
+        );
+    }
+
+    #[test]
+    fn install_page_resident_replaces_contents() {
+        let source = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+        allocate_page_with_row(&source, page_id, &[4, 5]);
+        let bytes = source.read_page(page_id).unwrap().to_bytes();
+        let buffer = create_buffer_manager(2);
+        allocate_page_with_row(&buffer, page_id, &[1, 2, 3]);
+
+        buffer.install_page(page_id, bytes).unwrap();
+
+        assert_eq!(buffer.read_page(page_id).unwrap().row(0).unwrap(), &[4, 5]);
+        assert_eq!(buffer.page_map.read().unwrap().len(), 1);
+        buffer.assert_consistent();
+    }
+
+    #[test]
+    fn install_page_concurrent_installs_of_same_page_claim_one_frame() {
+        let source = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+        allocate_page_with_row(&source, page_id, &[4, 5]);
+        let bytes = source.read_page(page_id).unwrap().to_bytes();
+        let buffer = Arc::new(create_buffer_manager(4));
+        buffer.set_test_gate(Arc::new(Barrier::new(2)));
+
+        let installers: Vec<_> = (0..2)
+            .map(|_| {
+                let buffer = buffer.clone();
+                let bytes = bytes.clone();
+                thread::spawn(move || buffer.install_page(page_id, bytes).unwrap())
+            })
+            .collect();
+        for installer in installers {
+            installer.join().unwrap();
+        }
+
+        buffer.assert_consistent();
+        let claimed = buffer
+            .frames
+            .iter()
+            .filter(|frame| frame.page_id.read().unwrap().is_some())
+            .count();
+        assert_eq!(claimed, 1);
+        assert_eq!(buffer.read_page(page_id).unwrap().row(0).unwrap(), &[4, 5]);
+    }
+
+    #[test]
+    fn install_page_corrupted_bytes_returns_error_and_leaves_page_untouched() {
+        let source = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+        allocate_page_with_row(&source, page_id, &[4, 5]);
+        let mut bytes = source.read_page(page_id).unwrap().to_bytes();
+        bytes[PAGE_SIZE - 100] ^= 0xFF;
+        let buffer = create_buffer_manager(2);
+        allocate_page_with_row(&buffer, page_id, &[1, 2, 3]);
+
+        let result = buffer.install_page(page_id, bytes);
+
+        assert!(matches!(result, Err(BufferError::InvalidPage(pid)) if pid == page_id));
+        assert_eq!(
+            buffer.read_page(page_id).unwrap().row(0).unwrap(),
+            &[1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn install_page_bytes_of_another_page_returns_error() {
+        let source = create_buffer_manager(2);
+        allocate_page_with_row(&source, PageId::new(1, 1), &[4, 5]);
+        let bytes = source.read_page(PageId::new(1, 1)).unwrap().to_bytes();
+        let buffer = create_buffer_manager(2);
+
+        let result = buffer.install_page(PageId::new(1, 2), bytes);
+
+        assert!(matches!(result, Err(BufferError::InvalidPage(pid)) if pid == PageId::new(1, 2)));
+        assert!(buffer.page_map.read().unwrap().is_empty());
+    }
+
+    /// Panics on another thread halfway through modifying the page, poisoning the frame latch.
+    fn poison_page(buffer: &BufferManager<MockFileManager>, page_id: PageId) {
+        thread::scope(|s| {
+            let result = s
+                .spawn(|| {
+                    let mut guard = buffer.read_page_mut(page_id).unwrap();
+                    guard.data_mut()[200] = 0xAB;
+                    panic!("writer panicked while modifying the page");
+                })
+                .join();
+            assert!(result.is_err());
+        });
+    }
+
+    #[test]
+    fn read_page_poisoned_frame_returns_error() {
+        let buffer = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+        poison_page(&buffer, page_id);
+
+        assert!(matches!(
+            buffer.read_page(page_id),
+            Err(BufferError::FramePoisoned(pid)) if pid == page_id
+        ));
+        assert!(matches!(
+            buffer.read_page_mut(page_id),
+            Err(BufferError::FramePoisoned(pid)) if pid == page_id
+        ));
+    }
+
+    #[test]
+    fn read_page_poisoned_frame_does_not_affect_other_pages() {
+        let buffer = create_buffer_manager(2);
+        poison_page(&buffer, PageId::new(1, 1));
+
+        let guard = buffer.read_page(PageId::new(1, 2)).unwrap();
+
+        assert_eq!(guard.page_id(), PageId::new(1, 2));
+    }
+
+    #[test]
+    fn flush_all_skips_poisoned_frame() {
+        let buffer = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+        poison_page(&buffer, page_id);
+
+        assert_eq!(buffer.flush_all().unwrap(), 0);
+        assert!(buffer.file_manager.written_pages.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn flush_all_waits_for_downgrading_frame() {
+        let buffer = Arc::new(create_buffer_manager(2));
+        let page_id = PageId::new(1, 1);
+        allocate_page_with_row(&buffer, page_id, &[1, 2, 3]);
+        // Pretend a writer released its latch and is about to take the read latch back
+        buffer.frames[0].downgrading.store(true, Ordering::Release);
+
+        let (tx, rx) = mpsc::channel();
+        let flusher = {
+            let buffer = buffer.clone();
+            thread::spawn(move || tx.send(buffer.flush_all().unwrap()).unwrap())
+        };
+        assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+        assert!(buffer.file_manager.written_pages.read().unwrap().is_empty());
+
+        buffer.frames[0].downgrading.store(false, Ordering::Release);
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), 1);
+        flusher.join().unwrap();
+    }
+
+    #[test]
+    fn try_upgrade_no_other_reader_returns_write_guard() {
+        let buffer = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+
+        let read_guard = buffer.read_page(page_id).unwrap();
+        let epoch_before = buffer.frames[0].write_epoch.load(Ordering::Relaxed);
+
+        let mut write_guard = read_guard.try_upgrade().unwrap();
+        write_guard.initialize(page_id, PageType::Unsorted).unwrap();
+
+        assert_eq!(write_guard.page_id(), page_id);
+        assert_eq!(
+            buffer.frames[0].write_epoch.load(Ordering::Relaxed),
+            epoch_before + 1
+        );
+        // The page was never re-read from disk
+        assert_eq!(buffer.file_manager.requested_pages.read().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn try_upgrade_another_reader_holds_latch_returns_read_guard_and_error() {
+        let buffer = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+
+        let first_reader = buffer.read_page(page_id).unwrap();
+        let second_reader = buffer.read_page(page_id).unwrap();
+
+        let (read_guard, err) = first_reader.try_upgrade().unwrap_err();
+
+        assert!(matches!(err, BufferError::LatchUpgradeFailed(pid) if pid == page_id));
+        assert_eq!(read_guard.page_id(), page_id);
+        assert_eq!(second_reader.page_id(), page_id);
+
+        // Once the other reader is gone, the returned guard can be upgraded.
+        drop(second_reader);
+        assert!(read_guard.try_upgrade().is_ok());
+    }
+
+    #[test]
+    fn try_upgrade_page_evicted_and_reloaded_in_between_returns_error() {
+        let buffer = Arc::new(create_buffer_manager(1));
+        let page_id = PageId::new(1, 1);
+        let read_guard = buffer.read_page(page_id).unwrap();
+        let epoch_before = buffer.frames[0].write_epoch.load(Ordering::Relaxed);
+
+        // Once the read latch is released, the only frame is handed over to another page, then the
+        // page is read back into that same frame.
+        let hook_buffer = buffer.clone();
+        set_upgrade_hook(move || {
+            drop(hook_buffer.read_page(PageId::new(1, 2)).unwrap());
+            drop(hook_buffer.read_page(page_id).unwrap());
+        });
+
+        let (read_guard, err) = read_guard.try_upgrade().unwrap_err();
+
+        assert!(matches!(err, BufferError::LatchUpgradeFailed(pid) if pid == page_id));
+        assert_eq!(read_guard.page_id(), page_id);
+        assert_eq!(buffer.file_manager.requested_pages.read().unwrap().len(), 3);
+        assert!(buffer.frames[0].write_epoch.load(Ordering::Relaxed) > epoch_before);
+    }
+
+    #[test]
+    fn downgrade_page_stays_readable_and_other_readers_get_in() {
+        let buffer = create_buffer_manager(2);
+        let page_id = PageId::new(1, 1);
+
+        let mut write_guard = buffer.read_page_mut(page_id).unwrap();
+        write_guard.initialize(page_id, PageType::Unsorted).unwrap();
+        let plan = write_guard.plan_insert(3).unwrap();
+        write_guard.insert_heap(plan, vec![1, 2, 3]).unwrap();
+        let version = buffer.frames[0].version.load(Ordering::Relaxed);
+
+        let read_guard = write_guard.downgrade();
+        let second_reader = buffer.read_page(page_id).unwrap();
+
+        assert_eq!(read_guard.row(0).unwrap(), &[1, 2, 3]);
+        assert_eq!(second_reader.row(0).unwrap(), &[1, 2, 3]);
+        assert_eq!(read_guard.version(), version);
+        // Still the same frame, the page was never re-read from disk
+        assert_eq!(buffer.file_manager.requested_pages.read().unwrap().len(), 1);
+        assert!(!buffer.frames[0].downgrading.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn downgrade_keeps_writers_and_evictions_away_until_dropped() {
+        let buffer = Arc::new(create_buffer_manager(1));
+        let page_id = PageId::new(1, 1);
+
+        let read_guard = buffer.read_page_mut(page_id).unwrap().downgrade();
+
+        // The only frame is latched, so no other page can take it
+        let other = PageId::new(1, 2);
+        assert!(
+            matches!(buffer.read_page(other), Err(BufferError::BufferFull(pid)) if pid == other)
+        );
+
+        let (tx, rx) = mpsc::channel();
+        let writer = {
+            let buffer = buffer.clone();
+            thread::spawn(move || {
+                let guard = buffer.read_page_mut(page_id).unwrap();
+                tx.send(guard.page_id()).unwrap();
+            })
+        };
+        assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+
+        drop(read_guard);
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), page_id);
+        writer.join().unwrap();
+        buffer.assert_consistent();
+    }
 }
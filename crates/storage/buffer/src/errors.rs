@@ -1,10 +1,58 @@
 use page::page_id::PageId;
+use page::page_type::PageType;
 
 /// Buffer error.
+/// Every variant carries the `PageId` of the page that was being served when the error occurred.
 #[derive(Debug)]
 pub enum BufferError {
-    /// Buffer was full
-    BufferFull,
+    /// Buffer was full, no frame could be claimed for the page
+    BufferFull(PageId),
     /// Could not read file from disk
     IoReadFailed(PageId),
+    /// A read latch could not be upgraded to a write latch, because the page was latched by someone else
+    LatchUpgradeFailed(PageId),
+    /// The latch of the frame holding the page was poisoned by a thread that panicked while holding it
+    /// exclusively, so the page might have been left half-modified
+    FramePoisoned(PageId),
+    /// The bytes of the page, read from disk or handed over, do not form a valid page, see `Page::validate`
+    InvalidPage(PageId),
+    /// The page is not of the type the caller expected, see `BufferManager::read_page_typed`
+    PageTypeMismatch {
+        /// The page that was requested
+        page_id: PageId,
+        /// The type the caller expected
+        expected: PageType,
+        /// The type stored in the header of the page
+        actual: PageType,
+    },
+}
+
+/// Internal, context-free failure reasons raised by the buffer internals.
+/// Converted into a `BufferError` via `WithPageId::with_page_id` once the `PageId` is attached.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum BufferOpError {
+    /// No free frame was available
+    BufferFull,
+    /// The file manager failed to read the page
+    IoReadFailed,
+    /// The page latch of the frame was poisoned
+    FramePoisoned,
+    /// The page failed validation, see `LoadMode::Strict`
+    InvalidPage,
+}
+
+/// Helper trait to attach page_id context when surfacing errors.
+pub(crate) trait WithPageId<T> {
+    fn with_page_id(self, page_id: PageId) -> Result<T, BufferError>;
+}
+
+impl<T> WithPageId<T> for Result<T, BufferOpError> {
+    fn with_page_id(self, page_id: PageId) -> Result<T, BufferError> {
+        self.map_err(|source| match source {
+            BufferOpError::BufferFull => BufferError::BufferFull(page_id),
+            BufferOpError::IoReadFailed => BufferError::IoReadFailed(page_id),
+            BufferOpError::FramePoisoned => BufferError::FramePoisoned(page_id),
+            BufferOpError::InvalidPage => BufferError::InvalidPage(page_id),
+        })
+    }
 }
@@ -0,0 +1,208 @@
+//! Policies choosing which frame gives up its page when the buffer is full
+
+pub use crate::frame::FrameId;
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Decides which frame is evicted when a page has to be loaded into a full buffer.
+///
+/// The buffer manager reports every access to a frame through `record_access`, then asks for a victim
+/// among the frames that can currently be evicted. Both methods take `&self`, as they are called
+/// concurrently by every thread using the buffer.
+pub trait EvictionPolicy: Debug {
+    /// Notes that the page held by `frame_id` was just latched, or just loaded into the frame.
+    fn record_access(&self, frame_id: FrameId);
+
+    /// Picks the frame to evict among `frames`.
+    ///
+    /// # Returns
+    /// One of `frames`, or `None` if `frames` is empty. The buffer manager might fail to evict the
+    /// victim (e.g. because its page got latched in the meantime), in which case it asks again without it.
+    fn pick_victim(&self, frames: &[FrameId]) -> Option<FrameId>;
+}
+
+/// The eviction policies available to the buffer manager
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvictionPolicyKind {
+    /// See `ClockPolicy`
+    #[default]
+    Clock,
+    /// See `LruPolicy`
+    Lru,
+}
+
+impl EvictionPolicyKind {
+    /// Creates the policy for a buffer of `frame_count` frames
+    pub fn build(self, frame_count: usize) -> Box<dyn EvictionPolicy + Send + Sync> {
+        match self {
+            EvictionPolicyKind::Clock => Box::new(ClockPolicy::new(frame_count)),
+            EvictionPolicyKind::Lru => Box::new(LruPolicy::new(frame_count)),
+        }
+    }
+}
+
+/// Second-chance policy: a hand sweeps over the frames in order, clearing the reference bit of the
+/// frames accessed since it last passed them, and evicts the first frame whose bit is already clear.
+/// Approximates LRU without ordering accesses.
+#[derive(Debug)]
+pub struct ClockPolicy {
+    /// Set on every access, cleared by the hand
+    referenced: Vec<AtomicBool>,
+    /// The frame the next sweep starts from
+    hand: Mutex<FrameId>,
+}
+
+impl ClockPolicy {
+    /// Creates a clock over `frame_count` frames, none of them referenced
+    pub fn new(frame_count: usize) -> Self {
+        Self {
+            referenced: (0..frame_count).map(|_| AtomicBool::new(false)).collect(),
+            hand: Mutex::new(0),
+        }
+    }
+}
+
+impl EvictionPolicy for ClockPolicy {
+    fn record_access(&self, frame_id: FrameId) {
+        self.referenced[frame_id].store(true, Ordering::Relaxed);
+    }
+
+    fn pick_victim(&self, frames: &[FrameId]) -> Option<FrameId> {
+        let frame_count = self.referenced.len();
+        let mut candidate = vec![false; frame_count];
+        for &frame_id in frames {
+            candidate[frame_id] = true;
+        }
+
+        let mut hand = self.hand.lock().unwrap();
+        // The first lap may only clear reference bits, the second one is bound to find a victim
+        for _ in 0..2 * frame_count {
+            let frame_id = *hand;
+            *hand = (frame_id + 1) % frame_count;
+            if candidate[frame_id] && !self.referenced[frame_id].swap(false, Ordering::Relaxed) {
+                return Some(frame_id);
+            }
+        }
+        None
+    }
+}
+
+/// Least recently used policy: evicts the frame whose last access is the oldest.
+#[derive(Debug)]
+pub struct LruPolicy {
+    /// Tick of the last access of every frame. `0` for frames never accessed.
+    last_access: Vec<AtomicU64>,
+    /// Source of access ticks
+    ticks: AtomicU64,
+}
+
+impl LruPolicy {
+    /// Creates an LRU policy over `frame_count` frames, none of them accessed
+    pub fn new(frame_count: usize) -> Self {
+        Self {
+            last_access: (0..frame_count).map(|_| AtomicU64::new(0)).collect(),
+            ticks: AtomicU64::new(1),
+        }
+    }
+}
+
+impl EvictionPolicy for LruPolicy {
+    fn record_access(&self, frame_id: FrameId) {
+        let tick = self.ticks.fetch_add(1, Ordering::Relaxed);
+        self.last_access[frame_id].store(tick, Ordering::Relaxed);
+    }
+
+    fn pick_victim(&self, frames: &[FrameId]) -> Option<FrameId> {
+        frames
+            .iter()
+            .copied()
+            .min_by_key(|&frame_id| self.last_access[frame_id].load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn access(policy: &dyn EvictionPolicy, frames: &[FrameId]) {
+        for &frame_id in frames {
+            policy.record_access(frame_id);
+        }
+    }
+
+    #[test]
+    fn clock_unreferenced_frames_evicted_in_hand_order() {
+        let clock = ClockPolicy::new(3);
+
+        assert_eq!(clock.pick_victim(&[0, 1, 2]), Some(0));
+        assert_eq!(clock.pick_victim(&[0, 1, 2]), Some(1));
+        assert_eq!(clock.pick_victim(&[0, 1, 2]), Some(2));
+        assert_eq!(clock.pick_victim(&[0, 1, 2]), Some(0));
+    }
+
+    #[test]
+    fn clock_referenced_frame_gets_a_second_chance() {
+        let clock = ClockPolicy::new(3);
+        access(&clock, &[0, 1, 2, 0]);
+
+        // Every frame is referenced, so the first lap clears all bits and the second evicts frame 0
+        assert_eq!(clock.pick_victim(&[0, 1, 2]), Some(0));
+        // Frame 1 is accessed again before the hand comes back to it
+        access(&clock, &[1]);
+        assert_eq!(clock.pick_victim(&[0, 1, 2]), Some(2));
+        assert_eq!(clock.pick_victim(&[0, 1, 2]), Some(0));
+        assert_eq!(clock.pick_victim(&[0, 1, 2]), Some(1));
+    }
+
+    #[test]
+    fn clock_only_picks_among_candidates() {
+        let clock = ClockPolicy::new(4);
+        access(&clock, &[3]);
+
+        assert_eq!(clock.pick_victim(&[1, 3]), Some(1));
+        assert_eq!(clock.pick_victim(&[3]), Some(3));
+        assert_eq!(clock.pick_victim(&[]), None);
+    }
+
+    #[test]
+    fn lru_evicts_least_recently_accessed_frame() {
+        let lru = LruPolicy::new(3);
+        access(&lru, &[0, 1, 2, 0, 1]);
+
+        assert_eq!(lru.pick_victim(&[0, 1, 2]), Some(2));
+        access(&lru, &[2]);
+        assert_eq!(lru.pick_victim(&[0, 1, 2]), Some(0));
+    }
+
+    #[test]
+    fn lru_never_accessed_frame_evicted_first() {
+        let lru = LruPolicy::new(3);
+        access(&lru, &[0, 2]);
+
+        assert_eq!(lru.pick_victim(&[0, 1, 2]), Some(1));
+    }
+
+    #[test]
+    fn lru_only_picks_among_candidates() {
+        let lru = LruPolicy::new(3);
+        access(&lru, &[0, 1, 2]);
+
+        assert_eq!(lru.pick_victim(&[1, 2]), Some(1));
+        assert_eq!(lru.pick_victim(&[]), None);
+    }
+
+    #[test]
+    fn policies_diverge_on_scripted_sequence() {
+        // Frame 0 is accessed once more than the others, after all of them were loaded
+        let script = [0, 1, 2, 0];
+        let clock = EvictionPolicyKind::Clock.build(3);
+        let lru = EvictionPolicyKind::Lru.build(3);
+        access(clock.as_ref(), &script);
+        access(lru.as_ref(), &script);
+
+        // Clock ignores the access order, once every bit is cleared it evicts from where the hand is
+        assert_eq!(clock.pick_victim(&[0, 1, 2]), Some(0));
+        assert_eq!(lru.pick_victim(&[0, 1, 2]), Some(1));
+    }
+}
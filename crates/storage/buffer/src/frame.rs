@@ -1,10 +1,14 @@
 use page::page::api::Page;
 use page::page_id::PageId;
 use std::sync::RwLock;
-use std::sync::atomic::{AtomicBool, AtomicU32};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 
 /// The ID of a frame is basically just its index in the buffer's vector
-pub(crate) type FrameId = usize;
+pub type FrameId = usize;
+
+/// Source of page versions. Shared by all frames, so a page moving to another frame after an eviction
+/// never goes back to a version a reader may have already observed.
+static NEXT_PAGE_VERSION: AtomicU64 = AtomicU64::new(1);
 
 /// A buffer frame is a memory allocation designed to store the contents of a data page in memory,
 /// along with other metadata needed by the buffer manager.
@@ -33,6 +37,31 @@ pub(crate) struct BufferFrame {
 
     /// Dirtiness of the page flag, backed by an `AtomicBool`
     pub(crate) dirty: AtomicBool,
+
+    /// Monotonic counter bumped every time a write latch on `page` is handed out by the buffer manager,
+    /// and every time the frame is claimed for a page.
+    /// Used to detect whether a writer or an eviction slipped in while a read latch was being upgraded.
+    pub(crate) write_epoch: AtomicU64,
+
+    /// Set while a write latch on `page` is being downgraded into a read latch. Writers obtaining the
+    /// write latch in the meantime give it back, so the downgrading thread gets the read latch first.
+    pub(crate) downgrading: AtomicBool,
+
+    /// Version of the page currently held by the frame. Bumped on every mutable access to the page and
+    /// whenever the frame is claimed for a page, so it strictly increases each time the page may have changed.
+    pub(crate) version: AtomicU64,
+
+    /// Set when the page was loaded with `LoadMode::Lenient` and failed validation. Strict accesses
+    /// validate the page again before handing it out, and clear the flag once it passes.
+    pub(crate) unvalidated: AtomicBool,
+}
+
+impl BufferFrame {
+    /// Assigns the frame a new version, higher than any version handed out before.
+    pub(crate) fn bump_version(&self) {
+        let version = NEXT_PAGE_VERSION.fetch_add(1, Ordering::AcqRel);
+        self.version.store(version, Ordering::Release);
+    }
 }
 
 impl Default for BufferFrame {
@@ -42,6 +71,10 @@ impl Default for BufferFrame {
             page: RwLock::new(Page::new_zeroed(PageId::new(0, 0))),
             pin_count: AtomicU32::new(0),
             dirty: AtomicBool::new(false),
+            write_epoch: AtomicU64::new(0),
+            downgrading: AtomicBool::new(false),
+            version: AtomicU64::new(0),
+            unvalidated: AtomicBool::new(false),
         }
     }
 }
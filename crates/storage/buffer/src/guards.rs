@@ -1,6 +1,26 @@
+use crate::errors::BufferError;
+use crate::frame::BufferFrame;
+use page::PAGE_SIZE;
+use page::errors::page_error::PageResult;
+use page::insertion_plan::InsertionPlan;
 use page::page::api::Page;
 use std::ops::{Deref, DerefMut};
-use std::sync::{RwLockReadGuard, RwLockWriteGuard};
+use std::sync::atomic::Ordering;
+use std::sync::{PoisonError, RwLockReadGuard, RwLockWriteGuard};
+
+#[cfg(test)]
+thread_local! {
+    /// Runs once between the release of the read latch and the probe for the write latch in
+    /// `PageReadGuard::try_upgrade`. Only used to test what happens to the frame in that window.
+    static UPGRADE_HOOK: std::cell::RefCell<Option<Box<dyn FnOnce()>>> =
+        std::cell::RefCell::new(None);
+}
+
+/// Setter for the hook run in the middle of the next `try_upgrade` on the current thread.
+#[cfg(test)]
+pub(crate) fn set_upgrade_hook(hook: impl FnOnce() + 'static) {
+    UPGRADE_HOOK.with(|cell| *cell.borrow_mut() = Some(Box::new(hook)));
+}
 
 /// Provides read access to a `Page` instance stored in one of the buffer's frames.
 /// Shared latch, allowing concurrent reads.
@@ -9,6 +29,90 @@ use std::sync::{RwLockReadGuard, RwLockWriteGuard};
 pub struct PageReadGuard<'a> {
     /// The underlying `RwLockReadGuard` which will be dereferenced to `&Page`
     pub guard: RwLockReadGuard<'a, Page>,
+    /// The frame holding the page. Needed to re-latch the page when upgrading.
+    pub(crate) frame: &'a BufferFrame,
+}
+
+impl<'a> PageReadGuard<'a> {
+    /// Attempts to upgrade the shared latch into an exclusive one, without going back through the
+    /// buffer manager.
+    ///
+    /// `std::sync::RwLock` has no native upgrade, so the transition is done optimistically:
+    /// 1. the frame's `write_epoch` and the page version are captured while the read latch is still held
+    /// 2. the read latch is released and a write latch is probed for via `try_write` (no waiting)
+    /// 3. if the write latch was obtained, the frame still holds the same page and neither the epoch nor
+    ///    the version moved, no writer touched the page in between, so the upgrade is equivalent to an
+    ///    atomic one
+    ///
+    /// The frame is not pinned while no latch is held, so it may be evicted and handed over to another
+    /// page - or even reloaded with the same one - in between. The version check catches both, as
+    /// claiming a frame always assigns it a new version.
+    ///
+    /// # Returns
+    /// - `Ok(PageWriteGuard)` if the upgrade succeeded.
+    /// - `Err((PageReadGuard, BufferError::LatchUpgradeFailed))` if another reader or writer holds
+    ///   the latch, or the page was modified or evicted while the upgrade was in progress. The returned
+    ///   read guard is freshly acquired, so the caller should re-validate anything it read before,
+    ///   starting with the ID of the page it latches.
+    pub fn try_upgrade(self) -> Result<PageWriteGuard<'a>, (PageReadGuard<'a>, BufferError)> {
+        let Self { guard, frame } = self;
+        let page_id = guard.page_id();
+        let epoch = frame.write_epoch.load(Ordering::Acquire);
+        let version = frame.version.load(Ordering::Acquire);
+        drop(guard);
+
+        #[cfg(test)]
+        if let Some(hook) = UPGRADE_HOOK.with(|cell| cell.borrow_mut().take()) {
+            hook();
+        }
+
+        if let Ok(write_guard) = frame.page.try_write()
+            && write_guard.page_id() == page_id
+            && frame.write_epoch.load(Ordering::Acquire) == epoch
+            && frame.version.load(Ordering::Acquire) == version
+            && !frame.downgrading.load(Ordering::Acquire)
+        {
+            frame.write_epoch.fetch_add(1, Ordering::AcqRel);
+            return Ok(PageWriteGuard {
+                guard: write_guard,
+                frame,
+            });
+        }
+
+        // A writer panicking in between is reported as a failed upgrade as well - the caller is
+        // already expected to re-validate what it read before.
+        let guard = frame.page.read().unwrap_or_else(PoisonError::into_inner);
+        Err((
+            PageReadGuard { guard, frame },
+            BufferError::LatchUpgradeFailed(page_id),
+        ))
+    }
+}
+
+impl<'a> PageReadGuard<'a> {
+    /// Retrieves a row of the page by its slot index. See `Page::row`.
+    pub fn row(&self, slot_index: u32) -> PageResult<&[u8]> {
+        self.guard.row(slot_index)
+    }
+
+    /// Returns the slot count of the page. See `Page::slot_count`.
+    pub fn slot_count(&self) -> PageResult<u16> {
+        self.guard.slot_count()
+    }
+
+    /// Returns the current version of the page.
+    /// Versions only ever increase, so a page read twice with the same version did not change in between.
+    pub fn version(&self) -> u64 {
+        self.frame.version.load(Ordering::Acquire)
+    }
+
+    /// Returns a copy of the bytes of the page, taken while latched, with an up to date checksum.
+    /// Meant for shipping the page elsewhere, see `Page::to_owned_bytes` and `BufferManager::install_page`.
+    pub fn to_bytes(&self) -> Box<[u8; PAGE_SIZE]> {
+        self.guard
+            .to_owned_bytes()
+            .expect("page header is always within bounds")
+    }
 }
 
 impl<'a> Deref for PageReadGuard<'a> {
@@ -25,6 +129,56 @@ impl<'a> Deref for PageReadGuard<'a> {
 pub struct PageWriteGuard<'a> {
     /// The underlying `RwLockWriteGuard` which will be dereferenced to `&Page`
     pub guard: RwLockWriteGuard<'a, Page>,
+    /// The frame holding the page. Needed to clear its dirty flag once the page is written to disk.
+    pub(crate) frame: &'a BufferFrame,
+}
+
+impl<'a> PageWriteGuard<'a> {
+    /// Retrieves a row of the page by its slot index. See `Page::row`.
+    pub fn row(&self, slot_index: u32) -> PageResult<&[u8]> {
+        self.guard.row(slot_index)
+    }
+
+    /// Returns the slot count of the page. See `Page::slot_count`.
+    pub fn slot_count(&self) -> PageResult<u16> {
+        self.guard.slot_count()
+    }
+
+    /// Plans the insertion of a row into the heap page. See `Page::plan_insert`.
+    pub fn plan_insert(&self, row_len: usize) -> PageResult<InsertionPlan> {
+        self.guard.plan_insert(row_len)
+    }
+
+    /// Inserts a row into the heap page using a plan obtained from `plan_insert`. See `Page::insert_heap`.
+    /// Goes through `DerefMut`, so the frame is marked dirty.
+    pub fn insert_heap(&mut self, plan: InsertionPlan, row: Vec<u8>) -> PageResult<u32> {
+        (**self).insert_heap(plan, row)
+    }
+}
+
+impl<'a> PageWriteGuard<'a> {
+    /// Turns the exclusive latch into a shared one, letting other readers in while still reading the
+    /// page, without going back through the buffer manager.
+    ///
+    /// `std::sync::RwLock` has no native downgrade, so the latch is swapped under the frame's latches:
+    /// 1. the frame's `page_id` latch is taken shared, which keeps evictions away from the frame - they
+    ///    need it exclusively
+    /// 2. the frame is flagged as `downgrading`, so writers obtaining the write latch next give it back
+    ///    right away instead of using it
+    /// 3. the write latch is released and the read latch acquired, then the flag is cleared
+    ///
+    /// The page stays in the frame for the whole operation, and no writer can modify it before the
+    /// returned guard is dropped.
+    pub fn downgrade(self) -> PageReadGuard<'a> {
+        let Self { guard, frame } = self;
+        let _claimed_by = frame.page_id.read().unwrap_or_else(PoisonError::into_inner);
+        frame.downgrading.store(true, Ordering::Release);
+        drop(guard);
+
+        let guard = frame.page.read().unwrap_or_else(PoisonError::into_inner);
+        frame.downgrading.store(false, Ordering::Release);
+        PageReadGuard { guard, frame }
+    }
 }
 
 impl<'a> Deref for PageWriteGuard<'a> {
@@ -35,7 +189,11 @@ impl<'a> Deref for PageWriteGuard<'a> {
 }
 
 impl<'a> DerefMut for PageWriteGuard<'a> {
+    /// Any mutable access marks the frame as dirty, so the page is picked up by `flush_all`, and bumps
+    /// the version of the page.
     fn deref_mut(&mut self) -> &mut Self::Target {
+        self.frame.dirty.store(true, Ordering::Release);
+        self.frame.bump_version();
         &mut self.guard
     }
 }
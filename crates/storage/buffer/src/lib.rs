@@ -1,8 +1,13 @@
 //! Buffer management module for storage system.
 
 pub mod buffer;
-mod errors;
+/// Errors surfaced by buffer operations
+pub mod errors;
+/// Policies choosing which page is evicted from a full buffer
+pub mod eviction;
 mod frame;
+#[cfg(test)]
+mod stress;
 
 /// Exposes `guard`-like structs that will provide the access to the `Page` instances
 /// from the buffer via `&Page`
@@ -0,0 +1,285 @@
+//! Multi-threaded stress harness for the `BufferManager`.
+//!
+//! Worker threads hammer a small pool with `read_page`, `read_page_mut` and `allocate_new_page`
+//! following a seeded pseudo-random schedule, so a failing run can be replayed with the same seed.
+//! Once all workers are done, the harness checks that:
+//! - no worker got stuck (`StressConfig::timeout`)
+//! - every page was read from disk exactly once and every allocated page is still resident
+//! - no increment done through a write guard was lost, or applied to the wrong page
+//! - `page_map` and `frames` agree with each other (`BufferManager::assert_consistent`)
+
+use crate::buffer::BufferManager;
+use file::api::FileManager;
+use file::errors::FileError;
+use file::file_catalog::FileCatalog;
+use page::page::api::Page;
+use page::page_id::{FileId, PageId};
+use page::page_type::PageType;
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// File holding the pages read from "disk" by the workers
+const DISK_FILE: FileId = 1;
+/// File holding the pages allocated by the workers
+const ALLOCATED_FILE: FileId = 2;
+/// Offset of the page ID stamp written on every page. Past the page header, in the free space of the
+/// empty pages served from "disk", so that they stay valid.
+const STAMP_OFFSET: usize = 128;
+/// Offset of the counter incremented through write guards
+const COUNTER_OFFSET: usize = STAMP_OFFSET + 8;
+
+/// Shape of a stress run.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StressConfig {
+    /// Number of worker threads
+    pub(crate) threads: usize,
+    /// Number of operations each worker performs
+    pub(crate) iterations: usize,
+    /// Number of distinct pages the workers read from disk
+    pub(crate) disk_pages: u32,
+    /// Number of new pages each worker allocates, spread over its iterations
+    pub(crate) allocations_per_thread: usize,
+    /// Seed of the per-worker operation schedule
+    pub(crate) seed: u64,
+    /// How long to wait for a worker before declaring a deadlock
+    pub(crate) timeout: Duration,
+}
+
+impl StressConfig {
+    /// Number of frames needed to hold every page touched by the run. The buffer only evicts once
+    /// it runs full, so with a pool of exactly this size every page stays resident, unless a frame
+    /// is leaked - which then shows up as a page read from disk twice.
+    pub(crate) fn pool_size(&self) -> usize {
+        self.disk_pages as usize + self.threads * self.allocations_per_thread
+    }
+}
+
+/// File manager serving pages stamped with their own ID, counting the reads of every page.
+#[derive(Debug)]
+pub(crate) struct StampingFileManager {
+    reads: Mutex<HashMap<PageId, usize>>,
+}
+
+impl FileManager for StampingFileManager {
+    fn new(_: Arc<FileCatalog>) -> Self {
+        Self {
+            reads: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn read_page(&self, page_id: PageId, destination: &mut [u8]) -> bool {
+        *self.reads.lock().unwrap().entry(page_id).or_default() += 1;
+        // Widen the window between claiming a frame and publishing the page
+        thread::yield_now();
+        let mut page = Page::new_zeroed(page_id);
+        page.initialize(page_id, PageType::Unsorted).unwrap();
+        destination.copy_from_slice(page.data());
+        stamp(destination, page_id);
+        true
+    }
+
+    fn page_exists(&self, _: PageId) -> Result<bool, FileError> {
+        Ok(true)
+    }
+
+    fn write_page(&self, _: PageId, _: &[u8]) {}
+
+    fn allocate_page(&self, _: FileId) -> Result<PageId, FileError> {
+        unimplemented!("the buffer manager never allocates pages on disk")
+    }
+}
+
+/// Deterministic xorshift64 generator, so that runs do not depend on an external crate.
+struct Schedule(u64);
+
+impl Schedule {
+    fn new(seed: u64, worker: usize) -> Self {
+        // xorshift gets stuck on 0, make sure every worker starts from a distinct non-zero state
+        Self((seed ^ (worker as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15)) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// What a single worker did, used to verify the final state of the pool.
+#[derive(Debug, Default)]
+struct WorkerReport {
+    /// Number of increments done through write guards, per page
+    increments: HashMap<PageId, u64>,
+    /// Pages allocated by the worker
+    allocated: Vec<PageId>,
+}
+
+fn stamp_of(page_id: PageId) -> [u8; 8] {
+    let mut stamp = [0u8; 8];
+    stamp[..4].copy_from_slice(&page_id.file_id.to_le_bytes());
+    stamp[4..].copy_from_slice(&page_id.page_number.to_le_bytes());
+    stamp
+}
+
+fn stamp(data: &mut [u8], page_id: PageId) {
+    data[STAMP_OFFSET..STAMP_OFFSET + 8].copy_from_slice(&stamp_of(page_id));
+}
+
+fn assert_stamped(page: &Page, page_id: PageId) {
+    assert_eq!(page.page_id(), page_id);
+    assert_eq!(
+        &page.data()[STAMP_OFFSET..STAMP_OFFSET + 8],
+        &stamp_of(page_id),
+        "page {page_id} holds the bytes of another page"
+    );
+}
+
+fn counter(page: &Page) -> u64 {
+    u64::from_le_bytes(
+        page.data()[COUNTER_OFFSET..COUNTER_OFFSET + 8]
+            .try_into()
+            .unwrap(),
+    )
+}
+
+fn run_worker(
+    buffer: &BufferManager<StampingFileManager>,
+    config: StressConfig,
+    worker: usize,
+) -> WorkerReport {
+    let mut schedule = Schedule::new(config.seed, worker);
+    let mut report = WorkerReport::default();
+    let allocate_every = config.iterations / config.allocations_per_thread.max(1);
+
+    for iteration in 0..config.iterations {
+        if report.allocated.len() < config.allocations_per_thread
+            && iteration % allocate_every.max(1) == 0
+        {
+            let page_id = PageId::new(
+                ALLOCATED_FILE,
+                (worker * config.allocations_per_thread + report.allocated.len()) as u32,
+            );
+            let mut guard = buffer.allocate_new_page(page_id).unwrap();
+            stamp(guard.data_mut(), page_id);
+            report.allocated.push(page_id);
+            continue;
+        }
+
+        // Mix the pages allocated so far into the pages being accessed
+        let candidates = config.disk_pages as usize + report.allocated.len();
+        let pick = (schedule.next() % candidates as u64) as usize;
+        let page_id = match pick.checked_sub(config.disk_pages as usize) {
+            Some(allocated) => report.allocated[allocated],
+            None => PageId::new(DISK_FILE, pick as u32),
+        };
+
+        if schedule.next().is_multiple_of(4) {
+            let mut guard = buffer.read_page_mut(page_id).unwrap();
+            assert_stamped(&guard, page_id);
+            let value = counter(&guard) + 1;
+            guard.data_mut()[COUNTER_OFFSET..COUNTER_OFFSET + 8]
+                .copy_from_slice(&value.to_le_bytes());
+            *report.increments.entry(page_id).or_default() += 1;
+        } else {
+            let guard = buffer.read_page(page_id).unwrap();
+            assert_stamped(&guard, page_id);
+        }
+    }
+
+    report
+}
+
+/// Runs the workers described by `config` against a fresh pool, then checks the invariants listed
+/// in the module docs. Panics on the first violation, or if a worker does not finish in time.
+pub(crate) fn run_stress(config: StressConfig) {
+    let file_manager = Arc::new(StampingFileManager::new(Arc::new(FileCatalog::new())));
+    let buffer = Arc::new(BufferManager::new(file_manager.clone(), config.pool_size()));
+    // Release all workers at once, to maximize contention on the first loads
+    let start = Arc::new(Barrier::new(config.threads));
+    let (sender, receiver) = mpsc::channel();
+
+    for worker in 0..config.threads {
+        let buffer = buffer.clone();
+        let start = start.clone();
+        let sender = sender.clone();
+        thread::spawn(move || {
+            start.wait();
+            // A panicking worker drops the sender without reporting, which surfaces below
+            let report = run_worker(&buffer, config, worker);
+            let _ = sender.send(report);
+        });
+    }
+    drop(sender);
+
+    let mut reports = Vec::with_capacity(config.threads);
+    for _ in 0..config.threads {
+        match receiver.recv_timeout(config.timeout) {
+            Ok(report) => reports.push(report),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                panic!("workers did not finish within {:?}", config.timeout)
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => panic!("a worker panicked"),
+        }
+    }
+
+    buffer.assert_consistent();
+
+    let reads = file_manager.reads.lock().unwrap();
+    for (page_id, count) in reads.iter() {
+        assert_eq!(*count, 1, "page {page_id} was read from disk {count} times");
+    }
+
+    let mut increments: HashMap<PageId, u64> = HashMap::new();
+    for report in &reports {
+        for (page_id, count) in &report.increments {
+            *increments.entry(*page_id).or_default() += count;
+        }
+        for page_id in &report.allocated {
+            let guard = buffer.read_page(*page_id).unwrap();
+            assert_stamped(&guard, *page_id);
+        }
+    }
+    for (page_id, expected) in increments {
+        let guard = buffer.read_page(page_id).unwrap();
+        assert_eq!(counter(&guard), expected, "lost updates on page {page_id}");
+    }
+
+    // Allocated pages never go through the file manager, and were checked above
+    assert!(reads.keys().all(|page_id| page_id.file_id == DISK_FILE));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(seed: u64) -> StressConfig {
+        StressConfig {
+            threads: 8,
+            iterations: 2_000,
+            disk_pages: 8,
+            allocations_per_thread: 2,
+            seed,
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    #[test]
+    fn stress_mixed_operations_small_pool_stays_consistent() {
+        for seed in [1, 42, 0xDEAD_BEEF] {
+            run_stress(config(seed));
+        }
+    }
+
+    #[test]
+    fn stress_single_hot_page_read_from_disk_once() {
+        run_stress(StressConfig {
+            disk_pages: 1,
+            allocations_per_thread: 0,
+            ..config(7)
+        });
+    }
+}
@@ -1,7 +1,8 @@
 //! Public API for the `file` crate
 
+use crate::errors::FileError;
 use crate::file_catalog::FileCatalog;
-use page::page_id::PageId;
+use page::page_id::{FileId, PageId};
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -34,11 +35,23 @@ pub trait FileManager {
     ///
     /// Return
     /// - `bool`: `true` if the page existed and was copied into
-    ///   `destination`; `false` if the page does not exist. Implementations
-    ///   may decide how to handle buffer-size mismatches (see implementation
-    ///   docs / future error types).
+    ///   `destination`; `false` if the page does not exist, or if
+    ///   `destination` is not exactly one page long, in which case it is
+    ///   left untouched.
     fn read_page(&self, page_id: PageId, destination: &mut [u8]) -> bool; //TODO change return type to Result
 
+    /// Definition
+    /// Check whether the page identified by `page_id` lies within its file, without reading it.
+    ///
+    /// Params
+    /// - `page_id`: Identifier of the page to look up.
+    ///
+    /// Return
+    /// - `Result<bool, FileError>`: `true` if the file extends past the start of the page, `false` if
+    ///   the page number is beyond the end of the file. Lets callers tell a page that was never
+    ///   allocated apart from one that exists but could not be read.
+    fn page_exists(&self, page_id: PageId) -> Result<bool, FileError>;
+
     /// Definition
     /// Write the contents of `page_data` as the page for `page_id`.
     ///
@@ -51,4 +64,29 @@ pub trait FileManager {
     /// - `()`: No value is returned. Implementations control how they report
     ///   internal failures; this trait does not expose an error type yet.
     fn write_page(&self, page_id: PageId, page_data: &[u8]); //TODO change return type to Result
+
+    /// Definition
+    /// Reserve a new page in the file identified by `file_id`, past every page handed out so far.
+    /// Implementations may hand out space reserved up front before growing the file.
+    ///
+    /// Params
+    /// - `file_id`: Identifier of the file to grow.
+    ///
+    /// Return
+    /// - `Result<PageId, FileError>`: the `PageId` of the new page. Every call returns a distinct page
+    ///   number, and the page is backed by zeroed storage by the time this returns.
+    fn allocate_page(&self, file_id: FileId) -> Result<PageId, FileError>;
+
+    /// Definition
+    /// Force all previous writes to the file identified by `file_id` to durable storage.
+    ///
+    /// Params
+    /// - `file_id`: Identifier of the file to sync.
+    ///
+    /// Return
+    /// - `io::Result<()>`: `Ok(())` once the data reached the storage device. Implementations
+    ///   without a durable backing store can rely on the default, which does nothing.
+    fn sync(&self, file_id: FileId) -> std::io::Result<()> {
+        Ok(())
+    }
 }
@@ -1,46 +1,118 @@
 use crate::api::FileManager;
+use crate::errors::FileError;
 use crate::file_catalog::FileCatalog;
 use page::PAGE_SIZE;
+use page::page::api::Page;
 use page::page_id::{FileId, PageId};
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::fs;
 use std::fs::{File, OpenOptions};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
+
+#[cfg(test)]
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[cfg(unix)]
 use std::os::unix::fs::FileExt;
 #[cfg(windows)]
 use std::os::windows::fs::FileExt;
 
+/// Controls when written pages are forced from the OS cache to the disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyncMode {
+    /// Every `write_page` is followed by a `sync_data` on the file. Durable, but slow.
+    EveryWrite,
+    /// Writes are only forced to disk when `sync` is called explicitly (e.g. by a flush).
+    #[default]
+    Explicit,
+}
+
 /// A disk based file manager
 #[derive(Debug)]
 pub struct DiskFileManager {
     files: RwLock<HashMap<FileId, Arc<File>>>,
     file_catalog: Arc<FileCatalog>,
+    sync_mode: SyncMode,
+    /// Allocation high-water mark of each file, in pages: pages below it were handed out by
+    /// `allocate_page` or written directly, pages at or above it are free for `allocate_page`, even
+    /// if the file was already extended past it by `preallocate`. A file gets its mark from its
+    /// length the first time it is needed, so preallocated pages are only reused by the manager that
+    /// preallocated them.
+    ///
+    /// The lock also serializes file extensions, so that two callers never extend a file to the same
+    /// length, or shrink it back after another caller extended it further.
+    allocation: Mutex<HashMap<FileId, u64>>,
+    /// Advisory locks handed out by `with_file_lock`, created on first use
+    file_locks: Mutex<HashMap<FileId, Arc<Mutex<()>>>>,
+    /// Number of `sync_data` calls issued, so that tests can verify the sync mode is honoured
+    #[cfg(test)]
+    sync_calls: AtomicUsize,
 }
 
 impl FileManager for DiskFileManager {
     fn new(file_catalog: Arc<FileCatalog>) -> Self {
-        Self {
-            files: RwLock::new(HashMap::new()),
-            file_catalog,
-        }
+        Self::with_sync_mode(file_catalog, SyncMode::default())
     }
 
+    /// A `destination` that is not exactly one page long is rejected up front and left untouched,
+    /// rather than being filled with a partial page.
     fn read_page(&self, page_id: PageId, destination: &mut [u8]) -> bool {
-        let file = self.get_or_open_file(page_id.file_id);
+        if destination.len() != PAGE_SIZE {
+            return false;
+        }
+
+        let file = self
+            .get_or_open_file(page_id.file_id)
+            .expect("Failed to open file");
 
         let offset = ((page_id.page_number as usize) * (PAGE_SIZE)) as u64;
 
         matches!(Self::read_at(file.as_ref(), destination, offset), Ok(n) if n == PAGE_SIZE)
     }
 
+    /// A trailing partial page (e.g. a torn write) exists, even though `read_page` fails on it.
+    /// A file that was not opened yet is only looked at read-only, so a missing file is reported as
+    /// holding no pages rather than created.
+    fn page_exists(&self, page_id: PageId) -> Result<bool, FileError> {
+        let offset = page_id.page_number as u64 * PAGE_SIZE as u64;
+
+        let cached = self.files.read().unwrap().get(&page_id.file_id).cloned();
+        let file = match cached {
+            Some(file) => file,
+            None => {
+                let path = self
+                    .file_catalog
+                    .get_file_name(page_id.file_id)
+                    .ok_or(FileError::UnknownFile(page_id.file_id))?;
+                match File::open(path) {
+                    Ok(file) => Arc::new(file),
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+                    Err(err) => return Err(err.into()),
+                }
+            }
+        };
+
+        Ok(offset < file.metadata()?.len())
+    }
+
     fn write_page(&self, page_id: PageId, page_data: &[u8]) {
-        let file = self.get_or_open_file(page_id.file_id);
+        let file = self
+            .get_or_open_file(page_id.file_id)
+            .expect("Failed to open file");
 
         let offset = ((page_id.page_number as usize) * (PAGE_SIZE)) as u64;
 
+        // Grow the file explicitly, rather than relying on how the platform handles writes past EOF
+        self.ensure_len(
+            page_id.file_id,
+            file.as_ref(),
+            offset + PAGE_SIZE as u64,
+            true,
+        )
+        .expect("failed to extend file");
+
         let mut written = 0;
         while written < PAGE_SIZE {
             let n = Self::write_at(
@@ -56,16 +128,341 @@ impl FileManager for DiskFileManager {
 
             written += n;
         }
+
+        if self.sync_mode == SyncMode::EveryWrite {
+            self.sync_file(file.as_ref()).expect("disk sync failed");
+        }
+    }
+
+    /// Pages reserved by `preallocate` are handed out first, the file is only extended once they are
+    /// all taken.
+    fn allocate_page(&self, file_id: FileId) -> Result<PageId, FileError> {
+        let file = self.get_or_open_file(file_id)?;
+        let mut marks = self.allocation.lock().unwrap();
+        let mark = Self::high_water_mark(&mut marks, file_id, file.as_ref())?;
+
+        let page_number = u32::try_from(*mark)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::FileTooLarge, e))?;
+        let end = (*mark + 1) * PAGE_SIZE as u64;
+        if file.metadata()?.len() < end {
+            file.set_len(end)?;
+
+            if self.sync_mode == SyncMode::EveryWrite {
+                self.sync_file(file.as_ref())?;
+            }
+        }
+
+        *mark += 1;
+        Ok(PageId::new(file_id, page_number))
+    }
+
+    fn sync(&self, file_id: FileId) -> std::io::Result<()> {
+        let file = self.files.read().unwrap().get(&file_id).cloned();
+
+        // A file that was never opened has nothing pending in the OS cache
+        match file {
+            Some(file) => self.sync_file(file.as_ref()),
+            None => Ok(()),
+        }
     }
 }
 
 impl DiskFileManager {
-    fn get_or_open_file(&self, file_id: FileId) -> Arc<File> {
+    /// Creates a new file manager, choosing when writes are forced to disk.
+    ///
+    /// # Params
+    /// - `file_catalog`: catalog used to resolve `FileId`s to paths
+    /// - `sync_mode`: whether to sync after every write, or only on explicit `sync` calls
+    pub fn with_sync_mode(file_catalog: Arc<FileCatalog>, sync_mode: SyncMode) -> Self {
+        Self {
+            files: RwLock::new(HashMap::new()),
+            file_catalog,
+            sync_mode,
+            allocation: Mutex::new(HashMap::new()),
+            file_locks: Mutex::new(HashMap::new()),
+            #[cfg(test)]
+            sync_calls: AtomicUsize::new(0),
+        }
+    }
+
+    /// Runs `f` while holding the advisory lock of a file.
+    ///
+    /// Single page reads and writes never take this lock. It only coordinates callers that need
+    /// several operations on the same file to appear atomic to each other (e.g. a split writing two
+    /// pages). Calls for the same file are serialized, calls for different files run concurrently.
+    ///
+    /// # Params
+    /// - `file_id`: the ID of the file to lock. It does not need to be registered in the catalog.
+    /// - `f`: the work to run under the lock
+    ///
+    /// # Returns
+    /// Whatever `f` returns
+    pub fn with_file_lock<R>(&self, file_id: FileId, f: impl FnOnce() -> R) -> R {
+        let lock = self
+            .file_locks
+            .lock()
+            .unwrap()
+            .entry(file_id)
+            .or_default()
+            .clone();
+
+        let _guard = lock.lock().unwrap();
+        f()
+    }
+
+    /// Offline integrity check of a whole file.
+    ///
+    /// Reads every page of the file, bypassing the buffer, and validates its header and checksum via
+    /// `Page::try_from_bytes`. Pages which are entirely zeroed were never written and are skipped.
+    /// A trailing partial page (torn write at the end of the file) is reported as failed.
+    ///
+    /// # Params
+    /// - `file_id`: the ID of the file to scrub, as registered in the `FileCatalog`
+    ///
+    /// # Returns
+    /// - `Ok(Vec<u32>)` with the page numbers that failed validation, in ascending order
+    /// - `Err(FileError::UnknownFile)` if the file is not in the catalog
+    /// - `Err(FileError::Io)` if the file could not be opened or read
+    pub fn scrub(&self, file_id: FileId) -> Result<Vec<u32>, FileError> {
+        let path = self
+            .file_catalog
+            .get_file_name(file_id)
+            .ok_or(FileError::UnknownFile(file_id))?;
+        let file = File::open(path)?;
+
+        let file_len = file.metadata()?.len() as usize;
+        let page_count = file_len.div_ceil(PAGE_SIZE);
+
+        let mut failed = Vec::new();
+        for page_number in 0..page_count as u32 {
+            let mut bytes = Box::new([0u8; PAGE_SIZE]);
+            let offset = (page_number as usize * PAGE_SIZE) as u64;
+            let read = Self::read_full_at(&file, &mut bytes[..], offset)?;
+
+            if read < PAGE_SIZE {
+                failed.push(page_number);
+                continue;
+            }
+
+            if bytes.iter().all(|b| *b == 0) {
+                continue;
+            }
+
+            if Page::try_from_bytes(bytes, PageId::new(file_id, page_number)).is_err() {
+                failed.push(page_number);
+            }
+        }
+
+        Ok(failed)
+    }
+
+    /// Writes the raw bytes of a page to a standalone file, bypassing the buffer.
+    /// Meant to capture a page for debugging, e.g. to reload it later with `import_page`.
+    ///
+    /// # Params
+    /// - `page_id`: the page to export
+    /// - `out_path`: the file to write the page to. Overwritten if it already exists.
+    ///
+    /// # Returns
+    /// - `Err(FileError::PageNotInFile)` if the file does not hold the whole page
+    /// - `Err(FileError::UnknownFile)` if the file is not in the catalog
+    /// - `Err(FileError::Io)` if either file could not be read or written
+    pub fn export_page(&self, page_id: PageId, out_path: &Path) -> Result<(), FileError> {
+        let file = self.get_or_open_file(page_id.file_id)?;
+        let mut bytes = Box::new([0u8; PAGE_SIZE]);
+        let offset = (page_id.page_number as usize * PAGE_SIZE) as u64;
+
+        if Self::read_full_at(file.as_ref(), &mut bytes[..], offset)? < PAGE_SIZE {
+            return Err(FileError::PageNotInFile(page_id));
+        }
+
+        fs::write(out_path, &bytes[..])?;
+        Ok(())
+    }
+
+    /// Loads a page snapshot produced by `export_page` and writes it at the offset of `page_id`,
+    /// bypassing the buffer. Nothing is written unless the snapshot is a valid page.
+    ///
+    /// # Params
+    /// - `in_path`: the snapshot file, holding exactly `PAGE_SIZE` bytes
+    /// - `page_id`: the page to overwrite. Must match the page number stored in the snapshot's header.
+    ///
+    /// # Returns
+    /// - `Err(FileError::SnapshotSizeMismatch)` if the snapshot is not exactly `PAGE_SIZE` bytes long
+    /// - `Err(FileError::InvalidPage)` if the snapshot's header or checksum is invalid
+    /// - `Err(FileError::UnknownFile)` if the file is not in the catalog
+    /// - `Err(FileError::Io)` if the snapshot could not be read
+    pub fn import_page(&self, in_path: &Path, page_id: PageId) -> Result<(), FileError> {
+        let snapshot = fs::read(in_path)?;
+        let bytes: Box<[u8; PAGE_SIZE]> =
+            snapshot
+                .into_boxed_slice()
+                .try_into()
+                .map_err(|snapshot: Box<[u8]>| FileError::SnapshotSizeMismatch {
+                    expected: PAGE_SIZE,
+                    actual: snapshot.len(),
+                })?;
+
+        let page = Page::try_from_bytes(bytes, page_id)?;
+        // Surface an unknown file as an error, rather than a panic in write_page
+        self.get_or_open_file(page_id.file_id)?;
+        self.write_page(page_id, page.data());
+        Ok(())
+    }
+
+    /// Reads `count` consecutive pages starting at `start_page` into `destination`, in a single positional read.
+    /// Meant for sequential scans, to amortize the syscall overhead over several pages.
+    ///
+    /// # Params
+    /// - `file_id`: the ID of the file to read from
+    /// - `start_page`: page number of the first page to read
+    /// - `count`: number of pages to read
+    /// - `destination`: buffer receiving the pages back to back. Must be exactly `count * PAGE_SIZE` bytes long.
+    ///
+    /// # Returns
+    /// - `Ok(usize)` with the number of whole pages read. Lower than `count` if the file ends within the range;
+    ///   the bytes of a trailing partial page are left in `destination`, but not counted.
+    /// - `Err(FileError::DestinationSizeMismatch)` if `destination` does not fit exactly `count` pages
+    /// - `Err(FileError::UnknownFile)` if the file is not in the catalog
+    /// - `Err(FileError::Io)` if the read failed
+    pub fn read_pages(
+        &self,
+        file_id: FileId,
+        start_page: u32,
+        count: u32,
+        destination: &mut [u8],
+    ) -> Result<usize, FileError> {
+        let expected = count as usize * PAGE_SIZE;
+        if destination.len() != expected {
+            return Err(FileError::DestinationSizeMismatch {
+                expected,
+                actual: destination.len(),
+            });
+        }
+
+        let file = self.get_or_open_file(file_id)?;
+        let offset = (start_page as usize * PAGE_SIZE) as u64;
+        let read = Self::read_full_at(file.as_ref(), destination, offset)?;
+
+        Ok(read / PAGE_SIZE)
+    }
+
+    /// Writes `data.len() / PAGE_SIZE` consecutive pages starting at `start_page`, in a single positional write.
+    /// The write counterpart of `read_pages`, meant for loaders staging many pages at once. The file is
+    /// extended if the range goes past its end.
+    ///
+    /// # Params
+    /// - `file_id`: the ID of the file to write to
+    /// - `start_page`: page number of the first page to write
+    /// - `data`: the pages, back to back. Must be a multiple of `PAGE_SIZE` bytes long.
+    ///
+    /// # Returns
+    /// - `Err(FileError::PartialPageData)` if `data` does not hold a whole number of pages, in which case
+    ///   nothing is written
+    /// - `Err(FileError::UnknownFile)` if the file is not in the catalog
+    /// - `Err(FileError::Io)` if the write failed
+    pub fn write_pages(
+        &self,
+        file_id: FileId,
+        start_page: u32,
+        data: &[u8],
+    ) -> Result<(), FileError> {
+        if !data.len().is_multiple_of(PAGE_SIZE) {
+            return Err(FileError::PartialPageData(data.len()));
+        }
+
+        let file = self.get_or_open_file(file_id)?;
+        let offset = start_page as u64 * PAGE_SIZE as u64;
+        self.ensure_len(file_id, file.as_ref(), offset + data.len() as u64, true)?;
+        Self::write_full_at(file.as_ref(), data, offset)?;
+
+        if self.sync_mode == SyncMode::EveryWrite {
+            self.sync_file(file.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Grows the file to hold at least `pages` pages up front, so that `allocate_page` can hand them
+    /// out without extending the file one page at a time. The new pages read back as zeroes. Never
+    /// shrinks the file.
+    ///
+    /// The reservation is not persisted: a manager opening the file later treats every page of it as
+    /// taken, so preallocated pages left unused are skipped rather than handed out.
+    ///
+    /// # Returns
+    /// - `Err(FileError::UnknownFile)` if the file is not in the catalog
+    /// - `Err(FileError::Io)` if the file could not be extended
+    pub fn preallocate(&self, file_id: FileId, pages: u32) -> Result<(), FileError> {
+        let file = self.get_or_open_file(file_id)?;
+        self.ensure_len(
+            file_id,
+            file.as_ref(),
+            pages as u64 * PAGE_SIZE as u64,
+            false,
+        )?;
+
+        if self.sync_mode == SyncMode::EveryWrite {
+            self.sync_file(file.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Extends `file` with zeroes up to `len` bytes. Never shrinks it.
+    /// With `claim`, the pages up to `len` are about to be written directly, so they are raised above
+    /// the allocation high-water mark and never handed out by `allocate_page`.
+    fn ensure_len(
+        &self,
+        file_id: FileId,
+        file: &File,
+        len: u64,
+        claim: bool,
+    ) -> std::io::Result<()> {
+        if !claim && file.metadata()?.len() >= len {
+            return Ok(());
+        }
+
+        // Re-check under the allocation lock, so a concurrent caller extending further is never undone.
+        // The mark is read before extending, so the new pages are not mistaken for taken ones.
+        let mut marks = self.allocation.lock().unwrap();
+        let mark = Self::high_water_mark(&mut marks, file_id, file)?;
+        if claim {
+            *mark = (*mark).max(len.div_ceil(PAGE_SIZE as u64));
+        }
+        if file.metadata()?.len() < len {
+            file.set_len(len)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the allocation high-water mark of a file, starting it at the length of the file.
+    /// A trailing partial page (e.g. a torn write) is treated as taken, so its bytes are never reused.
+    fn high_water_mark<'a>(
+        marks: &'a mut HashMap<FileId, u64>,
+        file_id: FileId,
+        file: &File,
+    ) -> std::io::Result<&'a mut u64> {
+        match marks.entry(file_id) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let len = file.metadata()?.len();
+                Ok(entry.insert(len.div_ceil(PAGE_SIZE as u64)))
+            }
+        }
+    }
+
+    fn sync_file(&self, file: &File) -> std::io::Result<()> {
+        #[cfg(test)]
+        self.sync_calls.fetch_add(1, Ordering::Relaxed);
+
+        file.sync_data()
+    }
+
+    fn get_or_open_file(&self, file_id: FileId) -> Result<Arc<File>, FileError> {
         // 1. Fast path — read lock
         {
             let files = self.files.read().unwrap();
             if let Some(file) = files.get(&file_id) {
-                return Arc::clone(file);
+                return Ok(Arc::clone(file));
             }
         }
 
@@ -74,30 +471,29 @@ impl DiskFileManager {
 
         // 3. Double-check
         if let Some(file) = files.get(&file_id) {
-            return Arc::clone(file);
+            return Ok(Arc::clone(file));
         }
 
         // 4. Actually open file
         let path = self
             .file_catalog
             .get_file_name(file_id)
-            .expect("File does not exist");
+            .ok_or(FileError::UnknownFile(file_id))?;
 
-        Self::ensure_parent_dir(&path);
+        Self::ensure_parent_dir(&path)?;
 
         let file = OpenOptions::new()
             .read(true)
             .write(true)
             .create(true)
             .truncate(false)
-            .open(path)
-            .expect("Failed to open file");
+            .open(path)?;
 
         let file = Arc::new(file);
 
         files.insert(file_id, Arc::clone(&file));
 
-        file
+        Ok(file)
     }
 
     #[inline]
@@ -113,6 +509,19 @@ impl DiskFileManager {
         }
     }
 
+    /// Keeps reading until `buf` is full or the end of the file is reached. Returns the number of bytes read.
+    fn read_full_at(file: &File, buf: &mut [u8], offset: u64) -> std::io::Result<usize> {
+        let mut read = 0;
+        while read < buf.len() {
+            let n = Self::read_at(file, &mut buf[read..], offset + read as u64)?;
+            if n == 0 {
+                break;
+            }
+            read += n;
+        }
+        Ok(read)
+    }
+
     #[inline]
     fn write_at(file: &File, buf: &[u8], offset: u64) -> std::io::Result<usize> {
         #[cfg(unix)]
@@ -126,11 +535,847 @@ impl DiskFileManager {
         }
     }
 
+    /// Keeps writing until all of `buf` is written
+    fn write_full_at(file: &File, buf: &[u8], offset: u64) -> std::io::Result<()> {
+        let mut written = 0;
+        while written < buf.len() {
+            let n = Self::write_at(file, &buf[written..], offset + written as u64)?;
+            if n == 0 {
+                return Err(std::io::ErrorKind::WriteZero.into());
+            }
+            written += n;
+        }
+        Ok(())
+    }
+
     fn ensure_parent_dir(path: &Path) -> std::io::Result<()> {
         if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).expect("failed to create data directory");
+            fs::create_dir_all(parent)?;
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod scrub_tests {
+    use super::*;
+    use page::page_type::PageType;
+    use tempfile::TempDir;
+
+    const FILE_ID: FileId = 1;
+
+    fn setup() -> (TempDir, DiskFileManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let catalog = Arc::new(FileCatalog::new());
+        catalog
+            .add_file(FILE_ID, dir.path().join("data.trdb"))
+            .unwrap();
+        (dir, DiskFileManager::new(catalog))
+    }
+
+    fn write_valid_page(manager: &DiskFileManager, page_number: u32) {
+        let page_id = PageId::new(FILE_ID, page_number);
+        let mut page = Page::new_zeroed(page_id);
+        page.initialize(page_id, PageType::Unsorted).unwrap();
+        page.data_mut()[200..210].fill(0xAB);
+        page.update_checksum().unwrap();
+        manager.write_page(page_id, page.data());
+    }
+
+    #[test]
+    fn scrub_all_pages_valid_returns_empty() {
+        let (_dir, manager) = setup();
+        for page_number in 0..4 {
+            write_valid_page(&manager, page_number);
+        }
+
+        assert!(manager.scrub(FILE_ID).unwrap().is_empty());
+    }
+
+    #[test]
+    fn scrub_one_corrupted_page_returns_its_page_number() {
+        let (_dir, manager) = setup();
+        for page_number in 0..4 {
+            write_valid_page(&manager, page_number);
+        }
+
+        // Flip a single byte of row data in page 2, leaving its stored checksum stale
+        let file = manager.get_or_open_file(FILE_ID).unwrap();
+        let offset = (2 * PAGE_SIZE + 205) as u64;
+        DiskFileManager::write_at(&file, &[0xCD], offset).unwrap();
+
+        assert_eq!(manager.scrub(FILE_ID).unwrap(), vec![2]);
+    }
+
+    #[test]
+    fn scrub_skips_never_written_pages() {
+        let (_dir, manager) = setup();
+        // Leaves pages 0..3 as a zero-filled hole in the file
+        write_valid_page(&manager, 3);
+
+        assert!(manager.scrub(FILE_ID).unwrap().is_empty());
+    }
+
+    #[test]
+    fn scrub_unknown_file_returns_error() {
+        let (_dir, manager) = setup();
+
+        assert!(matches!(manager.scrub(42), Err(FileError::UnknownFile(42))));
+    }
+}
+
+#[cfg(test)]
+mod read_pages_tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const FILE_ID: FileId = 1;
+
+    /// Creates a file with `page_count` pages, each page filled with its page number + 1
+    fn setup(page_count: u32) -> (TempDir, DiskFileManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let catalog = Arc::new(FileCatalog::new());
+        catalog
+            .add_file(FILE_ID, dir.path().join("data.trdb"))
+            .unwrap();
+        let manager = DiskFileManager::new(catalog);
+
+        for page_number in 0..page_count {
+            let page_data = [(page_number + 1) as u8; PAGE_SIZE];
+            manager.write_page(PageId::new(FILE_ID, page_number), &page_data);
+        }
+
+        (dir, manager)
+    }
+
+    #[test]
+    fn read_pages_window_within_file() {
+        let (_dir, manager) = setup(5);
+        let mut destination = vec![0u8; 3 * PAGE_SIZE];
+
+        let read = manager.read_pages(FILE_ID, 1, 3, &mut destination).unwrap();
+
+        assert_eq!(read, 3);
+        for (index, page) in destination.chunks(PAGE_SIZE).enumerate() {
+            assert!(page.iter().all(|b| *b == (index + 2) as u8));
+        }
+    }
+
+    #[test]
+    fn read_pages_window_past_eof_returns_complete_pages_only() {
+        let (_dir, manager) = setup(5);
+        let mut destination = vec![0u8; 3 * PAGE_SIZE];
+
+        let read = manager.read_pages(FILE_ID, 3, 3, &mut destination).unwrap();
+
+        assert_eq!(read, 2);
+        assert!(destination[..PAGE_SIZE].iter().all(|b| *b == 4));
+        assert!(
+            destination[PAGE_SIZE..2 * PAGE_SIZE]
+                .iter()
+                .all(|b| *b == 5)
+        );
+        assert!(destination[2 * PAGE_SIZE..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn read_pages_trailing_partial_page_is_not_counted() {
+        let (_dir, manager) = setup(2);
+        let file = manager.get_or_open_file(FILE_ID).unwrap();
+        file.set_len((2 * PAGE_SIZE + 100) as u64).unwrap();
+        let mut destination = vec![0u8; 3 * PAGE_SIZE];
+
+        let read = manager.read_pages(FILE_ID, 0, 3, &mut destination).unwrap();
+
+        assert_eq!(read, 2);
+    }
+
+    #[test]
+    fn read_pages_destination_size_mismatch_returns_error() {
+        let (_dir, manager) = setup(5);
+        let mut destination = vec![0u8; 2 * PAGE_SIZE];
+
+        let result = manager.read_pages(FILE_ID, 0, 3, &mut destination);
+
+        assert!(matches!(
+            result,
+            Err(FileError::DestinationSizeMismatch {
+                expected: 12288,
+                actual: 8192
+            })
+        ));
+    }
+}
+
+#[cfg(test)]
+mod write_pages_tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const FILE_ID: FileId = 1;
+
+    fn setup() -> (TempDir, DiskFileManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let catalog = Arc::new(FileCatalog::new());
+        catalog
+            .add_file(FILE_ID, dir.path().join("data.trdb"))
+            .unwrap();
+        (dir, DiskFileManager::new(catalog))
+    }
+
+    /// Three pages back to back, each filled with its index + 1
+    fn three_pages() -> Vec<u8> {
+        (1..=3u8).flat_map(|value| [value; PAGE_SIZE]).collect()
+    }
+
+    #[test]
+    fn write_pages_each_page_reads_back() {
+        let (_dir, manager) = setup();
+
+        manager.write_pages(FILE_ID, 0, &three_pages()).unwrap();
+
+        let mut destination = [0u8; PAGE_SIZE];
+        for page_number in 0..3 {
+            assert!(manager.read_page(PageId::new(FILE_ID, page_number), &mut destination));
+            assert!(destination.iter().all(|b| *b == page_number as u8 + 1));
+        }
+        assert!(!manager.page_exists(PageId::new(FILE_ID, 3)).unwrap());
+    }
+
+    #[test]
+    fn write_pages_past_end_of_file_extends_it() {
+        let (_dir, manager) = setup();
+        manager.write_page(PageId::new(FILE_ID, 0), &[9u8; PAGE_SIZE]);
+
+        manager.write_pages(FILE_ID, 2, &three_pages()).unwrap();
+
+        let mut destination = vec![0u8; 5 * PAGE_SIZE];
+        assert_eq!(
+            manager.read_pages(FILE_ID, 0, 5, &mut destination).unwrap(),
+            5
+        );
+        let pages: Vec<u8> = destination.chunks(PAGE_SIZE).map(|page| page[0]).collect();
+        // The page in between is left zeroed
+        assert_eq!(pages, vec![9, 0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn write_pages_partial_page_returns_error_and_writes_nothing() {
+        let (dir, manager) = setup();
+
+        let result = manager.write_pages(FILE_ID, 0, &[1u8; PAGE_SIZE + 1]);
+
+        assert!(matches!(result, Err(FileError::PartialPageData(len)) if len == PAGE_SIZE + 1));
+        assert!(!dir.path().join("data.trdb").exists());
+    }
+
+    #[test]
+    fn write_pages_unknown_file_returns_error() {
+        let (_dir, manager) = setup();
+
+        let result = manager.write_pages(42, 0, &three_pages());
+
+        assert!(matches!(result, Err(FileError::UnknownFile(42))));
+    }
+}
+
+#[cfg(test)]
+mod preallocate_tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const FILE_ID: FileId = 1;
+
+    fn setup() -> (TempDir, DiskFileManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let catalog = Arc::new(FileCatalog::new());
+        catalog
+            .add_file(FILE_ID, dir.path().join("data.trdb"))
+            .unwrap();
+        (dir, DiskFileManager::new(catalog))
+    }
+
+    fn file_len(dir: &TempDir) -> u64 {
+        fs::metadata(dir.path().join("data.trdb")).unwrap().len()
+    }
+
+    #[test]
+    fn preallocate_sets_file_length() {
+        let (dir, manager) = setup();
+
+        manager.preallocate(FILE_ID, 8).unwrap();
+
+        assert_eq!(file_len(&dir), 8 * PAGE_SIZE as u64);
+    }
+
+    #[test]
+    fn preallocate_unused_pages_read_back_as_zeroes() {
+        let (_dir, manager) = setup();
+        manager.preallocate(FILE_ID, 4).unwrap();
+
+        let mut destination = [0xEEu8; PAGE_SIZE];
+        for page_number in 0..4 {
+            assert!(manager.read_page(PageId::new(FILE_ID, page_number), &mut destination));
+            assert!(destination.iter().all(|b| *b == 0));
+        }
+    }
+
+    #[test]
+    fn preallocate_never_shrinks_and_keeps_written_pages() {
+        let (dir, manager) = setup();
+        manager.write_page(PageId::new(FILE_ID, 2), &[7u8; PAGE_SIZE]);
+
+        manager.preallocate(FILE_ID, 1).unwrap();
+
+        assert_eq!(file_len(&dir), 3 * PAGE_SIZE as u64);
+        let mut destination = [0u8; PAGE_SIZE];
+        assert!(manager.read_page(PageId::new(FILE_ID, 2), &mut destination));
+        assert!(destination.iter().all(|b| *b == 7));
+    }
+
+    #[test]
+    fn allocate_page_after_preallocate_reuses_preallocated_pages_first() {
+        let (dir, manager) = setup();
+        manager.preallocate(FILE_ID, 2).unwrap();
+
+        let allocated: Vec<PageId> = (0..3)
+            .map(|_| manager.allocate_page(FILE_ID).unwrap())
+            .collect();
+
+        assert_eq!(
+            allocated,
+            vec![
+                PageId::new(FILE_ID, 0),
+                PageId::new(FILE_ID, 1),
+                PageId::new(FILE_ID, 2)
+            ]
+        );
+        assert_eq!(file_len(&dir), 3 * PAGE_SIZE as u64);
+    }
+
+    #[test]
+    fn allocate_page_after_preallocate_skips_directly_written_pages() {
+        let (_dir, manager) = setup();
+        manager.allocate_page(FILE_ID).unwrap();
+        manager.preallocate(FILE_ID, 4).unwrap();
+        manager.write_page(PageId::new(FILE_ID, 2), &[7u8; PAGE_SIZE]);
+
+        assert_eq!(
+            manager.allocate_page(FILE_ID).unwrap(),
+            PageId::new(FILE_ID, 3)
+        );
+    }
+
+    #[test]
+    fn allocate_page_new_manager_treats_preallocated_pages_as_taken() {
+        let (dir, manager) = setup();
+        manager.preallocate(FILE_ID, 4).unwrap();
+        drop(manager);
+
+        let catalog = Arc::new(FileCatalog::new());
+        catalog
+            .add_file(FILE_ID, dir.path().join("data.trdb"))
+            .unwrap();
+        let manager = DiskFileManager::new(catalog);
+
+        assert_eq!(
+            manager.allocate_page(FILE_ID).unwrap(),
+            PageId::new(FILE_ID, 4)
+        );
+    }
+
+    #[test]
+    fn preallocate_unknown_file_returns_error() {
+        let (_dir, manager) = setup();
+
+        assert!(matches!(
+            manager.preallocate(42, 4),
+            Err(FileError::UnknownFile(42))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod sync_tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const FILE_ID: FileId = 1;
+
+    fn setup(sync_mode: SyncMode) -> (TempDir, DiskFileManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let catalog = Arc::new(FileCatalog::new());
+        catalog
+            .add_file(FILE_ID, dir.path().join("data.trdb"))
+            .unwrap();
+        (dir, DiskFileManager::with_sync_mode(catalog, sync_mode))
+    }
+
+    fn sync_calls(manager: &DiskFileManager) -> usize {
+        manager.sync_calls.load(Ordering::Relaxed)
+    }
+
+    #[test]
+    fn new_defaults_to_explicit_sync() {
+        let manager = DiskFileManager::new(Arc::new(FileCatalog::new()));
+
+        assert_eq!(manager.sync_mode, SyncMode::Explicit);
+    }
+
+    #[test]
+    fn write_page_every_write_mode_syncs_each_write() {
+        let (_dir, manager) = setup(SyncMode::EveryWrite);
+
+        for page_number in 0..3 {
+            manager.write_page(PageId::new(FILE_ID, page_number), &[1u8; PAGE_SIZE]);
+        }
+
+        assert_eq!(sync_calls(&manager), 3);
+    }
+
+    #[test]
+    fn write_page_explicit_mode_syncs_only_when_requested() {
+        let (_dir, manager) = setup(SyncMode::Explicit);
+
+        for page_number in 0..3 {
+            manager.write_page(PageId::new(FILE_ID, page_number), &[1u8; PAGE_SIZE]);
+        }
+        assert_eq!(sync_calls(&manager), 0);
+
+        manager.sync(FILE_ID).unwrap();
+        assert_eq!(sync_calls(&manager), 1);
+    }
+
+    #[test]
+    fn sync_file_never_opened_is_a_no_op() {
+        let (_dir, manager) = setup(SyncMode::Explicit);
+
+        manager.sync(FILE_ID).unwrap();
+
+        assert_eq!(sync_calls(&manager), 0);
+    }
+}
+
+#[cfg(test)]
+mod page_exists_tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const FILE_ID: FileId = 1;
+
+    fn setup() -> (TempDir, DiskFileManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let catalog = Arc::new(FileCatalog::new());
+        catalog
+            .add_file(FILE_ID, dir.path().join("data.trdb"))
+            .unwrap();
+        (dir, DiskFileManager::new(catalog))
+    }
+
+    #[test]
+    fn page_exists_within_file_returns_true() {
+        let (_dir, manager) = setup();
+        manager.allocate_page(FILE_ID).unwrap();
+        manager.allocate_page(FILE_ID).unwrap();
+
+        assert!(manager.page_exists(PageId::new(FILE_ID, 0)).unwrap());
+        assert!(manager.page_exists(PageId::new(FILE_ID, 1)).unwrap());
+    }
+
+    #[test]
+    fn page_exists_missing_file_returns_false_without_creating_it() {
+        let (dir, manager) = setup();
+
+        assert!(!manager.page_exists(PageId::new(FILE_ID, 0)).unwrap());
+        assert!(!dir.path().join("data.trdb").exists());
+    }
+
+    #[test]
+    fn page_exists_beyond_end_of_file_returns_false() {
+        let (_dir, manager) = setup();
+        manager.allocate_page(FILE_ID).unwrap();
+
+        assert!(!manager.page_exists(PageId::new(FILE_ID, 1)).unwrap());
+        assert!(!manager.page_exists(PageId::new(FILE_ID, 100)).unwrap());
+    }
+
+    #[test]
+    fn page_exists_empty_file_returns_false() {
+        let (_dir, manager) = setup();
+
+        assert!(!manager.page_exists(PageId::new(FILE_ID, 0)).unwrap());
+    }
+
+    #[test]
+    fn page_exists_trailing_partial_page_returns_true_but_read_fails() {
+        let (dir, manager) = setup();
+        fs::write(dir.path().join("data.trdb"), [1u8; PAGE_SIZE + 10]).unwrap();
+        let page_id = PageId::new(FILE_ID, 1);
+
+        assert!(manager.page_exists(page_id).unwrap());
+        let mut bytes = [0u8; PAGE_SIZE];
+        assert!(!manager.read_page(page_id, &mut bytes));
+    }
+
+    #[test]
+    fn page_exists_unknown_file_returns_error() {
+        let (_dir, manager) = setup();
+
+        assert!(matches!(
+            manager.page_exists(PageId::new(42, 0)),
+            Err(FileError::UnknownFile(42))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod allocate_page_tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const FILE_ID: FileId = 1;
+
+    fn setup() -> (TempDir, DiskFileManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let catalog = Arc::new(FileCatalog::new());
+        catalog
+            .add_file(FILE_ID, dir.path().join("data.trdb"))
+            .unwrap();
+        (dir, DiskFileManager::new(catalog))
+    }
+
+    fn file_len(dir: &TempDir) -> u64 {
+        fs::metadata(dir.path().join("data.trdb")).unwrap().len()
+    }
+
+    #[test]
+    fn allocate_page_empty_file_starts_at_zero() {
+        let (dir, manager) = setup();
+
+        let page_id = manager.allocate_page(FILE_ID).unwrap();
+
+        assert_eq!(page_id, PageId::new(FILE_ID, 0));
+        assert_eq!(file_len(&dir), PAGE_SIZE as u64);
+    }
+
+    #[test]
+    fn allocate_page_returns_distinct_zeroed_pages() {
+        let (dir, manager) = setup();
+
+        let first = manager.allocate_page(FILE_ID).unwrap();
+        let second = manager.allocate_page(FILE_ID).unwrap();
+
+        assert_eq!(first.page_number, 0);
+        assert_eq!(second.page_number, 1);
+        assert_eq!(file_len(&dir), 2 * PAGE_SIZE as u64);
+        let mut bytes = [0xFFu8; PAGE_SIZE];
+        assert!(manager.read_page(second, &mut bytes));
+        assert!(bytes.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn allocate_page_after_written_pages_appends() {
+        let (_dir, manager) = setup();
+        manager.write_page(PageId::new(FILE_ID, 2), &[1u8; PAGE_SIZE]);
+
+        let page_id = manager.allocate_page(FILE_ID).unwrap();
+
+        assert_eq!(page_id.page_number, 3);
+    }
+
+    #[test]
+    fn allocate_page_concurrent_callers_get_distinct_pages() {
+        let (_dir, manager) = setup();
+
+        let mut page_numbers: Vec<u32> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| s.spawn(|| manager.allocate_page(FILE_ID).unwrap().page_number))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        page_numbers.sort();
+
+        assert_eq!(page_numbers, (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn allocate_page_unknown_file_returns_error() {
+        let manager = DiskFileManager::new(Arc::new(FileCatalog::new()));
+
+        assert!(matches!(
+            manager.allocate_page(FILE_ID),
+            Err(FileError::UnknownFile(FILE_ID))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod read_page_tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const FILE_ID: FileId = 1;
+
+    fn setup() -> (TempDir, DiskFileManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let catalog = Arc::new(FileCatalog::new());
+        catalog
+            .add_file(FILE_ID, dir.path().join("data.trdb"))
+            .unwrap();
+        let manager = DiskFileManager::new(catalog);
+        manager.write_page(PageId::new(FILE_ID, 0), &[7u8; PAGE_SIZE]);
+        (dir, manager)
+    }
+
+    #[test]
+    fn read_page_exact_destination_copies_page() {
+        let (_dir, manager) = setup();
+        let mut bytes = [0u8; PAGE_SIZE];
+
+        assert!(manager.read_page(PageId::new(FILE_ID, 0), &mut bytes));
+        assert!(bytes.iter().all(|b| *b == 7));
+    }
+
+    #[test]
+    fn read_page_undersized_destination_returns_false_and_leaves_it_untouched() {
+        let (_dir, manager) = setup();
+        let mut bytes = vec![0xEEu8; PAGE_SIZE - 1];
+
+        assert!(!manager.read_page(PageId::new(FILE_ID, 0), &mut bytes));
+        assert!(bytes.iter().all(|b| *b == 0xEE));
+    }
+
+    #[test]
+    fn read_page_oversized_destination_returns_false_and_leaves_it_untouched() {
+        let (_dir, manager) = setup();
+        let mut bytes = vec![0xEEu8; PAGE_SIZE + 1];
+
+        assert!(!manager.read_page(PageId::new(FILE_ID, 0), &mut bytes));
+        assert!(bytes.iter().all(|b| *b == 0xEE));
+    }
+}
+
+#[cfg(test)]
+mod write_page_tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const FILE_ID: FileId = 1;
+
+    fn setup() -> (TempDir, DiskFileManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let catalog = Arc::new(FileCatalog::new());
+        catalog
+            .add_file(FILE_ID, dir.path().join("data.trdb"))
+            .unwrap();
+        (dir, DiskFileManager::new(catalog))
+    }
+
+    fn file_len(dir: &TempDir) -> u64 {
+        fs::metadata(dir.path().join("data.trdb")).unwrap().len()
+    }
+
+    #[test]
+    fn write_page_past_eof_grows_file_to_page_end() {
+        let (dir, manager) = setup();
+        manager.write_page(PageId::new(FILE_ID, 1), &[1u8; PAGE_SIZE]);
+
+        manager.write_page(PageId::new(FILE_ID, 5), &[6u8; PAGE_SIZE]);
+
+        assert_eq!(file_len(&dir), 6 * PAGE_SIZE as u64);
+    }
+
+    #[test]
+    fn write_page_past_eof_intermediate_pages_read_as_zeroes() {
+        let (_dir, manager) = setup();
+        manager.write_page(PageId::new(FILE_ID, 5), &[6u8; PAGE_SIZE]);
+
+        for page_number in 0..5 {
+            let mut bytes = [0xFFu8; PAGE_SIZE];
+            assert!(manager.read_page(PageId::new(FILE_ID, page_number), &mut bytes));
+            assert!(bytes.iter().all(|b| *b == 0));
+        }
+        let mut bytes = [0u8; PAGE_SIZE];
+        assert!(manager.read_page(PageId::new(FILE_ID, 5), &mut bytes));
+        assert!(bytes.iter().all(|b| *b == 6));
+    }
+
+    #[test]
+    fn write_page_within_file_keeps_length() {
+        let (dir, manager) = setup();
+        manager.write_page(PageId::new(FILE_ID, 3), &[1u8; PAGE_SIZE]);
+
+        manager.write_page(PageId::new(FILE_ID, 1), &[2u8; PAGE_SIZE]);
+
+        assert_eq!(file_len(&dir), 4 * PAGE_SIZE as u64);
+    }
+}
+
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use page::page_type::PageType;
+    use tempfile::TempDir;
+
+    const FILE_ID: FileId = 1;
+
+    fn setup() -> (TempDir, DiskFileManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let catalog = Arc::new(FileCatalog::new());
+        catalog
+            .add_file(FILE_ID, dir.path().join("data.trdb"))
+            .unwrap();
+        (dir, DiskFileManager::new(catalog))
+    }
+
+    fn valid_page(page_id: PageId, fill: u8) -> Page {
+        let mut page = Page::new_zeroed(page_id);
+        page.initialize(page_id, PageType::Unsorted).unwrap();
+        page.data_mut()[200..210].fill(fill);
+        page.update_checksum().unwrap();
+        page
+    }
+
+    fn read(manager: &DiskFileManager, page_id: PageId) -> [u8; PAGE_SIZE] {
+        let mut bytes = [0u8; PAGE_SIZE];
+        assert!(manager.read_page(page_id, &mut bytes));
+        bytes
+    }
+
+    #[test]
+    fn export_then_import_round_trips_page() {
+        let (dir, manager) = setup();
+        let page_id = PageId::new(FILE_ID, 2);
+        let original = valid_page(page_id, 0xAB);
+        manager.write_page(page_id, original.data());
+        let snapshot = dir.path().join("page.snapshot");
+
+        manager.export_page(page_id, &snapshot).unwrap();
+        assert_eq!(fs::read(&snapshot).unwrap(), original.data().to_vec());
+
+        // Overwrite the page, then restore it from the snapshot
+        manager.write_page(page_id, valid_page(page_id, 0xCD).data());
+        manager.import_page(&snapshot, page_id).unwrap();
+
+        assert_eq!(&read(&manager, page_id), original.data());
+    }
+
+    #[test]
+    fn export_page_past_eof_returns_error() {
+        let (dir, manager) = setup();
+        manager.write_page(PageId::new(FILE_ID, 0), &[1u8; PAGE_SIZE]);
+
+        let result = manager.export_page(PageId::new(FILE_ID, 3), &dir.path().join("out"));
+
+        assert!(
+            matches!(result, Err(FileError::PageNotInFile(page_id)) if page_id.page_number == 3)
+        );
+    }
+
+    #[test]
+    fn import_page_wrong_size_returns_error_and_leaves_page() {
+        let (dir, manager) = setup();
+        let page_id = PageId::new(FILE_ID, 0);
+        let original = valid_page(page_id, 0xAB);
+        manager.write_page(page_id, original.data());
+        let snapshot = dir.path().join("page.snapshot");
+        fs::write(&snapshot, &original.data()[..PAGE_SIZE - 1]).unwrap();
+
+        let result = manager.import_page(&snapshot, page_id);
+
+        assert!(matches!(
+            result,
+            Err(FileError::SnapshotSizeMismatch {
+                expected: PAGE_SIZE,
+                actual: 4095
+            })
+        ));
+        assert_eq!(&read(&manager, page_id), original.data());
+    }
+
+    #[test]
+    fn import_page_invalid_header_returns_error_and_leaves_page() {
+        let (dir, manager) = setup();
+        let page_id = PageId::new(FILE_ID, 0);
+        let original = valid_page(page_id, 0xAB);
+        manager.write_page(page_id, original.data());
+        let snapshot = dir.path().join("page.snapshot");
+        // Captured from another page, so its header does not match page 0
+        fs::write(&snapshot, valid_page(PageId::new(FILE_ID, 5), 0xCD).data()).unwrap();
+
+        let result = manager.import_page(&snapshot, page_id);
+
+        assert!(matches!(result, Err(FileError::InvalidPage(_))));
+        assert_eq!(&read(&manager, page_id), original.data());
+    }
+
+    #[test]
+    fn import_page_corrupted_snapshot_returns_error() {
+        let (dir, manager) = setup();
+        let page_id = PageId::new(FILE_ID, 0);
+        let mut bytes = *valid_page(page_id, 0xAB).data();
+        bytes[205] ^= 0xFF;
+        let snapshot = dir.path().join("page.snapshot");
+        fs::write(&snapshot, bytes).unwrap();
+
+        let result = manager.import_page(&snapshot, page_id);
+
+        assert!(matches!(result, Err(FileError::InvalidPage(_))));
+    }
+}
+
+#[cfg(test)]
+mod file_lock_tests {
+    use super::*;
+    use std::sync::Barrier;
+    use std::sync::atomic::AtomicBool;
+    use std::time::Duration;
+
+    #[test]
+    fn with_file_lock_returns_closure_result() {
+        let manager = DiskFileManager::new(Arc::new(FileCatalog::new()));
+
+        assert_eq!(manager.with_file_lock(1, || 42), 42);
+    }
+
+    #[test]
+    fn with_file_lock_same_file_serializes() {
+        let manager = DiskFileManager::new(Arc::new(FileCatalog::new()));
+        let inside = AtomicBool::new(false);
+        let overlaps = AtomicUsize::new(0);
+
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..10 {
+                        manager.with_file_lock(1, || {
+                            if inside.swap(true, Ordering::SeqCst) {
+                                overlaps.fetch_add(1, Ordering::SeqCst);
+                            }
+                            std::thread::sleep(Duration::from_millis(1));
+                            inside.store(false, Ordering::SeqCst);
+                        });
+                    }
+                });
+            }
+        });
+
+        assert_eq!(overlaps.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn with_file_lock_different_files_run_concurrently() {
+        let manager = DiskFileManager::new(Arc::new(FileCatalog::new()));
+        // Both closures must be running at the same time to get past the barrier
+        let both_inside = Barrier::new(2);
+
+        std::thread::scope(|s| {
+            for file_id in [1, 2] {
+                let manager = &manager;
+                let both_inside = &both_inside;
+                s.spawn(move || manager.with_file_lock(file_id, || both_inside.wait()));
+            }
+        });
+    }
+}
@@ -0,0 +1,68 @@
+//! Error types surfaced by the `file` crate
+
+use page::errors::page_error::PageError;
+use page::page_id::{FileId, PageId};
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// File catalog error.
+#[derive(Debug, Error)]
+pub enum CatalogError {
+    /// The `FileId` is already mapped to a file
+    #[error("File {0} is already registered in the file catalog")]
+    FileIdInUse(FileId),
+    /// A reloaded catalog maps a `FileId` to a different file than the one already registered
+    #[error(
+        "File {file_id} is registered as {existing:?}, but the reloaded catalog maps it to {found:?}"
+    )]
+    ConflictingPath {
+        /// The `FileId` mapped twice
+        file_id: FileId,
+        /// The file the `FileId` is registered as
+        existing: PathBuf,
+        /// The file the reloaded catalog maps the `FileId` to
+        found: PathBuf,
+    },
+    /// The serialized catalog could not be parsed
+    #[error("File catalog is malformed")]
+    Malformed,
+    /// The catalog file could not be read or written
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+}
+
+/// File error.
+#[derive(Debug, Error)]
+pub enum FileError {
+    /// The `FileId` was not registered in the `FileCatalog`
+    #[error("File {0} is not registered in the file catalog")]
+    UnknownFile(FileId),
+    /// The caller-provided buffer does not match the number of bytes to transfer
+    #[error("Destination buffer is {actual} bytes long, expected {expected}")]
+    DestinationSizeMismatch {
+        /// Number of bytes required by the operation
+        expected: usize,
+        /// Actual length of the provided buffer
+        actual: usize,
+    },
+    /// Bulk page data that does not hold a whole number of pages
+    #[error("Page data is {0} bytes long, not a multiple of the page size")]
+    PartialPageData(usize),
+    /// The page lies past the end of its file
+    #[error("Page {0} is past the end of its file")]
+    PageNotInFile(PageId),
+    /// A page snapshot does not hold exactly one page
+    #[error("Page snapshot is {actual} bytes long, expected {expected}")]
+    SnapshotSizeMismatch {
+        /// Size of a page
+        expected: usize,
+        /// Actual size of the snapshot
+        actual: usize,
+    },
+    /// The bytes of a page failed header or checksum validation
+    #[error("Invalid page: {0}")]
+    InvalidPage(#[from] PageError),
+    /// The underlying I/O operation failed
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+}
@@ -1,11 +1,25 @@
 //! A file catalog mapping file IDs to their file names
 
+use crate::errors::CatalogError;
+use binary_helpers::le::{read_bytes, read_le};
 use page::page_id::FileId;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::hash_map::Entry;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 
 /// Catalog holding the mappings between a `FileId` (a `u32`) and its corresponding filename (represented as a `PathBuf`)
+///
+/// The catalog is serialized as a `u32` mapping count, followed by one entry per mapping, in increasing
+/// `FileId` order:
+///
+/// | Field      | Type       | Description |
+/// |------------|------------|-------------|
+/// | `file_id`  | u32        | The registered file. |
+/// | `path_len` | u16        | Length of the path, in bytes. |
+/// | `path`     | `path_len` | UTF-8 path of the file. |
 #[derive(Debug)]
 pub struct FileCatalog {
     mappings: RwLock<HashMap<FileId, PathBuf>>,
@@ -45,11 +59,297 @@ impl FileCatalog {
     /// # Params
     /// - `file_id` (`u32`): the ID of the file to register
     /// - `path` (`PathBuf`): the name of the file to register
-    pub fn add_file(&self, file_id: FileId, path: PathBuf) {
+    ///
+    /// # Returns
+    /// `Err(CatalogError::FileIdInUse)` if `file_id` is already mapped, in which case the existing mapping is kept
+    pub fn add_file(&self, file_id: FileId, path: PathBuf) -> Result<(), CatalogError> {
+        let mut guard = self
+            .mappings
+            .write()
+            .expect("FileCatalog poisoned: another thread panicked while holding the lock");
+        if guard.contains_key(&file_id) {
+            return Err(CatalogError::FileIdInUse(file_id));
+        }
+        guard.insert(file_id, path);
+        Ok(())
+    }
+
+    /// Registers a file under a newly allocated `FileId`
+    ///
+    /// Ids are handed out in increasing order, starting from 1, always above the highest id already
+    /// in the catalog (including the ones added explicitly via `add_file`).
+    ///
+    /// # Params
+    /// - `path` (`PathBuf`): the name of the file to register
+    ///
+    /// # Returns
+    /// The `FileId` allocated for the file
+    pub fn register_file(&self, path: PathBuf) -> FileId {
         let mut guard = self
             .mappings
             .write()
             .expect("FileCatalog poisoned: another thread panicked while holding the lock");
+        let file_id = guard
+            .keys()
+            .max()
+            .map_or(1, |max| max.checked_add(1).expect("FileId space exhausted"));
         guard.insert(file_id, path);
+        file_id
+    }
+
+    /// Writes the catalog to `path`, replacing whatever the file held before.
+    /// The catalog is written and synced to a temporary file first, then renamed over `path`, and the
+    /// directory holding it is synced as well, so a crash midway leaves either the previous or the new
+    /// version intact.
+    ///
+    /// # Returns
+    /// - `Err(CatalogError::Io)` if the file could not be written
+    /// - `Err(CatalogError::Malformed)` if a registered path is not valid UTF-8, or too long to be stored
+    pub fn save(&self, path: &Path) -> Result<(), CatalogError> {
+        let bytes = self.to_bytes()?;
+        let tmp_path = path.with_extension("tmp");
+
+        let mut tmp = File::create(&tmp_path)?;
+        tmp.write_all(&bytes)?;
+        tmp.sync_all()?;
+        drop(tmp);
+
+        std::fs::rename(&tmp_path, path)?;
+        Self::sync_parent_dir(path)?;
+        Ok(())
+    }
+
+    /// Syncs the directory holding `path`, so that a rename into it survives a crash.
+    /// Directories cannot be opened as files on every platform, there the rename is left to the OS.
+    fn sync_parent_dir(path: &Path) -> std::io::Result<()> {
+        #[cfg(unix)]
+        {
+            let parent = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            };
+            File::open(parent)?.sync_all()?;
+        }
+        #[cfg(not(unix))]
+        let _ = path;
+
+        Ok(())
+    }
+
+    /// Merges the mappings of the catalog saved at `path` into this one, picking up files registered
+    /// by another process since this catalog was built. Mappings already in this catalog are kept.
+    ///
+    /// The merge is all or nothing: if any mapping conflicts, the catalog is left untouched.
+    ///
+    /// # Params
+    /// - `path` (`&Path`): a catalog previously written with `save`
+    ///
+    /// # Returns
+    /// - The number of mappings added
+    /// - `Err(CatalogError::Io)` if the file could not be read
+    /// - `Err(CatalogError::Malformed)` if the file is truncated, has trailing bytes, or holds a path that
+    ///   is not valid UTF-8
+    /// - `Err(CatalogError::ConflictingPath)` if the file maps an already registered `FileId` to another path
+    pub fn reload_from(&self, path: &Path) -> Result<usize, CatalogError> {
+        let loaded = Self::from_bytes(&std::fs::read(path)?)?;
+
+        let mut guard = self
+            .mappings
+            .write()
+            .expect("FileCatalog poisoned: another thread panicked while holding the lock");
+        for (file_id, found) in &loaded {
+            if let Some(existing) = guard.get(file_id)
+                && existing != found
+            {
+                return Err(CatalogError::ConflictingPath {
+                    file_id: *file_id,
+                    existing: existing.clone(),
+                    found: found.clone(),
+                });
+            }
+        }
+
+        let mut added = 0;
+        for (file_id, path) in loaded {
+            if let Entry::Vacant(entry) = guard.entry(file_id) {
+                entry.insert(path);
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>, CatalogError> {
+        let guard = self
+            .mappings
+            .read()
+            .expect("FileCatalog poisoned: another thread panicked while holding the lock");
+        let mut file_ids: Vec<FileId> = guard.keys().copied().collect();
+        file_ids.sort_unstable();
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(file_ids.len() as u32).to_le_bytes());
+        for file_id in file_ids {
+            let path = guard[&file_id].to_str().ok_or(CatalogError::Malformed)?;
+            let path_len = u16::try_from(path.len()).map_err(|_| CatalogError::Malformed)?;
+            bytes.extend_from_slice(&file_id.to_le_bytes());
+            bytes.extend_from_slice(&path_len.to_le_bytes());
+            bytes.extend_from_slice(path.as_bytes());
+        }
+        Ok(bytes)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Vec<(FileId, PathBuf)>, CatalogError> {
+        let mut mappings = Vec::new();
+        let mut offset = 0;
+
+        let count = read_le::<u32>(bytes, offset).map_err(|_| CatalogError::Malformed)?;
+        offset += size_of::<u32>();
+        for _ in 0..count {
+            let file_id = read_le::<u32>(bytes, offset).map_err(|_| CatalogError::Malformed)?;
+            offset += size_of::<u32>();
+            let path_len =
+                read_le::<u16>(bytes, offset).map_err(|_| CatalogError::Malformed)? as usize;
+            offset += size_of::<u16>();
+            let path = read_bytes(bytes, offset, path_len).map_err(|_| CatalogError::Malformed)?;
+            let path = String::from_utf8(path.to_vec()).map_err(|_| CatalogError::Malformed)?;
+            offset += path_len;
+            mappings.push((file_id, PathBuf::from(path)));
+        }
+
+        if offset != bytes.len() {
+            return Err(CatalogError::Malformed);
+        }
+        Ok(mappings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_file_hands_out_distinct_ids() {
+        let catalog = FileCatalog::new();
+
+        let first = catalog.register_file(PathBuf::from("a.tbl"));
+        let second = catalog.register_file(PathBuf::from("b.tbl"));
+
+        assert_eq!(first, 1);
+        assert_eq!(second, 2);
+        assert_eq!(catalog.get_file_name(first), Some(PathBuf::from("a.tbl")));
+        assert_eq!(catalog.get_file_name(second), Some(PathBuf::from("b.tbl")));
+    }
+
+    #[test]
+    fn register_file_skips_ids_added_explicitly() {
+        let catalog = FileCatalog::new();
+        catalog.add_file(5, PathBuf::from("a.tbl")).unwrap();
+
+        let file_id = catalog.register_file(PathBuf::from("b.tbl"));
+
+        assert_eq!(file_id, 6);
+    }
+
+    #[test]
+    fn add_file_existing_id_returns_error_and_keeps_mapping() {
+        let catalog = FileCatalog::new();
+        catalog.add_file(1, PathBuf::from("a.tbl")).unwrap();
+
+        let result = catalog.add_file(1, PathBuf::from("b.tbl"));
+
+        assert!(matches!(result, Err(CatalogError::FileIdInUse(1))));
+        assert_eq!(catalog.get_file_name(1), Some(PathBuf::from("a.tbl")));
+    }
+
+    fn saved_catalog(dir: &Path, mappings: &[(FileId, &str)]) -> PathBuf {
+        let catalog = FileCatalog::new();
+        for (file_id, path) in mappings {
+            catalog.add_file(*file_id, PathBuf::from(path)).unwrap();
+        }
+        let path = dir.join("files.cat");
+        catalog.save(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn save_replaces_previous_version_and_leaves_no_temporary_file() {
+        let dir = tempfile::tempdir().unwrap();
+        saved_catalog(dir.path(), &[(1, "a.tbl")]);
+        let saved = saved_catalog(dir.path(), &[(1, "a.tbl"), (2, "b.tbl")]);
+        let catalog = FileCatalog::new();
+
+        assert_eq!(catalog.reload_from(&saved).unwrap(), 2);
+        assert!(!saved.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn reload_from_adds_new_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let saved = saved_catalog(dir.path(), &[(1, "a.tbl"), (2, "b.tbl"), (7, "c.tbl")]);
+        let catalog = FileCatalog::new();
+
+        let added = catalog.reload_from(&saved).unwrap();
+
+        assert_eq!(added, 3);
+        assert_eq!(catalog.get_file_name(1), Some(PathBuf::from("a.tbl")));
+        assert_eq!(catalog.get_file_name(2), Some(PathBuf::from("b.tbl")));
+        assert_eq!(catalog.get_file_name(7), Some(PathBuf::from("c.tbl")));
+        assert_eq!(catalog.register_file(PathBuf::from("d.tbl")), 8);
+    }
+
+    #[test]
+    fn reload_from_leaves_existing_ids_intact() {
+        let dir = tempfile::tempdir().unwrap();
+        let saved = saved_catalog(dir.path(), &[(1, "a.tbl"), (2, "b.tbl")]);
+        let catalog = FileCatalog::new();
+        catalog.add_file(1, PathBuf::from("a.tbl")).unwrap();
+        catalog.add_file(3, PathBuf::from("local.tbl")).unwrap();
+
+        let added = catalog.reload_from(&saved).unwrap();
+
+        assert_eq!(added, 1);
+        assert_eq!(catalog.get_file_name(1), Some(PathBuf::from("a.tbl")));
+        assert_eq!(catalog.get_file_name(2), Some(PathBuf::from("b.tbl")));
+        assert_eq!(catalog.get_file_name(3), Some(PathBuf::from("local.tbl")));
+    }
+
+    #[test]
+    fn reload_from_conflicting_path_returns_error_and_adds_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let saved = saved_catalog(dir.path(), &[(1, "a.tbl"), (2, "other.tbl")]);
+        let catalog = FileCatalog::new();
+        catalog.add_file(2, PathBuf::from("b.tbl")).unwrap();
+
+        let result = catalog.reload_from(&saved);
+
+        assert!(matches!(
+            result,
+            Err(CatalogError::ConflictingPath { file_id: 2, ref existing, ref found })
+                if existing == Path::new("b.tbl") && found == Path::new("other.tbl")
+        ));
+        assert_eq!(catalog.get_file_name(1), None);
+        assert_eq!(catalog.get_file_name(2), Some(PathBuf::from("b.tbl")));
+    }
+
+    #[test]
+    fn reload_from_truncated_file_returns_malformed() {
+        let dir = tempfile::tempdir().unwrap();
+        let saved = saved_catalog(dir.path(), &[(1, "a.tbl")]);
+        let bytes = std::fs::read(&saved).unwrap();
+        std::fs::write(&saved, &bytes[..bytes.len() - 1]).unwrap();
+
+        let result = FileCatalog::new().reload_from(&saved);
+
+        assert!(matches!(result, Err(CatalogError::Malformed)));
+    }
+
+    #[test]
+    fn reload_from_missing_file_returns_io_error() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let result = FileCatalog::new().reload_from(&dir.path().join("files.cat"));
+
+        assert!(matches!(result, Err(CatalogError::Io(_))));
     }
 }
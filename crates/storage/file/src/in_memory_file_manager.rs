@@ -0,0 +1,156 @@
+//! A file manager keeping all pages in memory, for tests that should not touch the disk.
+
+use crate::api::FileManager;
+use crate::errors::FileError;
+use crate::file_catalog::FileCatalog;
+use page::PAGE_SIZE;
+use page::page_id::{FileId, PageId};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// An in-memory file manager, mirroring the behavior of `DiskFileManager` without any disk access.
+///
+/// Every file is as long as its highest written or allocated page. Pages within that length which were
+/// never written read back as zeroes, while reads past the end fail, just like on disk. Files do not need
+/// to be registered in the `FileCatalog`, and `sync` has nothing to do.
+#[derive(Debug)]
+pub struct InMemoryFileManager {
+    pages: RwLock<HashMap<PageId, [u8; PAGE_SIZE]>>,
+}
+
+impl FileManager for InMemoryFileManager {
+    fn new(_: Arc<FileCatalog>) -> Self {
+        Self {
+            pages: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn read_page(&self, page_id: PageId, destination: &mut [u8]) -> bool {
+        if destination.len() != PAGE_SIZE {
+            return false;
+        }
+
+        let pages = self.pages.read().unwrap();
+        match pages.get(&page_id) {
+            Some(bytes) => destination.copy_from_slice(bytes),
+            None if page_id.page_number < Self::page_count(&pages, page_id.file_id) => {
+                destination.fill(0)
+            }
+            None => return false,
+        }
+        true
+    }
+
+    fn page_exists(&self, page_id: PageId) -> Result<bool, FileError> {
+        let pages = self.pages.read().unwrap();
+        Ok(page_id.page_number < Self::page_count(&pages, page_id.file_id))
+    }
+
+    fn write_page(&self, page_id: PageId, page_data: &[u8]) {
+        let bytes: [u8; PAGE_SIZE] = page_data
+            .try_into()
+            .expect("page data must be exactly one page long");
+        self.pages.write().unwrap().insert(page_id, bytes);
+    }
+
+    fn allocate_page(&self, file_id: FileId) -> Result<PageId, FileError> {
+        let mut pages = self.pages.write().unwrap();
+        let page_id = PageId::new(file_id, Self::page_count(&pages, file_id));
+        pages.insert(page_id, [0; PAGE_SIZE]);
+        Ok(page_id)
+    }
+}
+
+impl InMemoryFileManager {
+    /// Returns the number of pages in the file, i.e. one past its highest written or allocated page.
+    fn page_count(pages: &HashMap<PageId, [u8; PAGE_SIZE]>, file_id: FileId) -> u32 {
+        pages
+            .keys()
+            .filter(|p| p.file_id == file_id)
+            .map(|p| p.page_number + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_manager() -> InMemoryFileManager {
+        InMemoryFileManager::new(Arc::new(FileCatalog::new()))
+    }
+
+    #[test]
+    fn read_page_written_page_returns_its_bytes() {
+        let fm = file_manager();
+        let page_id = PageId::new(1, 0);
+        fm.write_page(page_id, &[7u8; PAGE_SIZE]);
+
+        let mut bytes = [0u8; PAGE_SIZE];
+        assert!(fm.read_page(page_id, &mut bytes));
+        assert!(bytes.iter().all(|b| *b == 7));
+    }
+
+    #[test]
+    fn page_exists_within_file_returns_true() {
+        let fm = file_manager();
+        fm.write_page(PageId::new(1, 2), &[7u8; PAGE_SIZE]);
+
+        // Page 0 was never written, but lies before the highest written page
+        assert!(fm.page_exists(PageId::new(1, 0)).unwrap());
+        assert!(fm.page_exists(PageId::new(1, 2)).unwrap());
+    }
+
+    #[test]
+    fn page_exists_beyond_end_of_file_returns_false() {
+        let fm = file_manager();
+        fm.allocate_page(1).unwrap();
+
+        assert!(!fm.page_exists(PageId::new(1, 1)).unwrap());
+        assert!(!fm.page_exists(PageId::new(2, 0)).unwrap());
+    }
+
+    #[test]
+    fn read_page_past_end_of_file_returns_false() {
+        let fm = file_manager();
+        fm.write_page(PageId::new(1, 0), &[7u8; PAGE_SIZE]);
+
+        let mut bytes = [0u8; PAGE_SIZE];
+        assert!(!fm.read_page(PageId::new(1, 1), &mut bytes));
+        assert!(!fm.read_page(PageId::new(2, 0), &mut bytes));
+    }
+
+    #[test]
+    fn read_page_gap_before_written_page_reads_zeroes() {
+        let fm = file_manager();
+        fm.write_page(PageId::new(1, 3), &[7u8; PAGE_SIZE]);
+
+        let mut bytes = [5u8; PAGE_SIZE];
+        assert!(fm.read_page(PageId::new(1, 1), &mut bytes));
+        assert!(bytes.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn read_page_wrong_buffer_size_returns_false() {
+        let fm = file_manager();
+        fm.write_page(PageId::new(1, 0), &[7u8; PAGE_SIZE]);
+
+        let mut bytes = [0u8; PAGE_SIZE - 1];
+        assert!(!fm.read_page(PageId::new(1, 0), &mut bytes));
+    }
+
+    #[test]
+    fn allocate_page_appends_zeroed_pages_per_file() {
+        let fm = file_manager();
+        fm.write_page(PageId::new(1, 1), &[7u8; PAGE_SIZE]);
+
+        assert_eq!(fm.allocate_page(1).unwrap(), PageId::new(1, 2));
+        assert_eq!(fm.allocate_page(1).unwrap(), PageId::new(1, 3));
+        assert_eq!(fm.allocate_page(2).unwrap(), PageId::new(2, 0));
+
+        let mut bytes = [5u8; PAGE_SIZE];
+        assert!(fm.read_page(PageId::new(1, 3), &mut bytes));
+        assert!(bytes.iter().all(|b| *b == 0));
+    }
+}
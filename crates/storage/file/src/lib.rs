@@ -5,7 +5,16 @@
 
 pub mod api;
 
+pub mod errors;
+
 pub mod file_catalog;
 
+pub mod storage_layout;
+
 /// The actual disk based file manager
 pub mod disk_file_manager;
+
+/// File manager keeping pages in memory, for tests of downstream crates.
+/// Only available with the `test-util` feature.
+#[cfg(any(test, feature = "test-util"))]
+pub mod in_memory_file_manager;
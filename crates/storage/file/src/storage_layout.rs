@@ -0,0 +1,118 @@
+//! Deterministic mapping of file IDs to paths under the data directory
+
+use crate::errors::CatalogError;
+use crate::file_catalog::FileCatalog;
+use page::page_id::FileId;
+use std::path::{Path, PathBuf};
+
+/// Extension of the files holding table data
+const TABLE_FILE_EXTENSION: &str = "tbl";
+/// Name of the file holding the table schemas
+const SCHEMA_CATALOG_FILE_NAME: &str = "schemas.cat";
+
+/// Decides where the file of each `FileId` lives, so that paths are never built ad-hoc.
+///
+/// Every file is stored directly under the data directory, named after its ID: `{data_dir}/{file_id}.tbl`.
+/// The table schemas are stored next to them, in `{data_dir}/schemas.cat`.
+#[derive(Debug, Clone)]
+pub struct StorageLayout {
+    data_dir: PathBuf,
+}
+
+impl StorageLayout {
+    /// Creates a layout rooted at `data_dir`
+    pub fn new(data_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            data_dir: data_dir.into(),
+        }
+    }
+
+    /// Directory holding all the data files
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// Returns the path of the file holding `file_id`. Distinct IDs always map to distinct paths.
+    pub fn path_for(&self, file_id: FileId) -> PathBuf {
+        self.data_dir
+            .join(format!("{file_id}.{TABLE_FILE_EXTENSION}"))
+    }
+
+    /// Returns the path of the file holding the table schemas
+    pub fn schema_catalog_path(&self) -> PathBuf {
+        self.data_dir.join(SCHEMA_CATALOG_FILE_NAME)
+    }
+
+    /// Registers the path of `file_id` in `catalog`.
+    /// The file itself is created by the file manager the first time it is opened.
+    ///
+    /// # Returns
+    /// - `Ok(PathBuf)` with the registered path
+    /// - `Err(CatalogError::FileIdInUse)` if `file_id` is already mapped, in which case the existing mapping is kept
+    pub fn register(
+        &self,
+        catalog: &FileCatalog,
+        file_id: FileId,
+    ) -> Result<PathBuf, CatalogError> {
+        let path = self.path_for(file_id);
+        catalog.add_file(file_id, path.clone())?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn path_for_is_stable_and_under_data_dir() {
+        let layout = StorageLayout::new("data");
+
+        assert_eq!(layout.path_for(1), PathBuf::from("data").join("1.tbl"));
+        assert_eq!(layout.path_for(1), layout.path_for(1));
+        assert_eq!(layout.path_for(42), PathBuf::from("data").join("42.tbl"));
+    }
+
+    #[test]
+    fn path_for_distinct_ids_never_collide() {
+        let layout = StorageLayout::new("data");
+        let ids = [1, 2, 10, 11, 12, 21, 111, FileId::MAX];
+
+        let paths: HashSet<PathBuf> = ids.iter().map(|id| layout.path_for(*id)).collect();
+
+        assert_eq!(paths.len(), ids.len());
+    }
+
+    #[test]
+    fn schema_catalog_path_is_under_data_dir_and_not_a_table_file() {
+        let layout = StorageLayout::new("data");
+
+        let path = layout.schema_catalog_path();
+
+        assert_eq!(path, PathBuf::from("data").join("schemas.cat"));
+        assert_ne!(path.extension(), layout.path_for(1).extension());
+    }
+
+    #[test]
+    fn register_round_trips_through_catalog() {
+        let layout = StorageLayout::new("data");
+        let catalog = FileCatalog::new();
+
+        let path = layout.register(&catalog, 3).unwrap();
+
+        assert_eq!(path, layout.path_for(3));
+        assert_eq!(catalog.get_file_name(3), Some(path));
+    }
+
+    #[test]
+    fn register_existing_id_returns_error() {
+        let layout = StorageLayout::new("data");
+        let catalog = FileCatalog::new();
+        layout.register(&catalog, 3).unwrap();
+
+        let result = layout.register(&catalog, 3);
+
+        assert!(matches!(result, Err(CatalogError::FileIdInUse(3))));
+    }
+}
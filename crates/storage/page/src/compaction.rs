@@ -0,0 +1,25 @@
+//! Defines the order in which `Page::compact` packs the rows of a page, and what a compaction reports.
+
+/// The order in which a compaction lays out the valid rows of a page, starting right after the header.
+/// Either way every row keeps its slot index and contents, only its offset may change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompactionOrder {
+    /// Rows are laid out in slot index order, so reading the rows by slot walks the page front to back.
+    /// Rows are staged in a scratch buffer, as most of them may have to move.
+    #[default]
+    SlotIndexOrder,
+    /// Rows keep their relative physical order, each one sliding down to fill the gaps before it.
+    /// Done in place, and rows not preceded by any gap are not copied at all.
+    PhysicalOrder,
+}
+
+/// What a compaction did to the page, as reported by `Page::compact_reporting`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CompactionReport {
+    /// Number of row bytes that ended up at a different offset.
+    pub bytes_moved: usize,
+    /// Number of rows that ended up at a different offset, including zero-length ones.
+    pub rows_relocated: usize,
+    /// The `free_start` of the page once compacted, i.e. the end of the packed rows.
+    pub free_start: usize,
+}
@@ -0,0 +1,10 @@
+//! Defines the outcome of a row deletion, as reported by `Page::delete_row_reporting`.
+
+/// What a row deletion did to the page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeleteOutcome {
+    /// Number of bytes returned to the page's free space, i.e. the length of the deleted row.
+    pub bytes_freed: usize,
+    /// Whether the page was compacted after the deletion.
+    pub compacted: bool,
+}
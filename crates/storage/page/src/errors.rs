@@ -1,10 +1,15 @@
 pub(crate) mod header_error;
 pub(crate) mod insert_error;
 
+/// Public error type returned by all `Page` APIs.
 pub mod page_error;
 
 pub(crate) mod delete_error;
+pub(crate) mod merge_error;
+pub(crate) mod move_row_error;
 pub(crate) mod page_op_error;
 pub(crate) mod read_row_error;
 pub(crate) mod slot_error;
+pub(crate) mod split_error;
 pub(crate) mod update_error;
+pub(crate) mod validation_error;
@@ -1,11 +1,14 @@
 use crate::errors::header_error::HeaderError;
 use crate::errors::slot_error::SlotError;
+use binary_helpers::conversions::ConversionError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub(crate) enum DeleteError {
     #[error("Error while accessing slot array")]
-    SlotError(#[from] SlotError),
+    Slot(#[from] SlotError),
     #[error("Error while accessing header")]
-    HeaderError(#[from] HeaderError),
+    Header(#[from] HeaderError),
+    #[error("Error while converting between data types")]
+    Conversion(#[from] ConversionError),
 }
@@ -9,4 +9,15 @@ pub(crate) enum HeaderError {
     OffsetArithmetic,
     #[error("Provided slice length ({actual}) does not match the expected length")]
     HeaderSliceSizeMismatch { actual: usize, expected: usize },
+    #[error("Value does not fit in the header field")]
+    ConversionError(#[from] ConversionError),
+    #[error("Unknown page type {page_type}")]
+    UnknownPageType { page_type: u16 },
+    #[error("Page type {page_type} cannot be used for page number {page_number}")]
+    InvalidPageTypeForPage {
+        /// Page number the page was being initialized for
+        page_number: u32,
+        /// Requested page type
+        page_type: u16,
+    },
 }
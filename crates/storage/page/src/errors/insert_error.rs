@@ -1,5 +1,6 @@
 use crate::errors::header_error::HeaderError;
 use crate::errors::slot_error::SlotError;
+use binary_helpers::conversions::ConversionError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -11,8 +12,26 @@ pub(crate) enum InsertError {
         /// Actual number of free bytes in the page
         page_free_space: usize,
     },
+    #[error(
+        "Row of length {row_len} can never fit in a page, which holds rows of at most {max} bytes"
+    )]
+    RowTooLarge {
+        /// Length of the row
+        row_len: usize,
+        /// Largest row a page can hold, see `Page::max_row_size`
+        max: usize,
+    },
+    #[error("Slot {slot_index} already holds a row")]
+    SlotOccupied {
+        /// Index of the slot the row was meant to be placed at
+        slot_index: usize,
+    },
+    #[error("Insertion plan was computed against an older version of the page")]
+    StalePlan,
     #[error("Error while accessing slot array")]
     SlotError(#[from] SlotError),
     #[error("Error while accessing header")]
     HeaderError(#[from] HeaderError),
+    #[error("Error while converting between data types")]
+    ConversionError(#[from] ConversionError),
 }
@@ -0,0 +1,14 @@
+use crate::errors::header_error::HeaderError;
+use crate::errors::insert_error::InsertError;
+use crate::errors::slot_error::SlotError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub(crate) enum MergeError {
+    #[error("Error while accessing slot array")]
+    Slot(#[from] SlotError),
+    #[error("Error while accessing header")]
+    Header(#[from] HeaderError),
+    #[error("Error while inserting a row from the merged page")]
+    Insert(#[from] InsertError),
+}
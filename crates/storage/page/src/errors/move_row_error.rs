@@ -0,0 +1,35 @@
+use crate::errors::header_error::HeaderError;
+use crate::errors::slot_error::SlotError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub(crate) enum MoveRowError {
+    #[error(
+        "Cannot move row of length {length} to offset {offset}, rows must lie within {min}..{max}"
+    )]
+    DestinationOutOfBounds {
+        /// Requested offset of the row
+        offset: usize,
+        /// Length of the row
+        length: usize,
+        /// First offset of the data region
+        min: usize,
+        /// First offset past the data region, i.e. the start of the slot array
+        max: usize,
+    },
+    #[error(
+        "Cannot move row of length {length} to offset {offset}, the row of slot {slot} is in the way"
+    )]
+    DestinationOccupied {
+        /// Requested offset of the row
+        offset: usize,
+        /// Length of the row
+        length: usize,
+        /// Slot of the live row overlapping the destination
+        slot: usize,
+    },
+    #[error("Error while accessing slot array")]
+    SlotError(#[from] SlotError),
+    #[error("Error while accessing header")]
+    HeaderError(#[from] HeaderError),
+}
@@ -1,10 +1,15 @@
 use crate::errors::page_op_error::PageOpError;
 use crate::page_id::PageId;
+use std::error::Error;
+use std::fmt;
 
 /// Public facing error type returned by the Page crate.
 ///
+/// Displays as `page <page_id>: <operation> failed: <cause>`, where the cause spells out the whole
+/// chain of underlying errors, e.g.
+/// `page 1:0: insert failed: Unable to insert row of length 100 in page with 50 free bytes`.
 #[derive(Debug, thiserror::Error)]
-#[error("error on page {page_id}: {source}")]
+#[error("page {page_id}: {} failed: {}", .source.operation(), DisplayChain(.source))]
 pub struct PageError {
     /// The page ID on which the error occurred
     pub(crate) page_id: PageId,
@@ -12,6 +17,26 @@ pub struct PageError {
     pub(crate) source: PageOpError,
 }
 
+/// Displays the errors wrapped by a `PageOpError`, from the outermost to the innermost, separated by `: `.
+/// The `PageOpError` itself is skipped, as the operation name already covers it.
+struct DisplayChain<'a>(&'a PageOpError);
+
+impl fmt::Display for DisplayChain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut cause = self.0.source();
+        let mut first = true;
+        while let Some(error) = cause {
+            if !first {
+                f.write_str(": ")?;
+            }
+            write!(f, "{error}")?;
+            first = false;
+            cause = error.source();
+        }
+        Ok(())
+    }
+}
+
 /// Public facing result type of page operations.
 pub type PageResult<T> = Result<T, PageError>;
 
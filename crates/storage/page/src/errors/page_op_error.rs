@@ -1,9 +1,13 @@
 use crate::errors::delete_error::DeleteError;
 use crate::errors::header_error::HeaderError;
 use crate::errors::insert_error::InsertError;
+use crate::errors::merge_error::MergeError;
+use crate::errors::move_row_error::MoveRowError;
 use crate::errors::read_row_error::ReadRowError;
 use crate::errors::slot_error::SlotError;
+use crate::errors::split_error::SplitError;
 use crate::errors::update_error::UpdateError;
+use crate::errors::validation_error::ValidationError;
 use thiserror::Error;
 
 /// Aggregator error type for all possible page related sub-errors
@@ -21,4 +25,30 @@ pub(crate) enum PageOpError {
     DeleteRow(#[from] DeleteError),
     #[error("Error while updating row")]
     UpdateRow(#[from] UpdateError),
+    #[error("Error while splitting page")]
+    Split(#[from] SplitError),
+    #[error("Error while merging pages")]
+    Merge(#[from] MergeError),
+    #[error("Error while moving row")]
+    MoveRow(#[from] MoveRowError),
+    #[error("Error while validating page")]
+    Validation(#[from] ValidationError),
+}
+
+impl PageOpError {
+    /// Short name of the operation that failed, used when displaying a `PageError`
+    pub(crate) fn operation(&self) -> &'static str {
+        match self {
+            PageOpError::Header(_) => "header access",
+            PageOpError::Slot(_) => "slot access",
+            PageOpError::ReadRow(_) => "read",
+            PageOpError::Insert(_) => "insert",
+            PageOpError::DeleteRow(_) => "delete",
+            PageOpError::UpdateRow(_) => "update",
+            PageOpError::Split(_) => "split",
+            PageOpError::Merge(_) => "merge",
+            PageOpError::MoveRow(_) => "move",
+            PageOpError::Validation(_) => "validation",
+        }
+    }
 }
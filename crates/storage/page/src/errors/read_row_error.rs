@@ -7,4 +7,18 @@ use thiserror::Error;
 pub(crate) enum ReadRowError {
     #[error("Error while reading slot array")]
     SlotError(#[from] SlotError),
+    #[error("Row at offset {offset} with length {length} extends past the end of the page")]
+    RowOutOfBounds {
+        /// Offset of the row, as stored in its slot
+        offset: usize,
+        /// Length of the row, as stored in its slot
+        length: usize,
+    },
+    #[error("Row of length {required} does not fit in a destination buffer of {available} bytes")]
+    DestinationTooSmall {
+        /// Length of the row
+        required: usize,
+        /// Length of the destination buffer
+        available: usize,
+    },
 }
@@ -17,6 +17,23 @@ pub(crate) enum SlotError {
     },
     #[error("Attempted to access an invalid slot index: {slot_index}")]
     InvalidSlot { slot_index: usize },
+    #[error("Slot index {index} is out of range, the page has {slot_count} slots")]
+    IndexOutOfRange { index: usize, slot_count: usize },
+    #[error("Slot {slot_index} has no offset, but a length of {length}")]
+    CorruptSlot { slot_index: usize, length: usize },
+    #[error(
+        "Scratch buffer of {available} bytes cannot hold the {required} bytes of rows to compact"
+    )]
+    ScratchTooSmall { required: usize, available: usize },
+    #[error("Slot {field} {value} does not fit in the slot format, maximum is {max}")]
+    OffsetTooLarge {
+        /// Which slot field overflowed (`offset` or `length`)
+        field: &'static str,
+        /// The value that was attempted to be stored
+        value: usize,
+        /// Maximum value the field can hold
+        max: usize,
+    },
     #[error("Error while interpreting binary data.")]
     BinaryError(#[from] binary_helpers::bin_error::BinaryError),
     #[error("Error while reading page header")]
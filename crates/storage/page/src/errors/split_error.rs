@@ -0,0 +1,26 @@
+use crate::errors::delete_error::DeleteError;
+use crate::errors::header_error::HeaderError;
+use crate::errors::insert_error::InsertError;
+use crate::errors::slot_error::SlotError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub(crate) enum SplitError {
+    #[error(
+        "Destination page needs {required} free bytes for the moved rows, but only has {available}"
+    )]
+    DestinationTooSmall {
+        /// Number of bytes needed for the moved rows and their slots
+        required: usize,
+        /// Actual number of free bytes in the destination page
+        available: usize,
+    },
+    #[error("Error while accessing slot array")]
+    SlotError(#[from] SlotError),
+    #[error("Error while accessing header")]
+    HeaderError(#[from] HeaderError),
+    #[error("Error while inserting a row into the destination page")]
+    InsertError(#[from] InsertError),
+    #[error("Error while removing a moved row from the source page")]
+    DeleteError(#[from] DeleteError),
+}
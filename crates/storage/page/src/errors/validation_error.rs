@@ -0,0 +1,41 @@
+use crate::errors::header_error::HeaderError;
+use crate::errors::slot_error::SlotError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub(crate) enum ValidationError {
+    #[error("Unknown page type {page_type}")]
+    UnknownPageType { page_type: u16 },
+    #[error("Header field {field} has value {value}, expected to be within {min}..={max}")]
+    FieldOutOfRange {
+        /// Name of the offending header field
+        field: &'static str,
+        /// Actual value of the field
+        value: usize,
+        /// Minimum allowed value (inclusive)
+        min: usize,
+        /// Maximum allowed value (inclusive)
+        max: usize,
+    },
+    #[error("Stored checksum {stored:#010x} does not match the computed checksum {computed:#010x}")]
+    ChecksumMismatch { stored: u32, computed: u32 },
+    #[error("Row of slot {slot} spans {start}..{end}, outside of the data region {min}..{max}")]
+    RowOutOfBounds {
+        /// Index of the offending slot
+        slot: usize,
+        /// Offset of the first byte of the row
+        start: usize,
+        /// Offset of the first byte after the row
+        end: usize,
+        /// Start of the data region
+        min: usize,
+        /// End of the data region, i.e. where the slot array starts
+        max: usize,
+    },
+    #[error("Rows of slots {first} and {second} overlap")]
+    OverlappingRows { first: usize, second: usize },
+    #[error("Error while accessing header")]
+    HeaderError(#[from] HeaderError),
+    #[error("Error while accessing slot array")]
+    SlotError(#[from] SlotError),
+}
@@ -25,6 +25,8 @@
 //! | `left_page`    | u32   | [`LEFT_PAGE`]            | Pointer to the left sibling page (if applicable). |
 //! | `right_page`   | u32   | [`RIGHT_PAGE`]           | Pointer to the right sibling page. |
 //! | `last_lsn`     | u64   | [`LAST_LSN`]             | Last log sequence number applied to this page. |
+//! | `checksum`     | u32   | [`CHECKSUM`]             | CRC-32 of the page bytes, excluding this field. `0` if not computed. |
+//! | `live_row_count` | u16 | [`LIVE_ROW_COUNT`]       | Number of valid rows. Unlike `slot_count`, deleted slots are not counted. |
 //!
 //! ## Notes on Format Compatibility
 //!
@@ -32,6 +34,16 @@
 //! - The Java version included `parent_page` at offset 22; this Rust version
 //!   **intentionally omits** it.
 //! - `last_lsn` begins at offset 26 to maintain compatibility with the previous format.
+//! - `checksum` is new to this version and is placed right after `last_lsn`.
+//! - `live_row_count` is new to this version and is placed right after `checksum`.
+//! - Pages written before `checksum` and `live_row_count` existed hold zeros in both fields. See
+//!   `Page::upgrade_header` for bringing them up to date.
+//!
+//! # Page Type Specific Regions
+//!
+//! Index pages (`IndexRoot`, `IndexInternal`, `IndexLeaf`) reserve [`INDEX_HEADER_SIZE`] bytes right after
+//! the header, for index-specific fields such as the key count. Their tuple/data region therefore starts
+//! at `HEADER_SIZE + INDEX_HEADER_SIZE` instead of `HEADER_SIZE`. See [`data_start`].
 //!
 //! # Header Invariants
 //!
@@ -62,6 +74,8 @@
 //!
 //! ### Slot Allocation Rules
 //!
+//! - On an empty page, `free_start` equals [`data_start`] for the page type.
+//!
 //! - When the page contains **no slots**,
 //!   `free_end = PAGE_SIZE - 1`, therefore the slot array is empty.
 //!
@@ -87,11 +101,34 @@
 //!
 
 use crate::errors::header_error::HeaderError;
+use crate::header_snapshot::HeaderSnapshot;
 use crate::page_type::PageType;
 use crate::slot::SLOT_SIZE;
 use crate::{HEADER_SIZE, PAGE_SIZE};
+use binary_helpers::conversions::UsizeConversion;
 use binary_helpers::le::{read_le, write_le};
 use paste::paste;
+use std::ops::Range;
+
+/// Number of bytes reserved after the header of index pages, for fields such as the key count.
+pub(crate) const INDEX_HEADER_SIZE: usize = 8;
+
+/// Returns the offset where the tuple/data region starts for pages of the given type.
+pub(crate) fn data_start(page_type: PageType) -> usize {
+    match page_type {
+        PageType::Unsorted | PageType::Overflow => HEADER_SIZE,
+        PageType::IndexRoot | PageType::IndexInternal | PageType::IndexLeaf => {
+            HEADER_SIZE + INDEX_HEADER_SIZE
+        }
+    }
+}
+
+/// Returns the byte ranges of a page covered by its checksum: the whole page, except the `checksum`
+/// header field itself. Anything comparing page images (e.g. against a logged full-page image) should
+/// use the same ranges, so that it agrees with the checksum on which bytes are volatile.
+pub fn checksummed_ranges() -> [Range<usize>; 2] {
+    [0..CHECKSUM, CHECKSUM + size_of::<u32>()..PAGE_SIZE]
+}
 
 /// The 'HeaderRef' struct provides an immutable view into the header of a page.
 #[derive(Debug)]
@@ -130,7 +167,34 @@ impl<'a> HeaderRef<'a> {
             .get_free_end()?
             .checked_sub(self.get_free_start()?)
             .ok_or(HeaderError::OffsetArithmetic)?;
-        Ok(diff < (row_size + SLOT_SIZE) as u16)
+        Ok((diff as usize) < row_size + SLOT_SIZE)
+    }
+
+    /// Returns the offset where the tuple/data region starts, based on the page type stored in the header.
+    /// Returns `HeaderError::UnknownPageType` if the stored page type is not a valid `PageType`.
+    pub fn data_start(&self) -> Result<usize, HeaderError> {
+        let page_type = self.get_page_type()?;
+        PageType::try_from(page_type)
+            .map(data_start)
+            .map_err(|_| HeaderError::UnknownPageType { page_type })
+    }
+
+    /// Reads every header field into a `HeaderSnapshot`.
+    pub fn snapshot(&self) -> Result<HeaderSnapshot, HeaderError> {
+        Ok(HeaderSnapshot {
+            slot_count: self.get_slot_count()?,
+            free_start: self.get_free_start()?,
+            free_end: self.get_free_end()?,
+            free_space: self.get_free_space()?,
+            can_compact: self.get_can_compact()?,
+            page_number: self.get_page_number()?,
+            page_type: self.get_page_type()?,
+            left_page: self.get_left_page()?,
+            right_page: self.get_right_page()?,
+            last_lsn: self.get_last_lsn()?,
+            checksum: self.get_checksum()?,
+            live_row_count: self.get_live_row_count()?,
+        })
     }
 }
 
@@ -162,16 +226,37 @@ impl<'a> HeaderMut<'a> {
         })
     }
 
-    /// Initializes the header with default values for a new empty page.
+    /// Initializes the header with default values for a new empty page of the given type.
+    /// The free region starts after the type-specific reserved area, see [`data_start`].
+    ///
+    /// Returns `HeaderError::InvalidPageTypeForPage` for `IndexInternal`, `IndexLeaf` and `Overflow` pages at
+    /// page number 0. These pages are linked to their siblings through `left_page`/`right_page`, where 0 means
+    /// "no sibling", so a page at page number 0 could never be reached from its neighbours.
     pub(crate) fn default(
         &mut self,
         page_number: u32,
         page_type: PageType,
     ) -> Result<(), HeaderError> {
+        if page_number == 0
+            && matches!(
+                page_type,
+                PageType::IndexInternal | PageType::IndexLeaf | PageType::Overflow
+            )
+        {
+            return Err(HeaderError::InvalidPageTypeForPage {
+                page_number,
+                page_type: u16::from(page_type),
+            });
+        }
+
+        let data_start = data_start(page_type);
+        let free_space = PAGE_SIZE
+            .checked_sub(data_start)
+            .ok_or(HeaderError::OffsetArithmetic)?;
         self.set_page_number(page_number)?;
-        self.set_free_start(HEADER_SIZE as u16)?;
-        self.set_free_end((PAGE_SIZE - 1) as u16)?;
-        self.set_free_space((PAGE_SIZE - HEADER_SIZE) as u16)?;
+        self.set_free_start(data_start.to_u16()?)?;
+        self.set_free_end((PAGE_SIZE - 1).to_u16()?)?;
+        self.set_free_space(free_space.to_u16()?)?;
         self.set_page_type(u16::from(page_type))?;
         Ok(())
     }
@@ -247,6 +332,8 @@ impl_header_accessors! {
     right_page : u32 = 20;
     // In the Java implementation, we had parent_page at offset 22, but we will not be including it in this implementation.
     last_lsn : u64 = 26; // TODO for now, we will use offset 26 for last_lsn to maintain consistency with the Java implementation
+    checksum : u32 = 34;
+    live_row_count : u16 = 38;
 }
 
 #[cfg(test)]
@@ -269,6 +356,8 @@ mod header_ref_tests {
         header_bytes[LEFT_PAGE..LEFT_PAGE + 4].copy_from_slice(&0x778899AAu32.to_le_bytes()); // left_page
         header_bytes[RIGHT_PAGE..RIGHT_PAGE + 4].copy_from_slice(&0xDEADBEEFu32.to_le_bytes()); // right_page
         header_bytes[LAST_LSN..LAST_LSN + 8].copy_from_slice(&0x0123456789ABCDEFu64.to_le_bytes()); // last_lsn
+        header_bytes[CHECKSUM..CHECKSUM + 4].copy_from_slice(&0xCAFEF00Du32.to_le_bytes()); // checksum
+        header_bytes[LIVE_ROW_COUNT..LIVE_ROW_COUNT + 2].copy_from_slice(&0xBEEFu16.to_le_bytes()); // live_row_count
 
         let header = HeaderRef::new(&header_bytes).unwrap();
 
@@ -282,6 +371,8 @@ mod header_ref_tests {
         assert_eq!(header.get_left_page().unwrap(), 0x778899AAu32);
         assert_eq!(header.get_right_page().unwrap(), 0xDEADBEEFu32);
         assert_eq!(header.get_last_lsn().unwrap(), 0x0123456789ABCDEFu64);
+        assert_eq!(header.get_checksum().unwrap(), 0xCAFEF00Du32);
+        assert_eq!(header.get_live_row_count().unwrap(), 0xBEEFu16);
     }
 
     #[test]
@@ -308,6 +399,19 @@ mod header_ref_tests {
         assert!(!header.needs_compaction(row_size).unwrap());
     }
 
+    #[test]
+    fn needs_compaction_row_len_near_u16_max_does_not_wrap() {
+        let mut header_bytes = [0u8; HEADER_SIZE];
+        header_bytes[FREE_START..FREE_START + 2].copy_from_slice(&0x0010u16.to_le_bytes());
+        header_bytes[FREE_END..FREE_END + 2].copy_from_slice(&0x0020u16.to_le_bytes());
+
+        let header = HeaderRef::new(&header_bytes).unwrap();
+        // row_size + SLOT_SIZE would wrap around to 0 if computed as u16
+        let row_size = u16::MAX as usize - 3;
+
+        assert!(header.needs_compaction(row_size).unwrap());
+    }
+
     #[test]
     fn new_incorrect_slice_size_error_returned() {
         let header_bytes = [0u8; HEADER_SIZE + 1];
@@ -347,6 +451,8 @@ mod header_mut_tests {
         header_mut.set_left_page(0x11121314u32).unwrap();
         header_mut.set_right_page(0x15161718u32).unwrap();
         header_mut.set_last_lsn(0xDEADBEEFCAFEBABEu64).unwrap();
+        header_mut.set_checksum(0x192A3B4Cu32).unwrap();
+        header_mut.set_live_row_count(0x5D6Eu16).unwrap();
 
         assert_eq!(header_mut.get_slot_count().unwrap(), 0x0102u16);
         assert_eq!(header_mut.get_free_start().unwrap(), 0x0304u16);
@@ -358,6 +464,8 @@ mod header_mut_tests {
         assert_eq!(header_mut.get_left_page().unwrap(), 0x11121314u32);
         assert_eq!(header_mut.get_right_page().unwrap(), 0x15161718u32);
         assert_eq!(header_mut.get_last_lsn().unwrap(), 0xDEADBEEFCAFEBABEu64);
+        assert_eq!(header_mut.get_checksum().unwrap(), 0x192A3B4Cu32);
+        assert_eq!(header_mut.get_live_row_count().unwrap(), 0x5D6Eu16);
     }
 
     #[test]
@@ -381,7 +489,7 @@ mod header_mut_tests {
         let mut header_bytes = [0u8; HEADER_SIZE];
         let mut header_mut = HeaderMut::new(&mut header_bytes).unwrap();
 
-        header_mut.default(42, PageType::Unsorted);
+        header_mut.default(42, PageType::Unsorted).unwrap();
 
         let header_ref = HeaderRef::new(&header_bytes).unwrap();
 
@@ -393,4 +501,104 @@ mod header_mut_tests {
             u16::from(PageType::Unsorted)
         );
     }
+
+    #[test]
+    fn default_index_leaf_reserves_index_header() {
+        let mut header_bytes = [0u8; HEADER_SIZE];
+        let mut header_mut = HeaderMut::new(&mut header_bytes).unwrap();
+
+        header_mut.default(42, PageType::IndexLeaf).unwrap();
+
+        let header_ref = HeaderRef::new(&header_bytes).unwrap();
+        let expected_start = HEADER_SIZE + INDEX_HEADER_SIZE;
+        assert_eq!(header_ref.get_free_start().unwrap(), expected_start as u16);
+        assert_eq!(header_ref.get_free_end().unwrap(), (PAGE_SIZE - 1) as u16);
+        assert_eq!(
+            header_ref.get_free_space().unwrap(),
+            (PAGE_SIZE - expected_start) as u16
+        );
+        assert_eq!(header_ref.data_start().unwrap(), expected_start);
+    }
+
+    #[test]
+    fn default_index_leaf_differs_from_unsorted() {
+        let mut leaf_bytes = [0u8; HEADER_SIZE];
+        HeaderMut::new(&mut leaf_bytes)
+            .unwrap()
+            .default(42, PageType::IndexLeaf)
+            .unwrap();
+        let mut heap_bytes = [0u8; HEADER_SIZE];
+        HeaderMut::new(&mut heap_bytes)
+            .unwrap()
+            .default(42, PageType::Unsorted)
+            .unwrap();
+
+        let leaf = HeaderRef::new(&leaf_bytes).unwrap();
+        let heap = HeaderRef::new(&heap_bytes).unwrap();
+        assert_eq!(heap.get_free_start().unwrap(), HEADER_SIZE as u16);
+        assert_eq!(
+            heap.get_free_space().unwrap(),
+            (PAGE_SIZE - HEADER_SIZE) as u16
+        );
+        assert_eq!(
+            leaf.get_free_start().unwrap() - heap.get_free_start().unwrap(),
+            INDEX_HEADER_SIZE as u16
+        );
+        assert_eq!(
+            heap.get_free_space().unwrap() - leaf.get_free_space().unwrap(),
+            INDEX_HEADER_SIZE as u16
+        );
+    }
+
+    #[test]
+    fn default_sibling_linked_index_page_at_page_zero_error_returned() {
+        for page_type in [
+            PageType::IndexInternal,
+            PageType::IndexLeaf,
+            PageType::Overflow,
+        ] {
+            let mut header_bytes = [0u8; HEADER_SIZE];
+            let mut header_mut = HeaderMut::new(&mut header_bytes).unwrap();
+
+            let result = header_mut.default(0, page_type);
+
+            assert!(matches!(
+                result,
+                Err(HeaderError::InvalidPageTypeForPage { page_number: 0, page_type: t })
+                    if t == u16::from(page_type)
+            ));
+        }
+    }
+
+    #[test]
+    fn default_root_and_heap_pages_at_page_zero_allowed() {
+        for page_type in [PageType::Unsorted, PageType::IndexRoot] {
+            let mut header_bytes = [0u8; HEADER_SIZE];
+            let mut header_mut = HeaderMut::new(&mut header_bytes).unwrap();
+
+            header_mut.default(0, page_type).unwrap();
+        }
+    }
+
+    #[test]
+    fn data_start_unknown_page_type_error_returned() {
+        let mut header_bytes = [0u8; HEADER_SIZE];
+        header_bytes[PAGE_TYPE..PAGE_TYPE + 2].copy_from_slice(&99u16.to_le_bytes());
+
+        let header_ref = HeaderRef::new(&header_bytes).unwrap();
+
+        assert!(matches!(
+            header_ref.data_start(),
+            Err(HeaderError::UnknownPageType { page_type: 99 })
+        ));
+    }
+
+    #[test]
+    fn checksummed_ranges_skip_only_checksum_field() {
+        let [before, after] = checksummed_ranges();
+
+        assert_eq!(before, 0..CHECKSUM);
+        assert_eq!(after, CHECKSUM + 4..PAGE_SIZE);
+        assert_eq!(before.len() + after.len(), PAGE_SIZE - 4);
+    }
 }
@@ -0,0 +1,30 @@
+//! Defines a copy of every header field, as reported by `Page::header_snapshot`.
+
+/// The header fields of a page, read all at once. See the `header` module for what each field means.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderSnapshot {
+    /// Number of allocated slots, including the ones of deleted rows.
+    pub slot_count: u16,
+    /// Offset of the first free byte in the data region.
+    pub free_start: u16,
+    /// Offset of the last free byte before the slot array.
+    pub free_end: u16,
+    /// Total free space in bytes, including gaps.
+    pub free_space: u16,
+    /// Whether the page is fragmented (0 or 1).
+    pub can_compact: u16,
+    /// Page number within its file.
+    pub page_number: u32,
+    /// Raw page type, kept as stored so that pages of unknown types can still be inspected.
+    pub page_type: u16,
+    /// Page number of the left sibling. `0` means no sibling.
+    pub left_page: u32,
+    /// Page number of the right sibling. `0` means no sibling.
+    pub right_page: u32,
+    /// Last log sequence number applied to the page.
+    pub last_lsn: u64,
+    /// Stored checksum. `0` if not computed.
+    pub checksum: u32,
+    /// Number of live rows.
+    pub live_row_count: u16,
+}
@@ -1,5 +1,9 @@
 //! Defines the insertion plan struct for inserting records into unsorted heap pages, along with related enums.
 
+/// Compaction threshold that never compacts a page pro-actively: a page is only compacted when no gap can fit the row.
+/// See `Page::plan_insert_with_compaction_threshold`.
+pub const DEFAULT_COMPACTION_THRESHOLD: f64 = 1.0;
+
 /// Defines the offset at which a new record should be inserted in an unsorted heap page.
 #[derive(Debug)]
 pub enum InsertionOffset {
@@ -18,6 +22,16 @@ pub enum InsertionSlot {
     Reuse(usize),
 }
 
+/// Cheap snapshot of the header fields that an `InsertionPlan` depends on.
+/// Any insert, delete or compaction changes at least one of them, so a mismatch means the page
+/// was modified after the plan was computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageVersion {
+    pub(crate) slot_count: u16,
+    pub(crate) free_start: u16,
+    pub(crate) free_space: u16,
+}
+
 /// Represents a plan for inserting a new record into an unsorted heap page.
 #[derive(Debug)]
 pub struct InsertionPlan {
@@ -25,4 +39,6 @@ pub struct InsertionPlan {
     pub slot: InsertionSlot,
     /// The offset information for the insertion.
     pub offset: InsertionOffset,
+    /// The version of the page the plan was computed against.
+    pub(crate) version: PageVersion,
 }
@@ -5,6 +5,7 @@
 // The header module contains a doc comment with some links to constants defined in the same file. Due to a cargo doc bug,
 // adding module documentation here breaks those links. See https://github.com/rust-lang/rust/issues/119965
 pub mod header;
+pub mod header_snapshot;
 
 /// Slotted structure and related functionality.
 pub mod page;
@@ -12,12 +13,16 @@ pub mod page;
 /// Unique identifier for pages.
 pub mod page_id;
 
-mod errors;
+pub mod compaction;
+pub mod delete_outcome;
+/// Errors surfaced by page operations.
+pub mod errors;
 pub mod insertion_plan;
 /// Different types of pages supported.
 pub mod page_type;
 mod slot;
 mod slot_array;
+pub mod slot_info;
 
 #[cfg(test)]
 mod tests;
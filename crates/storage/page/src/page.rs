@@ -42,13 +42,21 @@ use crate::insertion_plan::InsertionPlan;
 use crate::page_id::PageId;
 
 pub(crate) mod accessors;
+pub(crate) mod coalesce;
 pub(crate) mod ctors;
 pub(crate) mod delete;
+pub(crate) mod extents;
 pub(crate) mod insert;
 pub(crate) mod internal;
+pub(crate) mod merge;
+pub(crate) mod move_row;
 pub(crate) mod plan_insert;
 pub(crate) mod read_row;
+pub(crate) mod replace;
+pub(crate) mod split;
+pub(crate) mod summary;
 pub(crate) mod update;
+pub(crate) mod validate;
 
 /// Public API for the `Page` struct
 pub mod api;
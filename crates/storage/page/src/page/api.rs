@@ -1,9 +1,17 @@
-use crate::PAGE_SIZE;
+use crate::compaction::{CompactionOrder, CompactionReport};
+use crate::delete_outcome::DeleteOutcome;
+use crate::errors::header_error::HeaderError;
 use crate::errors::page_error::{PageResult, WithPageId};
 use crate::errors::page_op_error::PageOpError;
-use crate::insertion_plan::InsertionPlan;
+use crate::header_snapshot::HeaderSnapshot;
+use crate::insertion_plan::{DEFAULT_COMPACTION_THRESHOLD, InsertionPlan};
+use crate::page::summary;
 use crate::page_id::PageId;
 use crate::page_type::PageType;
+use crate::slot::SLOT_SIZE;
+use crate::slot_info::SlotInfo;
+use crate::{HEADER_SIZE, PAGE_SIZE};
+use std::cmp::Ordering;
 
 /// Wrapper around a fixed-size byte array representing a page.
 #[derive(Debug)]
@@ -48,6 +56,145 @@ impl Page {
         Ok(())
     }
 
+    /// Removes all rows from the page, while preserving its identity.
+    ///
+    /// The `page_id`, page type, sibling pointers and last LSN are kept, while the slot array and the
+    /// free space tracking fields of the header are reset to the defaults of an empty page.
+    /// The data region is zeroed out.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<()>` - A result indicating success (`Ok(())`) or failure (`Err(PageError)`).
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the header could not be accessed.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn clear_rows(&mut self) -> PageResult<()> {
+        self.clear_rows_internal()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Creates a page from a byte array read from disk, after validating its contents.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The raw `PAGE_SIZE` bytes of the page.
+    /// * `page_id` - The `PageId` the bytes were read for.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<Page>` - The page if it passes every check of `validate`.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the bytes do not form a valid page, see `validate`.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn try_from_bytes(bytes: Box<[u8; PAGE_SIZE]>, page_id: PageId) -> PageResult<Self> {
+        let page = Self::new_from_bytes(bytes, page_id);
+        page.validate()?;
+        Ok(page)
+    }
+
+    /// Checks the integrity of the page: its header, its checksum and the placement of its rows.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the page type is unknown, a header field is out of range (including a
+    ///   `page_number` that does not match the `page_id` of the page), the stored checksum does not match
+    ///   the contents, or a live row lies outside of the data region or overlaps another one.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn validate(&self) -> PageResult<()> {
+        self.validate_internal()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Computes the checksum of the page contents, without storing it.
+    /// Covers every byte of the page except the `checksum` header field, see `header::checksummed_ranges`.
+    pub fn compute_checksum(&self) -> u32 {
+        self.compute_checksum_internal()
+    }
+
+    /// Computes the checksum of the page contents and stores it in the header.
+    /// Should be called right before the page is written to disk.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the header could not be accessed.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn update_checksum(&mut self) -> PageResult<()> {
+        self.update_checksum_internal()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Verifies that the checksum stored in the header matches the page contents.
+    /// Pages whose stored checksum is `0` were never checksummed and always pass.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the checksums do not match or the header could not be accessed.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn verify_checksum(&self) -> PageResult<()> {
+        self.verify_checksum_internal()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Brings the header of a page written before the `checksum` and `live_row_count` fields existed
+    /// up to date, by counting its live rows and computing its checksum.
+    /// Old-format pages are recognized by a zero checksum on an otherwise valid header.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<bool>` - `true` if the page was in the old format and has been upgraded, `false`
+    ///   if it was left untouched.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the header or the slot array could not be accessed, or a slot is corrupt.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn upgrade_header(&mut self) -> PageResult<bool> {
+        self.upgrade_header_internal()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Recomputes the free space of the page from its live rows and its slots, and stores it in the
+    /// header. Repairs a `free_space` that drifted from the contents of the page, e.g. after a bug in
+    /// its bookkeeping.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<u16>` - The recomputed free space, in bytes.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the header or the slot array could not be accessed, a slot is corrupt, or the
+    ///   rows and slots take more room than the page has.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn recompute_free_space(&mut self) -> PageResult<u16> {
+        self.recompute_free_space_internal()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
     /// Retrieves a row from the page by its slot index.
     ///
     /// # Arguments
@@ -62,7 +209,8 @@ impl Page {
     /// # Errors
     ///
     /// This method can return the following errors:
-    /// * `PageError` - If there is an issue with the operation, such as an invalid slot index.
+    /// * `PageError` - If there is an issue with the operation, such as an invalid slot index, or a slot
+    ///   whose row was deleted.
     ///
     /// The error is augmented with the `page_id` of the current page for better traceability.
     pub fn row(&self, slot_index: u32) -> PageResult<&[u8]> {
@@ -71,6 +219,127 @@ impl Page {
             .with_page_id(self.page_id)
     }
 
+    /// Retrieves several rows from the page in one call, e.g. for a batch of point lookups.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_indices` - The slots holding the rows. The same slot may be requested more than once.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<Vec<&[u8]>>` - The rows, in the order of `slot_indices`.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If any slot index is out of range or points to a deleted row. No rows are returned
+    ///   in that case, the error describes the first such slot.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn rows(&self, slot_indices: &[u32]) -> PageResult<Vec<&[u8]>> {
+        self.read_rows_internal(slot_indices)
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Copies a row into a caller-provided buffer, instead of borrowing it from the page.
+    /// Lets a scan reuse a single buffer for every row, without keeping the page borrowed.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_index` - The index of the slot holding the row.
+    /// * `destination` - Buffer receiving the row in its first bytes. The rest of it is left untouched.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<usize>` - The number of bytes copied, i.e. the length of the row.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the slot index is out of bounds, the slot points to a deleted row, or
+    ///   `destination` is shorter than the row. `destination` is left untouched in all cases.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn copy_row_into(&self, slot_index: u32, destination: &mut [u8]) -> PageResult<usize> {
+        self.copy_row_into_internal(slot_index, destination)
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Returns the length of a row, in bytes, without reading it.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_index` - The index of the slot holding the row. Indexing starts from 0.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the slot index is out of range, or the row it held was deleted.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn row_length(&self, slot_index: u32) -> PageResult<u16> {
+        self.row_length_internal(slot_index)
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Binary searches the rows of a page whose rows are sorted in slot index order, such as an index page.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmp` - Compares a row against the target, returning whether the row orders before (`Less`),
+    ///   after (`Greater`) or matches (`Equal`) the target, like the comparator of `slice::binary_search_by`.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<Result<u32, u32>>` - `Ok(slot_index)` of a matching row, or `Err(insertion_point)`,
+    ///   the slot index at which a row matching the target would keep the rows sorted. If several rows
+    ///   match, any one of them may be returned.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If a visited slot does not hold a row, or the slot array could not be read.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn binary_search_rows<F>(&self, cmp: F) -> PageResult<Result<u32, u32>>
+    where
+        F: Fn(&[u8]) -> Ordering,
+    {
+        self.binary_search_rows_internal(cmp)
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Retrieves a mutable view over a row, allowing its bytes to be patched in place.
+    ///
+    /// The returned slice spans exactly the bytes of the row, so the row length cannot be changed
+    /// through it. Use `update_row` for changes that require a different length.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_index` - The index of the slot to retrieve the row from. Indexing starts from 0.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<&mut [u8]>` - A result containing a mutable reference to the row data as a byte slice
+    ///   if successful, or an error wrapped in `PageResult` if the operation fails.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the slot index is out of bounds or the slot points to a deleted row.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn row_mut(&mut self, slot_index: u32) -> PageResult<&mut [u8]> {
+        let page_id = self.page_id;
+        self.row_mut_internal(slot_index)
+            .map_err(PageOpError::from)
+            .with_page_id(page_id)
+    }
+
     /// Plans the insertion of a row into the page. Used only for heap pages.
     ///
     /// # Arguments
@@ -90,12 +359,209 @@ impl Page {
     ///
     /// The error is augmented with the `page_id` of the current page for better traceability.
     pub fn plan_insert(&self, row_len: usize) -> PageResult<InsertionPlan> {
-        self.plan_insert_internal(row_len)
+        self.plan_insert_with_compaction_threshold(row_len, DEFAULT_COMPACTION_THRESHOLD)
+    }
+
+    /// Plans the insertion of a row into the page, compacting the page pro-actively if it is too fragmented.
+    /// Used only for heap pages.
+    ///
+    /// When the row does not fit in the contiguous free region, a page whose `fragmentation_ratio` is above
+    /// `compaction_threshold` is compacted instead of placing the row in one of its gaps. With
+    /// `DEFAULT_COMPACTION_THRESHOLD` this behaves exactly like `plan_insert`.
+    ///
+    /// # Arguments
+    ///
+    /// * `row_len` - The length of the row to be inserted, in bytes.
+    /// * `compaction_threshold` - Fragmentation ratio, between `0.0` and `1.0`.
+    ///
+    /// # Errors
+    ///
+    /// Same as `plan_insert`.
+    pub fn plan_insert_with_compaction_threshold(
+        &self,
+        row_len: usize,
+        compaction_threshold: f64,
+    ) -> PageResult<InsertionPlan> {
+        self.plan_insert_internal(row_len, compaction_threshold)
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Returns the size of the largest row a page could ever hold: the row that fills up a fresh heap page,
+    /// together with its slot.
+    pub fn max_row_size() -> usize {
+        PAGE_SIZE - HEADER_SIZE - SLOT_SIZE
+    }
+
+    /// Determines whether a row of `row_len` bytes can be inserted into the page, based on its current free space.
+    /// Like `plan_insert`, counts fragmented free space as well, since it can be reclaimed through compaction.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the header could not be accessed.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn can_fit(&self, row_len: usize) -> PageResult<bool> {
+        if row_len > Self::max_row_size() {
+            return Ok(false);
+        }
+
+        self.row_size_fits(row_len)
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Compacts the page if it is fragmented, moving all rows next to each other so that the free space
+    /// becomes a single contiguous region. `can_compact` is cleared afterward.
+    /// Pages without fragmentation are left untouched.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<bool>` - `true` if the page was compacted, `false` if there was nothing to do.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the header or the slot array could not be accessed.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn defragment(&mut self) -> PageResult<bool> {
+        self.defragment_internal()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Returns whether the page would benefit from a compaction, i.e. whether `defragment` would
+    /// compact it. That is the case when the header's `can_compact` flag is set, or when part of the
+    /// free space is scattered in gaps between rows (see `fragmentation_ratio`).
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the header could not be read.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn needs_compaction(&self) -> PageResult<bool> {
+        self.needs_compaction_internal()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Compacts the page unconditionally, staging the rows in a caller-provided `scratch` buffer
+    /// instead of allocating one. Lets a caller compacting many pages reuse a single buffer.
+    /// `can_compact` is cleared afterward.
+    ///
+    /// # Arguments
+    ///
+    /// * `scratch` - Buffer large enough to hold all the valid rows of the page. A buffer of
+    ///   `Page::max_row_size()` bytes fits the rows of any page. Its contents are overwritten.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If `scratch` is too small, in which case the page is left untouched, or if
+    ///   the header or the slot array could not be accessed.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn compact_into(&mut self, scratch: &mut [u8]) -> PageResult<()> {
+        self.compact_into_internal(scratch)
+            .and_then(|_| Ok(self.header_mut()?.set_can_compact(0)?))
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Compacts the page unconditionally, laying out the rows in the chosen `order`.
+    /// Every row keeps its slot index and contents. `can_compact` is cleared afterward.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - `CompactionOrder::SlotIndexOrder` matches the layout `defragment` produces, while
+    ///   `CompactionOrder::PhysicalOrder` copies fewer bytes, by leaving rows not preceded by a gap in place.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<usize>` - The number of row bytes that were moved to a different offset.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the header or the slot array could not be accessed.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn compact(&mut self, order: CompactionOrder) -> PageResult<usize> {
+        self.compact_reporting(order)
+            .map(|report| report.bytes_moved)
+    }
+
+    /// Compacts the page like `compact`, but reports everything the compaction did.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The order in which the rows are laid out, see `compact`.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<CompactionReport>` - The bytes moved, the rows relocated and the resulting
+    ///   `free_start` of the page.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the header or the slot array could not be accessed.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn compact_reporting(&mut self, order: CompactionOrder) -> PageResult<CompactionReport> {
+        self.compact_internal(order)
+            .and_then(|report| {
+                self.header_mut()?.set_can_compact(0)?;
+                Ok(report)
+            })
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Merges free gaps until one of them can hold `row_len` bytes, moving as few rows as possible.
+    ///
+    /// Unlike `compact`, only the cheapest run of physically adjacent rows is shifted towards the header,
+    /// the rest of the page is left in place. The space needed for a new slot is not accounted for.
+    ///
+    /// # Arguments
+    ///
+    /// * `row_len` - The size of the gap to open, in bytes.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<Option<usize>>` - The offset of the gap, or `None` if it could only be opened by
+    ///   a full compaction, in which case the page is left untouched.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the header or the slot array could not be accessed.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn coalesce_free_for(&mut self, row_len: usize) -> PageResult<Option<usize>> {
+        self.coalesce_free_for_internal(row_len)
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Returns the fraction of the free space that is scattered in gaps between rows, rather than
+    /// being part of the contiguous region between `free_start` and `free_end`.
+    /// `0.0` means the page is not fragmented, `1.0` means all of its free space is fragmented.
+    pub fn fragmentation_ratio(&self) -> PageResult<f64> {
+        self.fragmentation_ratio_internal()
             .map_err(PageOpError::from)
             .with_page_id(self.page_id)
     }
 
     /// Inserts a row into a heap page using the provided insertion plan.
+    /// The plan must come from a `plan_insert` call made against the current state of the page;
+    /// if the page was modified in between, `InsertError::StalePlan` is returned and nothing is written.
+    ///
+    /// Zero-length rows are supported: they only take up a slot, and read back as an empty slice until
+    /// deleted, like any other row.
     ///
     /// # Arguments
     ///
@@ -104,19 +570,48 @@ impl Page {
     ///
     /// # Returns
     ///
-    /// * `PageResult<()>` - A result indicating success (`Ok(())`) or failure (`Err(PageError)`).
+    /// * `PageResult<u32>` - The index of the slot the row was stored in, which is where it can be read from.
     ///
     /// # Errors
     ///
     /// This method can return the following errors:
     /// * `PageOpError` - If there is an issue during the insertion process. `PageOpError` will contain the source error.
     /// * The error is augmented with the `page_id` of the current page for better traceability.
-    pub fn insert_heap(&mut self, plan: InsertionPlan, row: Vec<u8>) -> PageResult<()> {
+    pub fn insert_heap(&mut self, plan: InsertionPlan, row: Vec<u8>) -> PageResult<u32> {
         self.insert_row_unsorted_internal(plan, row)
             .map_err(PageOpError::from)
             .with_page_id(self.page_id)
     }
 
+    /// Inserts a row and points the slot at `slot_index` to it, instead of letting the page pick the slot.
+    /// Meant for index pages, where the slot index encodes the position of the entry.
+    ///
+    /// The row bytes are placed anywhere in the free space, compacting the page if needed. No other slot
+    /// is moved. If `slot_index` is past the end of the slot array, the array is grown up to and including
+    /// it, and the slots in between are left empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_index` - The index of the slot to point to the row.
+    /// * `row` - A `Vec<u8>` containing the row data to be inserted.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<()>` - A result indicating success (`Ok(())`) or failure (`Err(PageError)`).
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the slot at `slot_index` already holds a row, or the page does not have enough
+    ///   free space for the row and the new slots. The page is left untouched in both cases.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn insert_at(&mut self, slot_index: u32, row: Vec<u8>) -> PageResult<()> {
+        self.insert_at_internal(slot_index, row)
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
     /// Deletes a row from the page at the specified slot index.
     ///
     /// # Arguments
@@ -139,11 +634,86 @@ impl Page {
     /// * `PageOpError` - If there is an issue during the deletion process, such as
     ///   an invalid slot index or other constraints preventing the deletion.
     pub fn delete_row(&mut self, slot_index: usize, compact_requested: bool) -> PageResult<()> {
+        self.delete_row_reporting(slot_index, compact_requested)
+            .map(|_| ())
+    }
+
+    /// Deletes the row at the given slot index like `delete_row`, reporting what the deletion did.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_index` - The index of the slot containing the row to delete.
+    /// * `compact_requested` - Whether the page should be compacted after the deletion.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<DeleteOutcome>` - The number of bytes returned to the free space (the length of
+    ///   the deleted row), and whether the page was compacted.
+    ///
+    /// # Errors
+    ///
+    /// Same as `delete_row`.
+    pub fn delete_row_reporting(
+        &mut self,
+        slot_index: usize,
+        compact_requested: bool,
+    ) -> PageResult<DeleteOutcome> {
         self.delete_row_internal(slot_index, compact_requested)
             .map_err(PageOpError::from)
             .with_page_id(self.page_id)
     }
 
+    /// Splits the page by moving roughly the upper half of its rows (by physical offset) into `dest`.
+    ///
+    /// The moved rows are removed from this page, which is then compacted, and `dest` is linked in as the
+    /// right sibling of this page. The previous right sibling of this page becomes the right sibling of
+    /// `dest`, but its own `left_page` pointer is left for the caller to update.
+    ///
+    /// # Arguments
+    ///
+    /// * `dest` - An initialized page receiving the moved rows, typically a freshly allocated one.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If `dest` does not have enough free space for the moved rows, in which case neither
+    ///   page is modified, or if either page could not be accessed.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn split_into(&mut self, dest: &mut Page) -> PageResult<()> {
+        self.split_into_internal(dest)
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Merges an underfull sibling into this page, by appending all of the valid rows of `source`.
+    /// The inverse of `split_into`.
+    ///
+    /// If `source` is linked as the right or left sibling of this page, that pointer is moved past
+    /// `source`, unlinking it from this page. `source` is left untouched, so the caller remains responsible
+    /// for releasing it and for updating the pointer of the sibling on its other side.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The page whose rows are moved into this page.
+    ///
+    /// # Returns
+    ///
+    /// * `PageResult<bool>` - `true` if the rows were merged, `false` if they do not fit in this page.
+    ///   Nothing is modified in the latter case.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If either page could not be accessed.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn merge_from(&mut self, source: &Page) -> PageResult<bool> {
+        self.merge_from_internal(source)
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
     /// Updates the contents of a row.
     /// New content can be of same size, smaller or larger.
     /// For larger content, the new row size must still fit within the current page. It there is not enough room, an error will be returned to indicate this.
@@ -171,6 +741,57 @@ impl Page {
             .with_page_id(self.page_id)
     }
 
+    /// Replaces the contents of a row, relocating it freely. Unlike `update_row`, the new contents never
+    /// reuse the space of the old row: they are written elsewhere first, then the slot is pointed at them,
+    /// so the change is all-or-nothing. The row keeps its slot index.
+    ///
+    /// The new row must fit in the free space of the page as it is, without counting the space of the
+    /// old row, which is only released once the replacement succeeded. The page is compacted if no
+    /// single free region can hold the new row.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_index`: the slot number of the row being replaced
+    /// * `row`: the new content of the row.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the slot is invalid, or the new row does not fit next to the old one. The page
+    ///   is left unchanged in both cases.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn replace_row(&mut self, slot_index: usize, row: Vec<u8>) -> PageResult<()> {
+        self.replace_row_internal(slot_index, row)
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Moves a single row to another offset of the data region, for tools and tests that need to control
+    /// the layout of the page. The slot keeps its index and now points to the new offset.
+    ///
+    /// The old bytes of the row are left behind as stale, and the page is flagged for compaction.
+    /// `free_start` is raised when the row moves past it, but never lowered.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_index` - The index of the slot holding the row.
+    /// * `new_offset` - The offset the row starts at after the move.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the slot index is out of range or points to a deleted row, the row would not fit
+    ///   between the header and the slot array at `new_offset`, or it would overlap another live row.
+    ///   The page is left untouched in all cases.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn move_row(&mut self, slot_index: u32, new_offset: usize) -> PageResult<()> {
+        self.move_row_internal(slot_index, new_offset)
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
     /// Returns an immutable reference to the underlying data of the page.
     pub fn data(&self) -> &[u8; PAGE_SIZE] {
         &self.data
@@ -181,6 +802,78 @@ impl Page {
         &mut self.data
     }
 
+    /// Returns a copy of the bytes of the page, with an up to date checksum, ready to be shipped elsewhere.
+    /// The page itself is left unchanged, its stored checksum included.
+    ///
+    /// The copy can be turned back into an equal page with `try_from_bytes`.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// * `PageError` - If the header could not be accessed.
+    ///
+    /// The error is augmented with the `page_id` of the current page for better traceability.
+    pub fn to_owned_bytes(&self) -> PageResult<Box<[u8; PAGE_SIZE]>> {
+        let mut copy = Self::new_from_bytes(Box::new(*self.data), self.page_id);
+        copy.update_checksum_internal()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)?;
+        Ok(copy.data)
+    }
+
+    /// Returns a multi-line, human-readable summary of the page, meant for debugging and test failures.
+    ///
+    /// Contains the `page_id`, page type, the free space tracking fields of the header, `can_compact`
+    /// and one line per slot with its offset, length and whether it is valid or deleted.
+    /// If the page cannot be read, the summary contains the error instead.
+    pub fn debug_summary(&self) -> String {
+        self.debug_summary_internal()
+            .unwrap_or_else(|e| format!("Page {} (unreadable: {e})\n", self.page_id))
+    }
+
+    /// Returns the logical contents of the page as a single-line JSON object, meant to be pasted into
+    /// bug reports.
+    ///
+    /// The object holds the `page_id`, every header field (see `header_snapshot`) and one entry per
+    /// slot, with its offset, length, whether it is valid and the hex of the first 16 bytes of its row.
+    /// If the page cannot be read, the object holds the `page_id` and the error instead.
+    pub fn dump_json(&self) -> String {
+        self.dump_json_internal().unwrap_or_else(|e| {
+            format!(
+                "{{\"page_id\":{},\"error\":{}}}",
+                summary::json_string(&self.page_id.to_string()),
+                summary::json_string(&e.to_string())
+            )
+        })
+    }
+
+    /// Returns an iterator over every slot of the page, in slot-index order, including the slots of
+    /// deleted rows (with `valid == false`). Meant for diagnostics and repair tools, which need to see
+    /// the whole slot array rather than only the live rows.
+    ///
+    /// # Errors
+    ///
+    /// Each item fails on its own if its slot could not be read, e.g. because it holds an offset and
+    /// length that are inconsistent with each other. If the slot count cannot be read, the iterator
+    /// yields that single error.
+    ///
+    /// The errors are augmented with the `page_id` of the current page for better traceability.
+    pub fn iter_slots(&self) -> impl Iterator<Item = PageResult<SlotInfo>> + '_ {
+        let (slot_count, header_error) = match self.slot_count() {
+            Ok(slot_count) => (slot_count as u32, None),
+            Err(error) => (0, Some(error)),
+        };
+
+        header_error
+            .map(Err)
+            .into_iter()
+            .chain((0..slot_count).map(|index| {
+                self.slot_info_internal(index)
+                    .map_err(PageOpError::from)
+                    .with_page_id(self.page_id)
+            }))
+    }
+
     /// Returns the slot count of the page
     pub fn slot_count(&self) -> PageResult<u16> {
         let header = self
@@ -193,4 +886,72 @@ impl Page {
             .map_err(PageOpError::from)
             .with_page_id(self.page_id)
     }
+
+    /// Returns the type of the page
+    pub fn page_type(&self) -> PageResult<PageType> {
+        let header = self
+            .header_ref()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)?;
+        let page_type = header
+            .get_page_type()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)?;
+
+        PageType::try_from(page_type)
+            .map_err(|_| PageOpError::from(HeaderError::UnknownPageType { page_type }))
+            .with_page_id(self.page_id)
+    }
+
+    /// Returns the page number of the right sibling of the page, within the same file. `0` means the page
+    /// has no right sibling.
+    pub fn right_page(&self) -> PageResult<u32> {
+        let header = self
+            .header_ref()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)?;
+
+        header
+            .get_right_page()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Links the page to its right sibling, within the same file. `0` unlinks it.
+    /// The sibling itself is left untouched, including its `left_page`.
+    pub fn set_right_page(&mut self, page_number: u32) -> PageResult<()> {
+        let page_id = self.page_id;
+        let mut header = self
+            .header_mut()
+            .map_err(PageOpError::from)
+            .with_page_id(page_id)?;
+
+        header
+            .set_right_page(page_number)
+            .map_err(PageOpError::from)
+            .with_page_id(page_id)
+    }
+
+    /// Returns the number of live rows on the page.
+    /// Unlike `slot_count`, slots of deleted rows which have not been reused are not counted.
+    pub fn live_row_count(&self) -> PageResult<u16> {
+        let header = self
+            .header_ref()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)?;
+
+        header
+            .get_live_row_count()
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
+
+    /// Returns a copy of every header field, read at once.
+    /// The page type is reported as stored, even if it is not a known `PageType`.
+    pub fn header_snapshot(&self) -> PageResult<HeaderSnapshot> {
+        self.header_ref()
+            .and_then(|header| header.snapshot())
+            .map_err(PageOpError::from)
+            .with_page_id(self.page_id)
+    }
 }
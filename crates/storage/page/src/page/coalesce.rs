@@ -0,0 +1,104 @@
+use crate::errors::header_error::HeaderError;
+use crate::errors::slot_error::SlotError;
+use crate::page::api::Page;
+use crate::page::extents::SlotExtent;
+use binary_helpers::conversions::UsizeConversion;
+
+/// Internal methods for merging free gaps without compacting the whole page.
+impl Page {
+    /// Shifts the cheapest run of physically adjacent rows towards the header, so that the gaps around
+    /// them merge into a single gap of at least `row_len` bytes.
+    ///
+    /// Every candidate run is packed against the row before it, which merges the gaps in front of and
+    /// within the run with the gap that follows it. The run moving the fewest bytes wins. A run covering
+    /// every row is a full compaction and is never picked, in which case `None` is returned.
+    ///
+    /// When the chosen run ends with the last row, `free_start` follows it down.
+    pub(super) fn coalesce_free_for_internal(
+        &mut self,
+        row_len: usize,
+    ) -> Result<Option<usize>, SlotError> {
+        let data_start = self.header_ref()?.data_start()?;
+        let free_end = self.header_ref()?.get_free_end()? as usize;
+
+        let mut rows: Vec<_> = self
+            .slot_extents()?
+            .into_iter()
+            .filter(|e| e.valid)
+            .collect();
+        rows.sort_by_key(|e| (e.offset, e.length));
+
+        let Some((first, last, gap_start)) = cheapest_run(&rows, data_start, free_end, row_len)
+        else {
+            return Ok(None);
+        };
+
+        let mut write_head = boundary_before(&rows, first, data_start);
+        for extent in &rows[first..last] {
+            if extent.offset != write_head {
+                self.relocate_row(extent, write_head)?;
+            }
+            write_head += extent.length;
+        }
+
+        if last == rows.len() {
+            let new_free_start = gap_start.to_u16().map_err(HeaderError::from)?;
+            self.header_mut()?.set_free_start(new_free_start)?;
+        }
+
+        Ok(Some(gap_start))
+    }
+}
+
+/// Offset of the first byte the row at `index` could be packed against.
+fn boundary_before(rows: &[SlotExtent], index: usize, data_start: usize) -> usize {
+    match index {
+        0 => data_start,
+        _ => rows[index - 1].end(),
+    }
+}
+
+/// Finds the run `rows[first..last]` that opens a gap of at least `row_len` bytes while moving the
+/// fewest bytes, and returns it along with the offset the gap would start at.
+/// `rows` must be sorted by offset.
+fn cheapest_run(
+    rows: &[SlotExtent],
+    data_start: usize,
+    free_end: usize,
+    row_len: usize,
+) -> Option<(usize, usize, usize)> {
+    let mut best: Option<(usize, (usize, usize, usize))> = None;
+
+    for first in 0..=rows.len() {
+        let mut write_head = boundary_before(rows, first, data_start);
+        let mut bytes_moved = 0usize;
+
+        for last in first..=rows.len() {
+            if last > first {
+                let extent = &rows[last - 1];
+                if extent.offset != write_head {
+                    bytes_moved += extent.length;
+                }
+                write_head += extent.length;
+            }
+
+            if first == 0 && last == rows.len() && !rows.is_empty() {
+                continue;
+            }
+            if best.is_some_and(|(cost, _)| cost <= bytes_moved) {
+                break;
+            }
+
+            let gap_end = match rows.get(last) {
+                Some(next) => next.offset,
+                None => free_end + 1,
+            };
+            if gap_end.saturating_sub(write_head) >= row_len {
+                best = Some((bytes_moved, (first, last, write_head)));
+                break;
+            }
+        }
+    }
+
+    best.map(|(_, run)| run)
+}
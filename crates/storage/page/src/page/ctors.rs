@@ -1,10 +1,11 @@
-use crate::PAGE_SIZE;
 use crate::errors::header_error::HeaderError;
 use crate::errors::page_error::{PageResult, WithPageId};
 use crate::errors::page_op_error::PageOpError;
 use crate::page::api::Page;
 use crate::page_id::PageId;
 use crate::page_type::PageType;
+use crate::{HEADER_SIZE, PAGE_SIZE};
+use binary_helpers::conversions::UsizeConversion;
 
 /// Internal methods for creating and initializing pages.
 impl Page {
@@ -20,10 +21,34 @@ impl Page {
     pub(crate) fn new_empty(page_id: PageId, page_type: PageType) -> Result<Self, HeaderError> {
         let mut page = Self::new_zeroed(page_id);
 
-        page.header_mut()?.default(page_id.page_number, page_type);
+        page.header_mut()?.default(page_id.page_number, page_type)?;
 
         Ok(page)
     }
+
+    /// Removes all the rows from the page, bringing it back to the state of a freshly initialized page,
+    /// without changing its identity.
+    /// `page_number`, `page_type`, sibling pointers and `last_lsn` are kept as-is, while the slot array,
+    /// free space tracking fields and `can_compact` are reset to the empty-page defaults for its page type.
+    /// The entire region after the header, including any type-specific reserved area, is zeroed out.
+    pub(super) fn clear_rows_internal(&mut self) -> Result<(), HeaderError> {
+        let data_start = self.header_ref()?.data_start()?;
+        self.data[HEADER_SIZE..].fill(0);
+
+        let free_space = PAGE_SIZE
+            .checked_sub(data_start)
+            .ok_or(HeaderError::OffsetArithmetic)?;
+
+        let mut header = self.header_mut()?;
+        header.set_slot_count(0)?;
+        header.set_free_start(data_start.to_u16()?)?;
+        header.set_free_end((PAGE_SIZE - 1).to_u16()?)?;
+        header.set_free_space(free_space.to_u16()?)?;
+        header.set_can_compact(0)?;
+        header.set_live_row_count(0)?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -1,19 +1,23 @@
-use crate::HEADER_SIZE;
+use crate::compaction::CompactionOrder;
+use crate::delete_outcome::DeleteOutcome;
 use crate::errors::delete_error::DeleteError;
+use crate::errors::header_error::HeaderError;
 use crate::errors::slot_error::SlotError;
 use crate::page::api::Page;
+use crate::slot::INVALID_SLOT_OFFSET;
+use binary_helpers::conversions::UsizeConversion;
 
 impl Page {
     pub(super) fn delete_row_internal(
         &mut self,
         slot_index: usize,
         compact_requested: bool,
-    ) -> Result<(), DeleteError> {
+    ) -> Result<DeleteOutcome, DeleteError> {
         // First check if the slot is valid before doing anything. We should not allow attempts to delete an invalid slot number (or at least not proceed with the process in case something gets fucked up)
         {
             let slot_array = self.slot_array_ref()?;
-            let slot_to_be_deleted = slot_array.slot_ref(slot_index as u32)?;
-            if !self.is_slot_valid(&slot_to_be_deleted)? {
+            let slot_to_be_deleted = slot_array.slot_ref(slot_index.to_u32()?)?;
+            if !slot_to_be_deleted.is_valid()? {
                 return Err(SlotError::InvalidSlot { slot_index }.into());
             }
         }
@@ -22,31 +26,41 @@ impl Page {
         let can_reset_free_start = self.try_to_find_new_free_start(slot_index)?;
 
         let mut slot_array = self.slot_array_mut()?;
-        let mut slot = slot_array.slot_mut(slot_index as u32)?;
-        let row_size = slot.length()?;
+        let mut slot = slot_array.slot_mut(slot_index.to_u32()?)?;
+        let row_size = slot.length()? as usize;
 
         slot.set_length(0)?;
-        slot.set_offset(0)?;
+        slot.set_offset(INVALID_SLOT_OFFSET)?;
 
         let mut header = self.header_mut()?;
 
-        let current_free_space = header.get_free_space()?;
-        let new_free_space = current_free_space + row_size;
+        let current_free_space = header.get_free_space()? as usize;
+        let new_free_space = (current_free_space + row_size).to_u16()?;
 
         header.set_free_space(new_free_space)?;
 
+        let new_live_row_count = header
+            .get_live_row_count()?
+            .checked_sub(1)
+            .ok_or(HeaderError::OffsetArithmetic)?;
+        header.set_live_row_count(new_live_row_count)?;
+
         // If we received a value here, it is the new free start that we will use.
         if let Some(new_free_start) = can_reset_free_start {
-            header.set_free_start(new_free_start as u16)?;
+            header.set_free_start(new_free_start.to_u16()?)?;
         } else {
             header.set_can_compact(1)?;
         }
 
         if (compact_requested) {
-            self.compact()?;
+            self.compact_internal(CompactionOrder::SlotIndexOrder)?;
+            self.header_mut()?.set_can_compact(0)?;
         }
 
-        Ok(())
+        Ok(DeleteOutcome {
+            bytes_freed: row_size,
+            compacted: compact_requested,
+        })
     }
 
     /// Determines if we are in an edge case of deleting the last row (physically) on the page:
@@ -74,26 +88,14 @@ impl Page {
         &mut self,
         deleted_idx: usize,
     ) -> Result<Option<usize>, DeleteError> {
-        let slot_array = self.slot_array_ref()?;
-
-        // Store the slot info for the row that is placed at the highest offset in the page
-        let (mut last_offset, mut last_len, mut last_idx) = (0usize, 0usize, 0usize);
+        // Store the slot info for the row that is placed at the highest offset in the page.
+        // `last_idx` stays at usize::MAX until a valid slot is found, as 0 is a legitimate slot index.
+        let (mut last_offset, mut last_len, mut last_idx) = (0usize, 0usize, usize::MAX);
         // Also store the slot info for the row at the 2nd to highest offset in the page. The end of this row will become the new free_start
         let (mut next_to_last_offset, mut next_to_last_len) = (0usize, 0usize);
 
-        let slot_count = self.header_ref()?.get_slot_count()? as usize;
-
-        for idx in 0..slot_count {
-            let current_slot = slot_array.slot_ref(idx as u32)?;
-
-            if !self.is_slot_valid(&current_slot)? {
-                continue;
-            }
-
-            let (offset, len) = (
-                current_slot.offset()? as usize,
-                current_slot.length()? as usize,
-            );
+        for extent in self.slot_extents()?.into_iter().filter(|e| e.valid) {
+            let (idx, offset, len) = (extent.index, extent.offset, extent.length);
 
             // If the current slot points to a bigger offset than what we have so far
             if last_idx == usize::MAX || offset >= last_offset {
@@ -124,7 +126,7 @@ impl Page {
 
         // The new free start will be at the 2nd highest offset + length of that row.
         let new_free_start = if next_to_last_offset + next_to_last_len == 0 {
-            HEADER_SIZE
+            self.header_ref()?.data_start()?
         } else {
             next_to_last_offset + next_to_last_len
         };
@@ -132,3 +134,38 @@ impl Page {
         Ok(Some(new_free_start))
     }
 }
+
+#[cfg(test)]
+mod free_start_tests {
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+
+    #[test]
+    fn new_free_start_only_invalid_slots_returns_none() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues { offset: 0, len: 0 },
+            SlotValues { offset: 0, len: 0 },
+        ]);
+
+        assert_eq!(page.try_to_find_new_free_start(0).unwrap(), None);
+    }
+
+    #[test]
+    fn new_free_start_rightmost_row_at_slot_zero() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 196,
+                len: 100,
+            },
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+        ]);
+
+        assert_eq!(page.try_to_find_new_free_start(0).unwrap(), Some(196));
+        assert_eq!(page.try_to_find_new_free_start(1).unwrap(), None);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::errors::slot_error::SlotError;
+use crate::page::api::Page;
+use crate::slot_info::SlotInfo;
+
+/// Physical placement of a row, as described by its slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SlotExtent {
+    /// Index of the slot in the slot array.
+    pub(crate) index: usize,
+    /// Offset of the row within the page. `0` for deleted rows.
+    pub(crate) offset: usize,
+    /// Length of the row, in bytes. `0` for deleted rows.
+    pub(crate) length: usize,
+    /// Whether the slot points to a live row.
+    pub(crate) valid: bool,
+}
+
+impl SlotExtent {
+    /// Offset of the first byte after the row.
+    pub(crate) fn end(&self) -> usize {
+        self.offset + self.length
+    }
+}
+
+/// Internal slot iteration methods for the `Page` struct.
+impl Page {
+    /// Returns the extent of every slot on the page, in slot-index order, including deleted ones.
+    pub(crate) fn slot_extents(&self) -> Result<Vec<SlotExtent>, SlotError> {
+        let slot_count = self.header_ref()?.get_slot_count()? as usize;
+        let slot_array = self.slot_array_ref()?;
+
+        let mut extents = Vec::with_capacity(slot_count);
+        for index in 0..slot_count {
+            let slot = slot_array.slot_ref(index as u32)?;
+            extents.push(SlotExtent {
+                index,
+                offset: slot.offset()? as usize,
+                length: slot.length()? as usize,
+                valid: slot.is_valid()?,
+            });
+        }
+
+        Ok(extents)
+    }
+
+    /// Describes the slot at `index`, whether it points to a live row or not.
+    pub(super) fn slot_info_internal(&self, index: u32) -> Result<SlotInfo, SlotError> {
+        let slot = self.slot_array_ref()?.slot_ref(index)?;
+
+        Ok(SlotInfo {
+            index,
+            offset: slot.offset()?,
+            length: slot.length()?,
+            valid: slot.is_valid()?,
+        })
+    }
+}
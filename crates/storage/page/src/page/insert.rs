@@ -1,18 +1,28 @@
+use crate::compaction::CompactionOrder;
+use crate::errors::header_error::HeaderError;
 use crate::errors::insert_error::InsertError;
-use crate::insertion_plan::{InsertionOffset, InsertionPlan, InsertionSlot};
+use crate::insertion_plan::{
+    DEFAULT_COMPACTION_THRESHOLD, InsertionOffset, InsertionPlan, InsertionSlot,
+};
 use crate::page::api::Page;
 use crate::slot::SLOT_SIZE;
+use binary_helpers::conversions::UsizeConversion;
 
 impl Page {
     pub(super) fn insert_row_unsorted_internal(
         &mut self,
         plan: InsertionPlan,
         bytes: Vec<u8>,
-    ) -> Result<(), InsertError> {
+    ) -> Result<u32, InsertError> {
+        // The plan is only meaningful for the exact page state it was computed against.
+        if self.page_version()? != plan.version {
+            return Err(InsertError::StalePlan);
+        }
+
         // If compaction is required, do it now.
         // After compaction, we will insert at the (new) free_start.
         if matches!(plan.offset, InsertionOffset::AfterCompactionFreeStart) {
-            self.compact()?
+            self.compact_internal(CompactionOrder::SlotIndexOrder)?;
         }
 
         let mut header_mut = self.header_mut()?;
@@ -39,34 +49,132 @@ impl Page {
             InsertionSlot::New => (old_slot_count, true),
         };
 
-        // Update header fields: slot_count, free_start/free_end, free_space.
+        // Compute all the new header values before touching the page, so that a value which does not
+        // fit in its header field leaves the page unchanged.
+        let row_len = bytes.len();
+        let slot_bytes = if inserting_new_slot { SLOT_SIZE } else { 0 };
+
+        let new_slot_count = (old_slot_count + 1).to_u16()?;
+        let new_free_end = (header_mut.get_free_end()? as usize)
+            .checked_sub(slot_bytes)
+            .ok_or(HeaderError::OffsetArithmetic)?
+            .to_u16()?;
+        let new_free_start = (current_free_start + row_len).to_u16()?;
+        // freeSpace always shrinks by row bytes, plus slot bytes only if creating a new slot
+        let new_free_space = (header_mut.get_free_space()? as usize)
+            .checked_sub(row_len + slot_bytes)
+            .ok_or(InsertError::NotEnoughSpace {
+                row_len,
+                page_free_space: header_mut.get_free_space()? as usize,
+            })?
+            .to_u16()?;
+        let new_live_row_count = (header_mut.get_live_row_count()? as usize + 1).to_u16()?;
+
+        // Update header fields: slot_count, free_start/free_end, free_space, live_row_count.
         if inserting_new_slot {
-            header_mut.set_slot_count((old_slot_count + 1) as u16)?;
-            let new_free_end = header_mut.get_free_end()? - SLOT_SIZE as u16;
+            header_mut.set_slot_count(new_slot_count)?;
             header_mut.set_free_end(new_free_end)?;
         }
 
         if inserting_at_free_start {
-            let new_free_start = header_mut.get_free_start()? + bytes.len() as u16;
             header_mut.set_free_start(new_free_start)?;
         }
 
-        // freeSpace always shrinks by row bytes, plus slot bytes only if creating a new slot
-        let new_free_space = header_mut.get_free_space()?
-            - bytes.len() as u16
-            - if inserting_new_slot {
-                SLOT_SIZE as u16
-            } else {
-                0
-            };
         header_mut.set_free_space(new_free_space)?;
+        header_mut.set_live_row_count(new_live_row_count)?;
 
         // Write the row bytes
         self.data[start_offset..(start_offset + bytes.len())].copy_from_slice(bytes.as_slice());
 
         // Write/update the slot entry
+        let slot_index = slot_index.to_u32()?;
+        self.slot_array_mut()?
+            .set_slot(slot_index, start_offset, row_len)?;
+
+        Ok(slot_index)
+    }
+
+    /// Places `bytes` anywhere in the free space of the page and points the slot at `slot_index` to them.
+    ///
+    /// If `slot_index` is past the end of the slot array, the array is grown up to and including it,
+    /// and the slots in between are left invalid. Otherwise, the slot must be invalid.
+    /// No other slot is touched, so the logical order of the rest of the rows is preserved.
+    pub(super) fn insert_at_internal(
+        &mut self,
+        slot_index: u32,
+        bytes: Vec<u8>,
+    ) -> Result<(), InsertError> {
+        let header = self.header_ref()?;
+        let slot_count = header.get_slot_count()? as usize;
+        let page_free_space = header.get_free_space()? as usize;
+        let index = slot_index as usize;
+
+        if index < slot_count && self.slot_array_ref()?.slot_ref(slot_index)?.is_valid()? {
+            return Err(InsertError::SlotOccupied { slot_index: index });
+        }
+
+        let row_len = bytes.len();
+        let new_slots = (index + 1).saturating_sub(slot_count);
+        if page_free_space < row_len + new_slots * SLOT_SIZE {
+            return Err(InsertError::NotEnoughSpace {
+                row_len,
+                page_free_space,
+            });
+        }
+
+        if new_slots > 0 {
+            self.grow_slot_array(new_slots)?;
+        }
+
+        let start_offset =
+            match self.find_insertion_offset(row_len, None, DEFAULT_COMPACTION_THRESHOLD)? {
+                InsertionOffset::Exact(pos) => pos,
+                InsertionOffset::AfterCompactionFreeStart => {
+                    self.compact_internal(CompactionOrder::SlotIndexOrder)?;
+                    self.header_ref()?.get_free_start()? as usize
+                }
+            };
+
+        let mut header_mut = self.header_mut()?;
+        let free_start = header_mut.get_free_start()? as usize;
+        let new_free_space = (header_mut.get_free_space()? as usize - row_len).to_u16()?;
+        let new_live_row_count = (header_mut.get_live_row_count()? as usize + 1).to_u16()?;
+        if start_offset == free_start {
+            header_mut.set_free_start((free_start + row_len).to_u16()?)?;
+        }
+        header_mut.set_free_space(new_free_space)?;
+        header_mut.set_live_row_count(new_live_row_count)?;
+
+        self.data[start_offset..start_offset + row_len].copy_from_slice(&bytes);
         self.slot_array_mut()?
-            .set_slot(slot_index as u32, start_offset as u16, bytes.len() as u16);
+            .set_slot(slot_index, start_offset, row_len)?;
+
+        Ok(())
+    }
+
+    /// Appends `count` invalid slots to the slot array, compacting the page first if the contiguous
+    /// free region cannot hold them. The caller must have checked that the page has enough free space.
+    fn grow_slot_array(&mut self, count: usize) -> Result<(), InsertError> {
+        let slot_bytes = count * SLOT_SIZE;
+
+        let header = self.header_ref()?;
+        if (header.get_free_end()? as usize).saturating_sub(header.get_free_start()? as usize)
+            < slot_bytes
+        {
+            self.compact_internal(CompactionOrder::SlotIndexOrder)?;
+        }
+
+        let mut header_mut = self.header_mut()?;
+        let free_end = header_mut.get_free_end()? as usize;
+        let new_slot_count = (header_mut.get_slot_count()? as usize + count).to_u16()?;
+        let new_free_end = (free_end - slot_bytes).to_u16()?;
+        let new_free_space = (header_mut.get_free_space()? as usize - slot_bytes).to_u16()?;
+        header_mut.set_slot_count(new_slot_count)?;
+        header_mut.set_free_end(new_free_end)?;
+        header_mut.set_free_space(new_free_space)?;
+
+        // The new slots lie right below the old ones and might hold leftovers of deleted rows
+        self.data[free_end + 1 - slot_bytes..=free_end].fill(0);
 
         Ok(())
     }
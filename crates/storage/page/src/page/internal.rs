@@ -1,9 +1,11 @@
+use crate::compaction::{CompactionOrder, CompactionReport};
 use crate::errors::header_error::HeaderError;
 use crate::errors::slot_error::SlotError;
 use crate::page::api::Page;
-use crate::slot::{SLOT_SIZE, SlotRef};
+use crate::slot::SLOT_SIZE;
 use crate::slot_array::{SlotArrayMut, SlotArrayRef};
 use crate::{HEADER_SIZE, PAGE_SIZE};
+use binary_helpers::conversions::UsizeConversion;
 
 /// Internal methods for the `Page` struct.
 impl Page {
@@ -27,50 +29,143 @@ impl Page {
     /// Returns a boolean or error if something goes wrong while processing the header.
     #[inline]
     pub(super) fn row_size_fits(&self, row_size: usize) -> Result<bool, HeaderError> {
-        Ok(self.header_ref()?.get_free_space()? >= (row_size + SLOT_SIZE) as u16)
+        Ok(self.header_ref()?.get_free_space()? as usize >= row_size + SLOT_SIZE)
     }
 
-    /// Determines if a slot is valid (used) or it has been invalidated (row referenced by it was deleted).
-    pub(super) fn is_slot_valid(&self, slot: &SlotRef) -> Result<bool, SlotError> {
-        Ok(slot.length()? != 0 && slot.offset()? != 0)
+    /// Returns the fraction of the free space that is not part of the contiguous region between
+    /// `free_start` and `free_end`, i.e. that is scattered in gaps between rows.
+    /// `0.0` for a page without gaps, `1.0` when all the free space is fragmented.
+    pub(super) fn fragmentation_ratio_internal(&self) -> Result<f64, HeaderError> {
+        let header = self.header_ref()?;
+        let free_space = header.get_free_space()? as usize;
+        if free_space == 0 {
+            return Ok(0.0);
+        }
+
+        let contiguous =
+            (header.get_free_end()? as usize + 1).saturating_sub(header.get_free_start()? as usize);
+        let fragmented = free_space.saturating_sub(contiguous);
+
+        Ok(fragmented as f64 / free_space as f64)
     }
 
-    pub(super) fn compact(&mut self) -> Result<(), SlotError> {
-        let start = HEADER_SIZE;
-        let end = self.header_ref()?.get_free_end()? as usize;
+    /// Whether the page would benefit from a compaction: either `can_compact` is set, or part of
+    /// the free space is scattered in gaps between rows.
+    pub(super) fn needs_compaction_internal(&self) -> Result<bool, HeaderError> {
+        let can_compact = self.header_ref()?.get_can_compact()? != 0;
+        Ok(can_compact || self.fragmentation_ratio_internal()? > 0.0)
+    }
 
-        let mut new_buffer = vec![0u8; end - start];
-        let total_slots = self.header_ref()?.get_slot_count()? as usize;
-        let mut write_head = 0usize;
+    /// Compacts the page if it has any fragmented free space, then clears `can_compact`.
+    /// Returns whether a compaction was performed.
+    pub(super) fn defragment_internal(&mut self) -> Result<bool, SlotError> {
+        if !self.needs_compaction_internal()? {
+            return Ok(false);
+        }
 
-        for slot_index in 0..total_slots {
-            let (slot_offset, slot_length) = {
-                let slot = self.slot_array_ref()?.slot_ref(slot_index as u32)?;
-                if !self.is_slot_valid(&slot)? {
-                    continue;
-                };
-                (slot.offset()? as usize, slot.length()? as usize)
-            };
+        self.compact_internal(CompactionOrder::SlotIndexOrder)?;
+        self.header_mut()?.set_can_compact(0)?;
 
-            let source = slot_offset..(slot_offset + slot_length);
-            let destination = write_head..(write_head + slot_length);
+        Ok(true)
+    }
 
-            new_buffer[destination].copy_from_slice(&self.data[source]);
+    /// Compacts the page, laying out the rows in `order`.
+    pub(super) fn compact_internal(
+        &mut self,
+        order: CompactionOrder,
+    ) -> Result<CompactionReport, SlotError> {
+        match order {
+            CompactionOrder::SlotIndexOrder => {
+                let start = self.header_ref()?.data_start()?;
+                let end = self.header_ref()?.get_free_end()? as usize;
+
+                self.compact_into_internal(&mut vec![0u8; end - start])
+            }
+            CompactionOrder::PhysicalOrder => self.compact_in_physical_order(),
+        }
+    }
+
+    /// Compacts the page in slot index order, using `scratch` to stage the rows instead of allocating a buffer.
+    /// `scratch` must be able to hold every valid row, otherwise the page is left untouched.
+    pub(super) fn compact_into_internal(
+        &mut self,
+        scratch: &mut [u8],
+    ) -> Result<CompactionReport, SlotError> {
+        let start = self.header_ref()?.data_start()?;
+        let extents: Vec<_> = self
+            .slot_extents()?
+            .into_iter()
+            .filter(|e| e.valid)
+            .collect();
+
+        let required = extents.iter().map(|e| e.length).sum::<usize>();
+        if scratch.len() < required {
+            return Err(SlotError::ScratchTooSmall {
+                required,
+                available: scratch.len(),
+            });
+        }
+
+        let mut write_head = 0usize;
+        let mut report = CompactionReport::default();
+
+        for extent in extents {
+            let source = extent.offset..extent.end();
+            let destination = write_head..(write_head + extent.length);
+
+            scratch[destination].copy_from_slice(&self.data[source]);
 
             let new_offset = start + write_head;
-            self.slot_array_mut()?.set_slot(
-                slot_index as u32,
-                new_offset as u16,
-                slot_length as u16,
-            )?;
-            write_head += slot_length;
+            if new_offset != extent.offset {
+                report.bytes_moved += extent.length;
+                report.rows_relocated += 1;
+            }
+            self.slot_array_mut()?
+                .set_slot(extent.index as u32, new_offset, extent.length)?;
+            write_head += extent.length;
+        }
+
+        self.data[start..start + write_head].copy_from_slice(&scratch[..write_head]);
+        report.free_start = start + write_head;
+        let new_free_start = report.free_start.to_u16().map_err(HeaderError::from)?;
+        self.header_mut()?.set_free_start(new_free_start)?;
+
+        Ok(report)
+    }
+
+    /// Compacts the page in place, keeping the rows in their physical order.
+    /// Each row only ever moves towards the header, past the gaps before it, so it cannot overwrite a
+    /// row that has not been moved yet.
+    fn compact_in_physical_order(&mut self) -> Result<CompactionReport, SlotError> {
+        let mut extents: Vec<_> = self
+            .slot_extents()?
+            .into_iter()
+            .filter(|e| e.valid)
+            .collect();
+        // A zero-length row can share its offset with the row after it. Keeping it first leaves it where it
+        // is on the next compaction, whatever the slot indexes.
+        extents.sort_by_key(|e| (e.offset, e.length));
+
+        let mut write_head = self.header_ref()?.data_start()?;
+        let mut report = CompactionReport::default();
+
+        for extent in extents {
+            if extent.offset != write_head {
+                self.data
+                    .copy_within(extent.offset..extent.end(), write_head);
+                self.slot_array_mut()?
+                    .set_slot(extent.index as u32, write_head, extent.length)?;
+                report.bytes_moved += extent.length;
+                report.rows_relocated += 1;
+            }
+            write_head += extent.length;
         }
 
-        self.data[start..start + write_head].copy_from_slice(&new_buffer[..write_head]);
-        self.header_mut()?
-            .set_free_start((start + write_head) as u16);
+        report.free_start = write_head;
+        let new_free_start = write_head.to_u16().map_err(HeaderError::from)?;
+        self.header_mut()?.set_free_start(new_free_start)?;
 
-        Ok(())
+        Ok(report)
     }
 }
 
@@ -104,6 +199,46 @@ mod private_methods_tests {
         let mut page = Page::new_empty(PageId::new(1, 0), PageType::Unsorted).unwrap();
         assert!(!page.row_size_fits(4000).unwrap());
     }
+
+    #[test]
+    fn row_fits_row_len_near_u16_max_does_not_wrap() {
+        let mut page = Page::new_empty(PageId::new(1, 0), PageType::Unsorted).unwrap();
+        // row_size + SLOT_SIZE would wrap around to 2 if computed as u16
+        assert!(!page.row_size_fits(u16::MAX as usize - 1).unwrap());
+    }
+
+    #[test]
+    fn max_row_size_fills_fresh_page() {
+        assert_eq!(Page::max_row_size(), 3996);
+
+        let page = Page::new_empty(PageId::new(1, 0), PageType::Unsorted).unwrap();
+        assert!(page.can_fit(Page::max_row_size()).unwrap());
+        assert!(!page.can_fit(Page::max_row_size() + 1).unwrap());
+    }
+
+    #[test]
+    fn can_fit_matches_row_size_fits() {
+        let empty = Page::new_empty(PageId::new(1, 0), PageType::Unsorted).unwrap();
+        let mut partially_filled = Page::new_empty(PageId::new(1, 0), PageType::Unsorted).unwrap();
+        let plan = partially_filled.plan_insert(1000).unwrap();
+        partially_filled.insert_heap(plan, vec![1; 1000]).unwrap();
+
+        for page in [&empty, &partially_filled] {
+            for row_len in [0, 1, 100, 2991, 2992, 2993, 3996, 3997, 4096] {
+                assert_eq!(
+                    page.can_fit(row_len).unwrap(),
+                    page.row_size_fits(row_len).unwrap(),
+                    "row_len {row_len}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn can_fit_row_len_near_usize_max_returns_false() {
+        let page = Page::new_empty(PageId::new(1, 0), PageType::Unsorted).unwrap();
+        assert!(!page.can_fit(usize::MAX).unwrap());
+    }
     // endregion
 
     // region Slot array
@@ -122,6 +257,24 @@ mod private_methods_tests {
             })
         ))
     }
+
+    #[test]
+    fn slot_array_move_slot_reorders_rows_without_moving_bytes() {
+        let mut page = Page::new_empty(PageId::new(1, 0), PageType::Unsorted).unwrap();
+        for (slot_index, value) in [(0, 1u8), (1, 2), (2, 3)] {
+            page.insert_at(slot_index, vec![value; 10]).unwrap();
+        }
+        let data_before = page.data()[HEADER_SIZE..HEADER_SIZE + 30].to_vec();
+
+        page.slot_array_mut().unwrap().move_slot(2, 0).unwrap();
+
+        assert_eq!(page.row(0).unwrap(), &[3u8; 10]);
+        assert_eq!(page.row(1).unwrap(), &[1u8; 10]);
+        assert_eq!(page.row(2).unwrap(), &[2u8; 10]);
+        assert_eq!(page.data()[HEADER_SIZE..HEADER_SIZE + 30], data_before);
+        let slot = page.slot_array_ref().unwrap().slot_ref(0).unwrap();
+        assert_eq!(slot.offset().unwrap() as usize, HEADER_SIZE + 20);
+    }
     // endregion
 
     // region Compact
@@ -131,7 +284,8 @@ mod private_methods_tests {
         // Ensure no slots
         page.header_mut().unwrap().set_slot_count(0).unwrap();
         // Call compact
-        page.compact().unwrap();
+        page.compact_internal(CompactionOrder::SlotIndexOrder)
+            .unwrap();
         // free_start should become HEADER_SIZE
         assert_eq!(
             page.header_ref().unwrap().get_free_start().unwrap() as usize,
@@ -159,7 +313,8 @@ mod private_methods_tests {
         // Do not copy the region yet — compact will update it in-place. Remember start index.
 
         // Compact
-        page.compact().unwrap();
+        page.compact_internal(CompactionOrder::SlotIndexOrder)
+            .unwrap();
 
         // After compact, free_start should be HEADER_SIZE
         assert_eq!(
@@ -188,17 +343,17 @@ mod private_methods_tests {
             .unwrap();
 
         // Fill source data at distinct locations
-        let a_offset = (HEADER_SIZE + 10) as u16;
-        let a_len = 5u16;
-        let b_offset = (HEADER_SIZE + 100) as u16; // for slot 2
-        let b_len = 3u16;
+        let a_offset = HEADER_SIZE + 10;
+        let a_len = 5usize;
+        let b_offset = HEADER_SIZE + 100; // for slot 2
+        let b_len = 3usize;
 
         // Write distinct bytes so we can verify copy
-        for i in 0..a_len as usize {
-            page.data[a_offset as usize + i] = 0xAAu8.wrapping_add(i as u8);
+        for i in 0..a_len {
+            page.data[a_offset + i] = 0xAAu8.wrapping_add(i as u8);
         }
-        for i in 0..b_len as usize {
-            page.data[b_offset as usize + i] = 0xC0u8.wrapping_add(i as u8);
+        for i in 0..b_len {
+            page.data[b_offset + i] = 0xC0u8.wrapping_add(i as u8);
         }
 
         {
@@ -214,12 +369,13 @@ mod private_methods_tests {
         eprintln!("DEBUG compact: slot_region_start={}", slot_region_start);
 
         // Run compact
-        page.compact().unwrap();
+        page.compact_internal(CompactionOrder::SlotIndexOrder)
+            .unwrap();
 
         // After compact, data should be laid out starting at HEADER_SIZE in slot-index order: slot0 then slot2
         let header = page.header_ref().unwrap();
         let expected_first = HEADER_SIZE;
-        let expected_second = HEADER_SIZE + a_len as usize;
+        let expected_second = HEADER_SIZE + a_len;
 
         // The compact implementation writes updated slot entries into the ORIGINAL slot array region
         // (slot_array_mut used the header values that were valid during compaction). Inspect that region.
@@ -231,28 +387,53 @@ mod private_methods_tests {
         let sa_ref = SlotArrayRef::new(&slot_region_bytes, slot_count).unwrap();
         let s0 = sa_ref.slot_ref(0).unwrap();
         assert_eq!(s0.offset().unwrap() as usize, expected_first);
-        assert_eq!(s0.length().unwrap(), a_len);
+        assert_eq!(s0.length().unwrap() as usize, a_len);
         let s1 = sa_ref.slot_ref(1).unwrap();
         assert_eq!(s1.offset().unwrap(), 0); // kept invalid
         assert_eq!(s1.length().unwrap(), 0);
         let s2 = sa_ref.slot_ref(2).unwrap();
         assert_eq!(s2.offset().unwrap() as usize, expected_second);
-        assert_eq!(s2.length().unwrap(), b_len);
+        assert_eq!(s2.length().unwrap() as usize, b_len);
 
         // verify the actual bytes were copied
-        for i in 0..a_len as usize {
+        for i in 0..a_len {
             assert_eq!(page.data[expected_first + i], 0xAAu8.wrapping_add(i as u8));
         }
-        for i in 0..b_len as usize {
+        for i in 0..b_len {
             assert_eq!(page.data[expected_second + i], 0xC0u8.wrapping_add(i as u8));
         }
 
         // free_start should be HEADER_SIZE + total_moved (per current compact implementation)
-        let total = a_len as usize + b_len as usize;
+        let total = a_len + b_len;
         assert_eq!(
             header.get_free_start().unwrap() as usize,
             HEADER_SIZE + total
         );
     }
+
+    #[test]
+    fn compact_into_matches_compact() {
+        let fragmented = || {
+            let mut page = Page::new_empty(PageId::new(1, 0), PageType::Unsorted).unwrap();
+            for (row_len, value) in [(100, 1u8), (40, 2), (70, 3), (30, 4)] {
+                let plan = page.plan_insert(row_len).unwrap();
+                page.insert_heap(plan, vec![value; row_len]).unwrap();
+            }
+            page.delete_row(0, false).unwrap();
+            page.delete_row(2, false).unwrap();
+            page
+        };
+        let mut expected = fragmented();
+        let mut page = fragmented();
+        // Leftover bytes in the scratch buffer must not leak into the page
+        let mut scratch = vec![0xEEu8; Page::max_row_size()];
+
+        expected
+            .compact_internal(CompactionOrder::SlotIndexOrder)
+            .unwrap();
+        page.compact_into_internal(&mut scratch).unwrap();
+
+        assert_eq!(page.data().to_vec(), expected.data().to_vec());
+    }
     // endregion
 }
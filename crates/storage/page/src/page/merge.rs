@@ -0,0 +1,47 @@
+use crate::errors::merge_error::MergeError;
+use crate::insertion_plan::DEFAULT_COMPACTION_THRESHOLD;
+use crate::page::api::Page;
+use crate::slot::SLOT_SIZE;
+
+/// Internal methods for merging a sibling page into this one.
+impl Page {
+    /// Appends all the valid rows of `source` to this page, in slot order, if they all fit.
+    /// Deleted slots of this page are reused first, so only the remaining rows need new slots.
+    ///
+    /// If `source` is the right (or left) sibling of this page, the right (or left) pointer of this page
+    /// is moved past it, so `source` is no longer linked from this page. `source` itself is not modified.
+    ///
+    /// # Returns
+    /// `true` if the rows were merged, `false` if they do not fit, in which case nothing is modified.
+    pub(super) fn merge_from_internal(&mut self, source: &Page) -> Result<bool, MergeError> {
+        let rows: Vec<_> = source
+            .slot_extents()?
+            .into_iter()
+            .filter(|e| e.valid)
+            .collect();
+
+        let reusable_slots = self.slot_extents()?.iter().filter(|e| !e.valid).count();
+        let new_slots = rows.len().saturating_sub(reusable_slots);
+        let required = rows.iter().map(|e| e.length).sum::<usize>() + new_slots * SLOT_SIZE;
+        if required > self.header_ref()?.get_free_space()? as usize {
+            return Ok(false);
+        }
+
+        for extent in rows {
+            let row = source.data[extent.offset..extent.end()].to_vec();
+            let plan = self.plan_insert_internal(row.len(), DEFAULT_COMPACTION_THRESHOLD)?;
+            self.insert_row_unsorted_internal(plan, row)?;
+        }
+
+        let source_page = source.page_id.page_number;
+        let source_header = source.header_ref()?;
+        let mut header = self.header_mut()?;
+        if header.get_right_page()? == source_page {
+            header.set_right_page(source_header.get_right_page()?)?;
+        } else if header.get_left_page()? == source_page {
+            header.set_left_page(source_header.get_left_page()?)?;
+        }
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,84 @@
+use crate::errors::header_error::HeaderError;
+use crate::errors::move_row_error::MoveRowError;
+use crate::errors::slot_error::SlotError;
+use crate::page::api::Page;
+use crate::page::extents::SlotExtent;
+use binary_helpers::conversions::UsizeConversion;
+
+/// Internal methods for relocating single rows within the page.
+impl Page {
+    /// Moves the row of `slot_index` to `new_offset`, which must lie within the data region and must not
+    /// overlap any other live row. The row may overlap its own current bytes.
+    /// The old bytes are left behind as stale, so the page is flagged for compaction. `free_start` is
+    /// raised when the row moves past it, but never lowered.
+    pub(super) fn move_row_internal(
+        &mut self,
+        slot_index: u32,
+        new_offset: usize,
+    ) -> Result<(), MoveRowError> {
+        let extents = self.slot_extents()?;
+        // Goes through the slot array, so that out of range slots are reported as such
+        let slot = self.slot_array_ref()?.slot_ref(slot_index)?;
+        if !slot.is_valid()? {
+            return Err(SlotError::InvalidSlot {
+                slot_index: slot_index as usize,
+            }
+            .into());
+        }
+        let extent = extents[slot_index as usize];
+
+        let header = self.header_ref()?;
+        let (min, max) = (header.data_start()?, header.get_free_end()? as usize + 1);
+        let out_of_bounds = MoveRowError::DestinationOutOfBounds {
+            offset: new_offset,
+            length: extent.length,
+            min,
+            max,
+        };
+        let Some(new_end) = new_offset.checked_add(extent.length) else {
+            return Err(out_of_bounds);
+        };
+        if new_offset < min || new_end > max {
+            return Err(out_of_bounds);
+        }
+
+        if let Some(other) = extents.iter().find(|other| {
+            other.valid
+                && other.index != extent.index
+                && other.offset < new_end
+                && new_offset < other.end()
+        }) {
+            return Err(MoveRowError::DestinationOccupied {
+                offset: new_offset,
+                length: extent.length,
+                slot: other.index,
+            });
+        }
+
+        if new_offset == extent.offset {
+            return Ok(());
+        }
+        self.relocate_row(&extent, new_offset)?;
+
+        let mut header = self.header_mut()?;
+        if new_end > header.get_free_start()? as usize {
+            header.set_free_start(new_end.to_u16().map_err(HeaderError::from)?)?;
+        }
+        header.set_can_compact(1)?;
+
+        Ok(())
+    }
+
+    /// Copies the row described by `extent` to `new_offset` and points its slot there, without any checks.
+    /// Used by the operations that move rows around, which know the destination is free.
+    pub(super) fn relocate_row(
+        &mut self,
+        extent: &SlotExtent,
+        new_offset: usize,
+    ) -> Result<(), SlotError> {
+        self.data
+            .copy_within(extent.offset..extent.end(), new_offset);
+        self.slot_array_mut()?
+            .set_slot(extent.index as u32, new_offset, extent.length)
+    }
+}
@@ -1,6 +1,9 @@
+use crate::errors::header_error::HeaderError;
 use crate::errors::insert_error::InsertError;
 use crate::errors::slot_error::SlotError;
-use crate::insertion_plan::{InsertionOffset, InsertionPlan, InsertionSlot};
+use crate::insertion_plan::{
+    DEFAULT_COMPACTION_THRESHOLD, InsertionOffset, InsertionPlan, InsertionSlot, PageVersion,
+};
 use crate::page::api::Page;
 use crate::slot::{SLOT_SIZE, SlotRef};
 
@@ -15,10 +18,19 @@ impl Page {
     /// # Arguments
     ///
     /// * `row_len` - The length of the row to be inserted, in bytes.
+    /// * `compaction_threshold` - Fragmentation ratio above which the page is compacted instead of
+    ///   placing the row in a gap. See `find_insertion_offset`.
     pub(super) fn plan_insert_internal(
         &self,
         row_len: usize,
+        compaction_threshold: f64,
     ) -> Result<InsertionPlan, InsertError> {
+        // No other page could hold the row either, so this is not reported as a lack of space
+        let max = Self::max_row_size();
+        if row_len > max {
+            return Err(InsertError::RowTooLarge { row_len, max });
+        }
+
         // Decide which slot will be used (reused or new)
         let slot = self.get_insertion_slot()?; // Reuse(idx) or New
 
@@ -38,9 +50,33 @@ impl Page {
         }
 
         // Offset planning
-        let offset = self.find_insertion_offset(row_len, None)?;
+        let mut offset = self.find_insertion_offset(row_len, None, compaction_threshold)?;
+
+        // A new slot grows the slot array into the contiguous free region, so it must not run into
+        // the row being placed there. Compacting consolidates enough room for both.
+        if needs_new_slot && let InsertionOffset::Exact(pos) = offset {
+            let free_start = header.get_free_start()? as usize;
+            let free_end = header.get_free_end()? as usize;
+            if free_start.max(pos + row_len) + SLOT_SIZE > free_end + 1 {
+                offset = InsertionOffset::AfterCompactionFreeStart;
+            }
+        }
 
-        Ok(InsertionPlan { slot, offset })
+        Ok(InsertionPlan {
+            slot,
+            offset,
+            version: self.page_version()?,
+        })
+    }
+
+    /// Captures the current version of the page, used to detect stale insertion plans.
+    pub(crate) fn page_version(&self) -> Result<PageVersion, HeaderError> {
+        let header = self.header_ref()?;
+        Ok(PageVersion {
+            slot_count: header.get_slot_count()?,
+            free_start: header.get_free_start()?,
+            free_space: header.get_free_space()?,
+        })
     }
 
     /// Determines the slot to use for the insertion.
@@ -54,7 +90,7 @@ impl Page {
 
         for slot_index in 0..slot_count {
             let current_slot = slot_array.slot_ref(slot_index as u32)?;
-            if !self.is_slot_valid(&current_slot)? {
+            if !current_slot.is_valid()? {
                 return Ok(InsertionSlot::Reuse(slot_index));
             }
         }
@@ -71,6 +107,10 @@ impl Page {
     /// 4) after a compaction
     ///
     /// The probes are short-circuiting - the first one to match triggers a return.
+    /// A zero-length row occupies no bytes, so it skips the probes and is anchored at the start of the
+    /// data region, where it can neither overlap the slot array nor move `free_start`.
+    /// If the fragmentation ratio of the page exceeds `compaction_threshold`, probes 2) and 3) are skipped
+    /// and the page is compacted right away, so the free space is consolidated before it gets even more scattered.
     ///
     /// # Arguments
     ///
@@ -78,43 +118,48 @@ impl Page {
     /// * `treat_slot_len_as_zero` - An optional metadata value used for row update flows.
     ///   If provided, the length of the row at the given index is ignored, allowing the
     ///   algorithm to consider the space occupied by the old row as available.
+    /// * `compaction_threshold` - Fragmentation ratio (see `fragmentation_ratio_internal`) above which
+    ///   the page is compacted instead of looking for a gap. `DEFAULT_COMPACTION_THRESHOLD` disables this.
     pub(super) fn find_insertion_offset(
         &self,
         row_len: usize,
         treat_slot_len_as_zero: Option<usize>,
+        compaction_threshold: f64,
     ) -> Result<InsertionOffset, InsertError> {
         let header = self.header_ref()?;
-        let slot_array = self.slot_array_ref()?;
+
+        if row_len == 0 {
+            return Ok(InsertionOffset::Exact(header.data_start()?));
+        }
 
         let free_start = header.get_free_start()? as usize;
         let free_end = header.get_free_end()? as usize;
-        let slot_count = header.get_slot_count()? as usize;
 
         // Fast path: row fits in contiguous free area (no compaction needed)
         if free_end.saturating_sub(free_start) >= row_len {
             return Ok(InsertionOffset::Exact(free_start));
         }
 
+        if self.fragmentation_ratio_internal()? > compaction_threshold {
+            return Ok(InsertionOffset::AfterCompactionFreeStart);
+        }
+
         // Collect physical extents of all valid rows.
         // Slot index order != physical order, so we sort by offset.
-        let mut extents: Vec<(usize, usize)> = Vec::new(); // (start, end)
-
-        for i in 0..slot_count {
-            let s = slot_array.slot_ref(i as u32)?;
-            if self.is_slot_valid(&s)? {
-                let start = s.offset()? as usize;
-
+        let mut extents: Vec<(usize, usize)> = self
+            .slot_extents()?
+            .into_iter()
+            .filter(|e| e.valid)
+            .map(|e| {
                 // for updates, we might be instructed to ignore the row that is being changed - the space occupied by it is not relevant, so it can be treated as non-existent.
                 // this allows us to isolate scenarios where the current_row.len = 100, there is a 50 bytes gap right after it and we want to update it to a new len of 150 => it should fit in the spot used by the old row + the existing gap.
-                let end = if Some(i) == treat_slot_len_as_zero {
-                    start
+                if Some(e.index) == treat_slot_len_as_zero {
+                    (e.offset, e.offset)
                 } else {
-                    start + s.length()? as usize
-                };
-
-                extents.push((start, end));
-            }
-        }
+                    (e.offset, e.end())
+                }
+            })
+            .collect(); // (start, end)
 
         // If there are no valid rows, then after compaction the page becomes
         // one contiguous free region
@@ -181,8 +226,8 @@ mod tests {
         // Populate both slots with non-zero offset and length
         {
             let mut sa = page.slot_array_mut().unwrap();
-            sa.set_slot(0, HEADER_SIZE as u16, 10).unwrap();
-            sa.set_slot(1, (HEADER_SIZE + 10) as u16, 20).unwrap();
+            sa.set_slot(0, HEADER_SIZE, 10).unwrap();
+            sa.set_slot(1, HEADER_SIZE + 10, 20).unwrap();
         }
 
         assert!(matches!(
@@ -208,8 +253,8 @@ mod tests {
         // Leave slot 0 as the default (zeros -> invalid). Set slots 1 and 2 to valid values.
         {
             let mut sa = page.slot_array_mut().unwrap();
-            sa.set_slot(1, HEADER_SIZE as u16, 10).unwrap();
-            sa.set_slot(2, (HEADER_SIZE + 10) as u16, 20).unwrap();
+            sa.set_slot(1, HEADER_SIZE, 10).unwrap();
+            sa.set_slot(2, HEADER_SIZE + 10, 20).unwrap();
         }
 
         assert!(matches!(
@@ -235,9 +280,9 @@ mod tests {
         // Make slot 0 valid, leave slot 1 invalid (zeros), slot 2 valid
         {
             let mut sa = page.slot_array_mut().unwrap();
-            sa.set_slot(0, HEADER_SIZE as u16, 8).unwrap();
+            sa.set_slot(0, HEADER_SIZE, 8).unwrap();
             // slot 1 left as zeros
-            sa.set_slot(2, (HEADER_SIZE + 8) as u16, 16).unwrap();
+            sa.set_slot(2, HEADER_SIZE + 8, 16).unwrap();
         }
 
         assert!(matches!(
@@ -257,7 +302,9 @@ mod tests {
             .set_free_end((PAGE_SIZE - 1) as u16)
             .unwrap();
 
-        let res = page.find_insertion_offset(50, None).unwrap();
+        let res = page
+            .find_insertion_offset(50, None, DEFAULT_COMPACTION_THRESHOLD)
+            .unwrap();
         assert!(matches!(res, InsertionOffset::Exact(100)));
     }
 
@@ -281,7 +328,9 @@ mod tests {
             .set_free_start(new_free_end - 10)
             .unwrap();
 
-        let res = page.find_insertion_offset(50, None).unwrap();
+        let res = page
+            .find_insertion_offset(50, None, DEFAULT_COMPACTION_THRESHOLD)
+            .unwrap();
         assert!(matches!(res, InsertionOffset::AfterCompactionFreeStart));
     }
 
@@ -307,12 +356,14 @@ mod tests {
         // Place two rows with a gap between them (physical offsets earlier in the page)
         {
             let mut sa = page.slot_array_mut().unwrap();
-            sa.set_slot(0, HEADER_SIZE as u16, 10).unwrap(); // ends at HEADER_SIZE+10
-            sa.set_slot(1, (HEADER_SIZE + 30) as u16, 10).unwrap(); // starts after a gap
+            sa.set_slot(0, HEADER_SIZE, 10).unwrap(); // ends at HEADER_SIZE+10
+            sa.set_slot(1, HEADER_SIZE + 30, 10).unwrap(); // starts after a gap
         }
 
         let expected = HEADER_SIZE + 10;
-        let res = page.find_insertion_offset(15, None).unwrap();
+        let res = page
+            .find_insertion_offset(15, None, DEFAULT_COMPACTION_THRESHOLD)
+            .unwrap();
         assert!(matches!(res, InsertionOffset::Exact(pos) if pos == expected));
     }
 
@@ -338,11 +389,13 @@ mod tests {
         // Single row that ends well before free_end
         {
             let mut sa = page.slot_array_mut().unwrap();
-            sa.set_slot(0, HEADER_SIZE as u16, 8).unwrap();
+            sa.set_slot(0, HEADER_SIZE, 8).unwrap();
         }
 
         let last_end = (HEADER_SIZE + 8);
-        let res = page.find_insertion_offset(10, None).unwrap();
+        let res = page
+            .find_insertion_offset(10, None, DEFAULT_COMPACTION_THRESHOLD)
+            .unwrap();
         assert!(matches!(res, InsertionOffset::Exact(pos) if pos == last_end));
     }
 
@@ -369,12 +422,14 @@ mod tests {
         {
             let mut sa = page.slot_array_mut().unwrap();
             // First row starts 40 bytes before free_end and is 20 bytes long (ends at free_end-20)
-            sa.set_slot(0, (new_free_end - 40), 20).unwrap();
+            sa.set_slot(0, (new_free_end - 40) as usize, 20).unwrap();
             // Second row starts at free_end-20 and is 20 bytes long (ends at free_end)
-            sa.set_slot(1, (new_free_end - 20), 20).unwrap();
+            sa.set_slot(1, (new_free_end - 20) as usize, 20).unwrap();
         }
 
-        let res = page.find_insertion_offset(10, None).unwrap();
+        let res = page
+            .find_insertion_offset(10, None, DEFAULT_COMPACTION_THRESHOLD)
+            .unwrap();
         assert!(matches!(res, InsertionOffset::AfterCompactionFreeStart));
     }
 
@@ -404,7 +459,9 @@ mod tests {
             sa.set_slot(2, 250, 3827).unwrap();
         }
 
-        let res = page.find_insertion_offset(150, Some(0)).unwrap();
+        let res = page
+            .find_insertion_offset(150, Some(0), DEFAULT_COMPACTION_THRESHOLD)
+            .unwrap();
         assert!(matches!(res, InsertionOffset::Exact(96)));
     }
 }
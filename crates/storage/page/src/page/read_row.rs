@@ -1,15 +1,123 @@
 use crate::errors::read_row_error::ReadRowError;
+use crate::errors::slot_error::SlotError;
 use crate::page::api::Page;
+use std::cmp::Ordering;
 
 /// Internal row access methods for the `Page` struct.
 impl Page {
     /// Retrieves a row by its slot index.
     /// Returns a slice of bytes representing the row data.
+    /// Slots of deleted rows are rejected with `SlotError::InvalidSlot`, so they cannot be mistaken for
+    /// zero-length rows. A slot pointing past the end of the page (i.e. a corrupted one) results in
+    /// `ReadRowError::RowOutOfBounds`.
     pub(super) fn read_row_internal(&self, slot_index: u32) -> Result<&[u8], ReadRowError> {
         let slot = self.slot_array_ref()?.slot_ref(slot_index)?;
+        if !slot.is_valid()? {
+            return Err(SlotError::InvalidSlot {
+                slot_index: slot_index as usize,
+            }
+            .into());
+        }
 
         let (offset, length) = (slot.offset()? as usize, slot.length()? as usize);
 
-        Ok(&self.data[offset..offset + length])
+        self.data
+            .get(offset..offset + length)
+            .ok_or(ReadRowError::RowOutOfBounds { offset, length })
+    }
+
+    /// Retrieves several rows at once, in the order of `slot_indices`.
+    /// Slots of deleted rows are rejected, like in `read_row_internal`. The first slot that cannot be read
+    /// fails the whole call, so no rows are returned.
+    pub(super) fn read_rows_internal(
+        &self,
+        slot_indices: &[u32],
+    ) -> Result<Vec<&[u8]>, ReadRowError> {
+        slot_indices
+            .iter()
+            .map(|&slot_index| self.read_row_internal(slot_index))
+            .collect()
+    }
+
+    /// Returns the length of the row held by a slot, without touching the row bytes.
+    /// Slots of deleted rows are rejected with `SlotError::InvalidSlot`, like out of range ones.
+    pub(super) fn row_length_internal(&self, slot_index: u32) -> Result<u16, SlotError> {
+        let slot_array = self.slot_array_ref()?;
+        let slot = slot_array.slot_ref(slot_index)?;
+        if !slot.is_valid()? {
+            return Err(SlotError::InvalidSlot {
+                slot_index: slot_index as usize,
+            });
+        }
+        slot.length()
+    }
+
+    /// Retrieves a row by its slot index, for in-place modification.
+    /// Returns a mutable slice spanning exactly the bytes of the row.
+    /// Slots of deleted rows are rejected, like in `read_row_internal`.
+    pub(super) fn row_mut_internal(&mut self, slot_index: u32) -> Result<&mut [u8], ReadRowError> {
+        let (offset, length) = {
+            let slot_array = self.slot_array_ref()?;
+            let slot = slot_array.slot_ref(slot_index)?;
+            if !slot.is_valid()? {
+                return Err(SlotError::InvalidSlot {
+                    slot_index: slot_index as usize,
+                }
+                .into());
+            }
+            (slot.offset()? as usize, slot.length()? as usize)
+        };
+
+        self.data
+            .get_mut(offset..offset + length)
+            .ok_or(ReadRowError::RowOutOfBounds { offset, length })
+    }
+
+    /// Copies a row into the beginning of `destination`, returning the length of the row.
+    /// Slots of deleted rows are rejected, like in `row_mut_internal`.
+    pub(super) fn copy_row_into_internal(
+        &self,
+        slot_index: u32,
+        destination: &mut [u8],
+    ) -> Result<usize, ReadRowError> {
+        let row = self.read_row_internal(slot_index)?;
+        if destination.len() < row.len() {
+            return Err(ReadRowError::DestinationTooSmall {
+                required: row.len(),
+                available: destination.len(),
+            });
+        }
+        destination[..row.len()].copy_from_slice(row);
+        Ok(row.len())
+    }
+
+    /// Binary searches the rows, in slot index order, using `cmp` to compare each row against the target.
+    /// The rows must be sorted consistently with `cmp`, and every slot must hold a row.
+    ///
+    /// Mirrors `slice::binary_search_by`: `Ok(slot_index)` of a matching row, or `Err(insertion_point)`,
+    /// the slot index at which a row matching the target would keep the rows sorted.
+    pub(super) fn binary_search_rows_internal<F>(
+        &self,
+        cmp: F,
+    ) -> Result<Result<u32, u32>, ReadRowError>
+    where
+        F: Fn(&[u8]) -> Ordering,
+    {
+        let slot_count = self
+            .header_ref()
+            .and_then(|h| h.get_slot_count())
+            .map_err(SlotError::from)? as u32;
+
+        let (mut low, mut high) = (0, slot_count);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match cmp(self.read_row_internal(mid)?) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Ok(Ok(mid)),
+            }
+        }
+
+        Ok(Err(low))
     }
 }
@@ -0,0 +1,66 @@
+use crate::compaction::CompactionOrder;
+use crate::errors::slot_error::SlotError;
+use crate::errors::update_error::UpdateError;
+use crate::insertion_plan::{DEFAULT_COMPACTION_THRESHOLD, InsertionOffset};
+use crate::page::api::Page;
+use binary_helpers::conversions::UsizeConversion;
+
+impl Page {
+    /// Writes `new_row` to a fresh location while the old row is still live, then points the slot at it.
+    /// The space of the old row is only released once the new row is in place, so every check is done
+    /// before the page is touched.
+    pub(super) fn replace_row_internal(
+        &mut self,
+        slot_index: usize,
+        new_row: Vec<u8>,
+    ) -> Result<(), UpdateError> {
+        let old_row_length = {
+            let slot_array = self.slot_array_ref()?;
+            let slot = slot_array.slot_ref(slot_index.to_u32()?)?;
+            if !slot.is_valid()? {
+                return Err(SlotError::InvalidSlot { slot_index }.into());
+            }
+            slot.length()? as usize
+        };
+
+        // The slot is reused, so only the row itself needs room
+        let free_space = self.header_ref()?.get_free_space()? as usize;
+        if new_row.len() > free_space {
+            return Err(UpdateError::NotEnoughSpace {
+                row_len: new_row.len(),
+                page_free_space: free_space,
+            });
+        }
+
+        // The old row is still live here, so the new one can never be placed on top of it
+        let insertion_offset =
+            match self.find_insertion_offset(new_row.len(), None, DEFAULT_COMPACTION_THRESHOLD)? {
+                InsertionOffset::Exact(offset) => offset,
+                InsertionOffset::AfterCompactionFreeStart => {
+                    self.compact_internal(CompactionOrder::SlotIndexOrder)?;
+                    self.header_ref()?.get_free_start()? as usize
+                }
+            };
+
+        self.data[insertion_offset..insertion_offset + new_row.len()].copy_from_slice(&new_row);
+
+        {
+            let mut slot_array_mut = self.slot_array_mut()?;
+            let mut slot = slot_array_mut.slot_mut(slot_index.to_u32()?)?;
+            slot.set_offset(insertion_offset.to_u16()?)?;
+            slot.set_length(new_row.len().to_u16()?)?;
+        }
+
+        let mut header = self.header_mut()?;
+        if insertion_offset == header.get_free_start()? as usize {
+            header.set_free_start((insertion_offset + new_row.len()).to_u16()?)?;
+        }
+        header.set_free_space((free_space + old_row_length - new_row.len()).to_u16()?)?;
+        // The old row leaves a gap behind
+        if old_row_length > 0 {
+            header.set_can_compact(1)?;
+        }
+
+        Ok(())
+    }
+}
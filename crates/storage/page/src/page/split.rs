@@ -0,0 +1,55 @@
+use crate::errors::split_error::SplitError;
+use crate::insertion_plan::DEFAULT_COMPACTION_THRESHOLD;
+use crate::page::api::Page;
+use crate::slot::SLOT_SIZE;
+
+/// Internal methods for splitting a page into two siblings.
+impl Page {
+    /// Moves the upper half of the rows, in physical offset order, from this page into `dest`, then
+    /// compacts this page. The moved rows get new slots in `dest`, while their slots in this page are
+    /// freed up. Sibling pointers are rewired so that `dest` sits right after this page:
+    /// `self.right_page = dest`, `dest.left_page = self` and `dest.right_page` takes over the previous
+    /// right sibling of this page. The left pointer of that previous right sibling is not updated,
+    /// since it lives on another page.
+    ///
+    /// Nothing is modified if `dest` does not have enough free space for all the moved rows.
+    pub(super) fn split_into_internal(&mut self, dest: &mut Page) -> Result<(), SplitError> {
+        let mut extents: Vec<_> = self
+            .slot_extents()?
+            .into_iter()
+            .filter(|e| e.valid)
+            .collect();
+        extents.sort_by_key(|e| e.offset);
+        let moved = &extents[extents.len() / 2..];
+
+        let required = moved.iter().map(|e| e.length + SLOT_SIZE).sum();
+        let available = dest.header_ref()?.get_free_space()? as usize;
+        if required > available {
+            return Err(SplitError::DestinationTooSmall {
+                required,
+                available,
+            });
+        }
+
+        for extent in moved {
+            let row = self.data[extent.offset..extent.end()].to_vec();
+            let plan = dest.plan_insert_internal(row.len(), DEFAULT_COMPACTION_THRESHOLD)?;
+            dest.insert_row_unsorted_internal(plan, row)?;
+            self.delete_row_internal(extent.index, false)?;
+        }
+        self.defragment_internal()?;
+
+        let source_page = self.page_id.page_number;
+        let dest_page = dest.page_id.page_number;
+        let previous_right = self.header_ref()?.get_right_page()?;
+
+        let mut header = self.header_mut()?;
+        header.set_right_page(dest_page)?;
+
+        let mut dest_header = dest.header_mut()?;
+        dest_header.set_left_page(source_page)?;
+        dest_header.set_right_page(previous_right)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use crate::errors::slot_error::SlotError;
+use crate::page::api::Page;
+use crate::page_type::PageType;
+use std::fmt::Write;
+
+/// Internal methods for rendering a human-readable view of the page.
+impl Page {
+    /// Renders the page id, the header fields and the slot array as a multi-line string.
+    /// Only reads the page through the header and slot array accessors.
+    pub(super) fn debug_summary_internal(&self) -> Result<String, SlotError> {
+        let header = self.header_ref()?;
+        let raw_page_type = header.get_page_type()?;
+        let page_type = match PageType::try_from(raw_page_type) {
+            Ok(page_type) => format!("{page_type:?}"),
+            Err(_) => format!("Unknown({raw_page_type})"),
+        };
+
+        let mut summary = String::new();
+        let _ = writeln!(summary, "Page {} ({page_type})", self.page_id);
+        let _ = writeln!(
+            summary,
+            "  slot_count: {}, free_start: {}, free_end: {}, free_space: {}, can_compact: {}",
+            header.get_slot_count()?,
+            header.get_free_start()?,
+            header.get_free_end()?,
+            header.get_free_space()?,
+            header.get_can_compact()?,
+        );
+
+        let slot_array = self.slot_array_ref()?;
+        for slot_index in 0..header.get_slot_count()? as u32 {
+            let slot = slot_array.slot_ref(slot_index)?;
+            let state = if slot.is_valid()? { "valid" } else { "deleted" };
+            let _ = writeln!(
+                summary,
+                "  [{slot_index}] offset: {}, length: {}, {state}",
+                slot.offset()?,
+                slot.length()?,
+            );
+        }
+
+        Ok(summary)
+    }
+
+    /// Renders the header snapshot and every slot, with a hex preview of its row, as a single JSON object.
+    /// Only reads the page through `header_snapshot` and `slot_extents`.
+    pub(super) fn dump_json_internal(&self) -> Result<String, SlotError> {
+        let header = self.header_ref()?.snapshot()?;
+
+        let mut json = String::new();
+        let _ = write!(
+            json,
+            "{{\"page_id\":{},\"header\":{{\"slot_count\":{},\"free_start\":{},\"free_end\":{},\"free_space\":{},\
+             \"can_compact\":{},\"page_number\":{},\"page_type\":{},\"left_page\":{},\"right_page\":{},\
+             \"last_lsn\":{},\"checksum\":{},\"live_row_count\":{}}},\"slots\":[",
+            json_string(&self.page_id.to_string()),
+            header.slot_count,
+            header.free_start,
+            header.free_end,
+            header.free_space,
+            header.can_compact,
+            header.page_number,
+            header.page_type,
+            header.left_page,
+            header.right_page,
+            header.last_lsn,
+            header.checksum,
+            header.live_row_count,
+        );
+
+        for (position, extent) in self.slot_extents()?.into_iter().enumerate() {
+            let preview = if extent.valid {
+                let end = extent.end().min(extent.offset + HEX_PREVIEW_LEN);
+                self.data.get(extent.offset..end).unwrap_or_default()
+            } else {
+                &[]
+            };
+            let hex_preview: String = preview.iter().map(|byte| format!("{byte:02x}")).collect();
+
+            if position > 0 {
+                json.push(',');
+            }
+            let _ = write!(
+                json,
+                "{{\"slot\":{},\"offset\":{},\"length\":{},\"valid\":{},\"hex_preview\":\"{hex_preview}\"}}",
+                extent.index, extent.offset, extent.length, extent.valid,
+            );
+        }
+        json.push_str("]}");
+
+        Ok(json)
+    }
+}
+
+/// Number of leading bytes of each row shown in `Page::dump_json`.
+const HEX_PREVIEW_LEN: usize = 16;
+
+/// Quotes `value` as a JSON string, escaping the characters JSON does not allow as is.
+pub(super) fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -1,7 +1,7 @@
 use crate::errors::header_error::HeaderError;
 use crate::errors::slot_error::SlotError;
 use crate::errors::update_error::UpdateError;
-use crate::insertion_plan::InsertionOffset;
+use crate::insertion_plan::{DEFAULT_COMPACTION_THRESHOLD, InsertionOffset};
 use crate::page::api::Page;
 use binary_helpers::conversions::UsizeConversion;
 
@@ -19,7 +19,7 @@ impl Page {
             let slot = slot_array.slot_ref(old_row_slot_index.to_u32()?)?;
 
             // quick check to ensure slot index is valid
-            if !self.is_slot_valid(&slot)? {
+            if !slot.is_valid()? {
                 return Err(SlotError::InvalidSlot {
                     slot_index: old_row_slot_index,
                 }
@@ -63,8 +63,11 @@ impl Page {
                 (old_row_offset, false)
             }
             UpdateScenario::Larger => {
-                let insertion_offset =
-                    self.find_insertion_offset(new_row.len(), Some(old_row_slot_index))?;
+                let insertion_offset = self.find_insertion_offset(
+                    new_row.len(),
+                    Some(old_row_slot_index),
+                    DEFAULT_COMPACTION_THRESHOLD,
+                )?;
                 match insertion_offset {
                     InsertionOffset::Exact(start_offset) => {
                         // We need to determine if this exact offset is somewhere in between two rows, or at the current free_start
@@ -76,6 +79,11 @@ impl Page {
                         // We need to delete the old row, otherwise the compaction will have no effect.
                         self.delete_row_internal(old_row_slot_index, true)?;
 
+                        // The row is written back below, so it is still live.
+                        let mut header = self.header_mut()?;
+                        let live_row_count = header.get_live_row_count()?;
+                        header.set_live_row_count((live_row_count as usize + 1).to_u16()?)?;
+
                         // In this case, it is pretty clear we will be inserting at the (new) free start
                         (self.header_ref()?.get_free_start()? as usize, true)
                     }
@@ -0,0 +1,203 @@
+use crate::errors::header_error::HeaderError;
+use crate::errors::slot_error::SlotError;
+use crate::errors::validation_error::ValidationError;
+use crate::header::{checksummed_ranges, data_start};
+use crate::page::api::Page;
+use crate::page_type::PageType;
+use crate::slot::SLOT_SIZE;
+use crate::{HEADER_SIZE, PAGE_SIZE};
+use binary_helpers::checksum::Crc32;
+use binary_helpers::conversions::UsizeConversion;
+
+/// Internal methods for verifying the integrity of a page.
+impl Page {
+    /// Computes the CRC-32 of the page over the `checksummed_ranges`, so the `checksum` header field
+    /// itself is skipped.
+    pub(super) fn compute_checksum_internal(&self) -> u32 {
+        let mut crc = Crc32::new();
+        for range in checksummed_ranges() {
+            crc.update(&self.data[range]);
+        }
+        crc.finalize()
+    }
+
+    /// Computes the checksum of the page and stores it in the header.
+    pub(super) fn update_checksum_internal(&mut self) -> Result<(), HeaderError> {
+        let checksum = self.compute_checksum_internal();
+        self.header_mut()?.set_checksum(checksum)
+    }
+
+    /// Compares the stored checksum against the one computed from the page contents.
+    /// A stored checksum of `0` means the checksum was never computed, in which case the check is skipped.
+    pub(super) fn verify_checksum_internal(&self) -> Result<(), ValidationError> {
+        let stored = self.header_ref()?.get_checksum()?;
+        if stored == 0 {
+            return Ok(());
+        }
+
+        let computed = self.compute_checksum_internal();
+        if stored != computed {
+            return Err(ValidationError::ChecksumMismatch { stored, computed });
+        }
+
+        Ok(())
+    }
+
+    /// Fills in the `checksum` and `live_row_count` header fields of a page written before they
+    /// existed. Such pages are recognized by a zero checksum on an otherwise valid header.
+    /// Returns whether the page was upgraded; pages in the current format are left untouched.
+    pub(super) fn upgrade_header_internal(&mut self) -> Result<bool, SlotError> {
+        if self.header_ref()?.get_checksum()? != 0 || self.validate_header_internal().is_err() {
+            return Ok(false);
+        }
+
+        let slot_array = self.slot_array_ref()?;
+        let mut live_row_count = 0u16;
+        for slot_index in 0..self.header_ref()?.get_slot_count()? as u32 {
+            if slot_array.slot_ref(slot_index)?.is_valid()? {
+                live_row_count += 1;
+            }
+        }
+
+        self.header_mut()?.set_live_row_count(live_row_count)?;
+        self.update_checksum_internal()?;
+        Ok(true)
+    }
+
+    /// Recomputes `free_space` from the rows and slots actually on the page, instead of trusting the
+    /// value maintained by each operation, then stores it in the header.
+    /// Free space is whatever the data region does not use for live rows or slots, gaps included.
+    pub(super) fn recompute_free_space_internal(&mut self) -> Result<u16, SlotError> {
+        let header = self.header_ref()?;
+        let data_start = header.data_start()?;
+        let slot_count = header.get_slot_count()? as usize;
+
+        let slot_array = self.slot_array_ref()?;
+        let mut row_bytes = 0usize;
+        for slot_index in 0..slot_count as u32 {
+            let slot = slot_array.slot_ref(slot_index)?;
+            if slot.is_valid()? {
+                row_bytes += slot.length()? as usize;
+            }
+        }
+
+        let free_space = (PAGE_SIZE - data_start)
+            .checked_sub(row_bytes + slot_count * SLOT_SIZE)
+            .ok_or(HeaderError::OffsetArithmetic)?;
+        let free_space = free_space.to_u16().map_err(HeaderError::from)?;
+        self.header_mut()?.set_free_space(free_space)?;
+        Ok(free_space)
+    }
+
+    /// Checks that the header fields are consistent with each other and with the page layout.
+    /// See the `Header Invariants` section of the `header` module for details.
+    pub(super) fn validate_header_internal(&self) -> Result<(), ValidationError> {
+        let header = self.header_ref()?;
+
+        let page_type = header.get_page_type()?;
+        let Ok(page_type) = PageType::try_from(page_type) else {
+            return Err(ValidationError::UnknownPageType { page_type });
+        };
+        let data_start = data_start(page_type);
+
+        // Catches pages that were written to the wrong location in the file
+        let page_number = header.get_page_number()? as usize;
+        let expected_page_number = self.page_id.page_number as usize;
+        check_range(
+            "page_number",
+            page_number,
+            expected_page_number,
+            expected_page_number,
+        )?;
+
+        let max_slot_count = (PAGE_SIZE - HEADER_SIZE) / SLOT_SIZE;
+        let slot_count = header.get_slot_count()? as usize;
+        check_range("slot_count", slot_count, 0, max_slot_count)?;
+
+        // The slot array always ends exactly at the end of the page
+        let free_end = header.get_free_end()? as usize;
+        let expected_free_end = PAGE_SIZE - 1 - slot_count * SLOT_SIZE;
+        check_range("free_end", free_end, expected_free_end, expected_free_end)?;
+
+        let free_start = header.get_free_start()? as usize;
+        check_range("free_start", free_start, data_start, free_end + 1)?;
+
+        let free_space = header.get_free_space()? as usize;
+        let max_free_space = PAGE_SIZE - data_start - slot_count * SLOT_SIZE;
+        check_range("free_space", free_space, 0, max_free_space)?;
+
+        let can_compact = header.get_can_compact()? as usize;
+        check_range("can_compact", can_compact, 0, 1)?;
+
+        let live_row_count = header.get_live_row_count()? as usize;
+        check_range("live_row_count", live_row_count, 0, slot_count)?;
+
+        Ok(())
+    }
+
+    /// Checks that every live row lies within the data region of the page, and that no two live rows
+    /// share a byte. Zero-length rows take up no bytes, so they are skipped.
+    /// The header must have been validated first, as the slot count is trusted.
+    pub(super) fn validate_rows_internal(&self) -> Result<(), ValidationError> {
+        let header = self.header_ref()?;
+        let page_type = header.get_page_type()?;
+        let Ok(page_type) = PageType::try_from(page_type) else {
+            return Err(ValidationError::UnknownPageType { page_type });
+        };
+        let min = data_start(page_type);
+        let max = header.get_free_end()? as usize + 1;
+
+        let mut extents: Vec<_> = self
+            .slot_extents()?
+            .into_iter()
+            .filter(|extent| extent.valid && extent.length > 0)
+            .collect();
+        extents.sort_by_key(|extent| extent.offset);
+
+        for extent in &extents {
+            if extent.offset < min || extent.end() > max {
+                return Err(ValidationError::RowOutOfBounds {
+                    slot: extent.index,
+                    start: extent.offset,
+                    end: extent.end(),
+                    min,
+                    max,
+                });
+            }
+        }
+        for pair in extents.windows(2) {
+            if pair[1].offset < pair[0].end() {
+                return Err(ValidationError::OverlappingRows {
+                    first: pair[0].index,
+                    second: pair[1].index,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs every integrity check: the header, the checksum, then the placement of the rows.
+    pub(super) fn validate_internal(&self) -> Result<(), ValidationError> {
+        self.validate_header_internal()?;
+        self.verify_checksum_internal()?;
+        self.validate_rows_internal()
+    }
+}
+
+fn check_range(
+    field: &'static str,
+    value: usize,
+    min: usize,
+    max: usize,
+) -> Result<(), ValidationError> {
+    if value < min || value > max {
+        return Err(ValidationError::FieldOutOfRange {
+            field,
+            value,
+            min,
+            max,
+        });
+    }
+    Ok(())
+}
@@ -21,6 +21,25 @@ impl PageId {
             page_number,
         }
     }
+
+    /// Returns the page right after this one, in the same file.
+    ///
+    /// # Panics
+    /// If `page_number` is `u32::MAX`, as no page follows it. See `try_next` for a checked variant.
+    pub fn next(&self) -> Self {
+        self.try_next()
+            .unwrap_or_else(|| panic!("page {self} is the last page a file can hold"))
+    }
+
+    /// Returns the page right after this one, in the same file, or `None` if `page_number` is `u32::MAX`.
+    pub fn try_next(&self) -> Option<Self> {
+        Some(self.with_page_number(self.page_number.checked_add(1)?))
+    }
+
+    /// Returns the page numbered `page_number`, in the same file as this one.
+    pub fn with_page_number(&self, page_number: u32) -> Self {
+        Self::new(self.file_id, page_number)
+    }
 }
 
 impl fmt::Display for PageId {
@@ -52,6 +71,37 @@ mod tests {
         assert_eq!(page_id.page_number, 1011);
     }
 
+    #[test]
+    fn next_increments_page_number_and_keeps_file_id() {
+        let page_id = PageId::new(7, 41);
+
+        assert_eq!(page_id.next(), PageId::new(7, 42));
+        assert_eq!(page_id.next().next(), PageId::new(7, 43));
+    }
+
+    #[test]
+    #[should_panic(expected = "page 7:4294967295 is the last page a file can hold")]
+    fn next_at_u32_max_panics() {
+        PageId::new(7, u32::MAX).next();
+    }
+
+    #[test]
+    fn try_next_at_u32_max_returns_none() {
+        assert_eq!(PageId::new(7, u32::MAX).try_next(), None);
+        assert_eq!(
+            PageId::new(7, u32::MAX - 1).try_next(),
+            Some(PageId::new(7, u32::MAX))
+        );
+    }
+
+    #[test]
+    fn with_page_number_keeps_file_id() {
+        let page_id = PageId::new(7, 41);
+
+        assert_eq!(page_id.with_page_number(3), PageId::new(7, 3));
+        assert_eq!(page_id.with_page_number(u32::MAX).file_id, 7);
+    }
+
     #[test]
     fn display_formats_correctly() {
         let page_id = PageId::new(123, 456);
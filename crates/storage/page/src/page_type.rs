@@ -12,6 +12,9 @@ pub enum PageType {
     IndexInternal = 3,
     /// B+ tree index leaf data page.
     IndexLeaf = 4,
+    /// Page holding one chunk of a row too large for a single page, chained to the next chunk through
+    /// `right_page`.
+    Overflow = 5,
 }
 
 impl From<PageType> for u16 {
@@ -31,6 +34,7 @@ impl TryFrom<u16> for PageType {
             2 => Ok(PageType::IndexRoot),
             3 => Ok(PageType::IndexInternal),
             4 => Ok(PageType::IndexLeaf),
+            5 => Ok(PageType::Overflow),
             _ => Err(()),
         }
     }
@@ -46,11 +50,13 @@ mod tests {
         assert_eq!(u16::from(PageType::IndexRoot), 2);
         assert_eq!(u16::from(PageType::IndexInternal), 3);
         assert_eq!(u16::from(PageType::IndexLeaf), 4);
+        assert_eq!(u16::from(PageType::Overflow), 5);
 
         assert_eq!(PageType::try_from(1).unwrap(), PageType::Unsorted);
         assert_eq!(PageType::try_from(2).unwrap(), PageType::IndexRoot);
         assert_eq!(PageType::try_from(3).unwrap(), PageType::IndexInternal);
         assert_eq!(PageType::try_from(4).unwrap(), PageType::IndexLeaf);
+        assert_eq!(PageType::try_from(5).unwrap(), PageType::Overflow);
 
         assert!(PageType::try_from(99).is_err());
     }
@@ -4,6 +4,15 @@ use binary_helpers::le::{read_le, write_le};
 /// Fixed-size of a slot in bytes.
 pub(crate) const SLOT_SIZE: usize = 4;
 
+/// Largest value that can be stored in either of the slot fields (offset and length).
+/// Both are stored on 2 bytes, so rows must start and end within the first 64 KiB of the page.
+/// Raising `PAGE_SIZE` beyond this value requires widening the slot format.
+pub(crate) const MAX_SLOT_VALUE: usize = u16::MAX as usize;
+
+/// Offset stored in the slot of a deleted row. Rows never start inside the header, so no live row can
+/// start at this offset. See `SlotRef::is_valid`.
+pub(crate) const INVALID_SLOT_OFFSET: u16 = 0;
+
 /// A zero-copy read-only view into a 4-byte slot (offset: u16, length: u16) in the byte array of the slotted page.
 #[derive(Debug)]
 pub(crate) struct SlotRef<'a> {
@@ -63,6 +72,27 @@ impl<'a> SlotRef<'a> {
     pub(crate) fn length(&self) -> Result<u16, SlotError> {
         Ok(read_le::<u16>(self.bytes, 2)?)
     }
+
+    /// Determines if the slot is valid (used) or it has been invalidated (row referenced by it was deleted).
+    ///
+    /// An offset of [`INVALID_SLOT_OFFSET`] is the sentinel of an invalidated slot.
+    /// The length takes no part in the decision, which lets a valid slot hold a zero-length row:
+    ///
+    /// | Offset                  | Length | Result                   |
+    /// |-------------------------|--------|--------------------------|
+    /// | `INVALID_SLOT_OFFSET`   | 0      | invalid                  |
+    /// | `INVALID_SLOT_OFFSET`   | > 0    | `SlotError::CorruptSlot` |
+    /// | any other               | any    | valid                    |
+    pub(crate) fn is_valid(&self) -> Result<bool, SlotError> {
+        match (self.offset()?, self.length()?) {
+            (INVALID_SLOT_OFFSET, 0) => Ok(false),
+            (INVALID_SLOT_OFFSET, length) => Err(SlotError::CorruptSlot {
+                slot_index: self.slot_index as usize,
+                length: length as usize,
+            }),
+            _ => Ok(true),
+        }
+    }
 }
 
 /// A zero-copy mutable view into a 4-byte slot (offset: u16, length: u16) in the byte array of the slotted page.
@@ -186,6 +216,46 @@ mod tests {
         assert_eq!(slot.length().unwrap(), 0);
     }
 
+    fn slot_ref(offset: u16, length: u16) -> [u8; SLOT_SIZE] {
+        let mut bytes = [0u8; SLOT_SIZE];
+        bytes[..2].copy_from_slice(&offset.to_le_bytes());
+        bytes[2..].copy_from_slice(&length.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn slot_ref_is_valid_sentinel_offset_and_zero_length_is_invalid() {
+        let bytes = slot_ref(INVALID_SLOT_OFFSET, 0);
+        let slot = SlotRef::from_raw(3, &bytes).unwrap();
+        assert!(!slot.is_valid().unwrap());
+    }
+
+    #[test]
+    fn slot_ref_is_valid_sentinel_offset_with_length_is_corrupt() {
+        for length in [1, 100, u16::MAX] {
+            let bytes = slot_ref(INVALID_SLOT_OFFSET, length);
+            let slot = SlotRef::from_raw(3, &bytes).unwrap();
+            assert!(
+                matches!(
+                    slot.is_valid(),
+                    Err(SlotError::CorruptSlot { slot_index: 3, length: l }) if l == length as usize
+                ),
+                "length {length}"
+            );
+        }
+    }
+
+    #[test]
+    fn slot_ref_is_valid_any_other_offset_is_valid_whatever_the_length() {
+        for offset in [1, 96, 2000, u16::MAX] {
+            for length in [0, 1, 100, u16::MAX] {
+                let bytes = slot_ref(offset, length);
+                let slot = SlotRef::from_raw(3, &bytes).unwrap();
+                assert!(slot.is_valid().unwrap(), "offset {offset}, length {length}");
+            }
+        }
+    }
+
     #[test]
     fn slot_mut_from_raw_with_valid_bytes_reads_fields_correctly() {
         let mut bytes = [0x34, 0x12, 0x78, 0x56];
@@ -52,11 +52,13 @@
 //! - `SlotMut<'a>` for modifying it
 //!
 //! The module performs strict length and bounds verification at construction,
-//! ensuring the provided byte region is exactly `slot_count * SLOT_SIZE`.
+//! ensuring the provided byte region is exactly `slot_count * SLOT_SIZE`. Slot indices
+//! are checked against `slot_count` on every access, see `SlotError::IndexOutOfRange`.
 
 use crate::PAGE_SIZE;
 use crate::errors::slot_error::SlotError;
-use crate::slot::{SLOT_SIZE, SlotMut, SlotRef};
+use crate::slot::{MAX_SLOT_VALUE, SLOT_SIZE, SlotMut, SlotRef};
+use binary_helpers::conversions::UsizeConversion;
 use std::ops::Range;
 
 /// Immutable zero-copy view into the slot array of a slotted page.
@@ -65,6 +67,8 @@ pub(crate) struct SlotArrayRef<'a> {
     /// View into the raw bytes of the slot array.
     /// Each slot is of length `SLOT_SIZE` bytes.
     bytes: &'a [u8],
+    /// Number of slots in the array.
+    slot_count: u16,
 }
 
 impl<'a> SlotArrayRef<'a> {
@@ -80,13 +84,14 @@ impl<'a> SlotArrayRef<'a> {
             });
         }
 
-        Ok(Self { bytes })
+        Ok(Self { bytes, slot_count })
     }
 
     /// Gets an immutable view of the slot at the given index.
     /// Slots are zero-indexed.
     /// Returns an error if the slot index is out of bounds.
     pub(crate) fn slot_ref(&self, slot_index: u32) -> Result<SlotRef<'a>, SlotError> {
+        check_index(slot_index, self.slot_count)?;
         let range = get_slot_range(self.bytes.len(), slot_index)?;
 
         let slot_bytes = self.bytes.get(range).ok_or(SlotError::InvalidSlot {
@@ -103,6 +108,8 @@ pub(crate) struct SlotArrayMut<'a> {
     /// View into the raw bytes of the slot array.
     /// Each slot is of length `SLOT_SIZE` bytes.
     bytes: &'a mut [u8],
+    /// Number of slots in the array.
+    slot_count: u16,
 }
 
 impl<'a> SlotArrayMut<'a> {
@@ -118,13 +125,14 @@ impl<'a> SlotArrayMut<'a> {
             });
         }
 
-        Ok(Self { bytes })
+        Ok(Self { bytes, slot_count })
     }
 
     /// Gets an immutable view of the slot at the given index
     /// Slots are zero-indexed
     /// Returns an error if the slot index is out of bounds
     pub(crate) fn slot_ref(&self, slot_index: u32) -> Result<SlotRef<'_>, SlotError> {
+        check_index(slot_index, self.slot_count)?;
         let range = get_slot_range(self.bytes.len(), slot_index)?;
 
         let slot_bytes = self.bytes.get(range).ok_or(SlotError::InvalidSlot {
@@ -138,6 +146,7 @@ impl<'a> SlotArrayMut<'a> {
     /// Slots are zero-indexed.
     /// Returns an error if the slot index is out of bounds.
     pub(crate) fn slot_mut(&mut self, slot_index: u32) -> Result<SlotMut<'_>, SlotError> {
+        check_index(slot_index, self.slot_count)?;
         let range = get_slot_range(self.bytes.len(), slot_index)?;
 
         let slot_bytes = self.bytes.get_mut(range).ok_or(SlotError::InvalidSlot {
@@ -148,17 +157,64 @@ impl<'a> SlotArrayMut<'a> {
     }
 
     /// Sets the values of a slot in the slot array.
+    /// Returns `SlotError::OffsetTooLarge` if either value exceeds `MAX_SLOT_VALUE`, leaving the slot untouched.
     pub(crate) fn set_slot(
         &mut self,
         slot_index: u32,
-        slot_offset: u16,
-        slot_length: u16,
+        slot_offset: usize,
+        slot_length: usize,
     ) -> Result<(), SlotError> {
+        let slot_offset = to_slot_value("offset", slot_offset)?;
+        let slot_length = to_slot_value("length", slot_length)?;
+
         let mut slot_mut = self.slot_mut(slot_index)?;
         slot_mut.set_length(slot_length)?;
         slot_mut.set_offset(slot_offset)?;
         Ok(())
     }
+
+    /// Moves the slot at index `from` to index `to`, shifting the slots in between by one position
+    /// towards `from`. Only slot entries are moved, the rows they point to stay where they are.
+    ///
+    /// Slots are stored right-to-left (slot 0 occupies the last `SLOT_SIZE` bytes), so the slots with
+    /// indices between `from` and `to` form a contiguous region which is rotated by one slot: to the right
+    /// (towards lower indices) when moving a slot to a higher index, to the left otherwise.
+    ///
+    /// Returns `SlotError::IndexOutOfRange` if either index is out of bounds, leaving the array untouched.
+    pub(crate) fn move_slot(&mut self, from: u32, to: u32) -> Result<(), SlotError> {
+        check_index(from, self.slot_count)?;
+        check_index(to, self.slot_count)?;
+
+        let (low, high) = (from.min(to), from.max(to));
+        let region_start = get_slot_start(self.bytes.len(), high)?;
+        let region_end = get_slot_range(self.bytes.len(), low)?.end;
+        let region = &mut self.bytes[region_start..region_end];
+
+        if from < to {
+            region.rotate_right(SLOT_SIZE);
+        } else {
+            region.rotate_left(SLOT_SIZE);
+        }
+        Ok(())
+    }
+}
+
+fn to_slot_value(field: &'static str, value: usize) -> Result<u16, SlotError> {
+    value.to_u16().map_err(|_| SlotError::OffsetTooLarge {
+        field,
+        value,
+        max: MAX_SLOT_VALUE,
+    })
+}
+
+fn check_index(slot_index: u32, slot_count: u16) -> Result<(), SlotError> {
+    if slot_index >= slot_count as u32 {
+        return Err(SlotError::IndexOutOfRange {
+            index: slot_index as usize,
+            slot_count: slot_count as usize,
+        });
+    }
+    Ok(())
 }
 
 fn get_slot_start(slot_array_size: usize, slot_index: u32) -> Result<usize, SlotError> {
@@ -205,7 +261,26 @@ mod slot_array_ref_test {
         let result = slot_array.slot_ref(3); // Invalid index
         assert!(matches!(
             result,
-            Err(SlotError::InvalidSlot { slot_index: 3 })
+            Err(SlotError::IndexOutOfRange {
+                index: 3,
+                slot_count: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn slot_array_ref_slot_ref_index_equal_to_slot_count_returns_index_out_of_range() {
+        let bytes = vec![0u8; SLOT_SIZE * 2];
+        let slot_array = SlotArrayRef::new(&bytes, 2).unwrap();
+
+        let result = slot_array.slot_ref(2);
+
+        assert!(matches!(
+            result,
+            Err(SlotError::IndexOutOfRange {
+                index: 2,
+                slot_count: 2
+            })
         ));
     }
 
@@ -260,7 +335,26 @@ mod slot_array_mut_test {
         let result = slot_array.slot_mut(3); // Invalid index
         assert!(matches!(
             result,
-            Err(SlotError::InvalidSlot { slot_index: 3 })
+            Err(SlotError::IndexOutOfRange {
+                index: 3,
+                slot_count: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn slot_array_mut_slot_mut_index_equal_to_slot_count_returns_index_out_of_range() {
+        let mut bytes = vec![0u8; SLOT_SIZE * 2];
+        let mut slot_array = SlotArrayMut::new(&mut bytes, 2).unwrap();
+
+        let result = slot_array.slot_mut(2);
+
+        assert!(matches!(
+            result,
+            Err(SlotError::IndexOutOfRange {
+                index: 2,
+                slot_count: 2
+            })
         ));
     }
 
@@ -290,7 +384,10 @@ mod slot_array_mut_test {
         let result = slot_array.slot_ref(3); // Invalid index
         assert!(matches!(
             result,
-            Err(SlotError::InvalidSlot { slot_index: 3 })
+            Err(SlotError::IndexOutOfRange {
+                index: 3,
+                slot_count: 2
+            })
         ));
     }
 
@@ -312,4 +409,126 @@ mod slot_array_mut_test {
         assert_eq!(slot1.offset().unwrap(), 3);
         assert_eq!(slot1.length().unwrap(), 4);
     }
+
+    #[test]
+    fn slot_array_mut_set_slot_max_values() {
+        let mut bytes = vec![0u8; SLOT_SIZE]; // 1 slot
+        let mut slot_array = SlotArrayMut::new(&mut bytes, 1).unwrap();
+
+        slot_array
+            .set_slot(0, MAX_SLOT_VALUE, MAX_SLOT_VALUE)
+            .unwrap();
+
+        let slot = slot_array.slot_ref(0).unwrap();
+        assert_eq!(slot.offset().unwrap(), u16::MAX);
+        assert_eq!(slot.length().unwrap(), u16::MAX);
+    }
+
+    #[test]
+    fn slot_array_mut_set_slot_offset_too_large() {
+        let mut bytes = vec![0u8; SLOT_SIZE]; // 1 slot
+        let mut slot_array = SlotArrayMut::new(&mut bytes, 1).unwrap();
+
+        let result = slot_array.set_slot(0, 65536, 10);
+
+        assert!(matches!(
+            result,
+            Err(SlotError::OffsetTooLarge {
+                field: "offset",
+                value: 65536,
+                max: 65535
+            })
+        ));
+        // Nothing was written, instead of a silently truncated offset of 0
+        assert_eq!(bytes, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn slot_array_mut_set_slot_length_too_large() {
+        let mut bytes = vec![0u8; SLOT_SIZE]; // 1 slot
+        let mut slot_array = SlotArrayMut::new(&mut bytes, 1).unwrap();
+
+        let result = slot_array.set_slot(0, 100, 70000);
+
+        assert!(matches!(
+            result,
+            Err(SlotError::OffsetTooLarge {
+                field: "length",
+                value: 70000,
+                ..
+            })
+        ));
+        assert_eq!(bytes, [0, 0, 0, 0]);
+    }
+
+    /// Builds a slot array of `count` slots, where slot `i` has offset `100 + i` and length `i + 1`
+    fn numbered_slots(count: u32) -> Vec<u8> {
+        let mut bytes = vec![0u8; SLOT_SIZE * count as usize];
+        let mut slot_array = SlotArrayMut::new(&mut bytes, count as u16).unwrap();
+        for i in 0..count {
+            slot_array
+                .set_slot(i, 100 + i as usize, i as usize + 1)
+                .unwrap();
+        }
+        bytes
+    }
+
+    /// Returns the offsets of the slots, in slot index order
+    fn offsets(slot_array: &SlotArrayMut, count: u32) -> Vec<u16> {
+        (0..count)
+            .map(|i| slot_array.slot_ref(i).unwrap().offset().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn slot_array_mut_move_slot_to_higher_index_shifts_others_down() {
+        let mut bytes = numbered_slots(5);
+        let mut slot_array = SlotArrayMut::new(&mut bytes, 5).unwrap();
+
+        slot_array.move_slot(1, 3).unwrap();
+
+        assert_eq!(offsets(&slot_array, 5), vec![100, 102, 103, 101, 104]);
+        // The moved slot keeps its length along with its offset
+        assert_eq!(slot_array.slot_ref(3).unwrap().length().unwrap(), 2);
+    }
+
+    #[test]
+    fn slot_array_mut_move_slot_to_lower_index_shifts_others_up() {
+        let mut bytes = numbered_slots(5);
+        let mut slot_array = SlotArrayMut::new(&mut bytes, 5).unwrap();
+
+        slot_array.move_slot(4, 0).unwrap();
+
+        assert_eq!(offsets(&slot_array, 5), vec![104, 100, 101, 102, 103]);
+        assert_eq!(slot_array.slot_ref(0).unwrap().length().unwrap(), 5);
+    }
+
+    #[test]
+    fn slot_array_mut_move_slot_same_index_is_noop() {
+        let mut bytes = numbered_slots(3);
+        let before = bytes.clone();
+        let mut slot_array = SlotArrayMut::new(&mut bytes, 3).unwrap();
+
+        slot_array.move_slot(1, 1).unwrap();
+
+        assert_eq!(bytes, before);
+    }
+
+    #[test]
+    fn slot_array_mut_move_slot_invalid_index_leaves_array_untouched() {
+        let mut bytes = numbered_slots(3);
+        let before = bytes.clone();
+        let mut slot_array = SlotArrayMut::new(&mut bytes, 3).unwrap();
+
+        let result = slot_array.move_slot(0, 3);
+
+        assert!(matches!(
+            result,
+            Err(SlotError::IndexOutOfRange {
+                index: 3,
+                slot_count: 3
+            })
+        ));
+        assert_eq!(bytes, before);
+    }
 }
@@ -0,0 +1,14 @@
+//! Defines the description of a slot, as reported by `Page::iter_slots`.
+
+/// A slot of the slot array, whether it points to a live row or not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotInfo {
+    /// Index of the slot in the slot array.
+    pub index: u32,
+    /// Offset of the row within the page. `0` for deleted rows.
+    pub offset: u16,
+    /// Length of the row, in bytes. `0` for deleted rows.
+    pub length: u16,
+    /// Whether the slot points to a live row.
+    pub valid: bool,
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::read_row_error::ReadRowError;
+    use crate::errors::slot_error::SlotError;
+    use crate::page::api::Page;
+
+    /// Builds a page holding one row per key, in slot order. Each row is the key as a little-endian
+    /// `u32`, followed by a one byte payload.
+    fn page_with_keys(keys: &[u32]) -> Page {
+        let mut page = Page::test_create_empty_heap();
+        for (slot_index, key) in keys.iter().enumerate() {
+            let mut row = key.to_le_bytes().to_vec();
+            row.push(slot_index as u8);
+            page.insert_at(slot_index as u32, row).unwrap();
+        }
+        page
+    }
+
+    fn by_key(target: u32) -> impl Fn(&[u8]) -> std::cmp::Ordering {
+        move |row| u32::from_le_bytes(row[..4].try_into().unwrap()).cmp(&target)
+    }
+
+    #[test]
+    fn binary_search_rows_finds_every_existing_key() {
+        let keys = [3, 8, 15, 21, 40, 41, 99];
+        let page = page_with_keys(&keys);
+
+        for (slot_index, key) in keys.iter().enumerate() {
+            assert_eq!(
+                page.binary_search_rows(by_key(*key)).unwrap(),
+                Ok(slot_index as u32)
+            );
+        }
+    }
+
+    #[test]
+    fn binary_search_rows_missing_keys_return_insertion_point() {
+        let keys = [3, 8, 15, 21, 40];
+        let page = page_with_keys(&keys);
+
+        for target in [0, 4, 16, 39, 41, 1000] {
+            let expected = keys.binary_search(&target);
+            assert!(expected.is_err());
+            assert_eq!(
+                page.binary_search_rows(by_key(target)).unwrap(),
+                expected.map(|i| i as u32).map_err(|i| i as u32)
+            );
+        }
+    }
+
+    #[test]
+    fn binary_search_rows_empty_page_returns_zero_insertion_point() {
+        let page = Page::test_create_empty_heap();
+
+        assert_eq!(page.binary_search_rows(by_key(5)).unwrap(), Err(0));
+    }
+
+    #[test]
+    fn binary_search_rows_visiting_deleted_slot_returns_error() {
+        let mut page = page_with_keys(&[1, 2, 3]);
+        page.delete_row(1, false).unwrap();
+
+        let err = page.binary_search_rows(by_key(2)).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_read_row_error(),
+            ReadRowError::SlotError(SlotError::InvalidSlot { slot_index: 1 })
+        ));
+    }
+}
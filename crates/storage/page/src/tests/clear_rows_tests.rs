@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::page::api::Page;
+    use crate::page_id::PageId;
+    use crate::page_type::PageType;
+    use crate::tests::SlotValues;
+    use crate::{HEADER_SIZE, PAGE_SIZE};
+
+    #[test]
+    fn clear_rows_resets_slots_and_free_space() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 196,
+                len: 50,
+            },
+        ]);
+        page.header_mut().unwrap().set_can_compact(1).unwrap();
+
+        page.clear_rows().unwrap();
+
+        assert_eq!(page.slot_count().unwrap(), 0);
+        page.assert_header(&[
+            &|h| assert_eq!(h.get_free_start().unwrap(), HEADER_SIZE as u16),
+            &|h| assert_eq!(h.get_free_end().unwrap(), (PAGE_SIZE - 1) as u16),
+            &|h| {
+                assert_eq!(
+                    h.get_free_space().unwrap(),
+                    (PAGE_SIZE - HEADER_SIZE) as u16
+                )
+            },
+            &|h| assert_eq!(h.get_can_compact().unwrap(), 0),
+        ]);
+        assert!(page.data()[HEADER_SIZE..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn clear_rows_preserves_identity() {
+        let mut page = Page::new_empty(PageId::new(3, 7), PageType::IndexLeaf).unwrap();
+        {
+            let mut header = page.header_mut().unwrap();
+            header.set_left_page(6).unwrap();
+            header.set_right_page(8).unwrap();
+            header.set_last_lsn(42).unwrap();
+        }
+        page.test_insert_rows(vec![SlotValues {
+            offset: 96,
+            len: 10,
+        }]);
+
+        page.clear_rows().unwrap();
+
+        assert_eq!(page.page_id(), PageId::new(3, 7));
+        page.assert_header(&[
+            &|h| assert_eq!(h.get_page_number().unwrap(), 7),
+            &|h| assert_eq!(h.get_page_type().unwrap(), u16::from(PageType::IndexLeaf)),
+            &|h| assert_eq!(h.get_left_page().unwrap(), 6),
+            &|h| assert_eq!(h.get_right_page().unwrap(), 8),
+            &|h| assert_eq!(h.get_last_lsn().unwrap(), 42),
+        ]);
+    }
+
+    #[test]
+    fn clear_rows_then_insert_lands_at_header_size() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 196,
+                len: 100,
+            },
+        ]);
+
+        page.clear_rows().unwrap();
+
+        let plan = page.plan_insert(20).unwrap();
+        page.insert_heap(plan, vec![9u8; 20]).unwrap();
+
+        page.assert_slot(0, HEADER_SIZE, 20);
+        assert_eq!(page.row(0).unwrap(), &[9u8; 20]);
+        assert_eq!(page.slot_count().unwrap(), 1);
+    }
+}
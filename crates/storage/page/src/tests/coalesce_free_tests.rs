@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod tests {
+    use crate::compaction::CompactionOrder;
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+
+    /// Full heap page with two small gaps on either side of a short row, followed by a long row:
+    ///
+    /// | Offset    | Row      |
+    /// |-----------|----------|
+    /// | 96..196   | slot 0   |
+    /// | 196..216  | gap      |
+    /// | 216..236  | slot 1   |
+    /// | 236..256  | gap      |
+    /// | 256..4084 | slot 2   |
+    fn gapped_heap() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 216,
+                len: 20,
+            },
+            SlotValues {
+                offset: 256,
+                len: 3828,
+            },
+        ]);
+        page
+    }
+
+    fn rows_by_slot(page: &Page) -> Vec<Vec<u8>> {
+        (0..page.slot_count().unwrap() as u32)
+            .map(|slot| page.row(slot).unwrap().to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn coalesce_free_for_shifts_only_the_row_between_gaps() {
+        let mut page = gapped_heap();
+        let before = rows_by_slot(&page);
+
+        assert_eq!(page.coalesce_free_for(40).unwrap(), Some(216));
+
+        page.assert_slot(0, 96, 100);
+        page.assert_slot(1, 196, 20);
+        page.assert_slot(2, 256, 3828);
+        page.assert_header(&[&|h| assert_eq!(h.get_free_start().unwrap(), 4084)]);
+        assert_eq!(rows_by_slot(&page), before);
+    }
+
+    #[test]
+    fn coalesce_free_for_moves_fewer_bytes_than_compact() {
+        let mut partial = gapped_heap();
+        let mut full = gapped_heap();
+
+        let before: Vec<_> = partial.iter_slots().map(Result::unwrap).collect();
+        partial.coalesce_free_for(40).unwrap();
+        let partial_moved: usize = partial
+            .iter_slots()
+            .map(Result::unwrap)
+            .zip(before)
+            .filter(|(after, before)| after.offset != before.offset)
+            .map(|(after, _)| after.length as usize)
+            .sum();
+        let full_moved = full
+            .compact_reporting(CompactionOrder::PhysicalOrder)
+            .unwrap()
+            .bytes_moved;
+
+        // Only slot 1 slides down, a full compaction also drags slot 2 along
+        assert_eq!(partial_moved, 20);
+        assert_eq!(full_moved, 20 + 3828);
+    }
+
+    #[test]
+    fn coalesce_free_for_existing_gap_moves_nothing() {
+        let mut page = gapped_heap();
+
+        assert_eq!(page.coalesce_free_for(20).unwrap(), Some(196));
+
+        page.assert_slot(0, 96, 100);
+        page.assert_slot(1, 216, 20);
+        page.assert_slot(2, 256, 3828);
+    }
+
+    #[test]
+    fn coalesce_free_for_more_than_free_space_returns_none() {
+        let mut page = gapped_heap();
+
+        assert_eq!(page.coalesce_free_for(41).unwrap(), None);
+
+        page.assert_slot(1, 216, 20);
+    }
+
+    /// A 20-byte gap in front of the first row and a 20-byte tail: only moving both rows merges them.
+    #[test]
+    fn coalesce_free_for_returns_none_when_every_row_must_move() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 116,
+                len: 100,
+            },
+            SlotValues {
+                offset: 216,
+                len: 3852,
+            },
+        ]);
+
+        assert_eq!(page.coalesce_free_for(40).unwrap(), None);
+
+        page.assert_slot(0, 116, 100);
+        page.assert_slot(1, 216, 3852);
+    }
+
+    #[test]
+    fn coalesce_free_for_lowers_free_start_when_the_last_row_moves() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 216,
+                len: 3862,
+            },
+        ]);
+
+        assert_eq!(page.coalesce_free_for(30).unwrap(), Some(4058));
+
+        page.assert_slot(0, 96, 100);
+        page.assert_slot(1, 196, 3862);
+        page.assert_header(&[&|h| assert_eq!(h.get_free_start().unwrap(), 4058)]);
+    }
+}
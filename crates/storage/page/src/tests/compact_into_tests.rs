@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::slot_error::SlotError;
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+    use crate::tests::tests_error_helpers;
+
+    /// Heap page with gaps left by deleted rows, with the remaining rows stored out of order
+    fn fragmented_heap() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 296,
+                len: 40,
+            },
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 196,
+                len: 50,
+            },
+            SlotValues {
+                offset: 246,
+                len: 50,
+            },
+        ]);
+        page.delete_row(1, false).unwrap();
+        page.delete_row(3, false).unwrap();
+        page
+    }
+
+    #[test]
+    fn compact_into_compacts_and_clears_can_compact() {
+        let mut page = fragmented_heap();
+        let mut scratch = vec![0u8; 90];
+
+        page.compact_into(&mut scratch).unwrap();
+
+        assert_eq!(page.fragmentation_ratio().unwrap(), 0.0);
+        page.assert_slot(0, 96, 40);
+        page.assert_slot(2, 136, 50);
+        page.assert_row_values(96, 40, 1);
+        page.assert_row_values(136, 50, 3);
+        page.assert_header(&[&|h| assert_eq!(h.get_free_start().unwrap(), 186), &|h| {
+            assert_eq!(h.get_can_compact().unwrap(), 0)
+        }]);
+    }
+
+    #[test]
+    fn compact_into_reuses_scratch_across_pages() {
+        let mut scratch = vec![0u8; Page::max_row_size()];
+        let mut expected = fragmented_heap();
+        expected.defragment().unwrap();
+
+        for _ in 0..3 {
+            let mut page = fragmented_heap();
+            page.compact_into(&mut scratch).unwrap();
+            assert_eq!(page.data().to_vec(), expected.data().to_vec());
+        }
+    }
+
+    #[test]
+    fn compact_into_undersized_scratch_returns_error_and_leaves_page() {
+        let mut page = fragmented_heap();
+        let before = page.data().to_vec();
+        let mut scratch = vec![0u8; 89];
+
+        let err = page.compact_into(&mut scratch).unwrap_err();
+
+        let slot_error = err.source.expect_slot_error();
+        assert!(matches!(
+            slot_error,
+            SlotError::ScratchTooSmall {
+                required: 90,
+                available: 89
+            }
+        ));
+        assert_eq!(page.data().to_vec(), before);
+    }
+}
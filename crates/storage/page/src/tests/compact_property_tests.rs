@@ -0,0 +1,237 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compaction::CompactionOrder;
+    use crate::page::api::Page;
+    use crate::slot::SLOT_SIZE;
+    use crate::{HEADER_SIZE, PAGE_SIZE};
+
+    /// Number of random layouts checked, per compaction order
+    const CASES: u64 = 500;
+    /// Upper bound on the slot count of a generated layout
+    const MAX_SLOTS: usize = 48;
+    /// Upper bound on the length of a generated row
+    const MAX_ROW_LEN: usize = 160;
+    /// Upper bound on the gap left in front of a generated row
+    const MAX_GAP: usize = 48;
+
+    /// Deterministic xorshift64 generator, so that a failing case can be replayed from its seed.
+    struct Generator(u64);
+
+    impl Generator {
+        fn new(seed: u64) -> Self {
+            // xorshift gets stuck on 0
+            Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+        }
+
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        /// Returns a value in `0..=max`
+        fn up_to(&mut self, max: usize) -> usize {
+            (self.next() % (max as u64 + 1)) as usize
+        }
+
+        fn one_in(&mut self, n: u64) -> bool {
+            self.next().is_multiple_of(n)
+        }
+    }
+
+    /// A generated layout, along with what every slot is expected to read as
+    struct Layout {
+        page: Page,
+        /// `None` for deleted slots
+        rows: Vec<Option<Vec<u8>>>,
+    }
+
+    /// Builds a valid heap page whose live rows are stored in a random physical order, with random gaps
+    /// between them. Deleted slots and zero-length rows are mixed in.
+    fn generate(seed: u64) -> Layout {
+        let mut generator = Generator::new(seed);
+        let slot_count = 1 + generator.up_to(MAX_SLOTS - 1);
+        let free_end = PAGE_SIZE - slot_count * SLOT_SIZE;
+
+        let rows: Vec<Option<Vec<u8>>> = (0..slot_count)
+            .map(|_| {
+                if generator.one_in(5) {
+                    return None;
+                }
+                let len = if generator.one_in(10) {
+                    0
+                } else {
+                    1 + generator.up_to(MAX_ROW_LEN - 1)
+                };
+                Some((0..len).map(|_| generator.next() as u8).collect())
+            })
+            .collect();
+
+        // Fisher-Yates shuffle of the slot indexes, giving the physical order of the rows
+        let mut physical_order: Vec<usize> = (0..slot_count).collect();
+        for i in (1..slot_count).rev() {
+            physical_order.swap(i, generator.up_to(i));
+        }
+
+        let mut page = Page::test_create_empty_heap();
+        page.test_set_slot_count(slot_count);
+        let mut offsets = vec![0; slot_count];
+        let mut cursor = HEADER_SIZE;
+        let mut has_gaps = false;
+        for index in physical_order {
+            let Some(row) = &rows[index] else { continue };
+            let gap = generator.up_to(MAX_GAP);
+            // Rows that would not fit are dropped, rather than retried with a smaller gap
+            if cursor + gap + row.len() > free_end {
+                continue;
+            }
+            cursor += gap;
+            has_gaps |= gap > 0;
+            page.data_mut()[cursor..cursor + row.len()].copy_from_slice(row);
+            offsets[index] = cursor;
+            cursor += row.len();
+        }
+        let rows: Vec<Option<Vec<u8>>> = rows
+            .into_iter()
+            .zip(&offsets)
+            .map(|(row, offset)| row.filter(|_| *offset != 0))
+            .collect();
+
+        let mut slot_array = page.slot_array_mut().unwrap();
+        for (index, row) in rows.iter().enumerate() {
+            let mut slot = slot_array.slot_mut(index as u32).unwrap();
+            slot.set_offset(offsets[index] as u16).unwrap();
+            slot.set_length(row.as_ref().map_or(0, Vec::len) as u16)
+                .unwrap();
+        }
+
+        let total_row_size: usize = rows.iter().flatten().map(Vec::len).sum();
+        let mut header = page.header_mut().unwrap();
+        header.set_free_start(cursor as u16).unwrap();
+        header
+            .set_free_space((free_end - HEADER_SIZE - total_row_size) as u16)
+            .unwrap();
+        header
+            .set_live_row_count(rows.iter().flatten().count() as u16)
+            .unwrap();
+        header.set_can_compact(has_gaps as u16).unwrap();
+
+        Layout { page, rows }
+    }
+
+    /// Checks every invariant a compacted page has to uphold
+    fn assert_compacted(page: &Page, rows: &[Option<Vec<u8>>], context: &str) {
+        assert_eq!(page.slot_count().unwrap() as usize, rows.len(), "{context}");
+        assert_eq!(
+            page.live_row_count().unwrap() as usize,
+            rows.iter().flatten().count(),
+            "{context}"
+        );
+
+        // Every live row keeps its bytes and its slot index, deleted slots stay deleted
+        for (index, expected) in rows.iter().enumerate() {
+            match expected {
+                Some(row) => assert_eq!(
+                    page.row(index as u32).unwrap(),
+                    row.as_slice(),
+                    "{context}: slot {index}"
+                ),
+                None => assert!(
+                    !page.iter_slots().nth(index).unwrap().unwrap().valid,
+                    "{context}: slot {index} came back to life"
+                ),
+            }
+        }
+
+        // Live rows are packed right after the header, without gaps, up to the free start
+        let mut extents: Vec<_> = page
+            .slot_extents()
+            .unwrap()
+            .into_iter()
+            .filter(|extent| extent.valid)
+            .collect();
+        extents.sort_by_key(|extent| (extent.offset, extent.length));
+        let mut cursor = HEADER_SIZE;
+        for extent in extents {
+            assert_eq!(
+                extent.offset, cursor,
+                "{context}: gap or overlap before slot {}",
+                extent.index
+            );
+            cursor = extent.end();
+        }
+
+        let free_end = PAGE_SIZE - rows.len() * SLOT_SIZE;
+        let header = page.header_ref().unwrap();
+        assert_eq!(
+            header.get_free_start().unwrap() as usize,
+            cursor,
+            "{context}"
+        );
+        assert_eq!(
+            header.get_free_space().unwrap() as usize,
+            free_end - cursor,
+            "{context}"
+        );
+        assert_eq!(header.get_can_compact().unwrap(), 0, "{context}");
+        assert_eq!(page.fragmentation_ratio().unwrap(), 0.0, "{context}");
+        Page::try_from_bytes(Box::new(*page.data()), page.page_id()).unwrap();
+    }
+
+    /// A compacted page has all its free space in one region, so a row filling it must be placed there
+    fn assert_free_space_is_usable(mut page: Page, context: &str) {
+        let free_space = page.header_ref().unwrap().get_free_space().unwrap() as usize;
+        let Some(len) = free_space.checked_sub(SLOT_SIZE) else {
+            return;
+        };
+        let row = vec![0xA5; len.min(Page::max_row_size())];
+
+        let plan = page.plan_insert(row.len()).unwrap();
+        page.insert_heap(plan, row.clone()).unwrap();
+
+        assert!(
+            (0..page.slot_count().unwrap() as u32)
+                .any(|slot| page.row(slot).is_ok_and(|bytes| bytes == row.as_slice())),
+            "{context}: inserted row cannot be read back"
+        );
+    }
+
+    #[test]
+    fn compact_random_layouts_upholds_invariants() {
+        for order in [
+            CompactionOrder::SlotIndexOrder,
+            CompactionOrder::PhysicalOrder,
+        ] {
+            for seed in 0..CASES {
+                let context = format!("{order:?}, seed {seed}");
+                let Layout { mut page, rows } = generate(seed);
+                Page::try_from_bytes(Box::new(*page.data()), page.page_id())
+                    .unwrap_or_else(|err| panic!("{context}: generated invalid layout: {err:?}"));
+
+                page.compact(order).unwrap();
+
+                assert_compacted(&page, &rows, &context);
+                assert_free_space_is_usable(page, &context);
+            }
+        }
+    }
+
+    #[test]
+    fn compact_random_layouts_is_idempotent() {
+        for order in [
+            CompactionOrder::SlotIndexOrder,
+            CompactionOrder::PhysicalOrder,
+        ] {
+            for seed in 0..CASES {
+                let Layout { mut page, .. } = generate(seed);
+                page.compact(order).unwrap();
+                let compacted = *page.data();
+
+                assert_eq!(page.compact(order).unwrap(), 0, "{order:?}, seed {seed}");
+                assert_eq!(page.data(), &compacted, "{order:?}, seed {seed}");
+            }
+        }
+    }
+}
@@ -0,0 +1,225 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compaction::{CompactionOrder, CompactionReport};
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+
+    /// Heap page whose rows are stored out of slot order, with a single gap left by a deleted row
+    /// right before the last row:
+    ///
+    /// | Offset  | Row              |
+    /// |---------|------------------|
+    /// | 96..196 | slot 1           |
+    /// | 196..246| slot 2           |
+    /// | 246..296| gap (slot 3)     |
+    /// | 296..336| slot 0           |
+    fn fragmented_heap() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 296,
+                len: 40,
+            },
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 196,
+                len: 50,
+            },
+            SlotValues {
+                offset: 246,
+                len: 50,
+            },
+        ]);
+        page.delete_row(3, false).unwrap();
+        page
+    }
+
+    /// Rows of the page by slot index, `None` for deleted slots
+    fn rows_by_slot(page: &Page) -> Vec<Option<Vec<u8>>> {
+        (0..page.slot_count().unwrap() as u32)
+            .map(|slot| page.row(slot).ok().map(<[u8]>::to_vec))
+            .collect()
+    }
+
+    #[test]
+    fn compact_both_orders_yield_same_rows_by_slot() {
+        let before = rows_by_slot(&fragmented_heap());
+
+        for order in [
+            CompactionOrder::SlotIndexOrder,
+            CompactionOrder::PhysicalOrder,
+        ] {
+            let mut page = fragmented_heap();
+
+            page.compact(order).unwrap();
+
+            assert_eq!(rows_by_slot(&page), before, "{order:?}");
+            assert_eq!(page.fragmentation_ratio().unwrap(), 0.0, "{order:?}");
+            page.assert_header(&[&|h| assert_eq!(h.get_free_start().unwrap(), 286)]);
+        }
+    }
+
+    #[test]
+    fn compact_physical_order_moves_fewer_bytes() {
+        let mut by_slot = fragmented_heap();
+        let mut physical = fragmented_heap();
+
+        // Every row lands at a new offset
+        assert_eq!(
+            by_slot.compact(CompactionOrder::SlotIndexOrder).unwrap(),
+            190
+        );
+        // Only the row after the gap slides down
+        assert_eq!(
+            physical.compact(CompactionOrder::PhysicalOrder).unwrap(),
+            40
+        );
+    }
+
+    #[test]
+    fn compact_slot_index_order_lays_rows_out_by_slot() {
+        let mut page = fragmented_heap();
+
+        page.compact(CompactionOrder::SlotIndexOrder).unwrap();
+
+        page.assert_slot(0, 96, 40);
+        page.assert_slot(1, 136, 100);
+        page.assert_slot(2, 236, 50);
+        page.assert_slot(3, 0, 0);
+    }
+
+    #[test]
+    fn compact_physical_order_keeps_rows_before_the_gap_in_place() {
+        let mut page = fragmented_heap();
+
+        page.compact(CompactionOrder::PhysicalOrder).unwrap();
+
+        page.assert_slot(0, 246, 40);
+        page.assert_slot(1, 96, 100);
+        page.assert_slot(2, 196, 50);
+        page.assert_slot(3, 0, 0);
+        page.assert_row_values(246, 40, 1);
+    }
+
+    #[test]
+    fn compact_slot_index_order_matches_defragment() {
+        let mut expected = fragmented_heap();
+        expected.defragment().unwrap();
+        let mut page = fragmented_heap();
+
+        page.compact(CompactionOrder::SlotIndexOrder).unwrap();
+
+        assert_eq!(page.data().to_vec(), expected.data().to_vec());
+    }
+
+    #[test]
+    fn compact_unfragmented_page_physical_order_moves_nothing() {
+        let mut page = fragmented_heap();
+        page.compact(CompactionOrder::PhysicalOrder).unwrap();
+        let before = page.data().to_vec();
+
+        assert_eq!(page.compact(CompactionOrder::PhysicalOrder).unwrap(), 0);
+        assert_eq!(page.data().to_vec(), before);
+    }
+
+    #[test]
+    fn compact_physical_order_zero_length_row_keeps_its_place_on_next_compaction() {
+        let mut page = Page::test_create_empty_heap();
+        // The zero-length row of slot 2 sits in the gap between slot 1 and slot 0
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 150,
+                len: 20,
+            },
+            SlotValues {
+                offset: 96,
+                len: 40,
+            },
+            SlotValues { offset: 0, len: 0 },
+        ]);
+        page.slot_array_mut().unwrap().set_slot(2, 140, 0).unwrap();
+        page.header_mut().unwrap().set_live_row_count(3).unwrap();
+
+        page.compact(CompactionOrder::PhysicalOrder).unwrap();
+        let before = page.data().to_vec();
+        page.assert_slot(2, 136, 0);
+        page.assert_slot(0, 136, 20);
+
+        assert_eq!(page.compact(CompactionOrder::PhysicalOrder).unwrap(), 0);
+        assert_eq!(page.data().to_vec(), before);
+    }
+
+    #[test]
+    fn compact_clears_can_compact() {
+        for order in [
+            CompactionOrder::SlotIndexOrder,
+            CompactionOrder::PhysicalOrder,
+        ] {
+            let mut page = fragmented_heap();
+            page.header_mut().unwrap().set_can_compact(1).unwrap();
+
+            page.compact(order).unwrap();
+
+            page.assert_header(&[&|h| assert_eq!(h.get_can_compact().unwrap(), 0)]);
+        }
+    }
+
+    #[test]
+    fn compact_reporting_slot_index_order_relocates_every_row() {
+        let mut page = fragmented_heap();
+
+        let report = page
+            .compact_reporting(CompactionOrder::SlotIndexOrder)
+            .unwrap();
+
+        assert_eq!(
+            report,
+            CompactionReport {
+                bytes_moved: 190,
+                rows_relocated: 3,
+                free_start: 286,
+            }
+        );
+        page.assert_header(&[&|h| assert_eq!(h.get_free_start().unwrap(), 286)]);
+    }
+
+    #[test]
+    fn compact_reporting_physical_order_relocates_only_row_after_gap() {
+        let mut page = fragmented_heap();
+
+        let report = page
+            .compact_reporting(CompactionOrder::PhysicalOrder)
+            .unwrap();
+
+        assert_eq!(
+            report,
+            CompactionReport {
+                bytes_moved: 40,
+                rows_relocated: 1,
+                free_start: 286,
+            }
+        );
+        page.assert_header(&[&|h| assert_eq!(h.get_free_start().unwrap(), 286)]);
+    }
+
+    #[test]
+    fn compact_reporting_compact_page_reports_nothing_moved() {
+        for order in [
+            CompactionOrder::SlotIndexOrder,
+            CompactionOrder::PhysicalOrder,
+        ] {
+            let mut page = fragmented_heap();
+            page.compact(order).unwrap();
+
+            let report = page.compact_reporting(order).unwrap();
+
+            assert_eq!(report.bytes_moved, 0);
+            assert_eq!(report.rows_relocated, 0);
+            assert_eq!(report.free_start, 286);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::read_row_error::ReadRowError;
+    use crate::errors::slot_error::SlotError;
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+    use crate::tests::tests_error_helpers;
+
+    fn page_with_rows() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 10,
+            },
+            SlotValues {
+                offset: 106,
+                len: 250,
+            },
+        ]);
+        page
+    }
+
+    #[test]
+    fn copy_row_into_exactly_sized_buffer_copies_row() {
+        let page = page_with_rows();
+        let mut destination = [0u8; 250];
+
+        let copied = page.copy_row_into(1, &mut destination).unwrap();
+
+        assert_eq!(copied, 250);
+        assert_eq!(&destination[..], page.row(1).unwrap());
+    }
+
+    #[test]
+    fn copy_row_into_larger_buffer_leaves_tail_untouched() {
+        let page = page_with_rows();
+        let mut destination = [0xEEu8; 16];
+
+        let copied = page.copy_row_into(0, &mut destination).unwrap();
+
+        assert_eq!(copied, 10);
+        assert_eq!(&destination[..10], &[1u8; 10]);
+        assert_eq!(&destination[10..], &[0xEEu8; 6]);
+    }
+
+    #[test]
+    fn copy_row_into_reused_buffer_across_rows() {
+        let page = page_with_rows();
+        let mut destination = vec![0u8; Page::max_row_size()];
+
+        for slot_index in 0..2 {
+            let copied = page.copy_row_into(slot_index, &mut destination).unwrap();
+            assert_eq!(&destination[..copied], page.row(slot_index).unwrap());
+        }
+    }
+
+    #[test]
+    fn copy_row_into_too_small_buffer_returns_error() {
+        let page = page_with_rows();
+        let mut destination = [0xEEu8; 249];
+
+        let err = page.copy_row_into(1, &mut destination).unwrap_err();
+
+        assert_eq!(err.page_id, page.page_id());
+        assert!(matches!(
+            err.source.expect_read_row_error(),
+            ReadRowError::DestinationTooSmall {
+                required: 250,
+                available: 249
+            }
+        ));
+        assert!(destination.iter().all(|b| *b == 0xEE));
+    }
+
+    #[test]
+    fn copy_row_into_deleted_slot_returns_invalid_slot() {
+        let mut page = page_with_rows();
+        page.delete_row(0, false).unwrap();
+        let mut destination = [0u8; 16];
+
+        let err = page.copy_row_into(0, &mut destination).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_read_row_error().expect_slot_error(),
+            SlotError::InvalidSlot { slot_index: 0 }
+        ));
+    }
+
+    #[test]
+    fn copy_row_into_out_of_range_slot_returns_error() {
+        let page = page_with_rows();
+        let mut destination = [0u8; 16];
+
+        assert!(page.copy_row_into(2, &mut destination).is_err());
+    }
+}
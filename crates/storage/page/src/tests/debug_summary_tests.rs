@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+
+    #[test]
+    fn debug_summary_two_rows_and_one_deleted_slot() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues { offset: 0, len: 0 },
+            SlotValues {
+                offset: 196,
+                len: 50,
+            },
+        ]);
+
+        let summary = page.debug_summary();
+
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Page 1:1 (Unsorted)",
+                "  slot_count: 3, free_start: 246, free_end: 4083, free_space: 3838, can_compact: 0",
+                "  [0] offset: 96, length: 100, valid",
+                "  [1] offset: 0, length: 0, deleted",
+                "  [2] offset: 196, length: 50, valid",
+            ]
+        );
+    }
+
+    #[test]
+    fn debug_summary_unknown_page_type() {
+        let mut page = Page::test_create_empty_heap();
+        page.header_mut().unwrap().set_page_type(42).unwrap();
+
+        assert!(page.debug_summary().starts_with("Page 1:1 (Unknown(42))\n"));
+    }
+
+    #[test]
+    fn dump_json_two_rows_and_one_deleted_slot() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 20,
+            },
+            SlotValues { offset: 0, len: 0 },
+            SlotValues {
+                offset: 116,
+                len: 3,
+            },
+        ]);
+
+        let dump: serde_json::Value = serde_json::from_str(&page.dump_json()).unwrap();
+
+        assert_eq!(dump["page_id"], "1:1");
+        assert_eq!(dump["header"]["slot_count"], 3);
+        assert_eq!(dump["header"]["free_start"], 119);
+        assert_eq!(dump["header"]["page_type"], 1);
+        assert_eq!(
+            dump["slots"],
+            serde_json::json!([
+                {"slot": 0, "offset": 96, "length": 20, "valid": true, "hex_preview": "01".repeat(16)},
+                {"slot": 1, "offset": 0, "length": 0, "valid": false, "hex_preview": ""},
+                {"slot": 2, "offset": 116, "length": 3, "valid": true, "hex_preview": "030303"},
+            ])
+        );
+    }
+
+    #[test]
+    fn dump_json_header_matches_header_snapshot() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![SlotValues {
+            offset: 96,
+            len: 10,
+        }]);
+        let snapshot = page.header_snapshot().unwrap();
+
+        let dump: serde_json::Value = serde_json::from_str(&page.dump_json()).unwrap();
+
+        assert_eq!(dump["header"]["free_end"], snapshot.free_end);
+        assert_eq!(dump["header"]["free_space"], snapshot.free_space);
+        assert_eq!(dump["header"]["live_row_count"], snapshot.live_row_count);
+        assert_eq!(dump["header"]["last_lsn"], snapshot.last_lsn);
+    }
+
+    #[test]
+    fn dump_json_unreadable_page_reports_error() {
+        let mut page = Page::test_create_empty_heap();
+        page.header_mut().unwrap().set_slot_count(5000).unwrap();
+
+        let dump: serde_json::Value = serde_json::from_str(&page.dump_json()).unwrap();
+
+        assert_eq!(dump["page_id"], "1:1");
+        assert!(dump["error"].is_string());
+        assert!(dump.get("slots").is_none());
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+
+    fn heap_with_rows() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 196,
+                len: 50,
+            },
+            SlotValues {
+                offset: 246,
+                len: 20,
+            },
+        ]);
+        page
+    }
+
+    #[test]
+    fn defragment_unfragmented_page_does_nothing() {
+        let mut page = heap_with_rows();
+        let before = page.data().to_vec();
+
+        assert!(!page.defragment().unwrap());
+        assert_eq!(page.data().to_vec(), before);
+    }
+
+    #[test]
+    fn defragment_empty_page_does_nothing() {
+        let mut page = Page::test_create_empty_heap();
+
+        assert!(!page.defragment().unwrap());
+    }
+
+    #[test]
+    fn defragment_fragmented_page_compacts_and_clears_can_compact() {
+        let mut page = heap_with_rows();
+        page.delete_row(0, false).unwrap();
+        assert!(page.fragmentation_ratio().unwrap() > 0.0);
+
+        assert!(page.defragment().unwrap());
+
+        assert_eq!(page.fragmentation_ratio().unwrap(), 0.0);
+        page.assert_slot(1, 96, 50);
+        page.assert_slot(2, 146, 20);
+        page.assert_row_values(96, 50, 2);
+        page.assert_row_values(146, 20, 3);
+        page.assert_header(&[&|h| assert_eq!(h.get_free_start().unwrap(), 166), &|h| {
+            assert_eq!(h.get_can_compact().unwrap(), 0)
+        }]);
+    }
+
+    #[test]
+    fn defragment_twice_second_call_does_nothing() {
+        let mut page = heap_with_rows();
+        page.delete_row(1, false).unwrap();
+
+        assert!(page.defragment().unwrap());
+        assert!(!page.defragment().unwrap());
+    }
+}
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::HEADER_SIZE;
+    use crate::delete_outcome::DeleteOutcome;
     use crate::errors::delete_error::DeleteError;
     use crate::errors::page_error::PageError;
     use crate::errors::page_op_error::PageOpError;
@@ -72,10 +74,22 @@ mod tests {
 
         let slot_error = result.source.expect_delete_error().expect_slot_error();
 
-        let SlotError::InvalidSlot { slot_index } = slot_error else {
-            panic!("expected InvalidSlot, got {slot_error:?}")
+        let SlotError::IndexOutOfRange { index, slot_count } = slot_error else {
+            panic!("expected IndexOutOfRange, got {slot_error:?}")
         };
-        assert_eq!(*slot_index, 2);
+        assert_eq!((*index, *slot_count), (2, 2));
+    }
+
+    #[test]
+    fn delete_row_slot_index_beyond_u32_returns_conversion_error() {
+        let mut page = Page::test_create_empty_heap();
+
+        let result = page.delete_row(u32::MAX as usize + 1, false).unwrap_err();
+
+        result
+            .source
+            .expect_delete_error()
+            .expect_conversion_error();
     }
 
     #[test]
@@ -153,6 +167,50 @@ mod tests {
         page.assert_header(&[&|h| assert_eq!(h.get_free_start().unwrap(), 96)])
     }
 
+    #[test]
+    fn delete_last_physical_row_at_slot_zero_shifts_free_start() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 196,
+                len: 100,
+            },
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+        ]);
+
+        page.delete_row(0, false).unwrap();
+
+        page.assert_header(&[&|h| assert_eq!(h.get_free_start().unwrap(), 196), &|h| {
+            assert_eq!(h.get_can_compact().unwrap(), 0)
+        }])
+    }
+
+    #[test]
+    fn delete_only_valid_row_at_slot_zero_resets_free_start_to_header_size() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 196,
+                len: 100,
+            },
+        ]);
+        page.delete_row(1, false).unwrap();
+
+        page.delete_row(0, false).unwrap();
+
+        page.assert_header(&[
+            &|h| assert_eq!(h.get_free_start().unwrap() as usize, HEADER_SIZE),
+            &|h| assert_eq!(h.get_can_compact().unwrap(), 0),
+        ])
+    }
+
     #[test]
     fn delete_when_only_invalid_slots_returns_error() {
         let mut page = Page::test_create_empty_heap();
@@ -180,4 +238,81 @@ mod tests {
 
         page.assert_header(&[&|h| assert_eq!(h.get_free_start().unwrap(), 96)])
     }
+
+    #[test]
+    fn delete_row_reporting_without_compaction_reports_row_length() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 196,
+                len: 50,
+            },
+        ]);
+
+        let outcome = page.delete_row_reporting(0, false).unwrap();
+
+        assert_eq!(
+            outcome,
+            DeleteOutcome {
+                bytes_freed: 100,
+                compacted: false,
+            }
+        );
+        page.assert_row_values(196, 50, 2);
+    }
+
+    #[test]
+    fn delete_row_reporting_with_compaction_reports_compacted() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 196,
+                len: 50,
+            },
+        ]);
+        let free_space_before = page.header_ref().unwrap().get_free_space().unwrap() as usize;
+
+        let outcome = page.delete_row_reporting(0, true).unwrap();
+
+        assert_eq!(
+            outcome,
+            DeleteOutcome {
+                bytes_freed: 100,
+                compacted: true,
+            }
+        );
+        page.assert_row_values(96, 50, 2);
+        page.assert_header(&[&|h| {
+            assert_eq!(
+                h.get_free_space().unwrap() as usize,
+                free_space_before + outcome.bytes_freed
+            )
+        }]);
+    }
+
+    #[test]
+    fn delete_row_reporting_invalid_slot_returns_error() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![SlotValues {
+            offset: 96,
+            len: 100,
+        }]);
+        page.delete_row(0, false).unwrap();
+
+        let err = page.delete_row_reporting(0, true).unwrap_err();
+
+        let slot_error = err.source.expect_delete_error().expect_slot_error();
+        assert!(matches!(
+            slot_error,
+            SlotError::InvalidSlot { slot_index: 0 }
+        ));
+    }
 }
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use crate::page::api::Page;
+    use crate::page_id::PageId;
+    use crate::page_type::PageType;
+
+    /// Index leaf page with two rows, one of them deleted, and every header field set to a distinct value.
+    fn populated_page() -> Page {
+        let mut page = Page::new_empty(PageId::new(1, 7), PageType::IndexLeaf).unwrap();
+        for len in [10, 20] {
+            page.insert_at(page.slot_count().unwrap() as u32, vec![1; len])
+                .unwrap();
+        }
+        page.delete_row(0, false).unwrap();
+
+        let mut header = page.header_mut().unwrap();
+        header.set_left_page(6).unwrap();
+        header.set_right_page(8).unwrap();
+        header.set_last_lsn(0x1122_3344_5566).unwrap();
+        header.set_checksum(0xABCD).unwrap();
+        page
+    }
+
+    #[test]
+    fn header_snapshot_matches_getters() {
+        let page = populated_page();
+        let header = page.header_ref().unwrap();
+
+        let snapshot = page.header_snapshot().unwrap();
+
+        assert_eq!(snapshot.slot_count, header.get_slot_count().unwrap());
+        assert_eq!(snapshot.free_start, header.get_free_start().unwrap());
+        assert_eq!(snapshot.free_end, header.get_free_end().unwrap());
+        assert_eq!(snapshot.free_space, header.get_free_space().unwrap());
+        assert_eq!(snapshot.can_compact, header.get_can_compact().unwrap());
+        assert_eq!(snapshot.page_number, header.get_page_number().unwrap());
+        assert_eq!(snapshot.page_type, header.get_page_type().unwrap());
+        assert_eq!(snapshot.left_page, header.get_left_page().unwrap());
+        assert_eq!(snapshot.right_page, header.get_right_page().unwrap());
+        assert_eq!(snapshot.last_lsn, header.get_last_lsn().unwrap());
+        assert_eq!(snapshot.checksum, header.get_checksum().unwrap());
+        assert_eq!(
+            snapshot.live_row_count,
+            header.get_live_row_count().unwrap()
+        );
+    }
+
+    #[test]
+    fn header_snapshot_matches_public_getters() {
+        let page = populated_page();
+
+        let snapshot = page.header_snapshot().unwrap();
+
+        assert_eq!(snapshot.slot_count, page.slot_count().unwrap());
+        assert_eq!(snapshot.live_row_count, page.live_row_count().unwrap());
+        assert_eq!(snapshot.right_page, page.right_page().unwrap());
+        assert_eq!(snapshot.page_type, u16::from(page.page_type().unwrap()));
+        assert_eq!(snapshot.slot_count, 2);
+        assert_eq!(snapshot.live_row_count, 1);
+        assert_eq!(snapshot.page_number, 7);
+        assert_eq!(snapshot.left_page, 6);
+        assert_eq!(snapshot.last_lsn, 0x1122_3344_5566);
+    }
+
+    #[test]
+    fn header_snapshot_unknown_page_type_is_reported_raw() {
+        let mut page = Page::test_create_empty_heap();
+        page.header_mut().unwrap().set_page_type(99).unwrap();
+
+        let snapshot = page.header_snapshot().unwrap();
+
+        assert_eq!(snapshot.page_type, 99);
+        assert!(page.page_type().is_err());
+    }
+}
@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::header_error::HeaderError;
+    use crate::header::INDEX_HEADER_SIZE;
+    use crate::page::api::Page;
+    use crate::page_id::PageId;
+    use crate::page_type::PageType;
+    use crate::tests::tests_error_helpers;
+    use crate::{HEADER_SIZE, PAGE_SIZE};
+
+    fn initialized(page_id: PageId, page_type: PageType) -> Page {
+        let mut page = Page::new_zeroed(page_id);
+        page.initialize(page_id, page_type).unwrap();
+        page
+    }
+
+    #[test]
+    fn initialize_index_leaf_reserves_index_header() {
+        let mut page = initialized(PageId::new(1, 3), PageType::IndexLeaf);
+
+        let data_start = HEADER_SIZE + INDEX_HEADER_SIZE;
+        page.assert_header(&[
+            &|h| assert_eq!(h.get_free_start().unwrap(), data_start as u16),
+            &|h| assert_eq!(h.get_free_end().unwrap(), (PAGE_SIZE - 1) as u16),
+            &|h| assert_eq!(h.get_free_space().unwrap(), (PAGE_SIZE - data_start) as u16),
+            &|h| assert_eq!(h.get_page_type().unwrap(), u16::from(PageType::IndexLeaf)),
+        ]);
+    }
+
+    #[test]
+    fn initialize_index_leaf_defaults_differ_from_unsorted() {
+        let leaf = initialized(PageId::new(1, 3), PageType::IndexLeaf);
+        let heap = initialized(PageId::new(1, 3), PageType::Unsorted);
+
+        assert_eq!(
+            heap.header_ref().unwrap().get_free_start().unwrap(),
+            HEADER_SIZE as u16
+        );
+        assert_eq!(
+            leaf.header_ref().unwrap().get_free_space().unwrap() + INDEX_HEADER_SIZE as u16,
+            heap.header_ref().unwrap().get_free_space().unwrap()
+        );
+    }
+
+    #[test]
+    fn initialize_index_leaf_rows_placed_after_index_header() {
+        let mut page = initialized(PageId::new(1, 3), PageType::IndexLeaf);
+
+        let plan = page.plan_insert(10).unwrap();
+        page.insert_heap(plan, vec![7; 10]).unwrap();
+
+        page.assert_slot(0, HEADER_SIZE + INDEX_HEADER_SIZE, 10);
+        Page::try_from_bytes(Box::new(*page.data()), page.page_id()).unwrap();
+    }
+
+    #[test]
+    fn initialize_index_leaf_at_page_zero_error_returned() {
+        let page_id = PageId::new(1, 0);
+        let mut page = Page::new_zeroed(page_id);
+
+        let err = page.initialize(page_id, PageType::IndexLeaf).unwrap_err();
+
+        assert_eq!(err.page_id, page_id);
+        assert!(matches!(
+            err.source.expect_header_error(),
+            HeaderError::InvalidPageTypeForPage {
+                page_number: 0,
+                page_type: 4
+            }
+        ));
+    }
+
+    #[test]
+    fn initialize_overflow_holds_a_max_size_row() {
+        let mut page = initialized(PageId::new(1, 3), PageType::Overflow);
+
+        let plan = page.plan_insert(Page::max_row_size()).unwrap();
+        page.insert_heap(plan, vec![7; Page::max_row_size()])
+            .unwrap();
+
+        assert_eq!(page.page_type().unwrap(), PageType::Overflow);
+        page.assert_slot(0, HEADER_SIZE, Page::max_row_size());
+        Page::try_from_bytes(Box::new(*page.data()), page.page_id()).unwrap();
+    }
+
+    #[test]
+    fn initialize_overflow_at_page_zero_error_returned() {
+        let page_id = PageId::new(1, 0);
+        let mut page = Page::new_zeroed(page_id);
+
+        let err = page.initialize(page_id, PageType::Overflow).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_header_error(),
+            HeaderError::InvalidPageTypeForPage {
+                page_number: 0,
+                page_type: 5
+            }
+        ));
+    }
+
+    #[test]
+    fn clear_rows_index_leaf_keeps_index_header_reserved() {
+        let mut page = initialized(PageId::new(1, 3), PageType::IndexLeaf);
+        let plan = page.plan_insert(10).unwrap();
+        page.insert_heap(plan, vec![7; 10]).unwrap();
+
+        page.clear_rows().unwrap();
+
+        page.assert_header(&[&|h| {
+            assert_eq!(
+                h.get_free_start().unwrap(),
+                (HEADER_SIZE + INDEX_HEADER_SIZE) as u16
+            )
+        }]);
+    }
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::insert_error::InsertError;
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+    use crate::{HEADER_SIZE, PAGE_SIZE};
+
+    #[test]
+    fn insert_at_chosen_indices_out_of_order() {
+        let mut page = Page::test_create_empty_heap();
+
+        page.insert_at(2, vec![3u8; 30]).unwrap();
+        page.insert_at(0, vec![1u8; 10]).unwrap();
+        page.insert_at(1, vec![2u8; 20]).unwrap();
+
+        assert_eq!(page.row(0).unwrap(), &[1u8; 10]);
+        assert_eq!(page.row(1).unwrap(), &[2u8; 20]);
+        assert_eq!(page.row(2).unwrap(), &[3u8; 30]);
+        // Rows are stored in arrival order, regardless of their slot
+        page.assert_slot(2, 96, 30);
+        page.assert_slot(0, 126, 10);
+        page.assert_slot(1, 136, 20);
+        page.assert_header(&[
+            &|h| assert_eq!(h.get_slot_count().unwrap(), 3),
+            &|h| assert_eq!(h.get_live_row_count().unwrap(), 3),
+            &|h| assert_eq!(h.get_free_start().unwrap(), 156),
+            &|h| assert_eq!(h.get_free_end().unwrap(), (PAGE_SIZE - 1 - 3 * 4) as u16),
+            &|h| assert_eq!(h.get_free_space().unwrap(), 4000 - 60 - 3 * 4),
+        ]);
+    }
+
+    #[test]
+    fn insert_at_past_end_leaves_slots_in_between_empty() {
+        let mut page = Page::test_create_empty_heap();
+
+        page.insert_at(3, vec![7u8; 10]).unwrap();
+
+        assert_eq!(page.slot_count().unwrap(), 4);
+        assert_eq!(page.live_row_count().unwrap(), 1);
+        for slot_index in 0..3 {
+            page.assert_slot(slot_index, 0, 0);
+        }
+        page.assert_slot(3, HEADER_SIZE, 10);
+        Page::try_from_bytes(Box::new(*page.data()), page.page_id()).unwrap();
+    }
+
+    #[test]
+    fn insert_at_reuses_deleted_slot_without_touching_others() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 10,
+            },
+            SlotValues {
+                offset: 106,
+                len: 10,
+            },
+        ]);
+        page.delete_row(0, false).unwrap();
+
+        page.insert_at(0, vec![9u8; 5]).unwrap();
+
+        assert_eq!(page.row(0).unwrap(), &[9u8; 5]);
+        assert_eq!(page.row(1).unwrap(), &[2u8; 10]);
+        assert_eq!(page.slot_count().unwrap(), 2);
+        assert_eq!(page.live_row_count().unwrap(), 2);
+    }
+
+    #[test]
+    fn insert_at_occupied_index_returns_error_and_leaves_page_untouched() {
+        let mut page = Page::test_create_empty_heap();
+        page.insert_at(0, vec![1u8; 10]).unwrap();
+        let before = *page.data();
+
+        let err = page.insert_at(0, vec![2u8; 10]).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_insert_error(),
+            InsertError::SlotOccupied { slot_index: 0 }
+        ));
+        assert_eq!(page.data(), &before);
+    }
+
+    #[test]
+    fn insert_at_not_enough_space_for_new_slots_returns_error() {
+        let mut page = Page::test_create_empty_heap();
+        let before = *page.data();
+
+        // 1000 slots alone take as much space as the whole page
+        let err = page.insert_at(999, vec![1u8; 10]).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_insert_error(),
+            InsertError::NotEnoughSpace { row_len: 10, .. }
+        ));
+        assert_eq!(page.data(), &before);
+    }
+
+    #[test]
+    fn insert_at_new_slots_not_fitting_contiguous_region_compacts_first() {
+        let mut page = Page::test_create_empty_heap();
+        page.insert_at(0, vec![1u8; 1900]).unwrap();
+        page.insert_at(1, vec![2u8; 1900]).unwrap();
+        page.insert_at(2, vec![3u8; 180]).unwrap();
+        page.delete_row(0, false).unwrap();
+
+        // Only 8 contiguous free bytes are left, not enough for the 3 new slots
+        page.insert_at(5, vec![4u8; 1000]).unwrap();
+
+        // Compaction moved the remaining rows to the start of the data region
+        page.assert_slot(1, HEADER_SIZE, 1900);
+        page.assert_slot(2, HEADER_SIZE + 1900, 180);
+        page.assert_slot(5, HEADER_SIZE + 2080, 1000);
+        assert_eq!(page.row(1).unwrap(), &[2u8; 1900]);
+        assert_eq!(page.row(2).unwrap(), &[3u8; 180]);
+        assert_eq!(page.row(5).unwrap(), &[4u8; 1000]);
+        assert_eq!(page.slot_count().unwrap(), 6);
+        Page::try_from_bytes(Box::new(*page.data()), page.page_id()).unwrap();
+    }
+}
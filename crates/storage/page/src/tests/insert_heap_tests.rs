@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::errors::header_error::HeaderError;
+    use crate::errors::insert_error::InsertError;
     use crate::insertion_plan::{InsertionOffset, InsertionPlan, InsertionSlot};
     use crate::page::api::Page;
     use crate::page_id::PageId;
@@ -14,8 +16,9 @@ mod tests {
         let mut page = Page::test_create_empty_heap();
 
         let plan = page.plan_insert(100).unwrap();
-        page.insert_heap(plan, vec![1u8; 100]).unwrap();
+        let slot = page.insert_heap(plan, vec![1u8; 100]).unwrap();
 
+        assert_eq!(slot, 0);
         page.assert_slot(0, 96, 100);
         page.assert_row_values(96, 100, 1);
 
@@ -48,10 +51,11 @@ mod tests {
 
         let row_len = 150;
         let plan = page.plan_insert(row_len).unwrap();
-        page.insert_heap(plan, vec![3u8; row_len]).unwrap();
+        let slot = page.insert_heap(plan, vec![3u8; row_len]).unwrap();
 
         let expected_offset_of_new_row = 96 + 100 + 50;
 
+        assert_eq!(slot, 2);
         page.assert_slot(2, expected_offset_of_new_row, row_len);
         page.assert_row_values(expected_offset_of_new_row, row_len, 3);
 
@@ -91,10 +95,11 @@ mod tests {
 
         let row_len = 150;
         let plan = page.plan_insert(row_len).unwrap();
-        page.insert_heap(plan, vec![3u8; row_len]).unwrap();
+        let slot = page.insert_heap(plan, vec![3u8; row_len]).unwrap();
 
         let expected_offset_of_new_row = 96 + 100 + 50;
 
+        assert_eq!(slot, 2);
         page.assert_slot(2, expected_offset_of_new_row, row_len);
         page.assert_row_values(expected_offset_of_new_row, row_len, 3);
 
@@ -137,10 +142,11 @@ mod tests {
 
         let row_len = 100;
         let plan = page.plan_insert(row_len).unwrap();
-        page.insert_heap(plan, vec![4u8; row_len]).unwrap();
+        let slot = page.insert_heap(plan, vec![4u8; row_len]).unwrap();
 
         let expected_offset_of_new_row = 296 + 50;
 
+        assert_eq!(slot, 1);
         page.assert_slot(1, expected_offset_of_new_row, row_len);
         page.assert_row_values(expected_offset_of_new_row, row_len, 4);
 
@@ -185,10 +191,11 @@ mod tests {
 
         let row_len = 100;
         let plan = page.plan_insert(row_len).unwrap();
-        page.insert_heap(plan, vec![4u8; row_len]).unwrap();
+        let slot = page.insert_heap(plan, vec![4u8; row_len]).unwrap();
 
         let expected_offset_of_new_row = 196;
 
+        assert_eq!(slot, 1);
         page.assert_slot(1, expected_offset_of_new_row, row_len);
         page.assert_row_values(expected_offset_of_new_row, row_len, 4);
 
@@ -230,7 +237,7 @@ mod tests {
         // There are two sections of free space, both smaller than 100 bytes
         let row_len = 100;
         let plan = page.plan_insert(row_len).unwrap();
-        page.insert_heap(plan, vec![4u8; row_len]).unwrap();
+        let slot = page.insert_heap(plan, vec![4u8; row_len]).unwrap();
 
         // The page should first be compacted:
         // Row 1: 96-3000 (2904 bytes)
@@ -238,6 +245,7 @@ mod tests {
         // new row will be placed at 3950
         let expected_offset_of_new_row = 3950;
 
+        assert_eq!(slot, 1);
         page.assert_slot(1, expected_offset_of_new_row, row_len);
         page.assert_row_values(expected_offset_of_new_row, row_len, 4);
 
@@ -253,9 +261,144 @@ mod tests {
         ])
     }
 
+    #[test]
+    fn insert_row_longer_than_u16_max_returns_conversion_error_and_leaves_page_unchanged() {
+        let mut page = Page::test_create_empty_heap();
+        let plan = InsertionPlan {
+            slot: InsertionSlot::New,
+            offset: InsertionOffset::Exact(HEADER_SIZE),
+            version: page.page_version().unwrap(),
+        };
+
+        let err = page
+            .insert_heap(plan, vec![1u8; u16::MAX as usize + 1])
+            .unwrap_err();
+
+        err.source.expect_insert_error().expect_conversion_error();
+        assert_eq!(page.slot_count().unwrap(), 0);
+        page.assert_header(&[
+            &|h| assert_eq!(h.get_free_start().unwrap(), HEADER_SIZE as u16),
+            &|h| assert_eq!(h.get_free_end().unwrap(), (PAGE_SIZE - 1) as u16),
+            &|h| {
+                assert_eq!(
+                    h.get_free_space().unwrap(),
+                    (PAGE_SIZE - HEADER_SIZE) as u16
+                )
+            },
+        ]);
+    }
+
+    #[test]
+    fn insert_row_free_end_below_slot_size_returns_header_error_and_leaves_page_unchanged() {
+        let mut page = Page::test_create_empty_heap();
+        // A drifted header, with no room left for a new slot entry
+        page.header_mut().unwrap().set_free_end(2).unwrap();
+        let plan = InsertionPlan {
+            slot: InsertionSlot::New,
+            offset: InsertionOffset::Exact(HEADER_SIZE),
+            version: page.page_version().unwrap(),
+        };
+
+        let err = page.insert_heap(plan, vec![1u8; 10]).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_insert_error().expect_header_error(),
+            HeaderError::OffsetArithmetic
+        ));
+        assert_eq!(page.slot_count().unwrap(), 0);
+        page.assert_header(&[
+            &|h| assert_eq!(h.get_free_start().unwrap(), HEADER_SIZE as u16),
+            &|h| assert_eq!(h.get_free_end().unwrap(), 2),
+        ]);
+    }
+
+    #[test]
+    fn insert_row_plan_used_immediately_succeeds() {
+        let mut page = Page::test_create_empty_heap();
+        page.insert_heap(page.plan_insert(10).unwrap(), vec![1u8; 10])
+            .unwrap();
+
+        let plan = page.plan_insert(20).unwrap();
+        let slot = page.insert_heap(plan, vec![2u8; 20]).unwrap();
+
+        assert_eq!(slot, 1);
+        page.assert_slot(1, 106, 20);
+        page.assert_row_values(106, 20, 2);
+    }
+
+    #[test]
+    fn insert_row_page_modified_after_plan_returns_stale_plan() {
+        let mut page = Page::test_create_empty_heap();
+
+        let plan = page.plan_insert(20).unwrap();
+        // Intervening write claims the offset and slot the plan points to
+        let other_plan = page.plan_insert(10).unwrap();
+        page.insert_heap(other_plan, vec![1u8; 10]).unwrap();
+
+        let err = page.insert_heap(plan, vec![2u8; 20]).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_insert_error(),
+            InsertError::StalePlan
+        ));
+        // The row written in between is left intact
+        assert_eq!(page.slot_count().unwrap(), 1);
+        page.assert_row_values(96, 10, 1);
+    }
+
+    #[test]
+    fn insert_row_row_deleted_after_plan_returns_stale_plan() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 10,
+            },
+            SlotValues {
+                offset: 106,
+                len: 10,
+            },
+            SlotValues {
+                offset: 116,
+                len: 10,
+            },
+        ]);
+
+        let plan = page.plan_insert(20).unwrap();
+        page.delete_row(1, false).unwrap();
+
+        let err = page.insert_heap(plan, vec![4u8; 20]).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_insert_error(),
+            InsertError::StalePlan
+        ));
+    }
+
     // Invalid scenarios as of right now:
     // - insert new row in between 2 rows (perfect fit), create new slot
     // - insert new row at free start only after compaction, create new slot
     // these are invalid because the only way to achieve fragmentation is through row deletion, but row deletion implies unused slots, thus it makes it impossible to have scenarios where we have both fragmentation + no unused slots.
     // TODO revisit these once row updates are implemented.
+
+    #[test]
+    fn insert_row_returned_slot_reads_back_the_row() {
+        let mut page = Page::test_create_empty_heap();
+        let rows: Vec<Vec<u8>> = (1..=3u8)
+            .map(|value| vec![value; 10 * value as usize])
+            .collect();
+        for row in &rows {
+            let plan = page.plan_insert(row.len()).unwrap();
+            let slot = page.insert_heap(plan, row.clone()).unwrap();
+
+            assert_eq!(page.row(slot).unwrap(), row.as_slice());
+        }
+
+        page.delete_row(1, false).unwrap();
+        let plan = page.plan_insert(5).unwrap();
+        let slot = page.insert_heap(plan, vec![9u8; 5]).unwrap();
+
+        assert_eq!(slot, 1);
+        assert_eq!(page.row(slot).unwrap(), &[9u8; 5]);
+    }
 }
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::page::api::Page;
+    use crate::slot_info::SlotInfo;
+    use crate::tests::SlotValues;
+
+    fn heap_with_deleted_middle_row() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 196,
+                len: 50,
+            },
+            SlotValues {
+                offset: 246,
+                len: 20,
+            },
+        ]);
+        page.delete_row(1, false).unwrap();
+        page
+    }
+
+    #[test]
+    fn iter_slots_empty_page_yields_nothing() {
+        let page = Page::test_create_empty_heap();
+
+        assert_eq!(page.iter_slots().count(), 0);
+    }
+
+    #[test]
+    fn iter_slots_deleted_middle_slot_is_yielded_as_invalid() {
+        let page = heap_with_deleted_middle_row();
+
+        let slots: Vec<SlotInfo> = page.iter_slots().map(Result::unwrap).collect();
+
+        assert_eq!(
+            slots,
+            vec![
+                SlotInfo {
+                    index: 0,
+                    offset: 96,
+                    length: 100,
+                    valid: true,
+                },
+                SlotInfo {
+                    index: 1,
+                    offset: 0,
+                    length: 0,
+                    valid: false,
+                },
+                SlotInfo {
+                    index: 2,
+                    offset: 246,
+                    length: 20,
+                    valid: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn iter_slots_covers_every_slot_including_deleted_ones() {
+        let page = heap_with_deleted_middle_row();
+
+        assert_eq!(
+            page.iter_slots().count(),
+            page.slot_count().unwrap() as usize
+        );
+        assert_eq!(
+            page.iter_slots()
+                .filter(|s| s.as_ref().unwrap().valid)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn iter_slots_corrupt_slot_fails_alone() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_create_slots(vec![
+            SlotValues {
+                offset: 96,
+                len: 10,
+            },
+            // A zero offset with a non-zero length is neither a live row nor a deleted one
+            SlotValues { offset: 0, len: 5 },
+            SlotValues {
+                offset: 106,
+                len: 10,
+            },
+        ]);
+
+        let slots: Vec<_> = page.iter_slots().collect();
+
+        assert_eq!(slots.len(), 3);
+        assert!(slots[0].as_ref().unwrap().valid);
+        let err = slots[1].as_ref().unwrap_err();
+        assert_eq!(err.page_id, page.page_id());
+        assert!(slots[2].as_ref().unwrap().valid);
+    }
+}
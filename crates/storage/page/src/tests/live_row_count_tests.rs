@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+
+    fn insert(page: &mut Page, row: Vec<u8>) {
+        let plan = page.plan_insert(row.len()).unwrap();
+        page.insert_heap(plan, row).unwrap();
+    }
+
+    #[test]
+    fn live_row_count_empty_page_is_zero() {
+        let page = Page::test_create_empty_heap();
+
+        assert_eq!(page.live_row_count().unwrap(), 0);
+    }
+
+    #[test]
+    fn live_row_count_two_inserts_one_delete() {
+        let mut page = Page::test_create_empty_heap();
+        insert(&mut page, vec![1; 10]);
+        insert(&mut page, vec![2; 20]);
+
+        page.delete_row(0, false).unwrap();
+
+        assert_eq!(page.live_row_count().unwrap(), 1);
+        assert_eq!(page.slot_count().unwrap(), 2);
+    }
+
+    #[test]
+    fn live_row_count_insert_reusing_deleted_slot() {
+        let mut page = Page::test_create_empty_heap();
+        insert(&mut page, vec![1; 10]);
+        insert(&mut page, vec![2; 20]);
+        page.delete_row(0, false).unwrap();
+
+        insert(&mut page, vec![3; 5]);
+
+        assert_eq!(page.live_row_count().unwrap(), 2);
+        assert_eq!(page.slot_count().unwrap(), 2);
+    }
+
+    #[test]
+    fn live_row_count_unchanged_by_delete_with_compaction() {
+        let mut page = Page::test_create_empty_heap();
+        insert(&mut page, vec![1; 10]);
+        insert(&mut page, vec![2; 20]);
+        insert(&mut page, vec![3; 30]);
+
+        page.delete_row(1, true).unwrap();
+
+        assert_eq!(page.live_row_count().unwrap(), 2);
+    }
+
+    #[test]
+    fn live_row_count_unchanged_by_update() {
+        let mut page = Page::test_create_empty_heap();
+        insert(&mut page, vec![1; 10]);
+        insert(&mut page, vec![2; 20]);
+
+        page.update_row(0, vec![9; 5]).unwrap();
+        page.update_row(1, vec![8; 40]).unwrap();
+
+        assert_eq!(page.live_row_count().unwrap(), 2);
+    }
+
+    #[test]
+    fn live_row_count_unchanged_by_update_requiring_compaction() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 1000,
+            },
+            SlotValues {
+                offset: 1096,
+                len: 1000,
+            },
+            SlotValues {
+                offset: 2096,
+                len: 1900,
+            },
+        ]);
+        page.delete_row(0, false).unwrap();
+
+        // Neither the gap left by row 0 nor the space taken by row 2 and the free region after it can fit
+        // the new row, only all of them combined.
+        page.update_row(2, vec![7; 2500]).unwrap();
+
+        assert_eq!(page.live_row_count().unwrap(), 2);
+        assert_eq!(page.row(2).unwrap(), vec![7; 2500].as_slice());
+    }
+
+    #[test]
+    fn live_row_count_reset_by_clear_rows() {
+        let mut page = Page::test_create_empty_heap();
+        insert(&mut page, vec![1; 10]);
+        insert(&mut page, vec![2; 20]);
+
+        page.clear_rows().unwrap();
+
+        assert_eq!(page.live_row_count().unwrap(), 0);
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::page::api::Page;
+    use crate::page_id::PageId;
+    use crate::page_type::PageType;
+    use crate::slot::SLOT_SIZE;
+    use crate::{HEADER_SIZE, PAGE_SIZE};
+
+    fn page_with_rows(page_number: u32, rows: &[(u8, usize)]) -> Page {
+        let mut page = Page::new_empty(PageId::new(1, page_number), PageType::Unsorted).unwrap();
+        for &(value, len) in rows {
+            let plan = page.plan_insert(len).unwrap();
+            page.insert_heap(plan, vec![value; len]).unwrap();
+        }
+        page
+    }
+
+    fn free_space(page: &Page) -> usize {
+        page.header_ref().unwrap().get_free_space().unwrap() as usize
+    }
+
+    #[test]
+    fn merge_from_two_half_full_pages_into_one() {
+        let half = (PAGE_SIZE - HEADER_SIZE) / 2 - 2 * SLOT_SIZE;
+        let mut page = page_with_rows(1, &[(1, half / 2), (2, half / 2)]);
+        let source = page_with_rows(2, &[(3, half / 2), (4, half / 2)]);
+        let (free_before, source_free) = (free_space(&page), free_space(&source));
+
+        assert!(page.merge_from(&source).unwrap());
+
+        assert_eq!(page.live_row_count().unwrap(), 4);
+        assert_eq!(page.row(0).unwrap(), vec![1u8; half / 2].as_slice());
+        assert_eq!(page.row(1).unwrap(), vec![2u8; half / 2].as_slice());
+        assert_eq!(page.row(2).unwrap(), vec![3u8; half / 2].as_slice());
+        assert_eq!(page.row(3).unwrap(), vec![4u8; half / 2].as_slice());
+        let source_used = PAGE_SIZE - HEADER_SIZE - source_free;
+        assert_eq!(free_space(&page), free_before - source_used);
+    }
+
+    #[test]
+    fn merge_from_reuses_deleted_slots_in_accounting() {
+        let mut page = page_with_rows(1, &[(1, 10), (2, 20)]);
+        page.delete_row(0, false).unwrap();
+        let source = page_with_rows(2, &[(3, 30)]);
+        let free_before = free_space(&page);
+
+        assert!(page.merge_from(&source).unwrap());
+
+        assert_eq!(page.slot_count().unwrap(), 2);
+        assert_eq!(page.row(0).unwrap(), &[3u8; 30]);
+        assert_eq!(free_space(&page), free_before - 30);
+    }
+
+    #[test]
+    fn merge_from_rows_do_not_fit_nothing_modified() {
+        let mut page = page_with_rows(1, &[(1, 3000)]);
+        let source = page_with_rows(2, &[(2, 500), (3, 500)]);
+        let before = page.data().to_vec();
+
+        assert!(!page.merge_from(&source).unwrap());
+
+        assert_eq!(page.data().to_vec(), before);
+    }
+
+    #[test]
+    fn merge_from_exact_fit_merges() {
+        let mut page = page_with_rows(1, &[(1, 1000)]);
+        let remaining = free_space(&page) - SLOT_SIZE;
+        let source = page_with_rows(2, &[(2, remaining)]);
+
+        assert!(page.merge_from(&source).unwrap());
+
+        assert_eq!(free_space(&page), 0);
+    }
+
+    #[test]
+    fn merge_from_right_sibling_unlinks_it() {
+        let mut page = page_with_rows(1, &[(1, 10)]);
+        let mut source = page_with_rows(2, &[(2, 10)]);
+        page.header_mut().unwrap().set_right_page(2).unwrap();
+        source.header_mut().unwrap().set_left_page(1).unwrap();
+        source.header_mut().unwrap().set_right_page(9).unwrap();
+
+        assert!(page.merge_from(&source).unwrap());
+
+        page.assert_header(&[&|h| assert_eq!(h.get_right_page().unwrap(), 9)]);
+    }
+
+    #[test]
+    fn merge_from_left_sibling_unlinks_it() {
+        let mut page = page_with_rows(2, &[(1, 10)]);
+        let mut source = page_with_rows(1, &[(2, 10)]);
+        page.header_mut().unwrap().set_left_page(1).unwrap();
+        source.header_mut().unwrap().set_left_page(7).unwrap();
+        source.header_mut().unwrap().set_right_page(2).unwrap();
+
+        assert!(page.merge_from(&source).unwrap());
+
+        page.assert_header(&[&|h| assert_eq!(h.get_left_page().unwrap(), 7), &|h| {
+            assert_eq!(h.get_right_page().unwrap(), 0)
+        }]);
+    }
+}
@@ -6,16 +6,74 @@ use crate::page_type::PageType;
 use crate::slot::SLOT_SIZE;
 use crate::{HEADER_SIZE, PAGE_SIZE};
 
+#[cfg(test)]
+mod binary_search_rows_tests;
+#[cfg(test)]
+mod clear_rows_tests;
+#[cfg(test)]
+mod coalesce_free_tests;
+#[cfg(test)]
+mod compact_into_tests;
+#[cfg(test)]
+mod compact_property_tests;
+#[cfg(test)]
+mod compact_tests;
+#[cfg(test)]
+mod copy_row_into_tests;
+#[cfg(test)]
+mod debug_summary_tests;
+#[cfg(test)]
+mod defragment_tests;
 #[cfg(test)]
 mod delete_row_tests;
 #[cfg(test)]
+mod header_snapshot_tests;
+#[cfg(test)]
+mod initialize_tests;
+#[cfg(test)]
+mod insert_at_tests;
+#[cfg(test)]
 mod insert_heap_tests;
 #[cfg(test)]
+mod iter_slots_tests;
+#[cfg(test)]
+mod live_row_count_tests;
+#[cfg(test)]
+mod merge_from_tests;
+#[cfg(test)]
+mod move_row_tests;
+#[cfg(test)]
+mod needs_compaction_tests;
+#[cfg(test)]
+mod page_error_tests;
+#[cfg(test)]
 mod plan_insert_tests;
 #[cfg(test)]
 mod read_row_tests;
 #[cfg(test)]
+mod recompute_free_space_tests;
+#[cfg(test)]
+mod replace_row_tests;
+#[cfg(test)]
+mod right_page_tests;
+#[cfg(test)]
+mod row_length_tests;
+#[cfg(test)]
+mod row_mut_tests;
+#[cfg(test)]
+mod slot_extents_tests;
+#[cfg(test)]
+mod split_into_tests;
+#[cfg(test)]
+mod to_owned_bytes_tests;
+#[cfg(test)]
 mod update_row_tests;
+#[cfg(test)]
+mod upgrade_header_tests;
+#[cfg(test)]
+mod validate_tests;
+#[cfg(test)]
+mod zero_length_row_tests;
 
 /// This section defines helper methods for the test suite. They are defined as methods on the `Page` struct, but are only available in the test environment.
 #[cfg(test)]
@@ -35,9 +93,19 @@ impl Page {
         header_mut.set_free_end(new_free_end as u16);
     }
 
+    /// Sets the live row count in the header to the number of valid slots among `slots`.
+    pub(super) fn test_set_live_row_count(&mut self, slots: &[SlotValues]) {
+        let live = slots.iter().filter(|s| s.offset != 0 && s.len != 0).count();
+        self.header_mut()
+            .unwrap()
+            .set_live_row_count(live as u16)
+            .unwrap();
+    }
+
     /// Populates the slot array with the provided slots. Sets up the header with the correct slot count as well.
     pub(super) fn test_create_slots(&mut self, slots: Vec<SlotValues>) {
         self.test_set_slot_count(slots.len());
+        self.test_set_live_row_count(&slots);
         let mut slot_array_mut = self.slot_array_mut().unwrap();
         for (index, slot) in slots.iter().enumerate() {
             slot_array_mut
@@ -68,6 +136,7 @@ impl Page {
     /// ```
     pub(super) fn test_insert_rows(&mut self, slots: Vec<SlotValues>) {
         self.test_set_slot_count(slots.len());
+        self.test_set_live_row_count(&slots);
         for (index, slot) in slots.iter().enumerate() {
             let mut slot_array_mut = self.slot_array_mut().unwrap();
             slot_array_mut
@@ -162,10 +231,13 @@ mod tests_error_helpers {
     use crate::errors::delete_error::DeleteError;
     use crate::errors::header_error::HeaderError;
     use crate::errors::insert_error::InsertError;
+    use crate::errors::move_row_error::MoveRowError;
     use crate::errors::page_op_error::PageOpError;
     use crate::errors::read_row_error::ReadRowError;
     use crate::errors::slot_error::SlotError;
+    use crate::errors::split_error::SplitError;
     use crate::errors::update_error::UpdateError;
+    use crate::errors::validation_error::ValidationError;
     use binary_helpers::bin_error::BinaryError;
     use binary_helpers::conversions::ConversionError;
 
@@ -194,6 +266,9 @@ mod tests_error_helpers {
     impl_expect_ref!(PageOpError, expect_insert_error, Insert => InsertError);
     impl_expect_ref!(PageOpError, expect_delete_error, DeleteRow => DeleteError);
     impl_expect_ref!(PageOpError, expect_update_error, UpdateRow => UpdateError);
+    impl_expect_ref!(PageOpError, expect_split_error, Split => SplitError);
+    impl_expect_ref!(PageOpError, expect_validation_error, Validation => ValidationError);
+    impl_expect_ref!(PageOpError, expect_move_row_error, MoveRow => MoveRowError);
 
     impl_expect_ref!(HeaderError, expect_binary_error, BinaryError => BinaryError);
 
@@ -203,10 +278,12 @@ mod tests_error_helpers {
     impl_expect_ref!(ReadRowError, expect_slot_error, SlotError => SlotError);
 
     impl_expect_ref!(InsertError, expect_slot_error, SlotError => SlotError);
-    impl_expect_ref!(InsertError, expect_header_error, SlotError => SlotError);
+    impl_expect_ref!(InsertError, expect_header_error, HeaderError => HeaderError);
+    impl_expect_ref!(InsertError, expect_conversion_error, ConversionError => ConversionError);
 
-    impl_expect_ref!(DeleteError, expect_slot_error, SlotError => SlotError);
-    impl_expect_ref!(DeleteError, expect_header_error, HeaderError => HeaderError);
+    impl_expect_ref!(DeleteError, expect_slot_error, Slot => SlotError);
+    impl_expect_ref!(DeleteError, expect_header_error, Header => HeaderError);
+    impl_expect_ref!(DeleteError, expect_conversion_error, Conversion => ConversionError);
 
     impl_expect_ref!(UpdateError, expect_slot_error, SlotError => SlotError);
     impl_expect_ref!(UpdateError, expect_header_error, HeaderError => HeaderError);
@@ -0,0 +1,182 @@
+#[cfg(test)]
+mod tests {
+    use crate::PAGE_SIZE;
+    use crate::errors::move_row_error::MoveRowError;
+    use crate::errors::slot_error::SlotError;
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+
+    /// Heap page with a gap between its first two rows and a deleted slot:
+    ///
+    /// | Offset  | Row      |
+    /// |---------|----------|
+    /// | 96..116 | slot 0   |
+    /// | 116..166| gap      |
+    /// | 166..196| slot 1   |
+    /// | 196..206| slot 2   |
+    fn gapped_heap() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 20,
+            },
+            SlotValues {
+                offset: 166,
+                len: 30,
+            },
+            SlotValues {
+                offset: 196,
+                len: 10,
+            },
+            SlotValues { offset: 0, len: 0 },
+        ]);
+        page
+    }
+
+    #[test]
+    fn move_row_into_gap_updates_slot_and_bytes() {
+        let mut page = gapped_heap();
+
+        page.move_row(2, 126).unwrap();
+
+        page.assert_slot(2, 126, 10);
+        assert_eq!(page.row(2).unwrap(), &[3; 10]);
+        // The old bytes are left as they were
+        assert_eq!(page.data()[196..206], [3; 10]);
+        page.assert_header(&[&|h| assert_eq!(h.get_free_start().unwrap(), 206), &|h| {
+            assert_eq!(h.get_can_compact().unwrap(), 1)
+        }]);
+        page.assert_slot(0, 96, 20);
+        page.assert_slot(1, 166, 30);
+    }
+
+    #[test]
+    fn move_row_overlapping_its_own_bytes() {
+        let mut page = gapped_heap();
+
+        page.move_row(1, 146).unwrap();
+
+        page.assert_slot(1, 146, 30);
+        assert_eq!(page.row(1).unwrap(), &[2; 30]);
+    }
+
+    #[test]
+    fn move_row_past_free_start_raises_free_start() {
+        let mut page = gapped_heap();
+
+        page.move_row(0, 300).unwrap();
+
+        page.assert_slot(0, 300, 20);
+        assert_eq!(page.row(0).unwrap(), &[1; 20]);
+        page.assert_header(&[&|h| assert_eq!(h.get_free_start().unwrap(), 320)]);
+    }
+
+    #[test]
+    fn move_row_to_current_offset_is_a_no_op() {
+        let mut page = gapped_heap();
+        let before = *page.data();
+
+        page.move_row(1, 166).unwrap();
+
+        assert_eq!(page.data(), &before);
+    }
+
+    #[test]
+    fn move_row_destination_past_free_end_returns_out_of_bounds() {
+        let mut page = gapped_heap();
+        let before = *page.data();
+
+        let err = page.move_row(1, PAGE_SIZE - 40).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_move_row_error(),
+            MoveRowError::DestinationOutOfBounds {
+                offset: 4056,
+                length: 30,
+                min: 96,
+                max: 4080
+            }
+        ));
+        assert_eq!(page.data(), &before);
+    }
+
+    #[test]
+    fn move_row_destination_inside_header_returns_out_of_bounds() {
+        let mut page = gapped_heap();
+
+        let err = page.move_row(2, 50).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_move_row_error(),
+            MoveRowError::DestinationOutOfBounds {
+                offset: 50,
+                min: 96,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn move_row_destination_overflowing_usize_returns_out_of_bounds() {
+        let mut page = gapped_heap();
+        let before = *page.data();
+
+        let err = page.move_row(1, usize::MAX).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_move_row_error(),
+            MoveRowError::DestinationOutOfBounds {
+                offset: usize::MAX,
+                length: 30,
+                ..
+            }
+        ));
+        assert_eq!(page.data(), &before);
+    }
+
+    #[test]
+    fn move_row_destination_overlapping_other_row_returns_occupied() {
+        let mut page = gapped_heap();
+        let before = *page.data();
+
+        let err = page.move_row(2, 110).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_move_row_error(),
+            MoveRowError::DestinationOccupied {
+                offset: 110,
+                length: 10,
+                slot: 0
+            }
+        ));
+        assert_eq!(page.data(), &before);
+    }
+
+    #[test]
+    fn move_row_deleted_slot_returns_invalid_slot() {
+        let mut page = gapped_heap();
+
+        let err = page.move_row(3, 120).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_move_row_error(),
+            MoveRowError::SlotError(SlotError::InvalidSlot { slot_index: 3 })
+        ));
+    }
+
+    #[test]
+    fn move_row_out_of_range_slot_returns_error() {
+        let mut page = gapped_heap();
+
+        let err = page.move_row(4, 120).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_move_row_error(),
+            MoveRowError::SlotError(SlotError::IndexOutOfRange {
+                index: 4,
+                slot_count: 4
+            })
+        ));
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+
+    fn heap_with_rows() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 196,
+                len: 50,
+            },
+            SlotValues {
+                offset: 246,
+                len: 20,
+            },
+        ]);
+        page
+    }
+
+    #[test]
+    fn needs_compaction_empty_page_returns_false() {
+        let page = Page::test_create_empty_heap();
+
+        assert!(!page.needs_compaction().unwrap());
+    }
+
+    #[test]
+    fn needs_compaction_unfragmented_page_returns_false() {
+        let page = heap_with_rows();
+
+        assert!(!page.needs_compaction().unwrap());
+    }
+
+    #[test]
+    fn needs_compaction_after_fragmenting_delete_returns_true() {
+        let mut page = heap_with_rows();
+
+        page.delete_row(1, false).unwrap();
+
+        assert!(page.needs_compaction().unwrap());
+    }
+
+    #[test]
+    fn needs_compaction_after_deleting_last_physical_row_returns_false() {
+        let mut page = heap_with_rows();
+
+        // The freed space is merged back into the contiguous region, leaving no gap
+        page.delete_row(2, false).unwrap();
+
+        assert!(!page.needs_compaction().unwrap());
+    }
+
+    #[test]
+    fn needs_compaction_after_defragment_returns_false() {
+        let mut page = heap_with_rows();
+        page.delete_row(0, false).unwrap();
+
+        page.defragment().unwrap();
+
+        assert!(!page.needs_compaction().unwrap());
+    }
+
+    #[test]
+    fn needs_compaction_after_compact_into_returns_false() {
+        let mut page = heap_with_rows();
+        page.delete_row(0, false).unwrap();
+
+        page.compact_into(&mut vec![0u8; Page::max_row_size()])
+            .unwrap();
+
+        assert!(!page.needs_compaction().unwrap());
+    }
+
+    #[test]
+    fn needs_compaction_after_compacting_delete_returns_false() {
+        let mut page = heap_with_rows();
+
+        page.delete_row(1, true).unwrap();
+
+        assert!(!page.needs_compaction().unwrap());
+        page.assert_header(&[&|h| assert_eq!(h.get_can_compact().unwrap(), 0)]);
+    }
+
+    #[test]
+    fn needs_compaction_can_compact_flag_set_returns_true() {
+        let mut page = heap_with_rows();
+        page.header_mut().unwrap().set_can_compact(1).unwrap();
+
+        assert_eq!(page.fragmentation_ratio().unwrap(), 0.0);
+        assert!(page.needs_compaction().unwrap());
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+
+    fn heap_with_rows() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 196,
+                len: 100,
+            },
+        ]);
+        page
+    }
+
+    #[test]
+    fn display_insert_failure_names_page_operation_and_cause() {
+        let page = heap_with_rows();
+
+        let err = page.plan_insert(3800).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "page 1:1: insert failed: Unable to insert row of length 3800 in page with 3792 free bytes"
+        );
+    }
+
+    #[test]
+    fn display_row_too_large_names_largest_row() {
+        let page = heap_with_rows();
+
+        let err = page.plan_insert(4000).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "page 1:1: insert failed: Row of length 4000 can never fit in a page, which holds rows of at most 3996 bytes"
+        );
+    }
+
+    #[test]
+    fn display_delete_failure_includes_nested_cause() {
+        let mut page = heap_with_rows();
+        page.delete_row(0, false).unwrap();
+
+        let err = page.delete_row(0, false).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "page 1:1: delete failed: Error while accessing slot array: Attempted to access an invalid slot index: 0"
+        );
+    }
+
+    #[test]
+    fn display_read_failure_names_page_and_operation() {
+        let page = heap_with_rows();
+
+        let message = page.row(5).unwrap_err().to_string();
+
+        assert!(message.starts_with("page 1:1: read failed: "), "{message}");
+    }
+
+    #[test]
+    fn display_update_failure_names_page_and_operation() {
+        let mut page = heap_with_rows();
+
+        let message = page.update_row(0, vec![1; 4000]).unwrap_err().to_string();
+
+        assert!(
+            message.starts_with("page 1:1: update failed: "),
+            "{message}"
+        );
+        assert!(message.contains("4000"), "{message}");
+    }
+}
@@ -8,6 +8,7 @@ mod plan_insert_test {
     use crate::page_id::PageId;
     use crate::page_type::PageType;
     use crate::slot::SLOT_SIZE;
+    use crate::tests::SlotValues;
     use crate::{HEADER_SIZE, PAGE_SIZE};
 
     #[test]
@@ -79,4 +80,145 @@ mod plan_insert_test {
                 }) if row_len == 50 && page_free_space == 10)
         ))
     }
+
+    #[test]
+    fn plan_insert_row_len_near_u16_max_returns_row_too_large() {
+        let page = Page::test_create_empty_heap();
+
+        let err = page.plan_insert(u16::MAX as usize - 1).unwrap_err();
+
+        assert!(matches!(
+            err.source,
+            PageOpError::Insert(InsertError::RowTooLarge { row_len, .. }) if row_len == u16::MAX as usize - 1
+        ));
+    }
+
+    #[test]
+    fn plan_insert_page_sized_row_returns_row_too_large() {
+        let page = Page::test_create_empty_heap();
+
+        let err = page.plan_insert(PAGE_SIZE).unwrap_err();
+
+        assert!(matches!(
+            err.source,
+            PageOpError::Insert(InsertError::RowTooLarge { row_len, max })
+                if row_len == PAGE_SIZE && max == Page::max_row_size()
+        ));
+    }
+
+    #[test]
+    fn plan_insert_row_one_byte_over_max_returns_row_too_large() {
+        let page = Page::test_create_empty_heap();
+
+        let err = page.plan_insert(Page::max_row_size() + 1).unwrap_err();
+
+        assert!(matches!(
+            err.source,
+            PageOpError::Insert(InsertError::RowTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn plan_insert_max_row_size_on_partly_filled_page_returns_not_enough_space() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![SlotValues {
+            offset: HEADER_SIZE,
+            len: 10,
+        }]);
+
+        let err = page.plan_insert(Page::max_row_size()).unwrap_err();
+
+        assert!(matches!(
+            err.source,
+            PageOpError::Insert(InsertError::NotEnoughSpace { row_len, .. })
+                if row_len == Page::max_row_size()
+        ));
+    }
+
+    /// Rows at 96 (1000 bytes), 1096 (1000 bytes) and 2096 (1500 bytes), with the middle one deleted.
+    /// Leaves a 1000-byte gap and 488 contiguous free bytes, so two thirds of the free space are fragmented.
+    fn moderately_fragmented_heap() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 1000,
+            },
+            SlotValues {
+                offset: 1096,
+                len: 1000,
+            },
+            SlotValues {
+                offset: 2096,
+                len: 1500,
+            },
+        ]);
+        page.delete_row(1, false).unwrap();
+        page
+    }
+
+    #[test]
+    fn fragmentation_ratio_empty_page_is_zero() {
+        let page = Page::test_create_empty_heap();
+
+        assert_eq!(page.fragmentation_ratio().unwrap(), 0.0);
+    }
+
+    #[test]
+    fn fragmentation_ratio_counts_gaps_between_rows() {
+        let page = moderately_fragmented_heap();
+
+        assert_eq!(page.fragmentation_ratio().unwrap(), 1000.0 / 1488.0);
+    }
+
+    #[test]
+    fn plan_insert_high_compaction_threshold_uses_gap() {
+        let page = moderately_fragmented_heap();
+
+        let plan = page
+            .plan_insert_with_compaction_threshold(600, 0.9)
+            .unwrap();
+
+        assert!(matches!(plan.slot, InsertionSlot::Reuse(1)));
+        assert!(matches!(plan.offset, InsertionOffset::Exact(1096)));
+    }
+
+    #[test]
+    fn plan_insert_default_compaction_threshold_uses_gap() {
+        let page = moderately_fragmented_heap();
+
+        let plan = page.plan_insert(600).unwrap();
+
+        assert!(matches!(plan.offset, InsertionOffset::Exact(1096)));
+    }
+
+    #[test]
+    fn plan_insert_low_compaction_threshold_compacts() {
+        let mut page = moderately_fragmented_heap();
+
+        let plan = page
+            .plan_insert_with_compaction_threshold(600, 0.5)
+            .unwrap();
+        assert!(matches!(
+            plan.offset,
+            InsertionOffset::AfterCompactionFreeStart
+        ));
+        page.insert_heap(plan, vec![9; 600]).unwrap();
+
+        // Row 2 was moved right after row 0, and the new row was appended after it
+        page.assert_slot(2, 1096, 1500);
+        page.assert_slot(1, 2596, 600);
+        assert_eq!(page.fragmentation_ratio().unwrap(), 0.0);
+    }
+
+    #[test]
+    fn plan_insert_low_compaction_threshold_fast_path_does_not_compact() {
+        let page = moderately_fragmented_heap();
+
+        let plan = page
+            .plan_insert_with_compaction_threshold(100, 0.0)
+            .unwrap();
+
+        assert!(matches!(plan.offset, InsertionOffset::Exact(3596)));
+    }
 }
@@ -10,6 +10,8 @@ mod read_row_test {
     use crate::page_id::PageId;
     use crate::page_type::PageType;
     use crate::slot::SlotMut;
+    use crate::tests::SlotValues;
+    use crate::tests::tests_error_helpers;
 
     #[test]
     fn read_row_out_of_bounds() {
@@ -75,4 +77,126 @@ mod read_row_test {
         assert_eq!([5u8; 10], *row_internal);
         assert_eq!([5u8; 10], *row);
     }
+
+    #[test]
+    fn read_row_slot_pointing_past_page_end_returns_error() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_create_slots(vec![SlotValues {
+            offset: PAGE_SIZE - 10,
+            len: 20,
+        }]);
+
+        let err = page.row(0).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_read_row_error(),
+            ReadRowError::RowOutOfBounds {
+                offset: 4086,
+                length: 20
+            }
+        ));
+    }
+
+    #[test]
+    fn row_mut_slot_pointing_past_page_end_returns_error() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_create_slots(vec![SlotValues {
+            offset: u16::MAX as usize,
+            len: u16::MAX as usize,
+        }]);
+
+        let err = page.row_mut(0).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_read_row_error(),
+            ReadRowError::RowOutOfBounds {
+                offset: 65535,
+                length: 65535
+            }
+        ));
+    }
+
+    /// Heap page with three live rows, each filled with its slot index + 1, and a deleted slot 3.
+    fn page_with_rows() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 10,
+            },
+            SlotValues {
+                offset: 106,
+                len: 20,
+            },
+            SlotValues {
+                offset: 126,
+                len: 5,
+            },
+            SlotValues { offset: 0, len: 0 },
+        ]);
+        page
+    }
+
+    #[test]
+    fn read_row_deleted_slot_returns_invalid_slot() {
+        let page = page_with_rows();
+
+        let err = page.row(3).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_read_row_error().expect_slot_error(),
+            SlotError::InvalidSlot { slot_index: 3 }
+        ));
+    }
+
+    #[test]
+    fn rows_returns_rows_in_requested_order() {
+        let page = page_with_rows();
+
+        let rows = page.rows(&[2, 0, 1, 0]).unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                &[3u8; 5][..],
+                &[1u8; 10][..],
+                &[2u8; 20][..],
+                &[1u8; 10][..]
+            ]
+        );
+    }
+
+    #[test]
+    fn rows_no_indices_returns_no_rows() {
+        let page = page_with_rows();
+
+        assert!(page.rows(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rows_out_of_range_index_fails_whole_call() {
+        let page = page_with_rows();
+
+        let err = page.rows(&[0, 1, 7, 2]).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_read_row_error().expect_slot_error(),
+            SlotError::IndexOutOfRange {
+                index: 7,
+                slot_count: 4
+            }
+        ));
+    }
+
+    #[test]
+    fn rows_deleted_slot_fails_whole_call() {
+        let page = page_with_rows();
+
+        let err = page.rows(&[0, 3]).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_read_row_error().expect_slot_error(),
+            SlotError::InvalidSlot { slot_index: 3 }
+        ));
+    }
 }
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use crate::compaction::CompactionOrder;
+    use crate::page::api::Page;
+    use crate::page_id::PageId;
+    use crate::page_type::PageType;
+
+    fn stored_free_space(page: &Page) -> u16 {
+        page.header_snapshot().unwrap().free_space
+    }
+
+    /// Asserts that the free space maintained by the operations so far matches the recomputed one.
+    fn assert_no_drift(page: &mut Page, step: &str) {
+        let stored = stored_free_space(page);
+        assert_eq!(page.recompute_free_space().unwrap(), stored, "{step}");
+        assert_eq!(stored_free_space(page), stored, "{step}");
+    }
+
+    fn insert(page: &mut Page, len: usize, value: u8) -> u32 {
+        let plan = page.plan_insert(len).unwrap();
+        page.insert_heap(plan, vec![value; len]).unwrap()
+    }
+
+    #[test]
+    fn recompute_free_space_empty_page_matches_initial_value() {
+        let mut page = Page::test_create_empty_heap();
+
+        assert_eq!(page.recompute_free_space().unwrap(), 4000);
+    }
+
+    #[test]
+    fn recompute_free_space_empty_index_page_skips_index_header() {
+        let mut page = Page::new_empty(PageId::new(1, 1), PageType::IndexLeaf).unwrap();
+
+        assert_eq!(page.recompute_free_space().unwrap(), 3992);
+        assert_no_drift(&mut page, "empty index page");
+    }
+
+    #[test]
+    fn recompute_free_space_matches_after_mixed_heap_operations() {
+        let mut page = Page::test_create_empty_heap();
+
+        let first = insert(&mut page, 100, 1);
+        let second = insert(&mut page, 50, 2);
+        let third = insert(&mut page, 200, 3);
+        assert_no_drift(&mut page, "inserts");
+
+        page.update_row(second as usize, vec![4; 80]).unwrap();
+        assert_no_drift(&mut page, "growing update");
+
+        page.update_row(third as usize, vec![5; 20]).unwrap();
+        assert_no_drift(&mut page, "shrinking update");
+
+        page.replace_row(first as usize, vec![6; 60]).unwrap();
+        assert_no_drift(&mut page, "replace");
+
+        page.delete_row(first as usize, false).unwrap();
+        assert_no_drift(&mut page, "delete");
+
+        // Reuses the slot of the deleted row
+        insert(&mut page, 30, 7);
+        assert_no_drift(&mut page, "insert into freed slot");
+
+        page.delete_row(third as usize, true).unwrap();
+        assert_no_drift(&mut page, "delete with compaction");
+
+        page.compact(CompactionOrder::PhysicalOrder).unwrap();
+        assert_no_drift(&mut page, "compaction");
+    }
+
+    #[test]
+    fn recompute_free_space_matches_after_mixed_index_operations() {
+        let mut page = Page::new_empty(PageId::new(1, 1), PageType::IndexLeaf).unwrap();
+
+        page.insert_at(0, vec![1; 10]).unwrap();
+        page.insert_at(1, vec![2; 20]).unwrap();
+        page.insert_at(2, vec![3; 30]).unwrap();
+        assert_no_drift(&mut page, "inserts");
+
+        page.delete_row(1, false).unwrap();
+        assert_no_drift(&mut page, "delete");
+
+        page.insert_at(1, vec![5; 15]).unwrap();
+        assert_no_drift(&mut page, "insert into freed slot");
+
+        page.update_row(0, vec![4; 40]).unwrap();
+        assert_no_drift(&mut page, "update");
+    }
+
+    #[test]
+    fn recompute_free_space_repairs_drifted_value() {
+        let mut page = Page::test_create_empty_heap();
+        insert(&mut page, 100, 1);
+        insert(&mut page, 50, 2);
+        let expected = stored_free_space(&page);
+        page.header_mut().unwrap().set_free_space(12).unwrap();
+
+        assert_eq!(page.recompute_free_space().unwrap(), expected);
+
+        assert_eq!(stored_free_space(&page), expected);
+        // 4000 bytes of data region, minus two rows and their slots
+        assert_eq!(expected, 4000 - 150 - 8);
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::slot_error::SlotError;
+    use crate::errors::update_error::UpdateError;
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+    use crate::tests::tests_error_helpers;
+
+    /// Heap page with 184 free bytes, all of them contiguous:
+    /// four rows of 1000, 1000, 1000 and 800 bytes, filled with `1`, `2`, `3` and `4`.
+    fn nearly_full_heap() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 1000,
+            },
+            SlotValues {
+                offset: 1096,
+                len: 1000,
+            },
+            SlotValues {
+                offset: 2096,
+                len: 1000,
+            },
+            SlotValues {
+                offset: 3096,
+                len: 800,
+            },
+        ]);
+        page
+    }
+
+    #[test]
+    fn replace_row_relocates_to_free_start() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 196,
+                len: 50,
+            },
+        ]);
+
+        page.replace_row(0, vec![9; 150]).unwrap();
+
+        page.assert_slot(0, 246, 150);
+        page.assert_row_values(246, 150, 9);
+        page.assert_slot(1, 196, 50);
+        page.assert_row_values(196, 50, 2);
+        page.assert_header(&[
+            &|h| assert_eq!(h.get_free_start().unwrap(), 396),
+            &|h| assert_eq!(h.get_free_space().unwrap(), 4000 - 8 - 150 - 50),
+            &|h| assert_eq!(h.get_can_compact().unwrap(), 1),
+            &|h| assert_eq!(h.get_live_row_count().unwrap(), 2),
+        ]);
+    }
+
+    #[test]
+    fn replace_row_smaller_row_is_relocated_too() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![SlotValues {
+            offset: 96,
+            len: 100,
+        }]);
+
+        page.replace_row(0, vec![9; 10]).unwrap();
+
+        page.assert_slot(0, 196, 10);
+        assert_eq!(page.row(0).unwrap(), &[9; 10]);
+        // The old row is left behind as a gap
+        assert!(page.needs_compaction().unwrap());
+    }
+
+    #[test]
+    fn replace_row_compacts_when_no_free_region_fits() {
+        let mut page = nearly_full_heap();
+        // Leaves a 1000 bytes gap, which cannot hold the new row on its own
+        page.delete_row(1, false).unwrap();
+
+        page.replace_row(3, vec![9; 1100]).unwrap();
+
+        page.assert_slot(0, 96, 1000);
+        page.assert_slot(2, 1096, 1000);
+        // The old row is still live during the compaction, so the new one lands after it
+        page.assert_slot(3, 2896, 1100);
+        page.assert_row_values(96, 1000, 1);
+        page.assert_row_values(1096, 1000, 3);
+        page.assert_row_values(2896, 1100, 9);
+        page.assert_header(&[&|h| assert_eq!(h.get_free_start().unwrap(), 3996), &|h| {
+            assert_eq!(h.get_free_space().unwrap(), 884)
+        }]);
+    }
+
+    #[test]
+    fn replace_row_not_enough_space_leaves_page_unchanged() {
+        let mut page = nearly_full_heap();
+        let before = page.data().to_vec();
+
+        // Would fit once the old row is gone, which is enough for `update_row` but not here
+        let err = page.replace_row(3, vec![9; 900]).unwrap_err();
+
+        let UpdateError::NotEnoughSpace {
+            row_len,
+            page_free_space,
+        } = err.source.expect_update_error()
+        else {
+            panic!("expected NotEnoughSpace");
+        };
+        assert_eq!(*row_len, 900);
+        assert_eq!(*page_free_space, 184);
+        assert_eq!(page.data().to_vec(), before);
+    }
+
+    #[test]
+    fn replace_row_invalid_slot_leaves_page_unchanged() {
+        let mut page = nearly_full_heap();
+        page.delete_row(1, false).unwrap();
+        let before = page.data().to_vec();
+
+        let err = page.replace_row(1, vec![9; 10]).unwrap_err();
+
+        let slot_error = err.source.expect_update_error().expect_slot_error();
+        assert!(matches!(
+            slot_error,
+            SlotError::InvalidSlot { slot_index: 1 }
+        ));
+        assert_eq!(page.data().to_vec(), before);
+    }
+}
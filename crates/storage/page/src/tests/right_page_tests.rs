@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::header_error::HeaderError;
+    use crate::page::api::Page;
+    use crate::page_id::PageId;
+    use crate::page_type::PageType;
+    use crate::tests::tests_error_helpers;
+
+    #[test]
+    fn right_page_new_page_has_no_sibling() {
+        let page = Page::test_create_empty_heap();
+
+        assert_eq!(page.right_page().unwrap(), 0);
+    }
+
+    #[test]
+    fn set_right_page_is_read_back_and_kept_by_bytes() {
+        let mut page = Page::test_create_empty_heap();
+
+        page.set_right_page(42).unwrap();
+
+        assert_eq!(page.right_page().unwrap(), 42);
+        let copy = Page::try_from_bytes(Box::new(*page.data()), page.page_id()).unwrap();
+        assert_eq!(copy.right_page().unwrap(), 42);
+    }
+
+    #[test]
+    fn set_right_page_zero_unlinks_page() {
+        let mut page = Page::test_create_empty_heap();
+        page.set_right_page(42).unwrap();
+
+        page.set_right_page(0).unwrap();
+
+        assert_eq!(page.right_page().unwrap(), 0);
+    }
+
+    #[test]
+    fn page_type_returns_initialized_type() {
+        for page_type in [PageType::Unsorted, PageType::IndexLeaf, PageType::Overflow] {
+            let page_id = PageId::new(1, 3);
+            let mut page = Page::new_zeroed(page_id);
+            page.initialize(page_id, page_type).unwrap();
+
+            assert_eq!(page.page_type().unwrap(), page_type);
+        }
+    }
+
+    #[test]
+    fn page_type_unknown_type_error_returned() {
+        let page = Page::new_zeroed(PageId::new(1, 3));
+
+        let err = page.page_type().unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_header_error(),
+            HeaderError::UnknownPageType { page_type: 0 }
+        ));
+    }
+}
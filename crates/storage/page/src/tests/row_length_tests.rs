@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::slot_error::SlotError;
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+
+    fn page_with_rows() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 10,
+            },
+            SlotValues {
+                offset: 106,
+                len: 250,
+            },
+            SlotValues {
+                offset: 356,
+                len: 1,
+            },
+        ]);
+        page
+    }
+
+    #[test]
+    fn row_length_valid_slots_match_row_lengths() {
+        let page = page_with_rows();
+
+        for slot_index in 0..3 {
+            assert_eq!(
+                page.row_length(slot_index).unwrap() as usize,
+                page.row(slot_index).unwrap().len()
+            );
+        }
+        assert_eq!(page.row_length(1).unwrap(), 250);
+    }
+
+    #[test]
+    fn row_length_zero_length_row_is_zero() {
+        let mut page = Page::test_create_empty_heap();
+        let plan = page.plan_insert(0).unwrap();
+        page.insert_heap(plan, Vec::new()).unwrap();
+
+        assert_eq!(page.row_length(0).unwrap(), 0);
+    }
+
+    #[test]
+    fn row_length_deleted_slot_returns_invalid_slot() {
+        let mut page = page_with_rows();
+        page.delete_row(1, false).unwrap();
+
+        let err = page.row_length(1).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_slot_error(),
+            SlotError::InvalidSlot { slot_index: 1 }
+        ));
+    }
+
+    #[test]
+    fn row_length_slot_index_equal_to_slot_count_returns_index_out_of_range() {
+        let page = page_with_rows();
+
+        let err = page.row_length(3).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_slot_error(),
+            SlotError::IndexOutOfRange {
+                index: 3,
+                slot_count: 3
+            }
+        ));
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::slot_error::SlotError;
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+    use crate::tests::tests_error_helpers;
+
+    fn heap_with_rows() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 10,
+            },
+            SlotValues {
+                offset: 106,
+                len: 20,
+            },
+        ]);
+        page
+    }
+
+    #[test]
+    fn row_mut_patch_bytes_in_place() {
+        let mut page = heap_with_rows();
+
+        let row = page.row_mut(1).unwrap();
+        assert_eq!(row.len(), 20);
+        row[0] = 0xAA;
+        row[19] = 0xBB;
+
+        let row = page.row(1).unwrap();
+        assert_eq!(row[0], 0xAA);
+        assert_eq!(row[1..19], [2u8; 18]);
+        assert_eq!(row[19], 0xBB);
+        // Neighbouring row and slot are untouched
+        page.assert_row_values(96, 10, 1);
+        page.assert_slot(1, 106, 20);
+    }
+
+    #[test]
+    fn row_mut_out_of_bounds_slot_index_returns_error() {
+        let mut page = heap_with_rows();
+
+        let err = page.row_mut(5).unwrap_err();
+
+        let slot_error = err.source.expect_read_row_error().expect_slot_error();
+        assert!(matches!(
+            slot_error,
+            SlotError::IndexOutOfRange { index: 5, .. }
+        ));
+    }
+
+    #[test]
+    fn row_mut_deleted_row_returns_invalid_slot_error() {
+        let mut page = heap_with_rows();
+        page.delete_row(0, false).unwrap();
+
+        let err = page.row_mut(0).unwrap_err();
+
+        let slot_error = err.source.expect_read_row_error().expect_slot_error();
+        assert!(matches!(
+            slot_error,
+            SlotError::InvalidSlot { slot_index: 0 }
+        ));
+    }
+}
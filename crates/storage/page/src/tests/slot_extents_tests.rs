@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::page::api::Page;
+    use crate::page::extents::SlotExtent;
+    use crate::tests::SlotValues;
+
+    #[test]
+    fn slot_extents_empty_page_returns_no_entries() {
+        let page = Page::test_create_empty_heap();
+
+        assert!(page.slot_extents().unwrap().is_empty());
+    }
+
+    #[test]
+    fn slot_extents_returns_entries_in_slot_index_order() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 196,
+                len: 30,
+            },
+            SlotValues { offset: 0, len: 0 },
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+        ]);
+
+        let extents = page.slot_extents().unwrap();
+
+        assert_eq!(
+            extents,
+            vec![
+                SlotExtent {
+                    index: 0,
+                    offset: 196,
+                    length: 30,
+                    valid: true,
+                },
+                SlotExtent {
+                    index: 1,
+                    offset: 0,
+                    length: 0,
+                    valid: false,
+                },
+                SlotExtent {
+                    index: 2,
+                    offset: 96,
+                    length: 100,
+                    valid: true,
+                },
+            ]
+        );
+        assert_eq!(extents[0].end(), 226);
+    }
+
+    #[test]
+    fn slot_extents_reflects_deleted_rows() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 10,
+            },
+            SlotValues {
+                offset: 106,
+                len: 20,
+            },
+        ]);
+
+        page.delete_row(0, false).unwrap();
+
+        let validity: Vec<bool> = page
+            .slot_extents()
+            .unwrap()
+            .iter()
+            .map(|e| e.valid)
+            .collect();
+        assert_eq!(validity, vec![false, true]);
+    }
+}
@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::split_error::SplitError;
+    use crate::page::api::Page;
+    use crate::page_id::PageId;
+    use crate::page_type::PageType;
+    use crate::slot::SLOT_SIZE;
+    use crate::tests::SlotValues;
+    use crate::tests::tests_error_helpers;
+    use crate::{HEADER_SIZE, PAGE_SIZE};
+
+    /// Four rows whose physical order (slot 1, 0, 2, 3) differs from their slot order.
+    fn heap_with_four_rows() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 196,
+                len: 30,
+            },
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 226,
+                len: 40,
+            },
+            SlotValues {
+                offset: 266,
+                len: 20,
+            },
+        ]);
+        page
+    }
+
+    fn empty_sibling() -> Page {
+        Page::new_empty(PageId::new(1, 2), PageType::Unsorted).unwrap()
+    }
+
+    #[test]
+    fn split_into_four_rows_leaves_two_in_each() {
+        let mut page = heap_with_four_rows();
+        let mut dest = empty_sibling();
+
+        page.split_into(&mut dest).unwrap();
+
+        assert_eq!(page.live_row_count().unwrap(), 2);
+        assert_eq!(dest.live_row_count().unwrap(), 2);
+        assert_eq!(dest.slot_count().unwrap(), 2);
+    }
+
+    #[test]
+    fn split_into_moves_upper_half_by_offset_and_preserves_bytes() {
+        let mut page = heap_with_four_rows();
+        let mut dest = empty_sibling();
+
+        page.split_into(&mut dest).unwrap();
+
+        // Rows at the two highest offsets moved, in offset order
+        assert_eq!(dest.row(0).unwrap(), &[3u8; 40]);
+        assert_eq!(dest.row(1).unwrap(), &[4u8; 20]);
+        assert_eq!(page.row(0).unwrap(), &[1u8; 30]);
+        assert_eq!(page.row(1).unwrap(), &[2u8; 100]);
+        page.assert_slot(2, 0, 0);
+        page.assert_slot(3, 0, 0);
+    }
+
+    #[test]
+    fn split_into_compacts_source_and_accounts_free_space() {
+        let mut page = heap_with_four_rows();
+        let mut dest = empty_sibling();
+
+        page.split_into(&mut dest).unwrap();
+
+        assert_eq!(page.fragmentation_ratio().unwrap(), 0.0);
+        page.assert_slot(0, HEADER_SIZE, 30);
+        page.assert_slot(1, HEADER_SIZE + 30, 100);
+        page.assert_header(&[
+            &|h| assert_eq!(h.get_free_start().unwrap(), (HEADER_SIZE + 130) as u16),
+            &|h| {
+                assert_eq!(
+                    h.get_free_space().unwrap(),
+                    (PAGE_SIZE - HEADER_SIZE - 130 - 4 * SLOT_SIZE) as u16
+                )
+            },
+            &|h| assert_eq!(h.get_can_compact().unwrap(), 0),
+        ]);
+        dest.assert_header(&[&|h| {
+            assert_eq!(
+                h.get_free_space().unwrap(),
+                (PAGE_SIZE - HEADER_SIZE - 60 - 2 * SLOT_SIZE) as u16
+            )
+        }]);
+    }
+
+    #[test]
+    fn split_into_links_siblings() {
+        let mut page = heap_with_four_rows();
+        page.header_mut().unwrap().set_left_page(7).unwrap();
+        page.header_mut().unwrap().set_right_page(5).unwrap();
+        let mut dest = empty_sibling();
+
+        page.split_into(&mut dest).unwrap();
+
+        page.assert_header(&[&|h| assert_eq!(h.get_left_page().unwrap(), 7), &|h| {
+            assert_eq!(h.get_right_page().unwrap(), 2)
+        }]);
+        dest.assert_header(&[&|h| assert_eq!(h.get_left_page().unwrap(), 1), &|h| {
+            assert_eq!(h.get_right_page().unwrap(), 5)
+        }]);
+    }
+
+    #[test]
+    fn split_into_destination_too_small_nothing_modified() {
+        let mut page = heap_with_four_rows();
+        let mut dest = empty_sibling();
+        let filler = vec![9u8; PAGE_SIZE - HEADER_SIZE - SLOT_SIZE - 50];
+        let plan = dest.plan_insert(filler.len()).unwrap();
+        dest.insert_heap(plan, filler).unwrap();
+        let (page_before, dest_before) = (page.data().to_vec(), dest.data().to_vec());
+
+        let err = page.split_into(&mut dest).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_split_error(),
+            SplitError::DestinationTooSmall {
+                required: 68,
+                available: 50
+            }
+        ));
+        assert_eq!(page.data().to_vec(), page_before);
+        assert_eq!(dest.data().to_vec(), dest_before);
+    }
+
+    #[test]
+    fn split_into_empty_page_only_links_siblings() {
+        let mut page = Page::test_create_empty_heap();
+        let mut dest = empty_sibling();
+
+        page.split_into(&mut dest).unwrap();
+
+        assert_eq!(dest.slot_count().unwrap(), 0);
+        page.assert_header(&[&|h| assert_eq!(h.get_right_page().unwrap(), 2)]);
+        dest.assert_header(&[&|h| assert_eq!(h.get_left_page().unwrap(), 1)]);
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PAGE_SIZE;
+    use crate::header::CHECKSUM;
+    use crate::page::api::Page;
+    use crate::tests::SlotValues;
+
+    fn heap_with_rows() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues { offset: 0, len: 0 },
+            SlotValues {
+                offset: 196,
+                len: 50,
+            },
+        ]);
+        page
+    }
+
+    #[test]
+    fn to_owned_bytes_round_trips_through_try_from_bytes() {
+        let page = heap_with_rows();
+
+        let bytes = page.to_owned_bytes().unwrap();
+        let copy = Page::try_from_bytes(bytes, page.page_id()).unwrap();
+
+        assert_eq!(copy.page_id(), page.page_id());
+        assert_eq!(copy.slot_count().unwrap(), 3);
+        for slot in 0..3 {
+            assert_eq!(copy.row(slot).ok(), page.row(slot).ok());
+        }
+        assert_eq!(copy.compute_checksum(), page.compute_checksum());
+    }
+
+    #[test]
+    fn to_owned_bytes_stale_checksum_is_refreshed_in_copy_only() {
+        let mut page = heap_with_rows();
+        page.update_checksum().unwrap();
+        page.row_mut(0).unwrap()[0] = 0xAB;
+        let stale = page.data().to_vec();
+
+        let bytes = page.to_owned_bytes().unwrap();
+
+        assert!(page.verify_checksum().is_err());
+        assert_eq!(page.data().to_vec(), stale);
+        let copy = Page::try_from_bytes(bytes, page.page_id()).unwrap();
+        assert_eq!(copy.row(0).unwrap()[0], 0xAB);
+    }
+
+    #[test]
+    fn to_owned_bytes_differs_from_page_only_in_checksum() {
+        let page = heap_with_rows();
+
+        let bytes = page.to_owned_bytes().unwrap();
+
+        let differing: Vec<usize> = (0..PAGE_SIZE)
+            .filter(|i| bytes[*i] != page.data()[*i])
+            .collect();
+        assert!(
+            differing
+                .iter()
+                .all(|i| (CHECKSUM..CHECKSUM + 4).contains(i))
+        );
+    }
+}
@@ -31,10 +31,10 @@ mod tests {
 
         let slot_error = result.source.expect_update_error().expect_slot_error();
 
-        let SlotError::InvalidSlot { slot_index } = slot_error else {
-            panic!("expected InvalidSlot, got {slot_error:?}")
+        let SlotError::IndexOutOfRange { index, slot_count } = slot_error else {
+            panic!("expected IndexOutOfRange, got {slot_error:?}")
         };
-        assert_eq!(*slot_index, 2);
+        assert_eq!((*index, *slot_count), (2, 2));
     }
 
     #[test]
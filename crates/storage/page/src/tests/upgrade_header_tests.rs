@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PAGE_SIZE;
+    use crate::page::api::Page;
+    use crate::page_id::PageId;
+    use crate::tests::SlotValues;
+
+    /// Builds a page as written before `checksum` and `live_row_count` existed: two live rows and a
+    /// deleted slot, with zeros in both fields.
+    fn old_format_page() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 10,
+            },
+            SlotValues { offset: 0, len: 0 },
+            SlotValues {
+                offset: 106,
+                len: 20,
+            },
+        ]);
+        page.header_mut().unwrap().set_live_row_count(0).unwrap();
+        page.header_mut().unwrap().set_checksum(0).unwrap();
+        page
+    }
+
+    #[test]
+    fn upgrade_header_old_format_page_fills_in_new_fields() {
+        let mut page = old_format_page();
+
+        assert!(page.upgrade_header().unwrap());
+
+        assert_eq!(page.live_row_count().unwrap(), 2);
+        assert_ne!(page.header_ref().unwrap().get_checksum().unwrap(), 0);
+        page.verify_checksum().unwrap();
+    }
+
+    #[test]
+    fn upgrade_header_upgraded_page_loads_and_verifies() {
+        let mut page = old_format_page();
+        page.upgrade_header().unwrap();
+        let bytes: Box<[u8; PAGE_SIZE]> = Box::new(*page.data());
+
+        let loaded = Page::try_from_bytes(bytes, page.page_id()).unwrap();
+
+        assert_eq!(loaded.live_row_count().unwrap(), 2);
+        assert_eq!(loaded.row(2).unwrap(), &[3; 20]);
+    }
+
+    #[test]
+    fn upgrade_header_already_upgraded_page_is_untouched() {
+        let mut page = old_format_page();
+        page.upgrade_header().unwrap();
+        let before = *page.data();
+
+        assert!(!page.upgrade_header().unwrap());
+
+        assert_eq!(page.data(), &before);
+    }
+
+    #[test]
+    fn upgrade_header_invalid_header_is_untouched() {
+        let mut page = old_format_page();
+        page.header_mut().unwrap().set_free_end(10).unwrap();
+        let before = *page.data();
+
+        assert!(!page.upgrade_header().unwrap());
+
+        assert_eq!(page.data(), &before);
+    }
+
+    #[test]
+    fn upgrade_header_zeroed_page_is_untouched() {
+        let mut page = Page::new_zeroed(PageId::new(1, 1));
+
+        assert!(!page.upgrade_header().unwrap());
+
+        assert!(page.data().iter().all(|b| *b == 0));
+    }
+}
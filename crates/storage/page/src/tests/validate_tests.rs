@@ -0,0 +1,251 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PAGE_SIZE;
+    use crate::errors::validation_error::ValidationError;
+    use crate::header::CHECKSUM;
+    use crate::page::api::Page;
+    use crate::page_id::PageId;
+    use crate::tests::SlotValues;
+    use crate::tests::tests_error_helpers;
+
+    fn heap_with_rows() -> Page {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 100,
+            },
+            SlotValues {
+                offset: 196,
+                len: 50,
+            },
+        ]);
+        page
+    }
+
+    fn into_bytes(page: Page) -> (Box<[u8; PAGE_SIZE]>, PageId) {
+        (page.data, page.page_id)
+    }
+
+    #[test]
+    fn try_from_bytes_valid_page_with_checksum() {
+        let mut page = heap_with_rows();
+        page.update_checksum().unwrap();
+        let (bytes, page_id) = into_bytes(page);
+
+        let page = Page::try_from_bytes(bytes, page_id).unwrap();
+
+        assert_eq!(page.page_id(), page_id);
+        assert_eq!(page.row(1).unwrap(), &[2u8; 50]);
+    }
+
+    #[test]
+    fn try_from_bytes_without_checksum_skips_verification() {
+        let mut page = heap_with_rows();
+        // Corrupt row data - without a stored checksum this cannot be detected
+        page.data_mut()[100] = 0xFF;
+        let (bytes, page_id) = into_bytes(page);
+
+        assert!(Page::try_from_bytes(bytes, page_id).is_ok());
+    }
+
+    #[test]
+    fn try_from_bytes_corrupted_row_data_returns_checksum_mismatch() {
+        let mut page = heap_with_rows();
+        page.update_checksum().unwrap();
+        page.data_mut()[100] ^= 0xFF;
+        let (bytes, page_id) = into_bytes(page);
+
+        let err = Page::try_from_bytes(bytes, page_id).unwrap_err();
+
+        assert_eq!(err.page_id, page_id);
+        assert!(matches!(
+            err.source.expect_validation_error(),
+            ValidationError::ChecksumMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn verify_checksum_ignores_checksum_field_itself() {
+        let mut page = heap_with_rows();
+        page.update_checksum().unwrap();
+        let first = page.header_ref().unwrap().get_checksum().unwrap();
+
+        // Computing again over a page which already holds a checksum must yield the same value
+        page.update_checksum().unwrap();
+
+        assert_eq!(page.header_ref().unwrap().get_checksum().unwrap(), first);
+        assert!(page.verify_checksum().is_ok());
+    }
+
+    #[test]
+    fn try_from_bytes_unknown_page_type() {
+        let mut page = heap_with_rows();
+        page.header_mut().unwrap().set_page_type(99).unwrap();
+        let (bytes, page_id) = into_bytes(page);
+
+        let err = Page::try_from_bytes(bytes, page_id).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_validation_error(),
+            ValidationError::UnknownPageType { page_type: 99 }
+        ));
+    }
+
+    #[test]
+    fn try_from_bytes_free_start_beyond_free_end() {
+        let mut page = heap_with_rows();
+        page.header_mut().unwrap().set_free_start(4093).unwrap();
+        let (bytes, page_id) = into_bytes(page);
+
+        let err = Page::try_from_bytes(bytes, page_id).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_validation_error(),
+            ValidationError::FieldOutOfRange {
+                field: "free_start",
+                value: 4093,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn try_from_bytes_free_end_not_matching_slot_count() {
+        let mut page = heap_with_rows();
+        page.header_mut().unwrap().set_slot_count(3).unwrap();
+        let (bytes, page_id) = into_bytes(page);
+
+        let err = Page::try_from_bytes(bytes, page_id).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_validation_error(),
+            ValidationError::FieldOutOfRange {
+                field: "free_end",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn try_from_bytes_page_number_not_matching_page_id() {
+        let page = heap_with_rows();
+        let (bytes, page_id) = into_bytes(page);
+
+        let err = Page::try_from_bytes(bytes, PageId::new(page_id.file_id, 7)).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_validation_error(),
+            ValidationError::FieldOutOfRange {
+                field: "page_number",
+                value: 1,
+                min: 7,
+                max: 7,
+            }
+        ));
+    }
+
+    #[test]
+    fn try_from_bytes_can_compact_out_of_range() {
+        let mut page = heap_with_rows();
+        page.header_mut().unwrap().set_can_compact(2).unwrap();
+        let (bytes, page_id) = into_bytes(page);
+
+        let err = Page::try_from_bytes(bytes, page_id).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_validation_error(),
+            ValidationError::FieldOutOfRange {
+                field: "can_compact",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn try_from_bytes_live_row_count_above_slot_count() {
+        let mut page = heap_with_rows();
+        page.header_mut().unwrap().set_live_row_count(3).unwrap();
+        let (bytes, page_id) = into_bytes(page);
+
+        let err = Page::try_from_bytes(bytes, page_id).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_validation_error(),
+            ValidationError::FieldOutOfRange {
+                field: "live_row_count",
+                value: 3,
+                min: 0,
+                max: 2,
+            }
+        ));
+    }
+
+    #[test]
+    fn try_from_bytes_overlapping_rows() {
+        let mut page = heap_with_rows();
+        page.slot_array_mut().unwrap().set_slot(1, 150, 50).unwrap();
+        let (bytes, page_id) = into_bytes(page);
+
+        let err = Page::try_from_bytes(bytes, page_id).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_validation_error(),
+            ValidationError::OverlappingRows {
+                first: 0,
+                second: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn try_from_bytes_row_overlapping_slot_array() {
+        let mut page = heap_with_rows();
+        page.slot_array_mut()
+            .unwrap()
+            .set_slot(1, PAGE_SIZE - 10, 10)
+            .unwrap();
+        let (bytes, page_id) = into_bytes(page);
+
+        let err = Page::try_from_bytes(bytes, page_id).unwrap_err();
+
+        assert!(matches!(
+            err.source.expect_validation_error(),
+            ValidationError::RowOutOfBounds { slot: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn validate_page_with_deleted_and_zero_length_rows() {
+        let mut page = heap_with_rows();
+        page.delete_row(0, false).unwrap();
+        let plan = page.plan_insert(0).unwrap();
+        page.insert_heap(plan, Vec::new()).unwrap();
+
+        page.validate().unwrap();
+    }
+
+    #[test]
+    fn compute_checksum_stable_when_checksum_field_changes() {
+        let mut page = heap_with_rows();
+        let before = page.compute_checksum();
+
+        for value in [1, 0xDEAD_BEEF, u32::MAX] {
+            page.header_mut().unwrap().set_checksum(value).unwrap();
+            assert_eq!(page.compute_checksum(), before);
+        }
+    }
+
+    #[test]
+    fn compute_checksum_changes_when_any_other_byte_changes() {
+        let mut page = heap_with_rows();
+        let before = page.compute_checksum();
+
+        for offset in (0..PAGE_SIZE).filter(|o| !(CHECKSUM..CHECKSUM + 4).contains(o)) {
+            page.data_mut()[offset] ^= 0x01;
+            assert_ne!(page.compute_checksum(), before, "byte {offset} not covered");
+            page.data_mut()[offset] ^= 0x01;
+        }
+    }
+}
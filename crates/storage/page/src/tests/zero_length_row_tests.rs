@@ -0,0 +1,143 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::page::api::Page;
+    use crate::slot::SLOT_SIZE;
+    use crate::tests::SlotValues;
+    use crate::{HEADER_SIZE, PAGE_SIZE};
+
+    fn insert(page: &mut Page, row: Vec<u8>) {
+        let plan = page.plan_insert(row.len()).unwrap();
+        page.insert_heap(plan, row).unwrap();
+    }
+
+    #[test]
+    fn insert_zero_length_row_is_live_and_reads_back_empty() {
+        let mut page = Page::test_create_empty_heap();
+
+        insert(&mut page, Vec::new());
+
+        assert_eq!(page.slot_count().unwrap(), 1);
+        assert_eq!(page.live_row_count().unwrap(), 1);
+        assert_eq!(page.row(0).unwrap(), &[] as &[u8]);
+        // The slot points at the free start, so it stays distinguishable from a deleted slot
+        page.assert_slot(0, HEADER_SIZE, 0);
+        page.assert_header(&[
+            &|h| assert_eq!(h.get_free_start().unwrap(), HEADER_SIZE as u16),
+            &|h| {
+                assert_eq!(
+                    h.get_free_space().unwrap(),
+                    (PAGE_SIZE - HEADER_SIZE - SLOT_SIZE) as u16
+                )
+            },
+        ]);
+        Page::try_from_bytes(Box::new(*page.data()), page.page_id()).unwrap();
+    }
+
+    #[test]
+    fn insert_zero_length_row_slot_is_not_reused() {
+        let mut page = Page::test_create_empty_heap();
+        insert(&mut page, Vec::new());
+
+        insert(&mut page, vec![1u8; 10]);
+
+        assert_eq!(page.slot_count().unwrap(), 2);
+        assert_eq!(page.row(0).unwrap(), &[] as &[u8]);
+        assert_eq!(page.row(1).unwrap(), &[1u8; 10]);
+    }
+
+    #[test]
+    fn insert_zero_length_row_only_needs_room_for_its_slot() {
+        let mut page = Page::test_create_empty_heap();
+        // Leave exactly one slot worth of free space
+        insert(
+            &mut page,
+            vec![1u8; PAGE_SIZE - HEADER_SIZE - 2 * SLOT_SIZE],
+        );
+
+        insert(&mut page, Vec::new());
+
+        assert_eq!(page.live_row_count().unwrap(), 2);
+        assert!(page.plan_insert(0).is_err());
+    }
+
+    #[test]
+    fn insert_zero_length_rows_in_full_contiguous_region_keep_page_valid() {
+        let mut page = Page::test_create_empty_heap();
+        insert(&mut page, vec![1u8; 100]);
+        // Fill the contiguous region, leaving room for one more slot only in the gap of the first row
+        insert(
+            &mut page,
+            vec![2u8; PAGE_SIZE - HEADER_SIZE - 100 - 2 * SLOT_SIZE],
+        );
+        page.delete_row(0, false).unwrap();
+
+        // Reuses the slot of the deleted row, and is anchored at the start of the data region
+        insert(&mut page, Vec::new());
+        page.assert_slot(0, HEADER_SIZE, 0);
+        // Needs a new slot, which does not fit in the contiguous region before compacting
+        insert(&mut page, Vec::new());
+
+        assert_eq!(page.slot_count().unwrap(), 3);
+        assert_eq!(page.live_row_count().unwrap(), 3);
+        assert_eq!(page.row(0).unwrap(), &[] as &[u8]);
+        assert_eq!(page.row(2).unwrap(), &[] as &[u8]);
+        Page::try_from_bytes(Box::new(*page.data()), page.page_id()).unwrap();
+    }
+
+    #[test]
+    fn delete_zero_length_row_frees_its_slot() {
+        let mut page = Page::test_create_empty_heap();
+        insert(&mut page, Vec::new());
+        insert(&mut page, vec![1u8; 10]);
+
+        page.delete_row(0, false).unwrap();
+
+        assert_eq!(page.live_row_count().unwrap(), 1);
+        page.assert_slot(0, 0, 0);
+        // Deleting it a second time fails, like for any other row
+        assert!(page.delete_row(0, false).is_err());
+        // The freed slot is reused by the next insert
+        insert(&mut page, vec![2u8; 5]);
+        assert_eq!(page.slot_count().unwrap(), 2);
+        assert_eq!(page.row(0).unwrap(), &[2u8; 5]);
+    }
+
+    #[test]
+    fn update_row_to_and_from_zero_length() {
+        let mut page = Page::test_create_empty_heap();
+        page.test_insert_rows(vec![
+            SlotValues {
+                offset: 96,
+                len: 10,
+            },
+            SlotValues {
+                offset: 106,
+                len: 10,
+            },
+        ]);
+
+        page.update_row(0, Vec::new()).unwrap();
+        assert_eq!(page.row(0).unwrap(), &[] as &[u8]);
+        assert_eq!(page.live_row_count().unwrap(), 2);
+
+        page.update_row(0, vec![7u8; 4]).unwrap();
+        assert_eq!(page.row(0).unwrap(), &[7u8; 4]);
+        assert_eq!(page.row(1).unwrap(), &[2u8; 10]);
+    }
+
+    #[test]
+    fn compaction_keeps_zero_length_row_live() {
+        let mut page = Page::test_create_empty_heap();
+        insert(&mut page, vec![1u8; 10]);
+        insert(&mut page, Vec::new());
+        insert(&mut page, vec![3u8; 10]);
+
+        page.delete_row(0, true).unwrap();
+
+        assert_eq!(page.live_row_count().unwrap(), 2);
+        assert_eq!(page.row(1).unwrap(), &[] as &[u8]);
+        assert_eq!(page.row(2).unwrap(), &[3u8; 10]);
+        Page::try_from_bytes(Box::new(*page.data()), page.page_id()).unwrap();
+    }
+}
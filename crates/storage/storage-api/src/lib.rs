@@ -1,4 +1,17 @@
 //! Storage API providing access to the storage engine.
 #![allow(unused)] // Silence compiler warnings about unused code until they are referenced in main binary. TODO: remove this
 
+pub mod metrics;
+pub mod overflow;
+pub mod retry;
+pub mod row_checksum;
+pub mod row_codec;
+pub mod scan;
+pub mod schema_catalog;
 pub mod storage_manager;
+pub mod table;
+
+/// Fixtures wiring a storage manager over in-memory files, for tests of this and downstream crates.
+/// Only available with the `test-util` feature.
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
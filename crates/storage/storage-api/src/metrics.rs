@@ -0,0 +1,93 @@
+//! Counters of the row operations served through the storage API
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Live counters, incremented once per operation with a relaxed atomic add.
+/// The counters are independent of each other, so a snapshot taken while operations are running
+/// may count an operation in one counter but not yet in another.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    inserts: AtomicU64,
+    deletes: AtomicU64,
+    updates: AtomicU64,
+    reads: AtomicU64,
+    compactions: AtomicU64,
+}
+
+/// Point in time copy of the `Metrics` counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// Rows inserted
+    pub inserts: u64,
+    /// Rows deleted
+    pub deletes: u64,
+    /// Rows updated
+    pub updates: u64,
+    /// Rows read
+    pub reads: u64,
+    /// Pages compacted as part of an operation
+    pub compactions: u64,
+}
+
+impl Metrics {
+    pub(crate) fn record_insert(&self) {
+        self.inserts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_delete(&self) {
+        self.deletes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_update(&self) {
+        self.updates.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_read(&self) {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_compaction(&self) {
+        self.compactions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the current value of every counter
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            inserts: self.inserts.load(Ordering::Relaxed),
+            deletes: self.deletes.load(Ordering::Relaxed),
+            updates: self.updates.load(Ordering::Relaxed),
+            reads: self.reads.load(Ordering::Relaxed),
+            compactions: self.compactions.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_reports_each_counter_separately() {
+        let metrics = Metrics::default();
+
+        metrics.record_insert();
+        metrics.record_insert();
+        metrics.record_delete();
+        metrics.record_update();
+        metrics.record_read();
+        metrics.record_read();
+        metrics.record_read();
+        metrics.record_compaction();
+
+        assert_eq!(
+            metrics.snapshot(),
+            MetricsSnapshot {
+                inserts: 2,
+                deletes: 1,
+                updates: 1,
+                reads: 3,
+                compactions: 1,
+            }
+        );
+    }
+}
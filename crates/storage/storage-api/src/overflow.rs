@@ -0,0 +1,665 @@
+//! Large-object storage, for rows that do not fit in a single page
+//!
+//! A large row is split into chunks of at most `Page::max_row_size` bytes. Each chunk is the only row of a
+//! `PageType::Overflow` page, and the pages are chained through `right_page`, `0` ending the chain.
+//! The row itself is stored as a descriptor pointing at the head of the chain:
+//!
+//! | Field       | Type    | Description |
+//! |-------------|---------|-------------|
+//! | `magic`     | [u8; 8] | [`DESCRIPTOR_MAGIC`], telling descriptors apart from regular rows. |
+//! | `head_page` | u32     | Page number of the first overflow page, in the file of the descriptor. `0` for an empty row. |
+//! | `length`    | u32     | Length of the whole row. |
+//! | `checksum`  | u32     | CRC-32 (IEEE) of the whole row, verified once it is reassembled. |
+//!
+//! All integers are little-endian. The descriptor is a row like any other, so scans return it as is.
+//! Deleting or replacing the descriptor through `StorageManager` releases the chain along with it.
+//!
+//! Regular rows are stored as is, except for the ones starting with [`DESCRIPTOR_MAGIC`] or
+//! [`ESCAPE_MAGIC`]: those get [`ESCAPE_MAGIC`] prepended, see `escape_row`. So no regular row can ever
+//! pass for a descriptor, whatever its contents.
+
+use crate::row_checksum;
+use crate::storage_manager::{StorageErrors, StorageManager};
+use crate::table::RowId;
+use binary_helpers::checksum::crc32;
+use file::api::FileManager;
+use page::insertion_plan::InsertionOffset;
+use page::page::api::Page;
+use page::page_id::{FileId, PageId};
+use page::page_type::PageType;
+
+/// Leading bytes of every descriptor row
+pub const DESCRIPTOR_MAGIC: [u8; 8] = *b"TRDB\0OVF";
+
+/// Size of a descriptor row, in bytes
+pub const DESCRIPTOR_SIZE: usize = DESCRIPTOR_MAGIC.len() + 3 * size_of::<u32>();
+
+/// Prepended to the regular rows that would otherwise start like a descriptor
+pub const ESCAPE_MAGIC: [u8; 8] = *b"TRDB\0ESC";
+
+/// Turns a regular row into the bytes to store, escaping it if it starts like a descriptor or like an
+/// escaped row. See the module docs.
+pub(crate) fn escape_row(row: Vec<u8>) -> Vec<u8> {
+    if !row.starts_with(&DESCRIPTOR_MAGIC) && !row.starts_with(&ESCAPE_MAGIC) {
+        return row;
+    }
+    let mut escaped = Vec::with_capacity(ESCAPE_MAGIC.len() + row.len());
+    escaped.extend_from_slice(&ESCAPE_MAGIC);
+    escaped.extend_from_slice(&row);
+    escaped
+}
+
+/// Reverts `escape_row`. Descriptors, and rows that were never escaped, are returned as is.
+pub(crate) fn unescape_row(stored: &[u8]) -> &[u8] {
+    stored.strip_prefix(&ESCAPE_MAGIC).unwrap_or(stored)
+}
+
+/// Points at the chain of overflow pages holding a large row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OverflowDescriptor {
+    /// Page number of the first overflow page, `0` if the row is empty
+    pub(crate) head_page: u32,
+    /// Length of the whole row
+    pub(crate) length: u32,
+    /// CRC-32 of the whole row
+    pub(crate) checksum: u32,
+}
+
+impl OverflowDescriptor {
+    /// Builds the row storing the descriptor
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut row = Vec::with_capacity(DESCRIPTOR_SIZE);
+        row.extend_from_slice(&DESCRIPTOR_MAGIC);
+        row.extend_from_slice(&self.head_page.to_le_bytes());
+        row.extend_from_slice(&self.length.to_le_bytes());
+        row.extend_from_slice(&self.checksum.to_le_bytes());
+        row
+    }
+
+    /// Reads a descriptor out of a row
+    ///
+    /// # Returns
+    /// The descriptor, or `None` if the row is not one.
+    pub(crate) fn decode(row: &[u8]) -> Option<Self> {
+        if row.len() != DESCRIPTOR_SIZE {
+            return None;
+        }
+        let (magic, fields) = row.split_at(DESCRIPTOR_MAGIC.len());
+        if magic != DESCRIPTOR_MAGIC {
+            return None;
+        }
+        let field = |index: usize| {
+            u32::from_le_bytes(fields[index * 4..(index + 1) * 4].try_into().unwrap())
+        };
+        Some(Self {
+            head_page: field(0),
+            length: field(1),
+            checksum: field(2),
+        })
+    }
+}
+
+impl<F: FileManager> StorageManager<F> {
+    /// Stores a row of any size, spilling it over a chain of overflow pages. See the module docs for the
+    /// layout. The row can be read back with `fetch_row`, which reassembles it.
+    ///
+    /// `page_count` is the number of pages of the table the row belongs to, as for `scan_file`. Like
+    /// `Table::insert`, the descriptor goes to the last of them, and only gets a new heap page if that one
+    /// has no room left. A table without pages gets its first page before the overflow pages, so that none
+    /// of them ends up at page number `0`.
+    /// If the row cannot be stored, the overflow pages written so far are released, see
+    /// `release_overflow_chain`. Every page is left dirty in the buffer, to be written to disk on eviction
+    /// or flush.
+    ///
+    /// # Errors
+    ///
+    /// * `StorageErrors::RowTooLarge` - If `data` is 4 GiB or longer, as its length would not fit in the
+    ///   descriptor. Nothing is allocated.
+    /// * `StorageErrors::AllocatePage` / `StorageErrors::NewPage` - If a page could not be allocated.
+    /// * `StorageErrors::ReadPage` - If the descriptor page could not be read back.
+    /// * `StorageErrors::Page` - If a page operation failed.
+    pub fn insert_large_row(
+        &self,
+        file_id: FileId,
+        page_count: u32,
+        data: &[u8],
+    ) -> Result<RowId, StorageErrors> {
+        let length =
+            u32::try_from(data.len()).map_err(|_| StorageErrors::RowTooLarge(data.len()))?;
+
+        let last_page = match page_count.checked_sub(1) {
+            Some(last_page) => last_page,
+            None => {
+                let mut guard = self.new_page(file_id)?;
+                let page_id = guard.page_id();
+                guard.initialize(page_id, PageType::Unsorted)?;
+                // Released while the chain is written, so that even a small buffer can hold the pages
+                self.write_page(page_id, guard);
+                page_id.page_number
+            }
+        };
+
+        let head_page = self.write_overflow_chain(file_id, data)?;
+        let descriptor = OverflowDescriptor {
+            head_page,
+            length,
+            checksum: crc32(data),
+        };
+        let row = self.seal_record(file_id, descriptor.encode());
+        let row_id = self
+            .insert_descriptor(PageId::new(file_id, last_page), row)
+            .inspect_err(|_| {
+                // Best effort: the error that made the insert fail is the one worth reporting
+                let _ = self.release_overflow_chain(file_id, head_page);
+            })?;
+        self.metrics().record_insert();
+
+        Ok(row_id)
+    }
+
+    /// Writes `data` to a new chain of overflow pages, returning the page number of its head, `0` for an
+    /// empty row. If a page cannot be written, the pages written so far are released.
+    fn write_overflow_chain(&self, file_id: FileId, data: &[u8]) -> Result<u32, StorageErrors> {
+        // Chunks are written last to first, so that every page knows its successor when it is written
+        let mut next_page = 0;
+        for chunk in data.chunks(Page::max_row_size()).rev() {
+            let written = self.new_page(file_id).and_then(|mut guard| {
+                let chunk_page_id = guard.page_id();
+                guard.initialize(chunk_page_id, PageType::Overflow)?;
+                guard.set_right_page(next_page)?;
+                let plan = guard.plan_insert(chunk.len())?;
+                guard.insert_heap(plan, chunk.to_vec())?;
+                self.write_page(chunk_page_id, guard);
+                Ok(chunk_page_id.page_number)
+            });
+            next_page = written.inspect_err(|_| {
+                // Best effort: the error that made the insert fail is the one worth reporting
+                let _ = self.release_overflow_chain(file_id, next_page);
+            })?;
+        }
+        Ok(next_page)
+    }
+
+    /// Inserts a descriptor row on `last_page`, or on a new heap page if it has no room left for it.
+    fn insert_descriptor(&self, last_page: PageId, row: Vec<u8>) -> Result<RowId, StorageErrors> {
+        let mut guard = self.read_page_mut(last_page)?;
+        if guard.page_type()? != PageType::Overflow
+            && let Ok(plan) =
+                guard.plan_insert_with_compaction_threshold(row.len(), self.compaction_threshold())
+        {
+            let compacts = matches!(plan.offset, InsertionOffset::AfterCompactionFreeStart);
+            let slot = guard.insert_heap(plan, row)?;
+            self.write_page(last_page, guard);
+            if compacts {
+                self.metrics().record_compaction();
+            }
+            return Ok(RowId {
+                page_id: last_page,
+                slot,
+            });
+        }
+        drop(guard);
+
+        let mut guard = self.new_page(last_page.file_id)?;
+        let page_id = guard.page_id();
+        guard.initialize(page_id, PageType::Unsorted)?;
+        let plan = guard.plan_insert(row.len())?;
+        let slot = guard.insert_heap(plan, row)?;
+        self.write_page(page_id, guard);
+        Ok(RowId { page_id, slot })
+    }
+
+    /// Releases the overflow pages of the chain starting at `head_page`, so that none of them holds any
+    /// part of a row anymore: each one is wiped back to an empty overflow page, which scans skip. The
+    /// pages stay allocated to the file.
+    ///
+    /// Stops at the first page that is not an overflow page, so a corrupted chain never wipes the pages
+    /// of other rows. Wiped pages no longer link anywhere, which also stops cycles.
+    ///
+    /// # Errors
+    ///
+    /// * `StorageErrors::ReadPage` - If a page of the chain could not be read.
+    /// * `StorageErrors::Page` - If a page of the chain could not be read or wiped.
+    pub(crate) fn release_overflow_chain(
+        &self,
+        file_id: FileId,
+        head_page: u32,
+    ) -> Result<(), StorageErrors> {
+        let mut page_number = head_page;
+        while page_number != 0 {
+            let page_id = PageId::new(file_id, page_number);
+            let mut guard = self.read_page_mut(page_id)?;
+            if guard.page_type()? != PageType::Overflow {
+                break;
+            }
+            page_number = guard.right_page()?;
+            guard.initialize(page_id, PageType::Overflow)?;
+            self.write_page(page_id, guard);
+        }
+        Ok(())
+    }
+
+    /// Returns the descriptor stored in `stored`, the bytes of a slot of `file_id`, or `None` if it holds
+    /// a regular row. A row that does not match its checksum is never taken for a descriptor.
+    pub(crate) fn stored_descriptor(
+        &self,
+        file_id: FileId,
+        stored: &[u8],
+    ) -> Option<OverflowDescriptor> {
+        let row = if self.row_checksums(file_id) {
+            row_checksum::verify(stored)?
+        } else {
+            stored
+        };
+        OverflowDescriptor::decode(row)
+    }
+
+    /// Reassembles the row the descriptor stored at `row_id` points at.
+    ///
+    /// # Errors
+    ///
+    /// * `StorageErrors::ReadPage` - If an overflow page could not be read.
+    /// * `StorageErrors::Page` - If a chunk could not be read from its page.
+    /// * `StorageErrors::BrokenOverflowChain` - If the chain runs through a page that is not an overflow
+    ///   page, or does not hold the row the descriptor describes.
+    pub(crate) fn read_overflow_chain(
+        &self,
+        row_id: RowId,
+        descriptor: OverflowDescriptor,
+    ) -> Result<Vec<u8>, StorageErrors> {
+        let length = descriptor.length as usize;
+        let mut data = Vec::with_capacity(length);
+        let mut page_number = descriptor.head_page;
+
+        // Stopping once the row is complete also stops cycles
+        while page_number != 0 && data.len() < length {
+            let guard = self.read_page(row_id.page_id.with_page_number(page_number))?;
+            if guard.page_type()? != PageType::Overflow {
+                return Err(StorageErrors::BrokenOverflowChain(row_id));
+            }
+            data.extend_from_slice(guard.row(0)?);
+            page_number = guard.right_page()?;
+        }
+
+        if data.len() != length || crc32(&data) != descriptor.checksum {
+            return Err(StorageErrors::BrokenOverflowChain(row_id));
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::row_checksum::ROW_CHECKSUM_SIZE;
+    use crate::schema_catalog::SchemaCatalog;
+    use crate::test_util::test_storage_with;
+    use file::in_memory_file_manager::InMemoryFileManager;
+    use page::page_id::PageId;
+    use std::sync::Arc;
+
+    /// The file registered by the storage fixture
+    const FILE_ID: FileId = 1;
+
+    fn setup(pool_size: usize) -> Arc<StorageManager<InMemoryFileManager>> {
+        let (storage, file_id) = test_storage_with(pool_size, StorageManager::new);
+        assert_eq!(file_id, FILE_ID);
+        storage
+    }
+
+    fn large_row(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn descriptor_encode_then_decode_returns_descriptor() {
+        let descriptor = OverflowDescriptor {
+            head_page: 7,
+            length: 10_000,
+            checksum: 0xDEAD_BEEF,
+        };
+
+        let row = descriptor.encode();
+
+        assert_eq!(row.len(), DESCRIPTOR_SIZE);
+        assert_eq!(OverflowDescriptor::decode(&row), Some(descriptor));
+    }
+
+    #[test]
+    fn descriptor_decode_regular_rows_returns_none() {
+        let mut row = OverflowDescriptor {
+            head_page: 7,
+            length: 10_000,
+            checksum: 1,
+        }
+        .encode();
+        row[0] ^= 1;
+
+        assert_eq!(OverflowDescriptor::decode(&row), None);
+        assert_eq!(OverflowDescriptor::decode(b"short row"), None);
+        assert_eq!(OverflowDescriptor::decode(&[0; DESCRIPTOR_SIZE]), None);
+    }
+
+    #[test]
+    fn escape_row_then_unescape_returns_row() {
+        let descriptor_like = OverflowDescriptor {
+            head_page: 7,
+            length: 10_000,
+            checksum: 1,
+        }
+        .encode();
+        let mut escape_like = ESCAPE_MAGIC.to_vec();
+        escape_like.extend_from_slice(b"row");
+
+        for row in [
+            b"regular row".to_vec(),
+            Vec::new(),
+            descriptor_like,
+            escape_like,
+        ] {
+            let stored = escape_row(row.clone());
+
+            assert_eq!(OverflowDescriptor::decode(&stored), None);
+            assert_eq!(unescape_row(&stored), row);
+        }
+        assert_eq!(escape_row(b"regular row".to_vec()), b"regular row");
+    }
+
+    #[test]
+    fn fetch_row_user_row_shaped_like_descriptor_returns_row_itself() {
+        let storage = setup(4);
+        let target = storage
+            .insert_large_row(FILE_ID, 0, &large_row(5000))
+            .unwrap();
+        // Points at the real chain, so reading it as a descriptor would return the large row
+        let descriptor = storage
+            .read_page(target.page_id)
+            .unwrap()
+            .row(0)
+            .unwrap()
+            .to_vec();
+        let row_id = storage.insert_large_row(FILE_ID, 1, &[]).unwrap();
+
+        storage.update_row(row_id, descriptor.clone()).unwrap();
+
+        assert_eq!(storage.fetch_row(row_id).unwrap(), descriptor);
+        let page_count = row_id.page_id.page_number + 1;
+        assert!(
+            storage
+                .scan_file(FILE_ID, page_count)
+                .unwrap()
+                .contains(&descriptor)
+        );
+    }
+
+    #[test]
+    fn insert_large_row_spans_several_pages_and_reads_back_intact() {
+        let storage = setup(2);
+        let data = large_row(10 * 1024);
+
+        let row_id = storage.insert_large_row(FILE_ID, 0, &data).unwrap();
+
+        assert_eq!(
+            row_id,
+            RowId {
+                page_id: PageId::new(FILE_ID, 0),
+                slot: 0
+            }
+        );
+        assert_eq!(storage.fetch_row(row_id).unwrap(), data);
+    }
+
+    #[test]
+    fn insert_large_row_chains_overflow_pages_in_order() {
+        let storage = setup(4);
+        let data = large_row(10 * 1024);
+
+        storage.insert_large_row(FILE_ID, 0, &data).unwrap();
+
+        // Chunks are allocated last to first, right after the descriptor page
+        let mut chain = Vec::new();
+        let mut page_number = 3;
+        while page_number != 0 {
+            let guard = storage
+                .read_page(PageId::new(FILE_ID, page_number))
+                .unwrap();
+            assert_eq!(guard.page_type().unwrap(), PageType::Overflow);
+            chain.push((page_number, guard.row(0).unwrap().len()));
+            page_number = guard.right_page().unwrap();
+        }
+        let max = Page::max_row_size();
+        assert_eq!(chain, vec![(3, max), (2, max), (1, 10 * 1024 - 2 * max)]);
+    }
+
+    #[test]
+    fn insert_large_row_exact_multiple_of_chunk_reads_back_intact() {
+        let storage = setup(2);
+        let data = large_row(2 * Page::max_row_size());
+
+        let row_id = storage.insert_large_row(FILE_ID, 0, &data).unwrap();
+
+        assert_eq!(storage.fetch_row(row_id).unwrap(), data);
+    }
+
+    #[test]
+    fn insert_large_row_empty_row_reads_back_empty() {
+        let storage = setup(2);
+
+        let row_id = storage.insert_large_row(FILE_ID, 0, &[]).unwrap();
+
+        assert_eq!(storage.fetch_row(row_id).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn insert_large_row_table_with_row_checksums_seals_descriptor() {
+        let schema_catalog = Arc::new(SchemaCatalog::new());
+        schema_catalog
+            .register_with_row_checksums(FILE_ID, Vec::new())
+            .unwrap();
+        let (storage, _) = test_storage_with(2, |file_manager, buffer_manager| {
+            StorageManager::new(file_manager, buffer_manager).with_schema_catalog(schema_catalog)
+        });
+        let data = large_row(5000);
+
+        let row_id = storage.insert_large_row(FILE_ID, 0, &data).unwrap();
+
+        let stored = storage
+            .read_page(row_id.page_id)
+            .unwrap()
+            .row(0)
+            .unwrap()
+            .to_vec();
+        assert_eq!(stored.len(), ROW_CHECKSUM_SIZE + DESCRIPTOR_SIZE);
+        assert_eq!(storage.fetch_row(row_id).unwrap(), data);
+    }
+
+    #[test]
+    fn insert_large_row_counts_one_insert_and_one_read() {
+        let storage = setup(2);
+        let row_id = storage
+            .insert_large_row(FILE_ID, 0, &large_row(5000))
+            .unwrap();
+
+        storage.fetch_row(row_id).unwrap();
+
+        let metrics = storage.metrics_snapshot();
+        assert_eq!((metrics.inserts, metrics.reads), (1, 1));
+    }
+
+    #[test]
+    fn fetch_row_corrupted_chunk_returns_error() {
+        let storage = setup(4);
+        let row_id = storage
+            .insert_large_row(FILE_ID, 0, &large_row(5000))
+            .unwrap();
+        storage
+            .read_page_mut(PageId::new(FILE_ID, 1))
+            .unwrap()
+            .row_mut(0)
+            .unwrap()[10] ^= 0xFF;
+
+        assert!(matches!(
+            storage.fetch_row(row_id),
+            Err(StorageErrors::BrokenOverflowChain(id)) if id == row_id
+        ));
+    }
+
+    #[test]
+    fn fetch_row_truncated_chain_returns_error() {
+        let storage = setup(4);
+        let row_id = storage
+            .insert_large_row(FILE_ID, 0, &large_row(5000))
+            .unwrap();
+        // Page 2 holds the first chunk, cut the chain right after it
+        storage
+            .read_page_mut(PageId::new(FILE_ID, 2))
+            .unwrap()
+            .set_right_page(0)
+            .unwrap();
+
+        assert!(matches!(
+            storage.fetch_row(row_id),
+            Err(StorageErrors::BrokenOverflowChain(id)) if id == row_id
+        ));
+    }
+
+    /// Asserts that the pages were released back to empty overflow pages
+    fn assert_released(storage: &StorageManager<InMemoryFileManager>, pages: &[u32]) {
+        for &page_number in pages {
+            let guard = storage
+                .read_page(PageId::new(FILE_ID, page_number))
+                .unwrap();
+            assert_eq!(guard.page_type().unwrap(), PageType::Overflow);
+            assert_eq!(guard.live_row_count().unwrap(), 0);
+            assert_eq!(guard.right_page().unwrap(), 0);
+        }
+    }
+
+    #[test]
+    fn insert_large_row_places_descriptor_on_last_page() {
+        let storage = setup(4);
+        let first = storage
+            .insert_large_row(FILE_ID, 0, &large_row(5000))
+            .unwrap();
+
+        let second = storage
+            .insert_large_row(FILE_ID, 1, &large_row(6000))
+            .unwrap();
+
+        assert_eq!(
+            second,
+            RowId {
+                page_id: PageId::new(FILE_ID, 0),
+                slot: 1
+            }
+        );
+        assert_eq!(storage.fetch_row(first).unwrap(), large_row(5000));
+        assert_eq!(storage.fetch_row(second).unwrap(), large_row(6000));
+    }
+
+    #[test]
+    fn insert_large_row_full_last_page_gets_new_heap_page() {
+        let storage = setup(4);
+        let mut guard = storage.new_page(FILE_ID).unwrap();
+        let page_id = guard.page_id();
+        guard.initialize(page_id, PageType::Unsorted).unwrap();
+        let plan = guard.plan_insert(Page::max_row_size()).unwrap();
+        guard
+            .insert_heap(plan, vec![1; Page::max_row_size()])
+            .unwrap();
+        drop(guard);
+
+        let row_id = storage
+            .insert_large_row(FILE_ID, 1, &large_row(5000))
+            .unwrap();
+
+        // After the two overflow pages
+        assert_eq!(row_id.page_id, PageId::new(FILE_ID, 3));
+        assert_eq!(
+            storage
+                .read_page(row_id.page_id)
+                .unwrap()
+                .page_type()
+                .unwrap(),
+            PageType::Unsorted
+        );
+        assert_eq!(storage.fetch_row(row_id).unwrap(), large_row(5000));
+    }
+
+    #[test]
+    fn insert_large_row_failing_descriptor_insert_releases_chain() {
+        let storage = setup(4);
+        // An empty row has no overflow pages, so the heap page is the only one
+        let empty = storage.insert_large_row(FILE_ID, 0, &[]).unwrap();
+
+        // Page 9 was never allocated, so the descriptor cannot be placed on it
+        let result = storage.insert_large_row(FILE_ID, 10, &large_row(5000));
+
+        assert!(result.is_err());
+        assert_released(&storage, &[1, 2]);
+        assert_eq!(storage.fetch_row(empty).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn delete_row_large_row_releases_overflow_pages() {
+        let storage = setup(4);
+        let row_id = storage
+            .insert_large_row(FILE_ID, 0, &large_row(10 * 1024))
+            .unwrap();
+
+        storage.delete_row(row_id, false).unwrap();
+
+        assert_released(&storage, &[1, 2, 3]);
+        assert!(matches!(
+            storage.fetch_row(row_id),
+            Err(StorageErrors::Page(_))
+        ));
+    }
+
+    #[test]
+    fn update_row_large_row_releases_overflow_pages() {
+        let storage = setup(4);
+        let row_id = storage
+            .insert_large_row(FILE_ID, 0, &large_row(10 * 1024))
+            .unwrap();
+
+        storage.update_row(row_id, b"small now".to_vec()).unwrap();
+
+        assert_eq!(storage.fetch_row(row_id).unwrap(), b"small now");
+        assert_released(&storage, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn update_row_large_row_not_fitting_keeps_overflow_pages() {
+        let storage = setup(4);
+        let row_id = storage
+            .insert_large_row(FILE_ID, 0, &large_row(5000))
+            .unwrap();
+
+        assert!(storage.update_row(row_id, large_row(5000)).is_err());
+
+        assert_eq!(storage.fetch_row(row_id).unwrap(), large_row(5000));
+    }
+
+    #[test]
+    fn read_overflow_chain_through_heap_page_returns_error() {
+        let storage = setup(4);
+        let row_id = storage
+            .insert_large_row(FILE_ID, 0, &large_row(5000))
+            .unwrap();
+        let mut guard = storage.new_page(FILE_ID).unwrap();
+        let heap_page = guard.page_id();
+        guard.initialize(heap_page, PageType::Unsorted).unwrap();
+        drop(guard);
+        let descriptor = OverflowDescriptor {
+            head_page: heap_page.page_number,
+            length: 5000,
+            checksum: crc32(&large_row(5000)),
+        };
+
+        assert!(matches!(
+            storage.read_overflow_chain(row_id, descriptor),
+            Err(StorageErrors::BrokenOverflowChain(id)) if id == row_id
+        ));
+    }
+}
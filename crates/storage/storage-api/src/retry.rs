@@ -0,0 +1,72 @@
+//! Retrying buffer operations that fail because every frame of the pool is pinned
+
+use std::time::Duration;
+
+/// How often, and how patiently, a buffer operation failing with `BufferError::BufferFull` is retried.
+/// Frames are unpinned as soon as their guards are dropped, so a full pool under contention usually
+/// frees up after a short wait.
+///
+/// The wait before retry `n` (starting at 0) is `initial_backoff * 2^n`, capped at `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt. `0` surfaces `BufferFull` right away.
+    pub max_retries: u32,
+    /// Wait before the first retry
+    pub initial_backoff: Duration,
+    /// Longest wait between two attempts
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Policy that never retries
+    pub const NONE: RetryPolicy = RetryPolicy {
+        max_retries: 0,
+        initial_backoff: Duration::ZERO,
+        max_backoff: Duration::ZERO,
+    };
+
+    /// Returns the wait before retry `retry`, starting at 0
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .checked_mul(2u32.saturating_pow(retry))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Retries 3 times, waiting 1ms, then 2ms, then 4ms.
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(16),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_until_capped() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+        };
+
+        let waits: Vec<_> = (0..5)
+            .map(|retry| policy.backoff(retry).as_millis())
+            .collect();
+
+        assert_eq!(waits, vec![1, 2, 4, 5, 5]);
+    }
+
+    #[test]
+    fn backoff_large_retry_does_not_overflow() {
+        let policy = RetryPolicy::default();
+
+        assert_eq!(policy.backoff(u32::MAX), policy.max_backoff);
+    }
+}
@@ -0,0 +1,66 @@
+//! Checksummed row records, catching corruption of a single row within an otherwise valid page
+//!
+//! Tables registered with row checksums store each row as a record made of a CRC-32 of the payload,
+//! followed by the payload itself:
+//!
+//! | Field      | Type  | Description |
+//! |------------|-------|-------------|
+//! | `checksum` | u32   | CRC-32 (IEEE) of `payload`, little-endian. |
+//! | `payload`  | bytes | The row, as encoded by the caller. |
+
+use binary_helpers::checksum::crc32;
+
+/// Number of bytes the checksum adds in front of every row
+pub const ROW_CHECKSUM_SIZE: usize = size_of::<u32>();
+
+/// Builds the record storing `payload`, prefixed with its checksum
+pub fn seal(payload: &[u8]) -> Vec<u8> {
+    let mut record = Vec::with_capacity(ROW_CHECKSUM_SIZE + payload.len());
+    record.extend_from_slice(&crc32(payload).to_le_bytes());
+    record.extend_from_slice(payload);
+    record
+}
+
+/// Checks a record built by `seal`
+///
+/// # Returns
+/// The payload of the record, or `None` if the record is too short to hold a checksum, or the payload
+/// does not match it.
+pub fn verify(record: &[u8]) -> Option<&[u8]> {
+    let (checksum, payload) = record.split_at_checked(ROW_CHECKSUM_SIZE)?;
+    let checksum = u32::from_le_bytes(checksum.try_into().unwrap());
+    (crc32(payload) == checksum).then_some(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_then_verify_returns_payload() {
+        let record = seal(b"some row");
+
+        assert_eq!(record.len(), ROW_CHECKSUM_SIZE + 8);
+        assert_eq!(verify(&record), Some(&b"some row"[..]));
+    }
+
+    #[test]
+    fn verify_empty_payload_returns_empty_payload() {
+        assert_eq!(verify(&seal(&[])), Some(&[][..]));
+    }
+
+    #[test]
+    fn verify_flipped_bit_returns_none() {
+        for index in 0..ROW_CHECKSUM_SIZE + 8 {
+            let mut record = seal(b"some row");
+            record[index] ^= 0x10;
+
+            assert_eq!(verify(&record), None, "flip at {index} went undetected");
+        }
+    }
+
+    #[test]
+    fn verify_record_shorter_than_checksum_returns_none() {
+        assert_eq!(verify(&[1, 2, 3]), None);
+    }
+}
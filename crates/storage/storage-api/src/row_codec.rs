@@ -0,0 +1,335 @@
+//! Encoding of typed rows into the raw bytes stored in pages
+
+use binary_helpers::bin_error::BinaryError;
+use binary_helpers::le::{read_le, write_le};
+use thiserror::Error;
+
+/// The type of a column.
+/// The discriminant identifies the type in serialized schemas, so it must never change once released.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    /// Unsigned 32-bit integer, stored on 4 bytes
+    U32 = 1,
+    /// Unsigned 64-bit integer, stored on 8 bytes
+    U64 = 2,
+    /// Signed 32-bit integer, stored on 4 bytes
+    I32 = 3,
+    /// UTF-8 string, stored as a `u16` byte length followed by the bytes
+    Str = 4,
+    /// Arbitrary bytes, stored as a `u16` length followed by the bytes
+    Bytes = 5,
+}
+
+impl From<ColumnType> for u8 {
+    /// Converts a `ColumnType` to its serialized tag.
+    fn from(c: ColumnType) -> Self {
+        c as u8
+    }
+}
+
+impl TryFrom<u8> for ColumnType {
+    type Error = ();
+
+    /// Attempts to convert a serialized tag back to its `ColumnType`.
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            1 => Ok(ColumnType::U32),
+            2 => Ok(ColumnType::U64),
+            3 => Ok(ColumnType::I32),
+            4 => Ok(ColumnType::Str),
+            5 => Ok(ColumnType::Bytes),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Definition of a single column of a table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    /// Name of the column
+    pub name: String,
+    /// Type of the values stored in the column
+    pub column_type: ColumnType,
+}
+
+impl Column {
+    /// Creates a new column definition
+    pub fn new(name: impl Into<String>, column_type: ColumnType) -> Self {
+        Self {
+            name: name.into(),
+            column_type,
+        }
+    }
+}
+
+/// A single typed value of a row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    /// Value of a `ColumnType::U32` column
+    U32(u32),
+    /// Value of a `ColumnType::U64` column
+    U64(u64),
+    /// Value of a `ColumnType::I32` column
+    I32(i32),
+    /// Value of a `ColumnType::Str` column
+    Str(String),
+    /// Value of a `ColumnType::Bytes` column
+    Bytes(Vec<u8>),
+}
+
+impl Value {
+    fn column_type(&self) -> ColumnType {
+        match self {
+            Value::U32(_) => ColumnType::U32,
+            Value::U64(_) => ColumnType::U64,
+            Value::I32(_) => ColumnType::I32,
+            Value::Str(_) => ColumnType::Str,
+            Value::Bytes(_) => ColumnType::Bytes,
+        }
+    }
+}
+
+/// A decoded row - one value per column, in the order of the schema
+pub type Row = Vec<Value>;
+
+/// Errors raised while encoding or decoding rows
+#[derive(Debug, Error)]
+pub enum CodecError {
+    /// The number of values does not match the number of columns
+    #[error("Expected {expected} values, got {actual}")]
+    ValueCountMismatch {
+        /// Number of columns in the schema
+        expected: usize,
+        /// Number of values provided
+        actual: usize,
+    },
+    /// A value does not match the type of its column
+    #[error("Column {column} expects a value of type {expected:?}, got {actual:?}")]
+    TypeMismatch {
+        /// Name of the column
+        column: String,
+        /// Type of the column
+        expected: ColumnType,
+        /// Type of the provided value
+        actual: ColumnType,
+    },
+    /// A string or bytes value is longer than what its length prefix can hold
+    #[error(
+        "Value of column {column} is {len} bytes long, maximum is {}",
+        u16::MAX
+    )]
+    StringTooLong {
+        /// Name of the column
+        column: String,
+        /// Length of the string, in bytes
+        len: usize,
+    },
+    /// The encoded row is shorter or longer than what the schema describes
+    #[error("Encoded row does not match the schema")]
+    Malformed,
+    /// A string value is not valid UTF-8
+    #[error("Value of column {column} is not valid UTF-8")]
+    InvalidUtf8 {
+        /// Name of the column
+        column: String,
+    },
+    /// Error while reading or writing a fixed-size value
+    #[error("Error while interpreting binary data")]
+    BinaryError(#[from] BinaryError),
+}
+
+/// Encodes `values` according to `columns`.
+/// Values are laid out back to back, in column order, with no padding.
+pub fn encode_row(columns: &[Column], values: &[Value]) -> Result<Vec<u8>, CodecError> {
+    if columns.len() != values.len() {
+        return Err(CodecError::ValueCountMismatch {
+            expected: columns.len(),
+            actual: values.len(),
+        });
+    }
+
+    let mut bytes = Vec::new();
+    for (column, value) in columns.iter().zip(values) {
+        if column.column_type != value.column_type() {
+            return Err(CodecError::TypeMismatch {
+                column: column.name.clone(),
+                expected: column.column_type,
+                actual: value.column_type(),
+            });
+        }
+
+        match value {
+            Value::U32(v) => bytes.extend_from_slice(&v.to_le_bytes()),
+            Value::U64(v) => bytes.extend_from_slice(&v.to_le_bytes()),
+            Value::I32(v) => bytes.extend_from_slice(&v.to_le_bytes()),
+            Value::Str(s) => encode_length_prefixed(&mut bytes, column, s.as_bytes())?,
+            Value::Bytes(b) => encode_length_prefixed(&mut bytes, column, b)?,
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Appends `value` to `bytes`, preceded by its length as a `u16`
+fn encode_length_prefixed(
+    bytes: &mut Vec<u8>,
+    column: &Column,
+    value: &[u8],
+) -> Result<(), CodecError> {
+    let len = u16::try_from(value.len()).map_err(|_| CodecError::StringTooLong {
+        column: column.name.clone(),
+        len: value.len(),
+    })?;
+    let start = bytes.len();
+    bytes.resize(start + size_of::<u16>(), 0);
+    write_le::<u16>(bytes, start, len)?;
+    bytes.extend_from_slice(value);
+    Ok(())
+}
+
+/// Reads a value written by `encode_length_prefixed` at `offset`, then moves `offset` past it
+fn decode_length_prefixed<'a>(bytes: &'a [u8], offset: &mut usize) -> Result<&'a [u8], CodecError> {
+    let len = read_le::<u16>(bytes, *offset).map_err(|_| CodecError::Malformed)? as usize;
+    *offset += size_of::<u16>();
+    let raw = bytes
+        .get(*offset..*offset + len)
+        .ok_or(CodecError::Malformed)?;
+    *offset += len;
+    Ok(raw)
+}
+
+/// Decodes a row previously encoded with `encode_row` using the same `columns`.
+pub fn decode_row(columns: &[Column], bytes: &[u8]) -> Result<Row, CodecError> {
+    let mut offset = 0;
+    let mut row = Vec::with_capacity(columns.len());
+
+    for column in columns {
+        let value = match column.column_type {
+            ColumnType::U32 => {
+                let v = read_le::<u32>(bytes, offset).map_err(|_| CodecError::Malformed)?;
+                offset += size_of::<u32>();
+                Value::U32(v)
+            }
+            ColumnType::U64 => {
+                let v = read_le::<u64>(bytes, offset).map_err(|_| CodecError::Malformed)?;
+                offset += size_of::<u64>();
+                Value::U64(v)
+            }
+            ColumnType::I32 => {
+                let v = read_le::<u32>(bytes, offset).map_err(|_| CodecError::Malformed)?;
+                offset += size_of::<i32>();
+                Value::I32(v as i32)
+            }
+            ColumnType::Str => {
+                let raw = decode_length_prefixed(bytes, &mut offset)?;
+                let s = String::from_utf8(raw.to_vec()).map_err(|_| CodecError::InvalidUtf8 {
+                    column: column.name.clone(),
+                })?;
+                Value::Str(s)
+            }
+            ColumnType::Bytes => Value::Bytes(decode_length_prefixed(bytes, &mut offset)?.to_vec()),
+        };
+        row.push(value);
+    }
+
+    if offset != bytes.len() {
+        return Err(CodecError::Malformed);
+    }
+
+    Ok(row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Vec<Column> {
+        vec![
+            Column::new("id", ColumnType::U32),
+            Column::new("name", ColumnType::Str),
+            Column::new("balance", ColumnType::U64),
+        ]
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let values = vec![
+            Value::U32(7),
+            Value::Str("alice".to_string()),
+            Value::U64(u64::MAX),
+        ];
+
+        let bytes = encode_row(&schema(), &values).unwrap();
+
+        assert_eq!(bytes.len(), 4 + 2 + 5 + 8);
+        assert_eq!(decode_row(&schema(), &bytes).unwrap(), values);
+    }
+
+    #[test]
+    fn encode_decode_signed_and_bytes_round_trip() {
+        let columns = vec![
+            Column::new("delta", ColumnType::I32),
+            Column::new("payload", ColumnType::Bytes),
+        ];
+        let values = vec![Value::I32(-42), Value::Bytes(vec![0, 0xFF, 7])];
+
+        let bytes = encode_row(&columns, &values).unwrap();
+
+        assert_eq!(bytes.len(), 4 + 2 + 3);
+        assert_eq!(decode_row(&columns, &bytes).unwrap(), values);
+    }
+
+    #[test]
+    fn column_type_tag_round_trip() {
+        for column_type in [
+            ColumnType::U32,
+            ColumnType::U64,
+            ColumnType::I32,
+            ColumnType::Str,
+            ColumnType::Bytes,
+        ] {
+            assert_eq!(ColumnType::try_from(u8::from(column_type)), Ok(column_type));
+        }
+        assert_eq!(ColumnType::try_from(0), Err(()));
+    }
+
+    #[test]
+    fn encode_type_mismatch_returns_error() {
+        let values = vec![Value::U32(7), Value::U32(8), Value::U64(9)];
+
+        let err = encode_row(&schema(), &values).unwrap_err();
+
+        assert!(matches!(
+            err,
+            CodecError::TypeMismatch { column, expected: ColumnType::Str, actual: ColumnType::U32 } if column == "name"
+        ));
+    }
+
+    #[test]
+    fn encode_value_count_mismatch_returns_error() {
+        let err = encode_row(&schema(), &[Value::U32(7)]).unwrap_err();
+
+        assert!(matches!(
+            err,
+            CodecError::ValueCountMismatch {
+                expected: 3,
+                actual: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn decode_truncated_row_returns_malformed() {
+        let values = vec![
+            Value::U32(7),
+            Value::Str("alice".to_string()),
+            Value::U64(1),
+        ];
+        let bytes = encode_row(&schema(), &values).unwrap();
+
+        let err = decode_row(&schema(), &bytes[..bytes.len() - 1]).unwrap_err();
+
+        assert!(matches!(err, CodecError::Malformed));
+    }
+}
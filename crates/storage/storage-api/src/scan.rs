@@ -0,0 +1,234 @@
+//! Full scans over the rows stored in the pages of a file
+//!
+//! Two flavours are provided:
+//! - `StorageManager::scan_file` copies every row into an owned `Vec<u8>`. Pages are released as soon
+//!   as their rows are copied, at the cost of one allocation per row.
+//! - `StorageManager::scan_file_borrowed` yields one `PageRows` per page, handing out slices into the
+//!   buffered page. Nothing is copied, but each page stays pinned (read-latched) in the buffer for as
+//!   long as its `PageRows` is alive, blocking writers and eviction of that page.
+
+use crate::metrics::Metrics;
+use crate::overflow;
+use crate::row_checksum;
+use crate::storage_manager::{StorageErrors, StorageManager};
+use crate::table::RowId;
+use buffer::guards::PageReadGuard;
+use file::api::FileManager;
+use page::page_id::{FileId, PageId};
+use page::page_type::PageType;
+
+/// The rows of a single page, borrowed from the buffer.
+/// Holds a read guard on the page, so the page cannot be modified or evicted until it is dropped.
+#[derive(Debug)]
+pub struct PageRows<'a> {
+    guard: PageReadGuard<'a>,
+    metrics: &'a Metrics,
+    /// Whether the rows are stored with a checksum, which is verified and stripped
+    row_checksums: bool,
+}
+
+impl<'a> PageRows<'a> {
+    /// The page the rows belong to
+    pub fn page_id(&self) -> PageId {
+        self.guard.page_id()
+    }
+
+    /// Returns the live rows of the page, in slot order. Deleted rows are skipped.
+    /// The slices point straight into the buffered page.
+    ///
+    /// Overflow pages hold chunks of large rows rather than rows, so they have none. Large rows show up as
+    /// their descriptor, see `overflow`.
+    ///
+    /// Fails with `StorageErrors::RowChecksumMismatch` if the table stores row checksums, and a row does
+    /// not match its own.
+    pub fn rows(&self) -> Result<Vec<&[u8]>, StorageErrors> {
+        let mut rows = Vec::new();
+        if self.guard.page_type()? == PageType::Overflow {
+            return Ok(rows);
+        }
+        for slot in self.guard.iter_slots() {
+            // Zero-length rows are live rows like any other, only the slot tells deleted ones apart
+            let slot = slot?;
+            if !slot.valid {
+                continue;
+            }
+            let slot = slot.index;
+            let mut bytes = self.guard.row(slot)?;
+            if self.row_checksums {
+                let row_id = RowId {
+                    page_id: self.page_id(),
+                    slot,
+                };
+                bytes = row_checksum::verify(bytes)
+                    .ok_or(StorageErrors::RowChecksumMismatch(row_id))?;
+            }
+            self.metrics.record_read();
+            rows.push(overflow::unescape_row(bytes));
+        }
+        Ok(rows)
+    }
+}
+
+impl<F: FileManager> StorageManager<F> {
+    /// Returns a copy of every live row of the first `page_count` pages of `file_id`, in page order,
+    /// then slot order. Each page is released before the next one is read.
+    ///
+    /// # Errors
+    ///
+    /// * `StorageErrors::ReadPage` - If a page could not be read.
+    /// * `StorageErrors::Page` - If a row could not be read from its page.
+    pub fn scan_file(
+        &self,
+        file_id: FileId,
+        page_count: u32,
+    ) -> Result<Vec<Vec<u8>>, StorageErrors> {
+        let mut rows = Vec::new();
+        for page in self.scan_file_borrowed(file_id, page_count) {
+            rows.extend(page?.rows()?.into_iter().map(<[u8]>::to_vec));
+        }
+        Ok(rows)
+    }
+
+    /// Zero-copy variant of `scan_file`, reading a single page at a time.
+    ///
+    /// Yields the pages lazily, so only the `PageRows` currently held by the caller pins its page.
+    /// Holding on to several of them pins all their pages at once.
+    ///
+    /// # Errors
+    ///
+    /// Each item fails with `StorageErrors::ReadPage` if its page could not be read.
+    pub fn scan_file_borrowed(
+        &self,
+        file_id: FileId,
+        page_count: u32,
+    ) -> impl Iterator<Item = Result<PageRows<'_>, StorageErrors>> {
+        (0..page_count).map(move |page_number| {
+            Ok(PageRows {
+                guard: self.read_page(PageId::new(file_id, page_number))?,
+                metrics: self.metrics(),
+                row_checksums: self.row_checksums(file_id),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::test_storage_with;
+    use file::in_memory_file_manager::InMemoryFileManager;
+    use std::sync::Arc;
+
+    /// The file registered by the storage fixture
+    const FILE_ID: FileId = 1;
+
+    /// Storage with `pages` pages, each holding a few rows and a deleted slot
+    fn setup(pages: u32) -> Arc<StorageManager<InMemoryFileManager>> {
+        let (storage, file_id) = test_storage_with(4, StorageManager::new);
+        assert_eq!(file_id, FILE_ID);
+
+        for page_number in 0..pages {
+            let mut guard = storage.new_page(FILE_ID).unwrap();
+            let page_id = guard.page_id();
+            guard.initialize(page_id, PageType::Unsorted).unwrap();
+            for row in 0..4u8 {
+                let bytes = vec![page_number as u8 * 10 + row; 10 + row as usize];
+                let plan = guard.plan_insert(bytes.len()).unwrap();
+                guard.insert_heap(plan, bytes).unwrap();
+            }
+            guard.delete_row(1, false).unwrap();
+            storage.write_page(page_id, guard);
+        }
+
+        storage
+    }
+
+    #[test]
+    fn scan_file_returns_live_rows_in_order() {
+        let storage = setup(2);
+
+        let rows = storage.scan_file(FILE_ID, 2).unwrap();
+
+        let expected: Vec<Vec<u8>> = [(0u8, 0u8), (0, 2), (0, 3), (1, 0), (1, 2), (1, 3)]
+            .iter()
+            .map(|(page, row)| vec![page * 10 + row; 10 + *row as usize])
+            .collect();
+        assert_eq!(rows, expected);
+    }
+
+    #[test]
+    fn scan_file_borrowed_matches_owned_scan() {
+        let storage = setup(3);
+        let owned = storage.scan_file(FILE_ID, 3).unwrap();
+
+        let mut borrowed = Vec::new();
+        for (page_number, page) in storage.scan_file_borrowed(FILE_ID, 3).enumerate() {
+            let page = page.unwrap();
+            assert_eq!(page.page_id(), PageId::new(FILE_ID, page_number as u32));
+            borrowed.extend(page.rows().unwrap().into_iter().map(<[u8]>::to_vec));
+        }
+
+        assert_eq!(borrowed, owned);
+    }
+
+    #[test]
+    fn scan_file_borrowed_pins_only_the_held_page() {
+        let storage = setup(2);
+        let mut pages = storage.scan_file_borrowed(FILE_ID, 2);
+
+        let first = pages.next().unwrap().unwrap();
+        let first_row = first.rows().unwrap()[0];
+        assert_eq!(first_row, &[0u8; 10][..]);
+        // The second page is not latched yet, so it can still be written to
+        drop(storage.read_page_mut(PageId::new(FILE_ID, 1)).unwrap());
+        drop(first);
+
+        assert!(pages.next().unwrap().is_ok());
+        assert!(pages.next().is_none());
+    }
+
+    #[test]
+    fn scan_file_skips_overflow_pages() {
+        let storage = setup(1);
+        let large_row = vec![7u8; 5000];
+        let row_id = storage.insert_large_row(FILE_ID, 1, &large_row).unwrap();
+
+        // The descriptor goes on the heap page, the two overflow pages follow it
+        assert_eq!(row_id.page_id, PageId::new(FILE_ID, 0));
+        let rows = storage.scan_file(FILE_ID, 3).unwrap();
+
+        assert_eq!(rows.len(), 4);
+        let descriptor = storage
+            .read_page(row_id.page_id)
+            .unwrap()
+            .row(row_id.slot)
+            .unwrap()
+            .to_vec();
+        assert!(rows.contains(&descriptor));
+        assert_eq!(storage.fetch_row(row_id).unwrap(), large_row);
+    }
+
+    #[test]
+    fn scan_file_returns_zero_length_rows() {
+        let storage = setup(1);
+        let mut guard = storage.read_page_mut(PageId::new(FILE_ID, 0)).unwrap();
+        let plan = guard.plan_insert(0).unwrap();
+        // Takes over the deleted slot
+        assert_eq!(guard.insert_heap(plan, Vec::new()).unwrap(), 1);
+        drop(guard);
+
+        let rows = storage.scan_file(FILE_ID, 1).unwrap();
+
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[1], Vec::<u8>::new());
+    }
+
+    #[test]
+    fn scan_file_counts_reads() {
+        let storage = setup(2);
+
+        storage.scan_file(FILE_ID, 2).unwrap();
+
+        assert_eq!(storage.metrics_snapshot().reads, 6);
+    }
+}
@@ -0,0 +1,434 @@
+//! A catalog holding the schema of every table, persisted next to the data files
+
+use crate::row_codec::{Column, ColumnType};
+use binary_helpers::le::{read_bytes, read_le};
+use page::page_id::FileId;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::RwLock;
+use thiserror::Error;
+
+/// Errors raised while registering, saving or loading schemas
+#[derive(Debug, Error)]
+pub enum SchemaError {
+    /// The `FileId` already has a schema
+    #[error("File {0} already has a schema")]
+    FileIdInUse(FileId),
+    /// Two columns of the same schema share a name
+    #[error("Column {0} is defined more than once")]
+    DuplicateColumn(String),
+    /// A column name is longer than what its length prefix can hold
+    #[error("Column name {0} is longer than {max} bytes", max = u16::MAX)]
+    ColumnNameTooLong(String),
+    /// A schema has more columns than what the column count can hold
+    #[error("Schema has {0} columns, maximum is {max}", max = u16::MAX)]
+    TooManyColumns(usize),
+    /// The serialized catalog could not be parsed
+    #[error("Schema catalog is malformed")]
+    Malformed,
+    /// The catalog file could not be read or written
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+}
+
+/// Catalog holding the ordered columns of the table stored in each `FileId`.
+///
+/// The catalog is serialized as a `u32` table count, followed by one entry per table, in increasing
+/// `FileId` order:
+///
+/// | Field          | Type       | Description |
+/// |----------------|------------|-------------|
+/// | `file_id`      | u32        | The file holding the table. |
+/// | `column_count` | u16        | Number of columns that follow. |
+/// | `column_type`  | u8         | Per column: the `ColumnType` tag. |
+/// | `name_len`     | u16        | Per column: length of the name, in bytes. |
+/// | `name`         | `name_len` | Per column: the name, as UTF-8. |
+///
+/// The tables are followed by the tables storing checksummed rows (see `row_checksum`). Catalogs
+/// saved before row checksums existed end right after the tables, and are loaded without any:
+///
+/// | Field            | Type | Description |
+/// |------------------|------|-------------|
+/// | `checksum_count` | u32  | Number of file IDs that follow. |
+/// | `file_id`        | u32  | Per table with row checksums, in increasing order: its file. |
+///
+/// All integers are little-endian.
+#[derive(Debug, Default)]
+pub struct SchemaCatalog {
+    schemas: RwLock<HashMap<FileId, TableSchema>>,
+}
+
+/// What the catalog knows about a single table
+#[derive(Debug, Clone)]
+struct TableSchema {
+    /// The columns of the table, in the order values are encoded
+    columns: Vec<Column>,
+    /// Whether rows are stored as checksummed records, see `row_checksum`
+    row_checksums: bool,
+}
+
+impl SchemaCatalog {
+    /// Creates a new empty `SchemaCatalog`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the schema of the table stored in `file_id`
+    ///
+    /// # Params
+    /// - `file_id`: the file holding the table
+    /// - `columns`: the columns of the table, in the order values are encoded
+    ///
+    /// # Returns
+    /// - `Err(SchemaError::FileIdInUse)` if `file_id` already has a schema, in which case it is kept
+    /// - `Err(SchemaError::DuplicateColumn)` if two columns share a name
+    /// - `Err(SchemaError::ColumnNameTooLong)` or `Err(SchemaError::TooManyColumns)` if the schema could
+    ///   not be serialized
+    pub fn register(&self, file_id: FileId, columns: Vec<Column>) -> Result<(), SchemaError> {
+        self.insert(file_id, columns, false)
+    }
+
+    /// Registers the schema of a table whose rows are stored with a checksum, so that corruption of a
+    /// single row is detected when it is read. See `register` for the parameters and errors.
+    ///
+    /// Only meant for new tables: rows stored before the table was registered have no checksum.
+    pub fn register_with_row_checksums(
+        &self,
+        file_id: FileId,
+        columns: Vec<Column>,
+    ) -> Result<(), SchemaError> {
+        self.insert(file_id, columns, true)
+    }
+
+    fn insert(
+        &self,
+        file_id: FileId,
+        columns: Vec<Column>,
+        row_checksums: bool,
+    ) -> Result<(), SchemaError> {
+        validate(&columns)?;
+
+        let mut guard = self
+            .schemas
+            .write()
+            .expect("SchemaCatalog poisoned: another thread panicked while holding the lock");
+        if guard.contains_key(&file_id) {
+            return Err(SchemaError::FileIdInUse(file_id));
+        }
+        guard.insert(
+            file_id,
+            TableSchema {
+                columns,
+                row_checksums,
+            },
+        );
+        Ok(())
+    }
+
+    /// Returns the columns of the table stored in `file_id`, if it has a schema
+    pub fn columns(&self, file_id: FileId) -> Option<Vec<Column>> {
+        let guard = self
+            .schemas
+            .read()
+            .expect("SchemaCatalog poisoned: another thread panicked while holding the lock");
+        guard.get(&file_id).map(|schema| schema.columns.clone())
+    }
+
+    /// Returns whether the rows of the table stored in `file_id` carry a checksum.
+    /// `false` for files without a schema.
+    pub fn row_checksums(&self, file_id: FileId) -> bool {
+        let guard = self
+            .schemas
+            .read()
+            .expect("SchemaCatalog poisoned: another thread panicked while holding the lock");
+        guard
+            .get(&file_id)
+            .is_some_and(|schema| schema.row_checksums)
+    }
+
+    /// Writes the catalog to `path`, replacing whatever the file held before.
+    /// The catalog is written to a temporary file first, then renamed over `path`, so a crash midway
+    /// leaves the previous version intact.
+    pub fn save(&self, path: &Path) -> Result<(), SchemaError> {
+        let bytes = self.to_bytes();
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, bytes)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Loads a catalog previously written with `save`
+    ///
+    /// # Returns
+    /// - `Err(SchemaError::Io)` if the file could not be read
+    /// - `Err(SchemaError::Malformed)` if the file is truncated, has trailing bytes, or holds an unknown
+    ///   column type
+    /// - Any error `register` would raise for one of the stored schemas
+    pub fn load(path: &Path) -> Result<Self, SchemaError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let guard = self
+            .schemas
+            .read()
+            .expect("SchemaCatalog poisoned: another thread panicked while holding the lock");
+        let mut file_ids: Vec<FileId> = guard.keys().copied().collect();
+        file_ids.sort_unstable();
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(file_ids.len() as u32).to_le_bytes());
+        for &file_id in &file_ids {
+            let columns = &guard[&file_id].columns;
+            bytes.extend_from_slice(&file_id.to_le_bytes());
+            // Both lengths were checked by `validate` on registration
+            bytes.extend_from_slice(&(columns.len() as u16).to_le_bytes());
+            for column in columns {
+                bytes.push(u8::from(column.column_type));
+                bytes.extend_from_slice(&(column.name.len() as u16).to_le_bytes());
+                bytes.extend_from_slice(column.name.as_bytes());
+            }
+        }
+
+        let checksummed: Vec<FileId> = file_ids
+            .into_iter()
+            .filter(|file_id| guard[file_id].row_checksums)
+            .collect();
+        bytes.extend_from_slice(&(checksummed.len() as u32).to_le_bytes());
+        for file_id in checksummed {
+            bytes.extend_from_slice(&file_id.to_le_bytes());
+        }
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, SchemaError> {
+        let mut schemas = Vec::new();
+        let mut offset = 0;
+
+        let table_count = read_le::<u32>(bytes, offset).map_err(|_| SchemaError::Malformed)?;
+        offset += size_of::<u32>();
+        for _ in 0..table_count {
+            let file_id = read_le::<u32>(bytes, offset).map_err(|_| SchemaError::Malformed)?;
+            offset += size_of::<u32>();
+            let column_count = read_le::<u16>(bytes, offset).map_err(|_| SchemaError::Malformed)?;
+            offset += size_of::<u16>();
+
+            let mut columns = Vec::with_capacity(column_count as usize);
+            for _ in 0..column_count {
+                let tag = *bytes.get(offset).ok_or(SchemaError::Malformed)?;
+                let column_type = ColumnType::try_from(tag).map_err(|_| SchemaError::Malformed)?;
+                offset += size_of::<u8>();
+                let name_len =
+                    read_le::<u16>(bytes, offset).map_err(|_| SchemaError::Malformed)? as usize;
+                offset += size_of::<u16>();
+                let name =
+                    read_bytes(bytes, offset, name_len).map_err(|_| SchemaError::Malformed)?;
+                let name = String::from_utf8(name.to_vec()).map_err(|_| SchemaError::Malformed)?;
+                offset += name_len;
+                columns.push(Column::new(name, column_type));
+            }
+
+            schemas.push((file_id, columns));
+        }
+
+        let mut checksummed = HashSet::new();
+        // Absent from catalogs saved before row checksums existed
+        if offset != bytes.len() {
+            let count = read_le::<u32>(bytes, offset).map_err(|_| SchemaError::Malformed)?;
+            offset += size_of::<u32>();
+            for _ in 0..count {
+                let file_id = read_le::<u32>(bytes, offset).map_err(|_| SchemaError::Malformed)?;
+                offset += size_of::<u32>();
+                checksummed.insert(file_id);
+            }
+        }
+
+        if offset != bytes.len() {
+            return Err(SchemaError::Malformed);
+        }
+
+        let catalog = Self::new();
+        for (file_id, columns) in schemas {
+            catalog.insert(file_id, columns, checksummed.remove(&file_id))?;
+        }
+        // Checksums of a table without a schema
+        if !checksummed.is_empty() {
+            return Err(SchemaError::Malformed);
+        }
+
+        Ok(catalog)
+    }
+}
+
+/// Checks that `columns` have distinct names and can be serialized
+fn validate(columns: &[Column]) -> Result<(), SchemaError> {
+    if columns.len() > u16::MAX as usize {
+        return Err(SchemaError::TooManyColumns(columns.len()));
+    }
+
+    let mut names = HashSet::with_capacity(columns.len());
+    for column in columns {
+        if column.name.len() > u16::MAX as usize {
+            return Err(SchemaError::ColumnNameTooLong(column.name.clone()));
+        }
+        if !names.insert(column.name.as_str()) {
+            return Err(SchemaError::DuplicateColumn(column.name.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accounts() -> Vec<Column> {
+        vec![
+            Column::new("id", ColumnType::U32),
+            Column::new("name", ColumnType::Str),
+            Column::new("balance", ColumnType::U64),
+            Column::new("delta", ColumnType::I32),
+            Column::new("avatar", ColumnType::Bytes),
+        ]
+    }
+
+    #[test]
+    fn register_then_columns_returns_schema_in_order() {
+        let catalog = SchemaCatalog::new();
+
+        catalog.register(1, accounts()).unwrap();
+
+        assert_eq!(catalog.columns(1), Some(accounts()));
+        assert_eq!(catalog.columns(2), None);
+    }
+
+    #[test]
+    fn register_existing_file_id_returns_error_and_keeps_schema() {
+        let catalog = SchemaCatalog::new();
+        catalog.register(1, accounts()).unwrap();
+
+        let result = catalog.register(1, vec![Column::new("other", ColumnType::U32)]);
+
+        assert!(matches!(result, Err(SchemaError::FileIdInUse(1))));
+        assert_eq!(catalog.columns(1), Some(accounts()));
+    }
+
+    #[test]
+    fn register_duplicate_column_name_returns_error() {
+        let catalog = SchemaCatalog::new();
+        let columns = vec![
+            Column::new("id", ColumnType::U32),
+            Column::new("id", ColumnType::Str),
+        ];
+
+        let result = catalog.register(1, columns);
+
+        assert!(matches!(result, Err(SchemaError::DuplicateColumn(name)) if name == "id"));
+        assert_eq!(catalog.columns(1), None);
+    }
+
+    #[test]
+    fn save_then_load_round_trips_every_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schemas.cat");
+        let catalog = SchemaCatalog::new();
+        catalog.register(2, accounts()).unwrap();
+        catalog
+            .register(1, vec![Column::new("key", ColumnType::U64)])
+            .unwrap();
+        catalog.register(3, Vec::new()).unwrap();
+
+        catalog.save(&path).unwrap();
+        let loaded = SchemaCatalog::load(&path).unwrap();
+
+        assert_eq!(loaded.columns(1), catalog.columns(1));
+        assert_eq!(loaded.columns(2), Some(accounts()));
+        assert_eq!(loaded.columns(3), Some(Vec::new()));
+        assert!(!path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn save_then_load_keeps_row_checksums_flag() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schemas.cat");
+        let catalog = SchemaCatalog::new();
+        catalog.register(1, accounts()).unwrap();
+        catalog.register_with_row_checksums(2, accounts()).unwrap();
+
+        catalog.save(&path).unwrap();
+        let loaded = SchemaCatalog::load(&path).unwrap();
+
+        assert!(!loaded.row_checksums(1));
+        assert!(loaded.row_checksums(2));
+        assert!(!loaded.row_checksums(3));
+        assert_eq!(loaded.columns(2), Some(accounts()));
+    }
+
+    #[test]
+    fn load_catalog_without_row_checksums_section_has_no_checksums() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schemas.cat");
+        // One table (file 1) with a single U32 column named "a", as saved before row checksums existed
+        let bytes = [1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 1, 1, 0, b'a'];
+        std::fs::write(&path, bytes).unwrap();
+
+        let loaded = SchemaCatalog::load(&path).unwrap();
+
+        assert_eq!(
+            loaded.columns(1),
+            Some(vec![Column::new("a", ColumnType::U32)])
+        );
+        assert!(!loaded.row_checksums(1));
+    }
+
+    #[test]
+    fn load_row_checksums_of_unknown_table_returns_malformed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schemas.cat");
+        // No tables, then checksums for file 1
+        let bytes = [0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0];
+        std::fs::write(&path, bytes).unwrap();
+
+        let result = SchemaCatalog::load(&path);
+
+        assert!(matches!(result, Err(SchemaError::Malformed)));
+    }
+
+    #[test]
+    fn load_truncated_file_returns_malformed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schemas.cat");
+        let catalog = SchemaCatalog::new();
+        catalog.register(1, accounts()).unwrap();
+        catalog.save(&path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+
+        let result = SchemaCatalog::load(&path);
+
+        assert!(matches!(result, Err(SchemaError::Malformed)));
+    }
+
+    #[test]
+    fn load_duplicate_column_names_returns_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schemas.cat");
+        // One table (file 1) with two U32 columns, both named "a"
+        let bytes = [1, 0, 0, 0, 1, 0, 0, 0, 2, 0, 1, 1, 0, b'a', 1, 1, 0, b'a'];
+        std::fs::write(&path, bytes).unwrap();
+
+        let result = SchemaCatalog::load(&path);
+
+        assert!(matches!(result, Err(SchemaError::DuplicateColumn(name)) if name == "a"));
+    }
+
+    #[test]
+    fn load_missing_file_returns_io_error() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let result = SchemaCatalog::load(&dir.path().join("schemas.cat"));
+
+        assert!(matches!(result, Err(SchemaError::Io(_))));
+    }
+}
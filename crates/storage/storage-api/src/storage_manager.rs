@@ -1,10 +1,21 @@
 //! The storage manager
+use crate::metrics::{Metrics, MetricsSnapshot};
+use crate::overflow::{self, OverflowDescriptor};
+use crate::retry::RetryPolicy;
+use crate::row_checksum;
+use crate::schema_catalog::SchemaCatalog;
+use crate::table::RowId;
 use buffer::buffer::BufferManager;
+use buffer::errors::BufferError;
 use buffer::guards::{PageReadGuard, PageWriteGuard};
 use file::api::FileManager;
+use file::errors::FileError;
 use file::file_catalog::FileCatalog;
+use page::compaction::{CompactionOrder, CompactionReport};
+use page::errors::page_error::PageError;
+use page::insertion_plan::DEFAULT_COMPACTION_THRESHOLD;
 use page::page::api::Page;
-use page::page_id::PageId;
+use page::page_id::{FileId, PageId};
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -13,38 +24,160 @@ use thiserror::Error;
 pub struct StorageManager<F: FileManager> {
     file_manager: Arc<F>,
     buffer_manager: Arc<BufferManager<F>>,
+    /// Fragmentation ratio above which heap pages are compacted on insert instead of filling a gap
+    compaction_threshold: f64,
+    /// Counters of the row operations served through this manager
+    metrics: Metrics,
+    /// Tells which tables store their rows with a checksum
+    schema_catalog: Arc<SchemaCatalog>,
+    /// How buffer operations failing because the pool is full are retried
+    retry_policy: RetryPolicy,
 }
 
 impl<F: FileManager> StorageManager<F> {
     /// Creates a new instance of the `StorageManager`
     pub fn new(file_manager: Arc<F>, buffer_manager: Arc<BufferManager<F>>) -> Self {
+        Self::with_compaction_threshold(file_manager, buffer_manager, DEFAULT_COMPACTION_THRESHOLD)
+    }
+
+    /// Creates a new instance of the `StorageManager`, choosing how eagerly heap pages are compacted.
+    ///
+    /// # Params
+    /// - `compaction_threshold`: fragmentation ratio, between `0.0` and `1.0`, above which an insert compacts
+    ///   the page instead of placing the row in a gap. `DEFAULT_COMPACTION_THRESHOLD` only compacts when no gap fits.
+    pub fn with_compaction_threshold(
+        file_manager: Arc<F>,
+        buffer_manager: Arc<BufferManager<F>>,
+        compaction_threshold: f64,
+    ) -> Self {
         Self {
             file_manager,
             buffer_manager,
+            compaction_threshold,
+            metrics: Metrics::default(),
+            schema_catalog: Arc::new(SchemaCatalog::new()),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Uses `schema_catalog` to tell which tables store their rows with a checksum.
+    /// Without it, no table does.
+    pub fn with_schema_catalog(mut self, schema_catalog: Arc<SchemaCatalog>) -> Self {
+        self.schema_catalog = schema_catalog;
+        self
+    }
+
+    /// Uses `retry_policy` when the buffer pool is full, instead of `RetryPolicy::default()`.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns how buffer operations failing because the pool is full are retried
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    /// Returns the fragmentation ratio above which heap pages are compacted on insert
+    pub fn compaction_threshold(&self) -> f64 {
+        self.compaction_threshold
+    }
+
+    /// Returns the number of row operations served so far, see `MetricsSnapshot`
+    pub fn metrics_snapshot(&self) -> MetricsSnapshot {
+        self.metrics.snapshot()
+    }
+
+    /// Counters to record row operations into
+    pub(crate) fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Whether rows of `file_id` are stored with a checksum, see `row_checksum`
+    pub(crate) fn row_checksums(&self, file_id: FileId) -> bool {
+        self.schema_catalog.row_checksums(file_id)
+    }
+
+    /// Turns `row` into the bytes to store in a page of `file_id`.
+    /// The row is escaped first, so that it cannot be mistaken for an overflow descriptor, see `overflow`.
+    pub(crate) fn seal_row(&self, file_id: FileId, row: Vec<u8>) -> Vec<u8> {
+        self.seal_record(file_id, overflow::escape_row(row))
+    }
+
+    /// Turns a record that is already in its stored form (an escaped row, or an overflow descriptor) into
+    /// the bytes to store in a page of `file_id`
+    pub(crate) fn seal_record(&self, file_id: FileId, record: Vec<u8>) -> Vec<u8> {
+        if self.row_checksums(file_id) {
+            row_checksum::seal(&record)
+        } else {
+            record
+        }
+    }
+
+    /// Runs `operation`, retrying it according to the retry policy for as long as it fails with
+    /// `BufferError::BufferFull`. Any other error is returned right away.
+    fn retry_buffer_full<T>(
+        &self,
+        mut operation: impl FnMut() -> Result<T, BufferError>,
+    ) -> Result<T, BufferError> {
+        let mut retry = 0;
+        loop {
+            match operation() {
+                Err(BufferError::BufferFull(page_id)) if retry < self.retry_policy.max_retries => {
+                    let backoff = self.retry_policy.backoff(retry);
+                    tracing::debug!(?page_id, retry, ?backoff, "buffer pool full, retrying");
+                    std::thread::sleep(backoff);
+                    retry += 1;
+                }
+                result => return result,
+            }
         }
     }
 
     /// Obtain a `&Page` via `PageReadGuard` for the provided `PageId`
+    ///
+    /// # Errors
+    ///
+    /// * `StorageErrors::BufferFull` - If every frame of the pool stayed pinned through all the retries
+    ///   of the retry policy.
+    /// * `StorageErrors::ReadPage` - If the page could not be read.
     pub fn read_page(&self, page_id: PageId) -> Result<PageReadGuard<'_>, StorageErrors> {
-        match self.buffer_manager.read_page(page_id) {
+        match self.retry_buffer_full(|| self.buffer_manager.read_page(page_id)) {
             Ok(page_read_guard) => Ok(page_read_guard),
+            Err(BufferError::BufferFull(page_id)) => Err(StorageErrors::BufferFull(page_id)),
             Err(_) => Err(StorageErrors::ReadPage),
         }
     }
 
     /// Obtain a `&mut Page` via `PageWriteGuard` for the provided `PageId`
+    ///
+    /// # Errors
+    ///
+    /// * `StorageErrors::BufferFull` - If every frame of the pool stayed pinned through all the retries
+    ///   of the retry policy.
+    /// * `StorageErrors::ReadPage` - If the page could not be read.
     pub fn read_page_mut(&self, page_id: PageId) -> Result<PageWriteGuard<'_>, StorageErrors> {
-        match self.buffer_manager.read_page_mut(page_id) {
+        match self.retry_buffer_full(|| self.buffer_manager.read_page_mut(page_id)) {
             Ok(page_write_guard) => Ok(page_write_guard),
+            Err(BufferError::BufferFull(page_id)) => Err(StorageErrors::BufferFull(page_id)),
             Err(_) => Err(StorageErrors::ReadPage),
         }
     }
 
-    /// Initialize a new `Page` on the buffer for the provided `PageId` and obtain a `&mut Page`
-    /// via a `PageWriteGuard`
-    pub fn new_page(&self, page_id: PageId) -> Result<PageWriteGuard<'_>, StorageErrors> {
-        match self.buffer_manager.allocate_new_page(page_id) {
+    /// Allocate a new page at the end of the file on disk, then initialize it on the buffer and obtain
+    /// a `&mut Page` via a `PageWriteGuard`. The `PageId` of the new page is available through the guard.
+    ///
+    /// # Errors
+    ///
+    /// * `StorageErrors::AllocatePage` - If the page could not be allocated on disk.
+    /// * `StorageErrors::BufferFull` - If every frame of the pool stayed pinned through all the retries
+    ///   of the retry policy. The page stays allocated on disk.
+    /// * `StorageErrors::NewPage` - If the page could not be initialized on the buffer.
+    pub fn new_page(&self, file_id: FileId) -> Result<PageWriteGuard<'_>, StorageErrors> {
+        let page_id = self.file_manager.allocate_page(file_id)?;
+        match self.retry_buffer_full(|| self.buffer_manager.allocate_new_page(page_id)) {
             Ok(page_write_guard) => Ok(page_write_guard),
+            Err(BufferError::BufferFull(page_id)) => Err(StorageErrors::BufferFull(page_id)),
             Err(_) => Err(StorageErrors::NewPage),
         }
     }
@@ -53,6 +186,117 @@ impl<F: FileManager> StorageManager<F> {
     pub fn write_page(&self, page_id: PageId, guard: PageWriteGuard<'_>) {
         self.buffer_manager.write_page(page_id, guard)
     }
+
+    /// Returns a copy of the bytes of the row at `row_id`.
+    /// Rows stored with `insert_large_row` are reassembled from their overflow pages.
+    ///
+    /// # Errors
+    ///
+    /// * `StorageErrors::ReadPage` - If the page, or one of the overflow pages, could not be read.
+    /// * `StorageErrors::Page` - If the slot is out of range or the row was deleted.
+    /// * `StorageErrors::RowChecksumMismatch` - If the table stores row checksums, and the row does not
+    ///   match its own.
+    /// * `StorageErrors::BrokenOverflowChain` - If the overflow pages of a large row do not hold it.
+    pub fn fetch_row(&self, row_id: RowId) -> Result<Vec<u8>, StorageErrors> {
+        let guard = self.read_page(row_id.page_id)?;
+        let mut row = guard.row(row_id.slot)?;
+        if self.row_checksums(row_id.page_id.file_id) {
+            row = row_checksum::verify(row).ok_or(StorageErrors::RowChecksumMismatch(row_id))?;
+        }
+        let row = match OverflowDescriptor::decode(row) {
+            Some(descriptor) => {
+                // The overflow pages are latched one at a time, without holding on to the descriptor
+                drop(guard);
+                self.read_overflow_chain(row_id, descriptor)?
+            }
+            None => overflow::unescape_row(row).to_vec(),
+        };
+        self.metrics.record_read();
+        Ok(row)
+    }
+
+    /// Deletes the row at `row_id`, optionally compacting its page afterwards.
+    /// Deleting a row stored with `insert_large_row` also releases its overflow pages, once the
+    /// descriptor is gone, see `release_overflow_chain`.
+    /// The pages are left dirty in the buffer, to be written to disk on eviction or flush.
+    ///
+    /// # Errors
+    ///
+    /// * `StorageErrors::ReadPage` - If the page, or one of the overflow pages, could not be read.
+    /// * `StorageErrors::Page` - If the slot is out of range or the row was already deleted.
+    pub fn delete_row(&self, row_id: RowId, compact: bool) -> Result<(), StorageErrors> {
+        let mut guard = self.read_page_mut(row_id.page_id)?;
+        let descriptor = self.descriptor_at(&guard, row_id);
+        let outcome = guard.delete_row_reporting(row_id.slot as usize, compact)?;
+        // The overflow pages are latched one at a time, without holding on to the page of the row
+        drop(guard);
+        self.metrics.record_delete();
+        if outcome.compacted {
+            self.metrics.record_compaction();
+        }
+        if let Some(descriptor) = descriptor {
+            self.release_overflow_chain(row_id.page_id.file_id, descriptor.head_page)?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the contents of the row at `row_id` with `bytes`. The row keeps its `RowId`.
+    /// Replacing a row stored with `insert_large_row` also releases its overflow pages, once the
+    /// descriptor is overwritten, see `release_overflow_chain`.
+    /// The pages are left dirty in the buffer, to be written to disk on eviction or flush.
+    /// Tables storing row checksums get the checksum of `bytes` stored along with them.
+    ///
+    /// # Errors
+    ///
+    /// * `StorageErrors::ReadPage` - If the page, or one of the overflow pages, could not be read.
+    /// * `StorageErrors::Page` - If the slot is invalid, or the new contents do not fit in the page.
+    pub fn update_row(&self, row_id: RowId, bytes: Vec<u8>) -> Result<(), StorageErrors> {
+        let mut guard = self.read_page_mut(row_id.page_id)?;
+        let descriptor = self.descriptor_at(&guard, row_id);
+        let bytes = self.seal_row(row_id.page_id.file_id, bytes);
+        guard.update_row(row_id.slot as usize, bytes)?;
+        // The overflow pages are latched one at a time, without holding on to the page of the row
+        drop(guard);
+        self.metrics.record_update();
+        if let Some(descriptor) = descriptor {
+            self.release_overflow_chain(row_id.page_id.file_id, descriptor.head_page)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the overflow descriptor stored at `row_id`, if the row was stored with
+    /// `insert_large_row`. Slots that cannot be read are left for the caller's own page operation to
+    /// report.
+    fn descriptor_at(&self, page: &Page, row_id: RowId) -> Option<OverflowDescriptor> {
+        let stored = page.row(row_id.slot).ok()?;
+        self.stored_descriptor(row_id.page_id.file_id, stored)
+    }
+
+    /// Compacts the page at `page_id`, laying out its rows in `order`, and logs what the compaction did.
+    /// The page is left dirty in the buffer, to be written to disk on eviction or flush.
+    ///
+    /// # Errors
+    ///
+    /// * `StorageErrors::ReadPage` - If the page could not be read.
+    /// * `StorageErrors::Page` - If the header or the slot array of the page could not be accessed.
+    pub fn compact_page(
+        &self,
+        page_id: PageId,
+        order: CompactionOrder,
+    ) -> Result<CompactionReport, StorageErrors> {
+        let mut guard = self.read_page_mut(page_id)?;
+        let report = guard.compact_reporting(order)?;
+        self.metrics.record_compaction();
+        tracing::debug!(
+            ?page_id,
+            ?order,
+            bytes_moved = report.bytes_moved,
+            rows_relocated = report.rows_relocated,
+            free_start = report.free_start,
+            "compacted page"
+        );
+        Ok(report)
+    }
 }
 
 /// Public storage API errors
@@ -64,4 +308,476 @@ pub enum StorageErrors {
     /// Error while creating new page
     #[error("Error while creating new page")]
     NewPage,
+    /// Every frame of the buffer pool was pinned, even after retrying, see `RetryPolicy`
+    #[error("Buffer pool full while serving page {0:?}")]
+    BufferFull(PageId),
+    /// Error while reserving space for a new page on disk
+    #[error("Error while allocating page on disk")]
+    AllocatePage(#[from] FileError),
+    /// A page operation failed
+    #[error("Page error: {0}")]
+    Page(#[from] PageError),
+    /// A stored row does not match its checksum
+    #[error("Row {0:?} does not match its checksum")]
+    RowChecksumMismatch(RowId),
+    /// The overflow pages a large row descriptor points at do not hold the row, see `overflow`
+    #[error("Overflow pages of row {0:?} do not hold the row")]
+    BrokenOverflowChain(RowId),
+    /// A row passed to `insert_large_row` is too long for its length to fit in a descriptor
+    #[error("Row of {0} bytes is too large to be stored")]
+    RowTooLarge(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use file::disk_file_manager::DiskFileManager;
+    use file::file_catalog::FileCatalog;
+    use page::PAGE_SIZE;
+    use page::page_type::PageType;
+    use tempfile::TempDir;
+
+    const FILE_ID: FileId = 1;
+
+    fn setup() -> (TempDir, StorageManager<DiskFileManager>) {
+        let dir = tempfile::tempdir().unwrap();
+        let catalog = Arc::new(FileCatalog::new());
+        catalog
+            .add_file(FILE_ID, dir.path().join("data.trdb"))
+            .unwrap();
+        let file_manager = Arc::new(DiskFileManager::new(catalog));
+        let buffer_manager = Arc::new(BufferManager::new(file_manager.clone(), 4));
+        (dir, StorageManager::new(file_manager, buffer_manager))
+    }
+
+    #[test]
+    fn new_page_sequential_calls_allocate_distinct_disk_backed_pages() {
+        let (dir, storage) = setup();
+
+        let first = storage.new_page(FILE_ID).unwrap().page_id();
+        let second = storage.new_page(FILE_ID).unwrap().page_id();
+
+        assert_eq!(first, PageId::new(FILE_ID, 0));
+        assert_eq!(second, PageId::new(FILE_ID, 1));
+        let len = std::fs::metadata(dir.path().join("data.trdb"))
+            .unwrap()
+            .len();
+        assert_eq!(len, 2 * PAGE_SIZE as u64);
+    }
+
+    #[test]
+    fn new_page_written_page_can_be_read_back_from_disk() {
+        let (_dir, storage) = setup();
+        let mut guard = storage.new_page(FILE_ID).unwrap();
+        let page_id = guard.page_id();
+        guard.initialize(page_id, PageType::Unsorted).unwrap();
+        storage.write_page(page_id, guard);
+
+        let mut bytes = [0u8; PAGE_SIZE];
+        assert!(storage.file_manager.read_page(page_id, &mut bytes));
+
+        let page = Page::try_from_bytes(Box::new(bytes), page_id).unwrap();
+        assert_eq!(page.slot_count().unwrap(), 0);
+    }
+
+    #[test]
+    fn new_page_unknown_file_returns_error() {
+        let (_dir, storage) = setup();
+
+        assert!(matches!(
+            storage.new_page(FILE_ID + 1),
+            Err(StorageErrors::AllocatePage(FileError::UnknownFile(2)))
+        ));
+    }
+
+    mod in_memory {
+        use super::*;
+        use crate::test_util::test_storage_with;
+        use file::in_memory_file_manager::InMemoryFileManager;
+        use std::time::{Duration, Instant};
+
+        /// Storage with a buffer of `pool_size` frames, built by `build`. Its registered file is `FILE_ID`.
+        fn setup_with(
+            pool_size: usize,
+            build: impl FnOnce(
+                Arc<InMemoryFileManager>,
+                Arc<BufferManager<InMemoryFileManager>>,
+            ) -> StorageManager<InMemoryFileManager>,
+        ) -> Arc<StorageManager<InMemoryFileManager>> {
+            let (storage, file_id) = test_storage_with(pool_size, build);
+            assert_eq!(file_id, FILE_ID);
+            storage
+        }
+
+        fn setup(pool_size: usize) -> Arc<StorageManager<InMemoryFileManager>> {
+            setup_with(pool_size, StorageManager::new)
+        }
+
+        fn insert_row(guard: &mut PageWriteGuard<'_>, row: &[u8]) {
+            let plan = guard.plan_insert(row.len()).unwrap();
+            guard.insert_heap(plan, row.to_vec()).unwrap();
+        }
+
+        #[test]
+        fn new_page_allocates_sequential_pages() {
+            let storage = setup(4);
+
+            let first = storage.new_page(FILE_ID).unwrap().page_id();
+            let second = storage.new_page(FILE_ID).unwrap().page_id();
+
+            assert_eq!(first, PageId::new(FILE_ID, 0));
+            assert_eq!(second, PageId::new(FILE_ID, 1));
+        }
+
+        #[test]
+        fn written_rows_survive_eviction() {
+            let storage = setup(1);
+            let mut guard = storage.new_page(FILE_ID).unwrap();
+            let page_id = guard.page_id();
+            guard.initialize(page_id, PageType::Unsorted).unwrap();
+            insert_row(&mut guard, b"first row");
+            insert_row(&mut guard, b"second row");
+            storage.write_page(page_id, guard);
+
+            // The pool only has one frame, so allocating another page evicts the first one
+            let other = storage.new_page(FILE_ID).unwrap().page_id();
+            assert_ne!(other, page_id);
+
+            let guard = storage.read_page(page_id).unwrap();
+            assert_eq!(guard.slot_count().unwrap(), 2);
+            assert_eq!(guard.row(0).unwrap(), b"first row");
+            assert_eq!(guard.row(1).unwrap(), b"second row");
+        }
+
+        #[test]
+        fn modified_page_evicted_without_write_page_is_persisted() {
+            let storage = setup(1);
+            let mut guard = storage.new_page(FILE_ID).unwrap();
+            let page_id = guard.page_id();
+            guard.initialize(page_id, PageType::Unsorted).unwrap();
+            insert_row(&mut guard, b"dirty row");
+            drop(guard);
+
+            drop(storage.new_page(FILE_ID).unwrap());
+
+            let guard = storage.read_page(page_id).unwrap();
+            assert_eq!(guard.row(0).unwrap(), b"dirty row");
+        }
+
+        fn page_with_rows(storage: &StorageManager<InMemoryFileManager>, rows: &[&[u8]]) -> PageId {
+            let mut guard = storage.new_page(FILE_ID).unwrap();
+            let page_id = guard.page_id();
+            guard.initialize(page_id, PageType::Unsorted).unwrap();
+            for row in rows {
+                insert_row(&mut guard, row);
+            }
+            storage.write_page(page_id, guard);
+            page_id
+        }
+
+        #[test]
+        fn delete_row_is_visible_on_fetch_row() {
+            let storage = setup(4);
+            let page_id = page_with_rows(&storage, &[b"first", b"second"]);
+            let deleted = RowId { page_id, slot: 0 };
+
+            storage.delete_row(deleted, false).unwrap();
+
+            assert!(matches!(
+                storage.fetch_row(deleted),
+                Err(StorageErrors::Page(_))
+            ));
+            assert_eq!(
+                storage.fetch_row(RowId { page_id, slot: 1 }).unwrap(),
+                b"second"
+            );
+        }
+
+        #[test]
+        fn update_row_is_visible_on_fetch_row() {
+            let storage = setup(4);
+            let page_id = page_with_rows(&storage, &[b"first", b"second"]);
+            let row_id = RowId { page_id, slot: 1 };
+
+            storage
+                .update_row(row_id, b"a longer second row".to_vec())
+                .unwrap();
+
+            assert_eq!(storage.fetch_row(row_id).unwrap(), b"a longer second row");
+            assert_eq!(
+                storage.fetch_row(RowId { page_id, slot: 0 }).unwrap(),
+                b"first"
+            );
+        }
+
+        #[test]
+        fn delete_and_update_survive_eviction() {
+            let storage = setup(1);
+            let page_id = page_with_rows(&storage, &[b"first", b"second"]);
+            storage
+                .delete_row(RowId { page_id, slot: 0 }, true)
+                .unwrap();
+            storage
+                .update_row(RowId { page_id, slot: 1 }, b"updated".to_vec())
+                .unwrap();
+
+            // The pool only has one frame, so allocating another page evicts the modified one
+            drop(storage.new_page(FILE_ID).unwrap());
+
+            assert!(storage.fetch_row(RowId { page_id, slot: 0 }).is_err());
+            assert_eq!(
+                storage.fetch_row(RowId { page_id, slot: 1 }).unwrap(),
+                b"updated"
+            );
+        }
+
+        #[test]
+        fn delete_row_already_deleted_returns_page_error() {
+            let storage = setup(4);
+            let page_id = page_with_rows(&storage, &[b"first"]);
+            let row_id = RowId { page_id, slot: 0 };
+            storage.delete_row(row_id, false).unwrap();
+
+            assert!(matches!(
+                storage.delete_row(row_id, false),
+                Err(StorageErrors::Page(_))
+            ));
+        }
+
+        #[test]
+        fn metrics_count_row_operations() {
+            let storage = setup(4);
+            let page_id = page_with_rows(&storage, &[b"first", b"second"]);
+
+            storage
+                .update_row(RowId { page_id, slot: 1 }, b"updated".to_vec())
+                .unwrap();
+            storage
+                .delete_row(RowId { page_id, slot: 0 }, true)
+                .unwrap();
+            storage.fetch_row(RowId { page_id, slot: 1 }).unwrap();
+            storage.fetch_row(RowId { page_id, slot: 0 }).unwrap_err();
+
+            assert_eq!(
+                storage.metrics_snapshot(),
+                MetricsSnapshot {
+                    deletes: 1,
+                    updates: 1,
+                    reads: 1,
+                    compactions: 1,
+                    ..MetricsSnapshot::default()
+                }
+            );
+        }
+
+        #[test]
+        fn compact_page_reports_rows_moved_over_gap() {
+            let storage = setup(4);
+            let page_id = page_with_rows(&storage, &[b"first", b"second", b"third"]);
+            storage
+                .delete_row(RowId { page_id, slot: 0 }, false)
+                .unwrap();
+
+            let report = storage
+                .compact_page(page_id, CompactionOrder::PhysicalOrder)
+                .unwrap();
+
+            assert_eq!(
+                report,
+                CompactionReport {
+                    bytes_moved: 11,
+                    rows_relocated: 2,
+                    free_start: 96 + 11,
+                }
+            );
+            assert_eq!(storage.metrics_snapshot().compactions, 1);
+            assert_eq!(
+                storage.fetch_row(RowId { page_id, slot: 2 }).unwrap(),
+                b"third"
+            );
+        }
+
+        #[test]
+        fn compact_page_never_allocated_returns_error() {
+            let storage = setup(4);
+
+            assert!(matches!(
+                storage.compact_page(PageId::new(FILE_ID, 3), CompactionOrder::SlotIndexOrder),
+                Err(StorageErrors::ReadPage)
+            ));
+            assert_eq!(storage.metrics_snapshot().compactions, 0);
+        }
+
+        fn checksummed_setup() -> Arc<StorageManager<InMemoryFileManager>> {
+            let schema_catalog = Arc::new(SchemaCatalog::new());
+            schema_catalog
+                .register_with_row_checksums(FILE_ID, Vec::new())
+                .unwrap();
+            setup_with(4, |file_manager, buffer_manager| {
+                StorageManager::new(file_manager, buffer_manager)
+                    .with_schema_catalog(schema_catalog)
+            })
+        }
+
+        /// Flips one bit of the stored bytes of the row at `row_id`, behind the storage manager's back
+        fn flip_stored_byte(
+            storage: &StorageManager<InMemoryFileManager>,
+            row_id: RowId,
+            index: usize,
+        ) {
+            let mut guard = storage.read_page_mut(row_id.page_id).unwrap();
+            guard.row_mut(row_id.slot).unwrap()[index] ^= 0x01;
+        }
+
+        #[test]
+        fn checksummed_update_row_is_verified_on_fetch_row() {
+            let storage = checksummed_setup();
+            let page_id = page_with_rows(&storage, &[b"placeholder"]);
+            let row_id = RowId { page_id, slot: 0 };
+
+            storage.update_row(row_id, b"checked".to_vec()).unwrap();
+
+            assert_eq!(storage.fetch_row(row_id).unwrap(), b"checked");
+            let guard = storage.read_page(page_id).unwrap();
+            assert_eq!(
+                guard.row(0).unwrap().len(),
+                row_checksum::ROW_CHECKSUM_SIZE + 7
+            );
+        }
+
+        #[test]
+        fn checksummed_flipped_payload_byte_detected_on_fetch_row() {
+            let storage = checksummed_setup();
+            let page_id = page_with_rows(&storage, &[b"placeholder", b"neighbour"]);
+            let row_id = RowId { page_id, slot: 0 };
+            let neighbour = RowId { page_id, slot: 1 };
+            storage.update_row(row_id, b"checked".to_vec()).unwrap();
+            storage
+                .update_row(neighbour, b"untouched".to_vec())
+                .unwrap();
+
+            flip_stored_byte(&storage, row_id, row_checksum::ROW_CHECKSUM_SIZE + 3);
+
+            assert!(matches!(
+                storage.fetch_row(row_id),
+                Err(StorageErrors::RowChecksumMismatch(id)) if id == row_id
+            ));
+            // Only the corrupted row is reported
+            assert_eq!(storage.fetch_row(neighbour).unwrap(), b"untouched");
+        }
+
+        #[test]
+        fn checksummed_flipped_checksum_byte_detected_on_fetch_row() {
+            let storage = checksummed_setup();
+            let page_id = page_with_rows(&storage, &[b"placeholder"]);
+            let row_id = RowId { page_id, slot: 0 };
+            storage.update_row(row_id, b"checked".to_vec()).unwrap();
+
+            flip_stored_byte(&storage, row_id, 0);
+
+            assert!(matches!(
+                storage.fetch_row(row_id),
+                Err(StorageErrors::RowChecksumMismatch(id)) if id == row_id
+            ));
+        }
+
+        #[test]
+        fn rows_of_tables_without_checksums_are_stored_as_is() {
+            let schema_catalog = Arc::new(SchemaCatalog::new());
+            schema_catalog
+                .register_with_row_checksums(FILE_ID + 1, Vec::new())
+                .unwrap();
+            let storage = setup_with(4, |file_manager, buffer_manager| {
+                StorageManager::new(file_manager, buffer_manager)
+                    .with_schema_catalog(schema_catalog)
+            });
+            let page_id = page_with_rows(&storage, &[b"placeholder"]);
+            let row_id = RowId { page_id, slot: 0 };
+
+            storage.update_row(row_id, b"plain".to_vec()).unwrap();
+
+            let guard = storage.read_page(page_id).unwrap();
+            assert_eq!(guard.row(0).unwrap(), b"plain");
+        }
+
+        #[test]
+        fn read_page_never_allocated_returns_error() {
+            let storage = setup(4);
+
+            assert!(matches!(
+                storage.read_page(PageId::new(FILE_ID, 0)),
+                Err(StorageErrors::ReadPage)
+            ));
+        }
+
+        /// Two initialized pages on a pool of a single frame, which holds the second page.
+        fn two_pages_one_frame(
+            retry_policy: RetryPolicy,
+        ) -> (Arc<StorageManager<InMemoryFileManager>>, PageId, PageId) {
+            let storage = setup_with(1, |file_manager, buffer_manager| {
+                StorageManager::new(file_manager, buffer_manager).with_retry_policy(retry_policy)
+            });
+            let mut page_ids = Vec::new();
+            for _ in 0..2 {
+                let mut guard = storage.new_page(FILE_ID).unwrap();
+                let page_id = guard.page_id();
+                guard.initialize(page_id, PageType::Unsorted).unwrap();
+                storage.write_page(page_id, guard);
+                page_ids.push(page_id);
+            }
+            (storage, page_ids[0], page_ids[1])
+        }
+
+        #[test]
+        fn read_page_retries_until_a_frame_is_unpinned() {
+            let retry_policy = RetryPolicy {
+                max_retries: 200,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(5),
+            };
+            let (storage, evicted, resident) = two_pages_one_frame(retry_policy);
+
+            // The only frame stays pinned for a while, then frees up for the waiting reader
+            let pinned = storage.read_page(resident).unwrap();
+            let read = std::thread::scope(|scope| {
+                let reader =
+                    scope.spawn(|| storage.read_page(evicted).map(|guard| guard.page_id()));
+                std::thread::sleep(Duration::from_millis(20));
+                drop(pinned);
+                reader.join().unwrap()
+            });
+
+            assert_eq!(read.unwrap(), evicted);
+        }
+
+        #[test]
+        fn read_page_exhausted_retries_return_buffer_full() {
+            let retry_policy = RetryPolicy {
+                max_retries: 3,
+                initial_backoff: Duration::from_millis(2),
+                max_backoff: Duration::from_millis(2),
+            };
+            let (storage, evicted, resident) = two_pages_one_frame(retry_policy);
+            let _pinned = storage.read_page(resident).unwrap();
+
+            let started = Instant::now();
+            let result = storage.read_page_mut(evicted);
+
+            assert!(
+                matches!(result, Err(StorageErrors::BufferFull(page_id)) if page_id == evicted)
+            );
+            assert!(started.elapsed() >= Duration::from_millis(6));
+        }
+
+        #[test]
+        fn read_page_without_retries_returns_buffer_full_right_away() {
+            let (storage, evicted, resident) = two_pages_one_frame(RetryPolicy::NONE);
+            let _pinned = storage.read_page(resident).unwrap();
+
+            assert!(matches!(
+                storage.read_page(evicted),
+                Err(StorageErrors::BufferFull(page_id)) if page_id == evicted
+            ));
+        }
+    }
 }
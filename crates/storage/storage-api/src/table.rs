@@ -0,0 +1,364 @@
+//! A table - a heap of typed rows stored in the pages of a single file
+
+use crate::row_codec::{CodecError, Column, Row, Value, decode_row, encode_row};
+use crate::storage_manager::{StorageErrors, StorageManager};
+use file::api::FileManager;
+use page::errors::page_error::PageError;
+use page::insertion_plan::InsertionOffset;
+use page::page_id::{FileId, PageId};
+use page::page_type::PageType;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+
+/// Location of a row within a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RowId {
+    /// The page holding the row
+    pub page_id: PageId,
+    /// The slot of the row within the page
+    pub slot: u32,
+}
+
+/// Errors raised by table operations
+#[derive(Debug, Error)]
+pub enum TableError {
+    /// The row could not be encoded or decoded using the table schema
+    #[error("Codec error: {0}")]
+    Codec(#[from] CodecError),
+    /// A page could not be obtained from the storage manager
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageErrors),
+    /// A page operation failed
+    #[error("Page error: {0}")]
+    Page(#[from] PageError),
+}
+
+/// A heap table: an ordered list of columns and the file storing the rows.
+/// Rows are appended to the last page of the file, and a new page is allocated when it is full.
+#[derive(Debug)]
+pub struct Table<F: FileManager> {
+    /// The file holding the pages of the table
+    file_id: FileId,
+    /// The schema of the table
+    columns: Vec<Column>,
+    /// The storage manager used to access the pages
+    storage: Arc<StorageManager<F>>,
+    /// Number of pages allocated to the table. Also serializes inserts.
+    page_count: Mutex<u32>,
+}
+
+impl<F: FileManager> Table<F> {
+    /// Creates a new, empty table stored in `file_id`
+    pub fn new(file_id: FileId, columns: Vec<Column>, storage: Arc<StorageManager<F>>) -> Self {
+        Self {
+            file_id,
+            columns,
+            storage,
+            page_count: Mutex::new(0),
+        }
+    }
+
+    /// Returns the file holding the pages of the table
+    pub fn file_id(&self) -> FileId {
+        self.file_id
+    }
+
+    /// Returns the schema of the table
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Encodes `values` using the table schema and appends the row to the table.
+    /// Tables registered with row checksums store the row along with its checksum.
+    ///
+    /// # Errors
+    ///
+    /// * `TableError::Codec` - If the values do not match the schema.
+    /// * `TableError::Storage` - If a page could not be read or allocated.
+    /// * `TableError::Page` - If the row does not fit even in an empty page.
+    pub fn insert(&self, values: &[Value]) -> Result<RowId, TableError> {
+        let row = encode_row(&self.columns, values)?;
+        let row = self.storage.seal_row(self.file_id, row);
+        let mut page_count = self.page_count.lock().unwrap();
+
+        if *page_count > 0 {
+            let page_id = PageId::new(self.file_id, *page_count - 1);
+            let mut guard = self.storage.read_page_mut(page_id)?;
+            let threshold = self.storage.compaction_threshold();
+            if let Ok(plan) = guard.plan_insert_with_compaction_threshold(row.len(), threshold) {
+                let compacts = matches!(plan.offset, InsertionOffset::AfterCompactionFreeStart);
+                let slot = guard.insert_heap(plan, row)?;
+                self.storage.write_page(page_id, guard);
+                self.record_insert(compacts);
+                return Ok(RowId { page_id, slot });
+            }
+        }
+
+        let mut guard = self.storage.new_page(self.file_id)?;
+        let page_id = guard.page_id();
+        guard.initialize(page_id, PageType::Unsorted)?;
+        let plan = guard.plan_insert(row.len())?;
+        let slot = guard.insert_heap(plan, row)?;
+        self.storage.write_page(page_id, guard);
+        *page_count = page_id.page_number + 1;
+        // A fresh page has nothing to compact
+        self.record_insert(false);
+
+        Ok(RowId { page_id, slot })
+    }
+
+    /// Returns an iterator over the rows of the table, decoded using the table schema.
+    /// Rows are returned in page order, then slot order. Deleted rows are skipped.
+    ///
+    /// # Errors
+    ///
+    /// * `TableError::Storage` - If a page could not be read, a row could not be read from its page, or
+    ///   a row does not match its checksum.
+    /// * `TableError::Codec` - If a stored row does not match the schema.
+    pub fn scan(&self) -> Result<impl Iterator<Item = Row>, TableError> {
+        let page_count = *self.page_count.lock().unwrap();
+        let mut rows = Vec::new();
+
+        // Rows are decoded straight from the buffered pages, without copying their bytes first
+        for page in self.storage.scan_file_borrowed(self.file_id, page_count) {
+            for bytes in page?.rows()? {
+                rows.push(decode_row(&self.columns, bytes)?);
+            }
+        }
+
+        Ok(rows.into_iter())
+    }
+
+    fn record_insert(&self, compacted: bool) {
+        let metrics = self.storage.metrics();
+        metrics.record_insert();
+        if compacted {
+            metrics.record_compaction();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::MetricsSnapshot;
+    use crate::row_checksum;
+    use crate::row_codec::ColumnType;
+    use crate::schema_catalog::SchemaCatalog;
+    use crate::test_util::{test_storage, test_storage_with};
+    use file::in_memory_file_manager::InMemoryFileManager;
+    use page::PAGE_SIZE;
+
+    fn create_table(pool_size: usize) -> Table<InMemoryFileManager> {
+        let (storage, file_id) = test_storage_with(pool_size, StorageManager::new);
+        Table::new(file_id, columns(), storage)
+    }
+
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("id", ColumnType::U32),
+            Column::new("name", ColumnType::Str),
+            Column::new("balance", ColumnType::U64),
+        ]
+    }
+
+    fn row(id: u32, name: &str, balance: u64) -> Vec<Value> {
+        vec![
+            Value::U32(id),
+            Value::Str(name.to_string()),
+            Value::U64(balance),
+        ]
+    }
+
+    #[test]
+    fn scan_empty_table_returns_no_rows() {
+        let table = create_table(4);
+
+        assert_eq!(table.scan().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn insert_then_scan_returns_decoded_rows() {
+        let table = create_table(4);
+
+        let first = table.insert(&row(1, "alice", 100)).unwrap();
+        let second = table.insert(&row(2, "bob", 200)).unwrap();
+
+        assert_eq!(
+            first,
+            RowId {
+                page_id: PageId::new(table.file_id, 0),
+                slot: 0
+            }
+        );
+        assert_eq!(
+            second,
+            RowId {
+                page_id: PageId::new(table.file_id, 0),
+                slot: 1
+            }
+        );
+        let rows: Vec<Row> = table.scan().unwrap().collect();
+        assert_eq!(rows, vec![row(1, "alice", 100), row(2, "bob", 200)]);
+    }
+
+    #[test]
+    fn insert_allocates_new_page_when_last_page_is_full() {
+        let table = create_table(4);
+        let name = "x".repeat(1000);
+
+        let row_ids: Vec<RowId> = (0..8)
+            .map(|i| table.insert(&row(i, &name, i as u64)).unwrap())
+            .collect();
+
+        assert_eq!(
+            row_ids.first().unwrap().page_id,
+            PageId::new(table.file_id, 0)
+        );
+        assert_eq!(
+            row_ids.last().unwrap().page_id,
+            PageId::new(table.file_id, 2)
+        );
+        let rows: Vec<Row> = table.scan().unwrap().collect();
+        assert_eq!(rows.len(), 8);
+        for (i, r) in rows.iter().enumerate() {
+            assert_eq!(r, &row(i as u32, &name, i as u64));
+        }
+    }
+
+    #[test]
+    fn insert_values_not_matching_schema_returns_codec_error() {
+        let table = create_table(4);
+
+        let err = table.insert(&[Value::U32(1)]).unwrap_err();
+
+        assert!(matches!(
+            err,
+            TableError::Codec(CodecError::ValueCountMismatch { .. })
+        ));
+        assert_eq!(table.scan().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn insert_row_larger_than_page_returns_page_error() {
+        let table = create_table(4);
+        let name = "x".repeat(PAGE_SIZE);
+
+        let err = table.insert(&row(1, &name, 1)).unwrap_err();
+
+        assert!(matches!(err, TableError::Page(_)));
+    }
+
+    fn create_checksummed_table() -> Table<InMemoryFileManager> {
+        let schema_catalog = Arc::new(SchemaCatalog::new());
+        let catalog = schema_catalog.clone();
+        let (storage, file_id) = test_storage_with(4, |file_manager, buffer_manager| {
+            StorageManager::new(file_manager, buffer_manager).with_schema_catalog(catalog)
+        });
+        schema_catalog
+            .register_with_row_checksums(file_id, columns())
+            .unwrap();
+        Table::new(file_id, columns(), storage)
+    }
+
+    #[test]
+    fn checksummed_insert_then_scan_returns_decoded_rows() {
+        let table = create_checksummed_table();
+
+        let row_id = table.insert(&row(1, "alice", 100)).unwrap();
+        table.insert(&row(2, "bob", 200)).unwrap();
+
+        let rows: Vec<Row> = table.scan().unwrap().collect();
+        assert_eq!(rows, vec![row(1, "alice", 100), row(2, "bob", 200)]);
+        let stored = table.storage.read_page(row_id.page_id).unwrap();
+        let encoded = encode_row(&table.columns, &row(1, "alice", 100)).unwrap();
+        assert_eq!(stored.row(0).unwrap(), row_checksum::seal(&encoded));
+    }
+
+    #[test]
+    fn checksummed_flipped_byte_detected_on_scan() {
+        let table = create_checksummed_table();
+        table.insert(&row(1, "alice", 100)).unwrap();
+        let row_id = table.insert(&row(2, "bob", 200)).unwrap();
+
+        let mut guard = table.storage.read_page_mut(row_id.page_id).unwrap();
+        guard.row_mut(row_id.slot).unwrap()[row_checksum::ROW_CHECKSUM_SIZE] ^= 0x80;
+        drop(guard);
+
+        assert!(matches!(
+            table.scan(),
+            Err(TableError::Storage(StorageErrors::RowChecksumMismatch(id))) if id == row_id
+        ));
+    }
+
+    #[test]
+    fn metrics_count_inserts_and_reads() {
+        let table = create_table(4);
+
+        for id in 0..3 {
+            table.insert(&row(id, "alice", 100)).unwrap();
+        }
+        assert_eq!(table.scan().unwrap().count(), 3);
+        assert_eq!(table.scan().unwrap().count(), 3);
+
+        assert_eq!(
+            table.storage.metrics_snapshot(),
+            MetricsSnapshot {
+                inserts: 3,
+                reads: 6,
+                ..MetricsSnapshot::default()
+            }
+        );
+    }
+
+    #[test]
+    fn metrics_failed_insert_is_not_counted() {
+        let table = create_table(4);
+
+        table.insert(&[Value::U32(1)]).unwrap_err();
+
+        assert_eq!(table.storage.metrics_snapshot(), MetricsSnapshot::default());
+    }
+
+    #[test]
+    fn metrics_count_insert_compacting_page() {
+        // Compact as soon as the page has any fragmentation
+        let (storage, file_id) = test_storage_with(4, |file_manager, buffer_manager| {
+            StorageManager::with_compaction_threshold(file_manager, buffer_manager, 0.0)
+        });
+        let table = Table::new(file_id, columns(), storage.clone());
+        // Three rows nearly fill the page, leaving no contiguous room for a fourth one
+        let name = "x".repeat(1200);
+        for id in 0..3 {
+            table.insert(&row(id, &name, 100)).unwrap();
+        }
+        // Leave a gap in the middle of the page
+        let page_id = PageId::new(file_id, 0);
+        let mut guard = storage.read_page_mut(page_id).unwrap();
+        guard.delete_row(1, false).unwrap();
+        storage.write_page(page_id, guard);
+
+        let row_id = table.insert(&row(3, &name, 200)).unwrap();
+
+        assert_eq!(row_id.page_id, page_id);
+
+        let snapshot = storage.metrics_snapshot();
+        assert_eq!(snapshot.inserts, 4);
+        assert_eq!(snapshot.compactions, 1);
+    }
+
+    #[test]
+    fn insert_then_scan_on_test_storage_returns_rows() {
+        let (storage, file_id) = test_storage();
+        let table = Table::new(file_id, columns(), storage);
+
+        let row_id = table.insert(&row(1, "alice", 100)).unwrap();
+        table.insert(&row(2, "bob", 200)).unwrap();
+
+        assert_eq!(row_id.page_id, PageId::new(file_id, 0));
+        assert_eq!(
+            table.scan().unwrap().collect::<Vec<_>>(),
+            vec![row(1, "alice", 100), row(2, "bob", 200)]
+        );
+    }
+}
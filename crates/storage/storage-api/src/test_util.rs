@@ -0,0 +1,39 @@
+//! Test fixtures assembling the storage stack over an `InMemoryFileManager`
+
+use crate::storage_manager::StorageManager;
+use buffer::buffer::BufferManager;
+use file::api::FileManager;
+use file::file_catalog::FileCatalog;
+use file::in_memory_file_manager::InMemoryFileManager;
+use page::page_id::FileId;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Number of frames in the buffer of the storage manager returned by `test_storage`
+pub const TEST_POOL_SIZE: usize = 16;
+
+/// Wires an in-memory file manager, a buffer of `TEST_POOL_SIZE` frames and a storage manager, and
+/// registers a file in the catalog, the same way `EngineEnvironment::new` assembles the disk-backed stack.
+///
+/// # Returns
+/// The storage manager, along with the `FileId` of the registered file
+pub fn test_storage() -> (Arc<StorageManager<InMemoryFileManager>>, FileId) {
+    test_storage_with(TEST_POOL_SIZE, StorageManager::new)
+}
+
+/// Same as `test_storage`, with a buffer of `pool_size` frames, and the storage manager built by
+/// `build` out of the file and buffer managers, e.g. `StorageManager::with_compaction_threshold`.
+pub fn test_storage_with(
+    pool_size: usize,
+    build: impl FnOnce(
+        Arc<InMemoryFileManager>,
+        Arc<BufferManager<InMemoryFileManager>>,
+    ) -> StorageManager<InMemoryFileManager>,
+) -> (Arc<StorageManager<InMemoryFileManager>>, FileId) {
+    let file_catalog = Arc::new(FileCatalog::new());
+    let file_id = file_catalog.register_file(PathBuf::from("test.trdb"));
+    let file_manager = Arc::new(InMemoryFileManager::new(file_catalog));
+    let buffer_manager = Arc::new(BufferManager::new(file_manager.clone(), pool_size));
+    let storage = Arc::new(build(file_manager, buffer_manager));
+    (storage, file_id)
+}
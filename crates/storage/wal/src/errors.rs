@@ -0,0 +1,24 @@
+//! Error types surfaced by the `wal` crate
+
+use thiserror::Error;
+
+/// Write-ahead log error.
+#[derive(Debug, Error)]
+pub enum WalError {
+    /// The payload does not fit in the length field of a record
+    #[error("Payload of {len} bytes is too large for a log record")]
+    PayloadTooLarge {
+        /// Length of the payload, in bytes
+        len: usize,
+    },
+    /// The writer was dropped before the record was made durable
+    #[error("Log writer closed before the record was synced")]
+    Closed,
+    /// A previous flush failed, so the writer refuses any further work. Records that were not durable
+    /// when the flush failed are lost.
+    #[error("Log writer is poisoned by a failed flush")]
+    Poisoned,
+    /// The underlying I/O operation failed
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+}
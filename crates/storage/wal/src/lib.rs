@@ -0,0 +1,13 @@
+//! Write-ahead log.
+//! Records are appended to a single log file, each one protected by its own checksum, so that a
+//! torn tail left behind by a crash is detected and discarded on recovery.
+
+#![allow(unused)] // Silence compiler warnings about unused code until they are referenced in main binary. TODO: remove this
+
+pub mod errors;
+
+/// Binary format of a single log record.
+pub mod record;
+
+/// Appending records to the log and reading them back.
+pub mod writer;
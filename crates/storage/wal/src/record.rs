@@ -0,0 +1,123 @@
+use crate::errors::WalError;
+use binary_helpers::checksum::Crc32;
+use binary_helpers::le::read_le;
+
+/// Size of the fields preceding the payload: `length` (u32) and `lsn` (u64).
+pub(crate) const RECORD_HEADER_SIZE: usize = 12;
+
+/// Size of the trailing CRC-32.
+pub(crate) const RECORD_CRC_SIZE: usize = 4;
+
+/// A single log record.
+///
+/// On disk, a record is laid out as follows (all fields little-endian):
+///
+/// | Field     | Type       | Description |
+/// |-----------|------------|-------------|
+/// | `length`  | u32        | Length of the payload, in bytes. |
+/// | `lsn`     | u64        | Log sequence number of the record. |
+/// | `payload` | `length` bytes | Opaque record contents. |
+/// | `crc`     | u32        | CRC-32 of `length`, `lsn` and `payload`. |
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalRecord {
+    /// Log sequence number of the record
+    pub lsn: u64,
+    /// Opaque record contents
+    pub payload: Vec<u8>,
+}
+
+impl WalRecord {
+    /// Serializes the record, including its trailing checksum.
+    pub(crate) fn encode(&self) -> Result<Vec<u8>, WalError> {
+        let len = u32::try_from(self.payload.len()).map_err(|_| WalError::PayloadTooLarge {
+            len: self.payload.len(),
+        })?;
+
+        let mut bytes =
+            Vec::with_capacity(RECORD_HEADER_SIZE + self.payload.len() + RECORD_CRC_SIZE);
+        bytes.extend_from_slice(&len.to_le_bytes());
+        bytes.extend_from_slice(&self.lsn.to_le_bytes());
+        bytes.extend_from_slice(&self.payload);
+
+        let mut crc = Crc32::new();
+        crc.update(&bytes);
+        bytes.extend_from_slice(&crc.finalize().to_le_bytes());
+
+        Ok(bytes)
+    }
+
+    /// Deserializes the record at the start of `bytes`.
+    ///
+    /// # Returns
+    /// - `Some((record, encoded_len))` if a complete record with a matching checksum was found.
+    /// - `None` if `bytes` ends before the record does, or the checksum does not match - both are
+    ///   what a torn write looks like.
+    pub(crate) fn decode(bytes: &[u8]) -> Option<(Self, usize)> {
+        let len = read_le::<u32>(bytes, 0).ok()? as usize;
+        let lsn = read_le::<u64>(bytes, 4).ok()?;
+
+        let crc_offset = RECORD_HEADER_SIZE.checked_add(len)?;
+        let stored_crc = read_le::<u32>(bytes, crc_offset).ok()?;
+
+        let mut crc = Crc32::new();
+        crc.update(&bytes[..crc_offset]);
+        if crc.finalize() != stored_crc {
+            return None;
+        }
+
+        let record = Self {
+            lsn,
+            payload: bytes[RECORD_HEADER_SIZE..crc_offset].to_vec(),
+        };
+        Some((record, crc_offset + RECORD_CRC_SIZE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> WalRecord {
+        WalRecord {
+            lsn: 42,
+            payload: b"insert 1:0".to_vec(),
+        }
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let bytes = record().encode().unwrap();
+
+        let (decoded, len) = WalRecord::decode(&bytes).unwrap();
+
+        assert_eq!(decoded, record());
+        assert_eq!(len, bytes.len());
+        assert_eq!(len, RECORD_HEADER_SIZE + 10 + RECORD_CRC_SIZE);
+    }
+
+    #[test]
+    fn decode_ignores_trailing_bytes() {
+        let mut bytes = record().encode().unwrap();
+        let len = bytes.len();
+        bytes.extend_from_slice(&[0xAB; 7]);
+
+        assert_eq!(WalRecord::decode(&bytes), Some((record(), len)));
+    }
+
+    #[test]
+    fn decode_truncated_record_returns_none() {
+        let bytes = record().encode().unwrap();
+
+        for cut in 0..bytes.len() {
+            assert_eq!(WalRecord::decode(&bytes[..cut]), None, "cut at {cut}");
+        }
+    }
+
+    #[test]
+    fn decode_corrupted_payload_returns_none() {
+        let mut bytes = record().encode().unwrap();
+        bytes[RECORD_HEADER_SIZE] ^= 0xFF;
+
+        assert_eq!(WalRecord::decode(&bytes), None);
+    }
+}
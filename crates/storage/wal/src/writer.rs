@@ -0,0 +1,589 @@
+use crate::errors::WalError;
+use crate::record::WalRecord;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+#[cfg(test)]
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Controls how appended records are batched before being forced to disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroupCommitConfig {
+    /// How often the background flusher syncs the pending records. See `WalWriter::spawn_flusher`.
+    pub flush_interval: Duration,
+    /// Number of pending bytes that triggers a sync right away, from within `append`.
+    pub max_pending_bytes: usize,
+}
+
+impl Default for GroupCommitConfig {
+    fn default() -> Self {
+        Self {
+            flush_interval: Duration::from_millis(5),
+            max_pending_bytes: 64 * 1024,
+        }
+    }
+}
+
+/// Appends records to a log file, syncing them in groups.
+///
+/// Every record gets the next log sequence number, starting at 1 for an empty log.
+/// Appended records are buffered in memory and written + synced together, either by the background
+/// flusher, once enough bytes are pending, or by an explicit `flush_now`. Callers wait for their own
+/// record to become durable through the returned `CommitTicket`, so concurrent transactions share a
+/// single fsync instead of paying for one each.
+///
+/// The file is written and synced without holding the lock appends go through, so appending never
+/// waits behind an fsync. A failed flush poisons the writer: the file is cut back to its last durable
+/// length, and every record that was not durable yet is reported as lost to its ticket.
+#[derive(Debug)]
+pub struct WalWriter {
+    /// The records not yet written to the log file
+    inner: Mutex<WalInner>,
+    /// The log file. Held for the whole flush, so flushes write their records in LSN order.
+    file: Mutex<WalFile>,
+    /// Highest LSN known to be on durable storage, and whether the writer is poisoned.
+    /// Waited on by `CommitTicket`s.
+    durability: watch::Sender<Durability>,
+    config: GroupCommitConfig,
+    /// Number of `sync_data` calls issued, so that tests can verify appends are batched
+    #[cfg(test)]
+    sync_calls: AtomicUsize,
+    /// Makes the next `sync_data` call fail, so that tests can exercise a failed flush
+    #[cfg(test)]
+    fail_next_sync: AtomicBool,
+}
+
+/// State of the writer that is only accessed under its `inner` mutex.
+#[derive(Debug)]
+struct WalInner {
+    /// LSN to assign to the next appended record
+    next_lsn: u64,
+    /// Encoded records appended since the last flush
+    pending: Vec<u8>,
+    /// Set once a flush failed. See `WalError::Poisoned`.
+    poisoned: bool,
+}
+
+/// State of the writer that is only accessed under its `file` mutex.
+#[derive(Debug)]
+struct WalFile {
+    /// The log file, positioned at its end
+    file: File,
+    /// Length of the file up to the last synced record
+    durable_len: u64,
+}
+
+/// Progress of the log towards durable storage, as observed by `CommitTicket`s.
+#[derive(Debug, Clone, Copy)]
+struct Durability {
+    /// Highest LSN known to be on durable storage
+    lsn: u64,
+    /// Whether a flush failed, so no later LSN will ever become durable
+    poisoned: bool,
+}
+
+/// Handle to an appended record, used to wait until it is durable.
+#[derive(Debug)]
+pub struct CommitTicket {
+    lsn: u64,
+    durability: watch::Receiver<Durability>,
+}
+
+impl CommitTicket {
+    /// Returns the LSN assigned to the record.
+    pub fn lsn(&self) -> u64 {
+        self.lsn
+    }
+
+    /// Returns whether the record has already reached durable storage.
+    pub fn is_durable(&self) -> bool {
+        self.durability.borrow().lsn >= self.lsn
+    }
+
+    /// Waits until the record reaches durable storage.
+    ///
+    /// # Returns
+    /// The LSN of the record, `WalError::Poisoned` if a flush failed before the record became durable,
+    /// or `WalError::Closed` if the writer was dropped before syncing it.
+    pub async fn durable(mut self) -> Result<u64, WalError> {
+        let lsn = self.lsn;
+        let durability = *self
+            .durability
+            .wait_for(|durability| durability.lsn >= lsn || durability.poisoned)
+            .await
+            .map_err(|_| WalError::Closed)?;
+        if durability.lsn < lsn {
+            return Err(WalError::Poisoned);
+        }
+        Ok(lsn)
+    }
+}
+
+impl WalWriter {
+    /// Opens the log at `path`, creating it if needed, with the default `GroupCommitConfig`.
+    ///
+    /// A torn tail left behind by a crash (see `recover`) is cut off, so new records are appended right
+    /// after the last intact one.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, WalError> {
+        Self::open_with_config(path, GroupCommitConfig::default())
+    }
+
+    /// Opens the log at `path`, creating it if needed, choosing how records are batched.
+    /// See `open`.
+    pub fn open_with_config(
+        path: impl AsRef<Path>,
+        config: GroupCommitConfig,
+    ) -> Result<Self, WalError> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        let (records, valid_len) = read_intact_prefix(&mut file)?;
+        file.set_len(valid_len as u64)?;
+        file.seek(SeekFrom::Start(valid_len as u64))?;
+
+        let last_lsn = records.last().map_or(0, |r| r.lsn);
+        Ok(Self {
+            inner: Mutex::new(WalInner {
+                next_lsn: last_lsn + 1,
+                pending: Vec::new(),
+                poisoned: false,
+            }),
+            file: Mutex::new(WalFile {
+                file,
+                durable_len: valid_len as u64,
+            }),
+            durability: watch::Sender::new(Durability {
+                lsn: last_lsn,
+                poisoned: false,
+            }),
+            config,
+            #[cfg(test)]
+            sync_calls: AtomicUsize::new(0),
+            #[cfg(test)]
+            fail_next_sync: AtomicBool::new(false),
+        })
+    }
+
+    /// Appends a record holding `payload` to the log.
+    ///
+    /// The record is only buffered, unless this pushes the pending bytes over
+    /// `GroupCommitConfig::max_pending_bytes`, in which case all pending records are synced right away.
+    ///
+    /// # Returns
+    /// A `CommitTicket` to wait on until the record is durable, or `WalError::Poisoned` if a flush
+    /// failed before.
+    pub fn append(&self, payload: &[u8]) -> Result<CommitTicket, WalError> {
+        let (lsn, must_flush) = {
+            let mut inner = self.inner.lock().unwrap();
+            if inner.poisoned {
+                return Err(WalError::Poisoned);
+            }
+            let record = WalRecord {
+                lsn: inner.next_lsn,
+                payload: payload.to_vec(),
+            };
+            inner.pending.extend_from_slice(&record.encode()?);
+            inner.next_lsn += 1;
+            (
+                record.lsn,
+                inner.pending.len() >= self.config.max_pending_bytes,
+            )
+        };
+        // Subscribed before flushing, so the ticket also observes a flush failing right away
+        let ticket = CommitTicket {
+            lsn,
+            durability: self.durability.subscribe(),
+        };
+
+        if must_flush {
+            self.flush_now()?;
+        }
+
+        Ok(ticket)
+    }
+
+    /// Writes and syncs all pending records, regardless of the group commit timer.
+    /// Meant for shutdown, or for callers that need durability right away.
+    ///
+    /// # Returns
+    /// The highest durable LSN, or `WalError::Poisoned` if a flush failed before.
+    pub fn flush_now(&self) -> Result<u64, WalError> {
+        let mut file = self.file.lock().unwrap();
+        self.flush_locked(&mut file)
+    }
+
+    /// Starts the background task syncing pending records every `GroupCommitConfig::flush_interval`.
+    /// Must be called from within a tokio runtime. The task stops once the writer is dropped.
+    pub fn spawn_flusher(self: &Arc<Self>) -> JoinHandle<()> {
+        let writer = Arc::downgrade(self);
+        let period = self.config.flush_interval;
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+            loop {
+                interval.tick().await;
+                let Some(writer) = writer.upgrade() else {
+                    break;
+                };
+                let flushed = tokio::task::spawn_blocking(move || writer.flush_now()).await;
+                match flushed {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(err)) => tracing::warn!("Log group commit failed: {:?}", err),
+                    Err(err) => tracing::warn!("Log group commit task failed: {:?}", err),
+                }
+            }
+        })
+    }
+
+    /// Returns the LSN the next appended record will get.
+    pub fn next_lsn(&self) -> u64 {
+        self.inner.lock().unwrap().next_lsn
+    }
+
+    /// Returns the highest LSN known to be on durable storage.
+    pub fn durable_lsn(&self) -> u64 {
+        self.durability.borrow().lsn
+    }
+
+    /// Takes the pending records out from under the `inner` lock, then writes them, syncs the file and
+    /// wakes up the callers waiting on them. Appends keep going while the file is written.
+    /// A flush with nothing pending does not touch the file.
+    ///
+    /// On a failed write or sync, the writer is poisoned, see `poison`.
+    fn flush_locked(&self, file: &mut WalFile) -> Result<u64, WalError> {
+        let (pending, last_lsn) = {
+            let mut inner = self.inner.lock().unwrap();
+            if inner.poisoned {
+                return Err(WalError::Poisoned);
+            }
+            (std::mem::take(&mut inner.pending), inner.next_lsn - 1)
+        };
+        if pending.is_empty() {
+            return Ok(self.durable_lsn());
+        }
+
+        if let Err(err) = self.write_and_sync(&mut file.file, &pending) {
+            self.poison(file);
+            return Err(err.into());
+        }
+
+        file.durable_len += pending.len() as u64;
+        self.durability
+            .send_modify(|durability| durability.lsn = last_lsn);
+        Ok(last_lsn)
+    }
+
+    /// Appends `bytes` at the current position of the file and syncs it.
+    fn write_and_sync(&self, file: &mut File, bytes: &[u8]) -> std::io::Result<()> {
+        file.write_all(bytes)?;
+
+        #[cfg(test)]
+        {
+            self.sync_calls.fetch_add(1, Ordering::Relaxed);
+            if self.fail_next_sync.swap(false, Ordering::Relaxed) {
+                return Err(std::io::Error::other("injected sync failure"));
+            }
+        }
+        file.sync_data()
+    }
+
+    /// Gives up on every record that is not durable yet, after a failed flush.
+    ///
+    /// The file is cut back to its last durable length, so that a partially written batch is never
+    /// replayed by `recover`, then the writer is flagged as poisoned and the waiting tickets are woken
+    /// up with `WalError::Poisoned`.
+    fn poison(&self, file: &mut WalFile) {
+        let durable_len = file.durable_len;
+        if let Err(err) = file
+            .file
+            .set_len(durable_len)
+            .and_then(|_| file.file.seek(SeekFrom::Start(durable_len)))
+        {
+            tracing::warn!("Truncating the log after a failed flush failed: {:?}", err);
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.poisoned = true;
+        inner.pending.clear();
+        self.durability
+            .send_modify(|durability| durability.poisoned = true);
+    }
+}
+
+/// Reads back all the intact records of the log at `path`, in the order they were appended.
+///
+/// Reading stops at the first record that is incomplete or whose checksum does not match: that is the
+/// torn tail of a write interrupted by a crash, so it and everything after it are ignored rather than
+/// reported as an error.
+pub fn recover(path: impl AsRef<Path>) -> Result<Vec<WalRecord>, WalError> {
+    let mut file = File::open(path)?;
+    let (records, _) = read_intact_prefix(&mut file)?;
+    Ok(records)
+}
+
+/// Decodes records from the start of `file` until the first one that is not intact.
+///
+/// # Returns
+/// The intact records, along with the number of bytes they span.
+fn read_intact_prefix(file: &mut File) -> Result<(Vec<WalRecord>, usize), WalError> {
+    let mut bytes = Vec::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_end(&mut bytes)?;
+
+    let mut records = Vec::new();
+    let mut offset = 0;
+    while let Some((record, len)) = WalRecord::decode(&bytes[offset..]) {
+        records.push(record);
+        offset += len;
+    }
+
+    Ok((records, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, std::path::PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trdb.wal");
+        (dir, path)
+    }
+
+    fn write_records(path: &Path, payloads: &[&[u8]]) {
+        let writer = WalWriter::open(path).unwrap();
+        for payload in payloads {
+            writer.append(payload).unwrap();
+        }
+        writer.flush_now().unwrap();
+    }
+
+    #[test]
+    fn recover_replays_all_records_in_order() {
+        let (_dir, path) = setup();
+        write_records(&path, &[b"first", b"second", b"third"]);
+
+        let records = recover(&path).unwrap();
+
+        assert_eq!(
+            records,
+            vec![
+                WalRecord {
+                    lsn: 1,
+                    payload: b"first".to_vec()
+                },
+                WalRecord {
+                    lsn: 2,
+                    payload: b"second".to_vec()
+                },
+                WalRecord {
+                    lsn: 3,
+                    payload: b"third".to_vec()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn recover_file_truncated_mid_record_replays_intact_prefix() {
+        let (_dir, path) = setup();
+        write_records(&path, &[b"first", b"second", b"third"]);
+        let len = fs::metadata(&path).unwrap().len();
+        OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(len - 3)
+            .unwrap();
+
+        let records = recover(&path).unwrap();
+
+        let payloads: Vec<&[u8]> = records.iter().map(|r| r.payload.as_slice()).collect();
+        assert_eq!(payloads, vec![b"first".as_slice(), b"second".as_slice()]);
+    }
+
+    #[test]
+    fn recover_corrupted_record_stops_before_it() {
+        let (_dir, path) = setup();
+        write_records(&path, &[b"first", b"second", b"third"]);
+        let mut bytes = fs::read(&path).unwrap();
+        // Flip a payload byte of the second record: 12 header + 5 payload + 4 crc for the first one
+        bytes[21 + 12] ^= 0xFF;
+        fs::write(&path, &bytes).unwrap();
+
+        let records = recover(&path).unwrap();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].payload, b"first");
+    }
+
+    #[test]
+    fn open_discards_torn_tail_and_continues_lsns() {
+        let (_dir, path) = setup();
+        write_records(&path, &[b"first", b"second"]);
+        let len = fs::metadata(&path).unwrap().len();
+        OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(len - 1)
+            .unwrap();
+
+        let writer = WalWriter::open(&path).unwrap();
+        assert_eq!(writer.next_lsn(), 2);
+        assert_eq!(writer.durable_lsn(), 1);
+        writer.append(b"again").unwrap();
+        writer.flush_now().unwrap();
+
+        let records = recover(&path).unwrap();
+        let lsns: Vec<u64> = records.iter().map(|r| r.lsn).collect();
+        assert_eq!(lsns, vec![1, 2]);
+        assert_eq!(records[1].payload, b"again");
+    }
+
+    #[test]
+    fn recover_empty_log_returns_no_records() {
+        let (_dir, path) = setup();
+        WalWriter::open(&path).unwrap();
+
+        assert!(recover(&path).unwrap().is_empty());
+    }
+
+    fn sync_calls(writer: &WalWriter) -> usize {
+        writer.sync_calls.load(Ordering::Relaxed)
+    }
+
+    fn config(flush_interval: Duration, max_pending_bytes: usize) -> GroupCommitConfig {
+        GroupCommitConfig {
+            flush_interval,
+            max_pending_bytes,
+        }
+    }
+
+    #[test]
+    fn append_is_not_durable_until_flushed() {
+        let (_dir, path) = setup();
+        let writer = WalWriter::open(&path).unwrap();
+
+        let ticket = writer.append(b"first").unwrap();
+
+        assert_eq!(ticket.lsn(), 1);
+        assert!(!ticket.is_durable());
+        assert!(recover(&path).unwrap().is_empty());
+
+        writer.flush_now().unwrap();
+        assert!(ticket.is_durable());
+        assert_eq!(recover(&path).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn flush_now_nothing_pending_does_not_sync() {
+        let (_dir, path) = setup();
+        let writer = WalWriter::open(&path).unwrap();
+
+        assert_eq!(writer.flush_now().unwrap(), 0);
+        assert_eq!(sync_calls(&writer), 0);
+    }
+
+    #[test]
+    fn append_over_size_threshold_syncs_right_away() {
+        let (_dir, path) = setup();
+        let writer =
+            WalWriter::open_with_config(&path, config(Duration::from_secs(60), 64)).unwrap();
+
+        let small = writer.append(&[1; 10]).unwrap();
+        assert_eq!(sync_calls(&writer), 0);
+        let large = writer.append(&[2; 50]).unwrap();
+
+        assert_eq!(sync_calls(&writer), 1);
+        assert!(small.is_durable());
+        assert!(large.is_durable());
+    }
+
+    #[tokio::test]
+    async fn concurrent_appends_share_a_single_sync() {
+        let (_dir, path) = setup();
+        let writer = Arc::new(
+            WalWriter::open_with_config(&path, config(Duration::from_millis(50), usize::MAX))
+                .unwrap(),
+        );
+        let flusher = writer.spawn_flusher();
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for i in 0..10u8 {
+            let writer = writer.clone();
+            tasks.spawn(async move { writer.append(&[i; 16]).unwrap().durable().await });
+        }
+        let mut lsns: Vec<u64> = tasks
+            .join_all()
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        lsns.sort();
+
+        assert_eq!(lsns, (1..=10).collect::<Vec<_>>());
+        assert_eq!(sync_calls(&writer), 1);
+        assert_eq!(recover(&path).unwrap().len(), 10);
+        flusher.abort();
+    }
+
+    #[tokio::test]
+    async fn failed_sync_poisons_writer_and_fails_pending_tickets() {
+        let (_dir, path) = setup();
+        write_records(&path, &[b"first"]);
+        let durable_len = fs::metadata(&path).unwrap().len();
+        let writer = WalWriter::open(&path).unwrap();
+        let ticket = writer.append(b"lost").unwrap();
+
+        writer.fail_next_sync.store(true, Ordering::Relaxed);
+
+        assert!(matches!(writer.flush_now(), Err(WalError::Io(_))));
+        assert!(matches!(ticket.durable().await, Err(WalError::Poisoned)));
+        assert!(matches!(writer.append(b"again"), Err(WalError::Poisoned)));
+        assert!(matches!(writer.flush_now(), Err(WalError::Poisoned)));
+        assert_eq!(writer.durable_lsn(), 1);
+        // The batch written before the failed sync is not left behind for recovery to replay
+        assert_eq!(fs::metadata(&path).unwrap().len(), durable_len);
+        assert_eq!(recover(&path).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn durable_writer_dropped_before_flush_returns_closed() {
+        let (_dir, path) = setup();
+        let writer = WalWriter::open(&path).unwrap();
+        let ticket = writer.append(b"lost").unwrap();
+
+        drop(writer);
+
+        assert!(matches!(ticket.durable().await, Err(WalError::Closed)));
+    }
+
+    #[tokio::test]
+    async fn flusher_stops_once_writer_is_dropped() {
+        let (_dir, path) = setup();
+        let writer = Arc::new(
+            WalWriter::open_with_config(&path, config(Duration::from_millis(1), usize::MAX))
+                .unwrap(),
+        );
+        let flusher = writer.spawn_flusher();
+
+        drop(writer);
+
+        tokio::time::timeout(Duration::from_secs(5), flusher)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}